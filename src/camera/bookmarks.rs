@@ -0,0 +1,182 @@
+use cgmath::{Deg, InnerSpace, Point3, Rad, Vector3};
+use serde::{Deserialize, Serialize};
+
+use super::projection::{MAX_FOV, MIN_FOV};
+use super::CameraPose;
+
+/// version written in exported bookmarks, bumped when the format changes
+const FORMAT_VERSION: u32 = 1;
+
+/// A named camera pose, stored in plain numbers so the JSON stays readable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// number key recalling the bookmark
+    pub slot: u8,
+    pub name: String,
+    pub eye: [f32; 3],
+    pub focus: [f32; 3],
+    pub up: [f32; 3],
+    /// field of view in degrees
+    pub fov: f32,
+    #[serde(default)]
+    pub orthographic: bool,
+}
+
+impl Bookmark {
+    pub fn new(slot: u8, name: String, pose: CameraPose, orthographic: bool) -> Self {
+        Self {
+            slot,
+            name,
+            eye: pose.eye.into(),
+            focus: pose.focus.into(),
+            up: pose.up.into(),
+            fov: Deg::from(pose.fov).0,
+            orthographic,
+        }
+    }
+
+    /// A pose the camera can take: finite numbers, the eye off the focus,
+    /// an up vector across the view direction and the fov within limits.
+    fn validate(&self) -> Result<(), String> {
+        let pose = self.pose();
+        let mut numbers = self.eye.iter().chain(&self.focus).chain(&self.up).chain(std::iter::once(&self.fov));
+        if !numbers.all(|n| n.is_finite()) {
+            return Err("a number is not finite".to_string());
+        }
+        let direction = pose.focus - pose.eye;
+        if direction.magnitude2() <= f32::EPSILON {
+            return Err("the eye is on the focus".to_string());
+        }
+        if direction.normalize().cross(pose.up).magnitude2() <= f32::EPSILON {
+            return Err("the up vector is along the view direction".to_string());
+        }
+        if !(MIN_FOV..=MAX_FOV).contains(&pose.fov.0) {
+            return Err(format!(
+                "the fov {} is outside of {:.0}..{:.0} degrees",
+                self.fov,
+                Deg::from(Rad(MIN_FOV)).0,
+                Deg::from(Rad(MAX_FOV)).0
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            eye: Point3::from(self.eye),
+            focus: Point3::from(self.focus),
+            up: Vector3::from(self.up),
+            fov: Rad::from(Deg(self.fov)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BookmarkFile {
+    version: u32,
+    bookmarks: Vec<Bookmark>,
+}
+
+/// Bookmarks of one lesson, at most one per slot.
+#[derive(Default)]
+pub struct Bookmarks {
+    bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn get(&self, slot: u8) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|b| b.slot == slot)
+    }
+
+    /// Adds a bookmark, replacing the one in the same slot.
+    pub fn set(&mut self, bookmark: Bookmark) {
+        self.bookmarks.retain(|b| b.slot != bookmark.slot);
+        self.bookmarks.push(bookmark);
+        self.bookmarks.sort_by_key(|b| b.slot);
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = BookmarkFile {
+            version: FORMAT_VERSION,
+            bookmarks: self.bookmarks.clone(),
+        };
+        serde_json::to_string_pretty(&file).expect("bookmarks serialize error")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: BookmarkFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if file.version != FORMAT_VERSION {
+            return Err(format!("unsupported bookmarks version {}", file.version));
+        }
+        let mut bookmarks = Self::default();
+        for bookmark in file.bookmarks {
+            bookmark
+                .validate()
+                .map_err(|e| format!("bookmark {} {:?}: {}", bookmark.slot, bookmark.name, e))?;
+            bookmarks.set(bookmark);
+        }
+        Ok(bookmarks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(eye: &str, focus: &str, up: &str, fov: &str) -> String {
+        format!(
+            r#"{{"version": 1, "bookmarks": [{{"slot": 1, "name": "a", "eye": {}, "focus": {}, "up": {}, "fov": {}}}]}}"#,
+            eye, focus, up, fov
+        )
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let pose = CameraPose {
+            eye: Point3::new(1.0, 2.0, 3.0),
+            focus: Point3::new(0.0, 0.5, 0.0),
+            up: Vector3::unit_y(),
+            fov: Rad(1.0),
+        };
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.set(Bookmark::new(3, "side".to_string(), pose, true));
+        let imported = Bookmarks::from_json(&bookmarks.to_json()).unwrap();
+        let bookmark = imported.get(3).unwrap();
+        assert_eq!(bookmark.name, "side");
+        assert!(bookmark.orthographic);
+        assert_eq!(bookmark.pose().eye, pose.eye);
+        assert!((bookmark.pose().fov.0 - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn accepts_a_valid_pose() {
+        assert!(Bookmarks::from_json(&file("[0, 0, 10]", "[0, 0, 0]", "[0, 1, 0]", "60")).is_ok());
+    }
+
+    #[test]
+    fn rejects_poses_the_camera_cannot_take() {
+        let invalid = [
+            file("[0, 0, 10]", "[0, 0, 10]", "[0, 1, 0]", "60"),
+            file("[0, 0, 10]", "[0, 0, 0]", "[0, 0, 1]", "60"),
+            file("[0, 0, 10]", "[0, 0, 0]", "[0, 0, 0]", "60"),
+            file("[0, 0, 1e39]", "[0, 0, 0]", "[0, 1, 0]", "60"),
+            file("[0, 0, 10]", "[0, 0, 0]", "[0, 1, 0]", "1"),
+            file("[0, 0, 10]", "[0, 0, 0]", "[0, 1, 0]", "170"),
+        ];
+        for json in &invalid {
+            assert!(Bookmarks::from_json(json).is_err(), "{}", json);
+        }
+        let mut bookmark = Bookmarks::from_json(&file("[0, 0, 10]", "[0, 0, 0]", "[0, 1, 0]", "60"))
+            .unwrap()
+            .get(1)
+            .unwrap()
+            .clone();
+        bookmark.focus[1] = f32::NAN;
+        assert!(bookmark.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        assert!(Bookmarks::from_json(r#"{"version": 2, "bookmarks": []}"#).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Transform};
+
+/// Axis aligned bounding box.
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl Aabb {
+    pub fn new(min: Point3<f32>, max: Point3<f32>) -> Self {
+        Self { min, max }
+    }
+
+    /// Box around flat `x, y, z` positions.
+    pub fn from_positions(positions: &[f32]) -> Self {
+        let mut min = Point3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Point3::new(f32::MIN, f32::MIN, f32::MIN);
+        for p in positions.chunks(3) {
+            min = Point3::new(min.x.min(p[0]), min.y.min(p[1]), min.z.min(p[2]));
+            max = Point3::new(max.x.max(p[0]), max.y.max(p[1]), max.z.max(p[2]));
+        }
+        Self { min, max }
+    }
+
+    pub fn center(&self) -> Point3<f32> {
+        self.min.midpoint(self.max)
+    }
+
+    /// Radius of the sphere around the box, half its diagonal.
+    pub fn bounding_radius(&self) -> f32 {
+        (self.max - self.min).magnitude() / 2.0
+    }
+
+    pub fn corners(&self) -> [Point3<f32>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Point3::new(min.x, min.y, min.z),
+            Point3::new(max.x, min.y, min.z),
+            Point3::new(min.x, max.y, min.z),
+            Point3::new(max.x, max.y, min.z),
+            Point3::new(min.x, min.y, max.z),
+            Point3::new(max.x, min.y, max.z),
+            Point3::new(min.x, max.y, max.z),
+            Point3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    /// Box around both boxes.
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: Point3::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            max: Point3::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+        }
+    }
+
+    /// Box around this one once transformed by `matrix`.
+    pub fn transformed(&self, matrix: &Matrix4<f32>) -> Self {
+        let positions: Vec<f32> = self
+            .corners()
+            .iter()
+            .flat_map(|&corner| {
+                let p = matrix.transform_point(corner);
+                vec![p.x, p.y, p.z]
+            })
+            .collect();
+        Self::from_positions(&positions)
+    }
+}
@@ -0,0 +1,290 @@
+use cgmath::{InnerSpace, One, Point3, Quaternion, Rad, Rotation, Rotation3, Vector1, Vector2, Vector3, Zero};
+
+use super::super::easing::Easing;
+use super::super::{look_rotation, orientation_lerp, Camera, TRANSITION_TIME};
+use super::inertia::Inertia;
+use super::{CameraController, ControllerSettings, Drag, MouseButton, ViewPreset, MOVE_PAN_SPEED, MOVE_ZOOM_SPEED};
+
+/// radius of the virtual sphere, in the units of `Drag::from` and `Drag::to`
+const SPHERE_RADIUS: f32 = 0.9;
+
+/// part of the distance to the focus panned per pixel, at speed 1.0
+const PAN_SCALE: f32 = 0.0015;
+
+/// Maps a pointer position on the virtual sphere centered on the viewport.
+/// Outside of the sphere the point slides on a hyperbolic sheet instead of
+/// the sphere edge, so the rotation stays smooth all over the viewport.
+pub fn project_to_sphere(pointer: Vector2<f32>, radius: f32) -> Vector3<f32> {
+    let d2 = pointer.magnitude2();
+    let r2 = radius * radius;
+    let z = if d2 <= r2 / 2.0 { (r2 - d2).sqrt() } else { r2 / 2.0 / d2.sqrt() };
+    Vector3::new(pointer.x, pointer.y, z).normalize()
+}
+
+/// Rotation turning the sphere under the pointer from `from` to `to`.
+pub fn arcball_rotation(from: Vector2<f32>, to: Vector2<f32>, radius: f32) -> Quaternion<f32> {
+    Quaternion::from_arc(project_to_sphere(from, radius), project_to_sphere(to, radius), None)
+}
+
+/// Turntable rotation: `yaw` around the world y axis and `pitch` around the
+/// camera's own x axis, without any clamping.
+pub fn turntable_rotation(orientation: Quaternion<f32>, yaw: Rad<f32>, pitch: Rad<f32>) -> Quaternion<f32> {
+    (Quaternion::from_angle_y(yaw) * orientation * Quaternion::from_angle_x(pitch)).normalize()
+}
+
+/// Rotation of `rotation.magnitude()` radians around `rotation`.
+fn from_rotation_vector(rotation: Vector3<f32>) -> Quaternion<f32> {
+    let angle = rotation.magnitude();
+    if angle == 0.0 {
+        return Quaternion::one();
+    }
+    Quaternion::from_axis_angle(rotation / angle, Rad(angle))
+}
+
+/// Axis scaled by the angle, the inverse of `from_rotation_vector`.
+fn to_rotation_vector(rotation: Quaternion<f32>) -> Vector3<f32> {
+    // keep the short way around
+    let rotation = if rotation.s < 0.0 { -rotation } else { rotation };
+    let sin = rotation.v.magnitude();
+    if sin == 0.0 {
+        return Vector3::new(0.0, 0.0, 0.0);
+    }
+    rotation.v * (2.0 * sin.atan2(rotation.s) / sin)
+}
+
+struct ArcballTransition {
+    from: Quaternion<f32>,
+    to: Quaternion<f32>,
+    elapsed: f32,
+}
+
+/// Keeps the camera orientation as a quaternion, so nothing stops it from
+/// looking straight down or rolling over. The left button rolls the
+/// scene like a trackball (or turns it like a turntable, when toggled),
+/// the right button rolls the view, the middle button pans. Every motion
+/// carries on a little after the input stops.
+pub struct ArcballController {
+    settings: ControllerSettings,
+    /// rotation from camera space to world space, the camera looks down its -z
+    orientation: Quaternion<f32>,
+    transition: Option<ArcballTransition>,
+    /// rotation in camera space, as a rotation vector
+    spin: Inertia<Vector3<f32>>,
+    /// turntable (yaw, pitch) in radians
+    turn: Inertia<Vector2<f32>>,
+    zoom: Inertia<Vector1<f32>>,
+    /// in pixels
+    panning: Inertia<Vector2<f32>>,
+    turntable: bool,
+    /// wanted movement as (right, up, forward), panning and dollying
+    movement: Vector3<f32>,
+    r: f32,
+    focus: Point3<f32>,
+}
+
+impl ArcballController {
+    pub fn new() -> Self {
+        Self {
+            settings: ControllerSettings {
+                speed: 1.0,
+                sensitivity: 0.01,
+                damping: 12.0,
+            },
+            orientation: Quaternion::one(),
+            transition: None,
+            spin: Inertia::new(),
+            turn: Inertia::new(),
+            zoom: Inertia::new(),
+            panning: Inertia::new(),
+            turntable: false,
+            movement: Vector3::new(0.0, 0.0, 0.0),
+            r: 15.0,
+            focus: Point3::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    fn stop(&mut self) {
+        self.spin.stop();
+        self.turn.stop();
+        self.zoom.stop();
+        self.panning.stop();
+    }
+
+    fn pan(&mut self, dx: f32, dy: f32) {
+        let right = self.orientation.rotate_vector(Vector3::unit_x());
+        let up = self.orientation.rotate_vector(Vector3::unit_y());
+        let scale = self.r * PAN_SCALE * self.settings.speed;
+        self.focus += (up * dy - right * dx) * scale;
+    }
+}
+
+impl CameraController for ArcballController {
+    fn name(&self) -> &'static str {
+        if self.turntable {
+            "turntable"
+        } else {
+            "arcball"
+        }
+    }
+
+    fn settings_mut(&mut self) -> &mut ControllerSettings {
+        &mut self.settings
+    }
+
+    fn sync(&mut self, camera: &Camera) {
+        let direction = camera.focus() - camera.get_eye();
+        self.transition = None;
+        self.stop();
+        self.focus = camera.focus();
+        self.r = direction.magnitude();
+        self.orientation = look_rotation(direction, camera.up());
+    }
+
+    fn apply(&self, camera: &mut Camera) {
+        let eye = self.focus + self.orientation.rotate_vector(Vector3::unit_z()) * self.r;
+        let up = self.orientation.rotate_vector(Vector3::unit_y());
+        camera.set_view_up(eye, self.focus, up);
+    }
+
+    fn drag(&mut self, drag: &Drag) {
+        let sensitivity = self.settings.sensitivity;
+        let damping = self.settings.damping;
+        if drag.button != MouseButton::Middle {
+            self.transition = None;
+        }
+        match drag.button {
+            MouseButton::Left if self.turntable => self.turn.push(Vector2::new(-drag.dx, -drag.dy) * sensitivity, damping),
+            MouseButton::Left => {
+                // the scene follows the pointer, so the camera turns the other way
+                let rotation = arcball_rotation(drag.to, drag.from, SPHERE_RADIUS);
+                self.spin.push(to_rotation_vector(rotation), damping);
+            }
+            MouseButton::Right => self.spin.push(Vector3::unit_z() * drag.dx * sensitivity, damping),
+            MouseButton::Middle => self.panning.push(Vector2::new(drag.dx, drag.dy), damping),
+        }
+    }
+
+    fn turn(&mut self, dx: f32, dy: f32) {
+        self.drag(&Drag::from_center(MouseButton::Left, dx, dy));
+    }
+
+    fn movement(&mut self, axes: Vector3<f32>) {
+        self.movement = axes;
+    }
+
+    fn wheel(&mut self, delta: f32) {
+        self.zoom.push(Vector1::new(delta * self.settings.speed), self.settings.damping);
+    }
+
+    fn pinch(&mut self, scale: f32) {
+        // the distance follows the fingers, so the scene keeps its size under them
+        self.zoom.push(Vector1::new(self.r / scale - self.r), self.settings.damping);
+    }
+
+    fn toggle_mode(&mut self) {
+        self.turntable = !self.turntable;
+        log::info!("camera controller: {}", self.name());
+    }
+
+    fn view_preset(&mut self, preset: ViewPreset) {
+        let (pitch, yaw) = preset.angles();
+        let to = Quaternion::from_angle_y(Rad(yaw)) * Quaternion::from_angle_x(Rad(-pitch));
+        self.spin.stop();
+        self.turn.stop();
+        self.transition = Some(ArcballTransition {
+            from: self.orientation,
+            to,
+            elapsed: 0.0,
+        });
+    }
+
+    fn update(&mut self, dt: f32) {
+        let damping = self.settings.damping;
+        if !self.movement.is_zero() {
+            let pan = Vector2::new(-self.movement.x, self.movement.y) * MOVE_PAN_SPEED * dt;
+            self.panning.push(pan, damping);
+            // forward closes in, by a part of the distance so it slows down near the focus
+            let zoom = -self.movement.z * self.r * MOVE_ZOOM_SPEED * self.settings.speed * dt;
+            self.zoom.push(Vector1::new(zoom), damping);
+        }
+        let spin = self.spin.step(dt, damping);
+        self.orientation = (self.orientation * from_rotation_vector(spin)).normalize();
+        let turn = self.turn.step(dt, damping);
+        self.orientation = turntable_rotation(self.orientation, Rad(turn.x), Rad(turn.y));
+        let zoom = self.zoom.step(dt, damping);
+        self.r = (self.r + zoom.x).clamp(0.1, 50.0);
+        let pan = self.panning.step(dt, damping);
+        self.pan(pan.x, pan.y);
+
+        if let Some(transition) = self.transition.as_mut() {
+            transition.elapsed += dt;
+            let t = Easing::Smoothstep.ease(transition.elapsed / TRANSITION_TIME);
+            self.orientation = orientation_lerp(transition.from, transition.to, t);
+            if transition.elapsed >= TRANSITION_TIME {
+                self.transition = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Vector3<f32>, b: Vector3<f32>, epsilon: f32) {
+        assert!((a - b).magnitude() < epsilon, "{:?} instead of {:?}", a, b);
+    }
+
+    /// the same rotation, whichever of `q` and `-q` it is written as
+    fn assert_identity(q: Quaternion<f32>) {
+        assert!((q.s.abs() - 1.0).abs() < 1e-5 && q.v.magnitude() < 1e-3, "{:?}", q);
+    }
+
+    #[test]
+    fn the_sphere_meets_the_hyperbolic_sheet_smoothly() {
+        assert_close(project_to_sphere(Vector2::new(0.0, 0.0), SPHERE_RADIUS), Vector3::unit_z(), 1e-6);
+        let seam = SPHERE_RADIUS / 2f32.sqrt();
+        let direction = Vector2::new(0.6, -0.8);
+        let inside = project_to_sphere(direction * seam * 0.9999, SPHERE_RADIUS);
+        let outside = project_to_sphere(direction * seam * 1.0001, SPHERE_RADIUS);
+        assert_close(inside, outside, 1e-3);
+        // far out the point lies down toward the edge of the sphere
+        let far = project_to_sphere(direction * 100.0, SPHERE_RADIUS);
+        assert!(far.z > 0.0 && far.z < 1e-3);
+        assert!((far.magnitude() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn dragging_back_undoes_the_rotation() {
+        let a = Vector2::new(-0.3, 0.2);
+        for &b in &[Vector2::new(0.4, -0.1), Vector2::new(1.5, 0.9), Vector2::new(-0.3, 0.2)] {
+            assert_identity(arcball_rotation(b, a, SPHERE_RADIUS) * arcball_rotation(a, b, SPHERE_RADIUS));
+        }
+    }
+
+    #[test]
+    fn the_turntable_never_rolls() {
+        let mut orientation = Quaternion::one();
+        for _ in 0..10 {
+            orientation = turntable_rotation(orientation, Rad(0.7), Rad(-0.3));
+            let right = orientation.rotate_vector(Vector3::unit_x());
+            assert!(right.y.abs() < 1e-5, "{:?}", right);
+        }
+    }
+
+    #[test]
+    fn rotation_vectors_round_trip() {
+        let vectors = [
+            Vector3::new(0.3, -1.2, 0.5),
+            Vector3::new(0.0, 0.0, 3.0),
+            Vector3::new(1e-6, 0.0, -2e-6),
+            Vector3::new(0.0, 0.0, 0.0),
+        ];
+        for &v in &vectors {
+            assert_close(to_rotation_vector(from_rotation_vector(v)), v, 1e-5);
+        }
+        // the long way around comes back as the short one
+        let long = from_rotation_vector(Vector3::unit_y() * 1.5 * std::f32::consts::PI);
+        assert_close(to_rotation_vector(long), -Vector3::unit_y() * 0.5 * std::f32::consts::PI, 1e-5);
+    }
+}
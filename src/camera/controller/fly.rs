@@ -0,0 +1,91 @@
+use cgmath::{InnerSpace, Point3, Vector3};
+
+use super::super::Camera;
+use super::{CameraController, ControllerSettings, Drag, Look, MouseButton};
+
+/// distance moved by one wheel step, relative to the speed
+const WHEEL_STEP: f32 = 0.2;
+
+/// Free flight: moving follows the looking direction, up and down stay vertical,
+/// the mouse looks around.
+pub struct FlyController {
+    settings: ControllerSettings,
+    position: Point3<f32>,
+    look: Look,
+    /// wanted movement as (right, up, forward)
+    movement: Vector3<f32>,
+    /// how far ahead the focus is kept, it sizes the orthographic view
+    focus_distance: f32,
+}
+
+impl FlyController {
+    pub fn new() -> Self {
+        Self {
+            settings: ControllerSettings {
+                speed: 5.0,
+                sensitivity: 0.003,
+                // looking around follows the mouse directly
+                damping: ControllerSettings::MAX_DAMPING,
+            },
+            position: Point3::new(0.0, 0.0, 15.0),
+            look: Look { yaw: 0.0, pitch: 0.0 },
+            movement: Vector3::new(0.0, 0.0, 0.0),
+            focus_distance: 15.0,
+        }
+    }
+}
+
+impl CameraController for FlyController {
+    fn name(&self) -> &'static str {
+        "fly"
+    }
+
+    fn settings_mut(&mut self) -> &mut ControllerSettings {
+        &mut self.settings
+    }
+
+    fn sync(&mut self, camera: &Camera) {
+        let direction = camera.focus() - camera.get_eye();
+        self.position = camera.get_eye();
+        self.look = Look::from_direction(direction);
+        self.focus_distance = direction.magnitude();
+    }
+
+    fn apply(&self, camera: &mut Camera) {
+        camera.set_view(self.position, self.position + self.look.forward() * self.focus_distance);
+    }
+
+    fn drag(&mut self, drag: &Drag) {
+        if drag.button == MouseButton::Left {
+            self.look(drag.dx, drag.dy);
+        }
+    }
+
+    fn look(&mut self, dx: f32, dy: f32) {
+        self.look.turn(dx, dy, self.settings.sensitivity);
+    }
+
+    fn wheel(&mut self, delta: f32) {
+        self.position -= self.look.forward() * delta * self.settings.speed * WHEEL_STEP;
+    }
+
+    fn movement(&mut self, axes: Vector3<f32>) {
+        self.movement = axes;
+    }
+
+    fn update(&mut self, dt: f32) {
+        let axes = self.movement;
+        let direction = self.look.right() * axes.x + Vector3::unit_y() * axes.y + self.look.forward() * axes.z;
+        // keys held together don't go faster, a half tilted stick goes slower
+        let direction = if direction.magnitude2() > 1.0 {
+            direction.normalize()
+        } else {
+            direction
+        };
+        self.position += direction * self.settings.speed * dt;
+    }
+
+    fn wants_pointer_lock(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,46 @@
+use cgmath::{InnerSpace, VectorSpace};
+
+/// below this speed, in units per second, the motion is considered over
+const REST_SPEED: f32 = 1e-4;
+
+/// Motion that keeps going after the input and slows down exponentially.
+///
+/// Input pushes displacements in. Each push is spread over the following
+/// frames so that the total motion equals the displacement pushed, whatever
+/// the frame rate: a velocity `v` decaying as `exp(-damping * t)` travels
+/// `v / damping` in total, so a push of `d` adds `d * damping` to the velocity.
+#[derive(Clone, Copy)]
+pub struct Inertia<V> {
+    velocity: V,
+}
+
+impl<V> Inertia<V>
+where
+    V: VectorSpace<Scalar = f32> + InnerSpace,
+{
+    pub fn new() -> Self {
+        Self { velocity: V::zero() }
+    }
+
+    pub fn push(&mut self, displacement: V, damping: f32) {
+        self.velocity = self.velocity + displacement * damping;
+    }
+
+    /// Motion over the next `dt` seconds.
+    pub fn step(&mut self, dt: f32, damping: f32) -> V {
+        if self.velocity.is_zero() {
+            return V::zero();
+        }
+        let decay = (-damping * dt).exp();
+        let moved = self.velocity * ((1.0 - decay) / damping);
+        self.velocity = self.velocity * decay;
+        if self.velocity.magnitude() < REST_SPEED {
+            self.stop();
+        }
+        moved
+    }
+
+    pub fn stop(&mut self) {
+        self.velocity = V::zero();
+    }
+}
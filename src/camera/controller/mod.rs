@@ -0,0 +1,160 @@
+use cgmath::{InnerSpace, Vector2, Vector3};
+use std::f32::consts::PI;
+
+use super::Camera;
+pub use crate::input::MouseButton;
+pub use arcball::ArcballController;
+pub use fly::FlyController;
+pub use orbit::{OrbitController, ViewPreset};
+pub use walk::WalkController;
+
+mod arcball;
+mod fly;
+mod inertia;
+mod orbit;
+mod walk;
+
+/// steepest a first person view may look up or down, straight up would
+/// make `look_at` degenerate with the y up vector
+const MAX_LOOK_PITCH: f32 = PI / 2.0 - 0.01;
+
+/// One mouse move with a button held.
+pub struct Drag {
+    pub button: MouseButton,
+    /// movement in pixels, y goes down
+    pub dx: f32,
+    pub dy: f32,
+    /// pointer before and after the move, relative to the viewport center,
+    /// with the shorter side of the viewport spanning -1..1 and y going up
+    pub from: Vector2<f32>,
+    pub to: Vector2<f32>,
+}
+
+/// pixels worth of panning per second, for orbiting controllers moving at full speed
+const MOVE_PAN_SPEED: f32 = 600.0;
+/// part of the distance to the focus dollied per second, moving at full speed
+const MOVE_ZOOM_SPEED: f32 = 1.0;
+
+/// viewport height a turn without a pointer is measured against, in pixels
+const NOMINAL_VIEWPORT_HEIGHT: f32 = 1000.0;
+
+impl Drag {
+    /// A drag of `dx`, `dy` pixels from the viewport center, for input without
+    /// a pointer like a gamepad stick.
+    pub fn from_center(button: MouseButton, dx: f32, dy: f32) -> Self {
+        let scale = 2.0 / NOMINAL_VIEWPORT_HEIGHT;
+        Self {
+            button,
+            dx,
+            dy,
+            from: Vector2::new(0.0, 0.0),
+            to: Vector2::new(dx, -dy) * scale,
+        }
+    }
+}
+
+/// Tuning of one controller, every controller keeps its own.
+#[derive(Clone, Copy, Debug)]
+pub struct ControllerSettings {
+    /// moving speed in world units per second, or a scale on panning and zooming
+    pub speed: f32,
+    /// radians turned per pixel of mouse movement
+    pub sensitivity: f32,
+    /// how fast motion stops once the input does, per second, higher is snappier
+    pub damping: f32,
+}
+
+impl ControllerSettings {
+    /// bounds of `damping`, the inertia spreads a move over about `1 / damping` seconds
+    pub const MIN_DAMPING: f32 = 1.0;
+    pub const MAX_DAMPING: f32 = 60.0;
+
+    pub fn scale_damping(&mut self, factor: f32) {
+        self.damping = (self.damping * factor).clamp(Self::MIN_DAMPING, Self::MAX_DAMPING);
+    }
+}
+
+/// Turns input into camera movements. The state keeps several of them and
+/// forwards the input to the active one, which then places the camera.
+pub trait CameraController {
+    fn name(&self) -> &'static str;
+
+    fn settings_mut(&mut self) -> &mut ControllerSettings;
+
+    /// Takes over the current view of `camera`, so switching controller doesn't jump.
+    fn sync(&mut self, camera: &Camera);
+
+    /// Moves `camera` to the view of the controller.
+    fn apply(&self, camera: &mut Camera);
+
+    fn drag(&mut self, _drag: &Drag) {}
+
+    /// Mouse moved by `dx`, `dy` pixels while the pointer is locked.
+    fn look(&mut self, _dx: f32, _dy: f32) {}
+
+    /// Turns the view by `dx`, `dy` pixels worth of mouse movement, from
+    /// input that has no pointer, like a gamepad stick.
+    fn turn(&mut self, dx: f32, dy: f32) {
+        self.look(dx, dy);
+    }
+
+    fn wheel(&mut self, _delta: f32) {}
+
+    /// Two fingers moved apart by `scale` times their former distance.
+    fn pinch(&mut self, scale: f32) {
+        // a wheel step for every tenth of the distance
+        self.wheel((1.0 - scale) * 10.0);
+    }
+
+    /// Wanted movement as (right, up, forward), each from -1 to 1, given every
+    /// frame. First person controllers move, orbiting ones pan and dolly.
+    fn movement(&mut self, _axes: Vector3<f32>) {}
+
+    /// Switches between the variants of the controller, if it has some.
+    fn toggle_mode(&mut self) {}
+
+    fn view_preset(&mut self, _preset: ViewPreset) {}
+
+    /// Advances the controller by `dt` seconds.
+    fn update(&mut self, _dt: f32) {}
+
+    /// Whether the mouse should be captured for mouse-look.
+    fn wants_pointer_lock(&self) -> bool {
+        false
+    }
+}
+
+/// Looking direction of a first person view, yaw 0 looks down -z.
+#[derive(Clone, Copy)]
+struct Look {
+    yaw: f32,
+    pitch: f32,
+}
+
+impl Look {
+    fn from_direction(direction: Vector3<f32>) -> Self {
+        let direction = direction.normalize();
+        Self {
+            yaw: (-direction.x).atan2(-direction.z),
+            pitch: direction.y.asin().clamp(-MAX_LOOK_PITCH, MAX_LOOK_PITCH),
+        }
+    }
+
+    fn turn(&mut self, dx: f32, dy: f32, sensitivity: f32) {
+        self.yaw -= dx * sensitivity;
+        self.pitch = (self.pitch - dy * sensitivity).clamp(-MAX_LOOK_PITCH, MAX_LOOK_PITCH);
+    }
+
+    fn forward(&self) -> Vector3<f32> {
+        Vector3::new(-self.yaw.sin() * self.pitch.cos(), self.pitch.sin(), -self.yaw.cos() * self.pitch.cos())
+    }
+
+    /// Forward on the ground plane, ignoring the pitch.
+    fn heading(&self) -> Vector3<f32> {
+        Vector3::new(-self.yaw.sin(), 0.0, -self.yaw.cos())
+    }
+
+    fn right(&self) -> Vector3<f32> {
+        Vector3::new(self.yaw.cos(), 0.0, -self.yaw.sin())
+    }
+}
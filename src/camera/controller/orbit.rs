@@ -0,0 +1,225 @@
+use cgmath::{InnerSpace, Point3, Vector1, Vector2, Vector3, Zero};
+use std::f32::consts::PI;
+
+use super::super::easing::Easing;
+use super::super::{Camera, TRANSITION_TIME};
+use super::inertia::Inertia;
+use super::{CameraController, ControllerSettings, Drag, MouseButton, MOVE_PAN_SPEED, MOVE_ZOOM_SPEED};
+
+/// part of the orbit radius panned per pixel, at speed 1.0
+const PAN_SCALE: f32 = 0.0015;
+/// looking straight down would make `look_at` degenerate with the y up vector
+const MAX_PITCH: f32 = PI / 2.0 - 0.001;
+const MIN_PITCH: f32 = -(PI / 1.9);
+
+/// Axis aligned views, like the numpad views of a CAD tool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewPreset {
+    Top,
+    Front,
+    Side,
+    Isometric,
+}
+
+impl ViewPreset {
+    /// (pitch, yaw) of the preset
+    pub(super) fn angles(self) -> (f32, f32) {
+        match self {
+            ViewPreset::Top => (MAX_PITCH, 0.0),
+            ViewPreset::Front => (0.0, 0.0),
+            ViewPreset::Side => (0.0, PI / 2.0),
+            ViewPreset::Isometric => ((1.0 / 2.0f32.sqrt()).atan(), PI / 4.0),
+        }
+    }
+}
+
+struct OrbitTransition {
+    from: (f32, f32),
+    to: (f32, f32),
+    elapsed: f32,
+}
+
+/// Turns around `focus` with the left button, pans `focus` with the right
+/// or middle button and zooms with the wheel. Every motion carries on a
+/// little after the input stops.
+pub struct OrbitController {
+    settings: ControllerSettings,
+    transition: Option<OrbitTransition>,
+    /// (yaw, pitch) in radians
+    spin: Inertia<Vector2<f32>>,
+    zoom: Inertia<Vector1<f32>>,
+    /// in pixels
+    panning: Inertia<Vector2<f32>>,
+    up_down_angle: f32,    // pitch
+    left_right_angle: f32, // yaw
+    /// wanted movement as (right, up, forward), panning and dollying
+    movement: Vector3<f32>,
+    r: f32,
+    focus: Point3<f32>,
+}
+
+impl OrbitController {
+    pub fn new() -> Self {
+        Self {
+            settings: ControllerSettings {
+                speed: 1.0,
+                sensitivity: 0.02,
+                damping: 12.0,
+            },
+            transition: None,
+            spin: Inertia::new(),
+            zoom: Inertia::new(),
+            panning: Inertia::new(),
+            left_right_angle: 45.0f32.to_radians(),
+            up_down_angle: 80.0f32.to_radians(),
+            movement: Vector3::new(0.0, 0.0, 0.0),
+            r: 15.0,
+            focus: Point3::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Unit vector from the focus to the eye.
+    fn direction(&self) -> Vector3<f32> {
+        let pitch = self.up_down_angle;
+        let yaw = self.left_right_angle;
+        Vector3::new(pitch.cos() * yaw.sin(), pitch.sin(), pitch.cos() * yaw.cos())
+    }
+
+    fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.left_right_angle += yaw;
+        // the top view sits on the limit, so an orbit never pulls the camera off it
+        self.up_down_angle = (self.up_down_angle + pitch).clamp(MIN_PITCH, MAX_PITCH);
+    }
+
+    fn stop(&mut self) {
+        self.spin.stop();
+        self.zoom.stop();
+        self.panning.stop();
+    }
+
+    /// Slides the focus in the view plane, by a distance proportional to the
+    /// orbit radius so the scene follows the mouse at any zoom.
+    fn pan(&mut self, dx: f32, dy: f32) {
+        let forward = -self.direction();
+        let right = forward.cross(Vector3::unit_y()).normalize();
+        let up = right.cross(forward);
+        let scale = self.r * PAN_SCALE * self.settings.speed;
+        self.focus += (up * dy - right * dx) * scale;
+    }
+}
+
+impl CameraController for OrbitController {
+    fn name(&self) -> &'static str {
+        "orbit"
+    }
+
+    fn settings_mut(&mut self) -> &mut ControllerSettings {
+        &mut self.settings
+    }
+
+    fn sync(&mut self, camera: &Camera) {
+        let offset = camera.get_eye() - camera.focus();
+        self.transition = None;
+        self.stop();
+        self.focus = camera.focus();
+        self.r = offset.magnitude();
+        self.up_down_angle = (offset.y / self.r).asin().clamp(MIN_PITCH, MAX_PITCH);
+        self.left_right_angle = offset.x.atan2(offset.z);
+    }
+
+    fn apply(&self, camera: &mut Camera) {
+        camera.set_view(self.focus + self.direction() * self.r, self.focus);
+    }
+
+    fn drag(&mut self, drag: &Drag) {
+        let damping = self.settings.damping;
+        match drag.button {
+            MouseButton::Left => {
+                self.transition = None;
+                let sensitivity = self.settings.sensitivity;
+                self.spin.push(Vector2::new(-drag.dx, drag.dy) * sensitivity, damping);
+            }
+            MouseButton::Middle | MouseButton::Right => self.panning.push(Vector2::new(drag.dx, drag.dy), damping),
+        }
+    }
+
+    fn turn(&mut self, dx: f32, dy: f32) {
+        self.drag(&Drag::from_center(MouseButton::Left, dx, dy));
+    }
+
+    fn movement(&mut self, axes: Vector3<f32>) {
+        self.movement = axes;
+    }
+
+    fn wheel(&mut self, delta: f32) {
+        self.zoom.push(Vector1::new(delta * self.settings.speed), self.settings.damping);
+    }
+
+    fn pinch(&mut self, scale: f32) {
+        // the distance follows the fingers, so the scene keeps its size under them
+        self.zoom.push(Vector1::new(self.r / scale - self.r), self.settings.damping);
+    }
+
+    /// Animates the orbit to one of the preset views.
+    fn view_preset(&mut self, preset: ViewPreset) {
+        let (pitch, yaw) = preset.angles();
+        // turn the short way around
+        let mut from_yaw = self.left_right_angle % (2.0 * PI);
+        if from_yaw - yaw > PI {
+            from_yaw -= 2.0 * PI;
+        } else if yaw - from_yaw > PI {
+            from_yaw += 2.0 * PI;
+        }
+        self.spin.stop();
+        self.transition = Some(OrbitTransition {
+            from: (self.up_down_angle, from_yaw),
+            to: (pitch, yaw),
+            elapsed: 0.0,
+        });
+    }
+
+    fn update(&mut self, dt: f32) {
+        let damping = self.settings.damping;
+        if !self.movement.is_zero() {
+            let pan = Vector2::new(-self.movement.x, self.movement.y) * MOVE_PAN_SPEED * dt;
+            self.panning.push(pan, damping);
+            // forward closes in, by a part of the distance so it slows down near the focus
+            let zoom = -self.movement.z * self.r * MOVE_ZOOM_SPEED * self.settings.speed * dt;
+            self.zoom.push(Vector1::new(zoom), damping);
+        }
+        let spin = self.spin.step(dt, damping);
+        self.orbit(spin.x, spin.y);
+        let zoom = self.zoom.step(dt, damping);
+        self.r = (self.r + zoom.x).clamp(0.1, 50.0);
+        let pan = self.panning.step(dt, damping);
+        self.pan(pan.x, pan.y);
+
+        if let Some(transition) = self.transition.as_mut() {
+            transition.elapsed += dt;
+            let t = Easing::Smoothstep.ease(transition.elapsed / TRANSITION_TIME);
+            self.up_down_angle = transition.from.0 + (transition.to.0 - transition.from.0) * t;
+            self.left_right_angle = transition.from.1 + (transition.to.1 - transition.from.1) * t;
+            if transition.elapsed >= TRANSITION_TIME {
+                self.transition = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_top_view_stays_on_top_after_its_transition() {
+        let mut orbit = OrbitController::new();
+        orbit.view_preset(ViewPreset::Top);
+        let dt = 1.0 / 60.0;
+        for _ in 0..((TRANSITION_TIME / dt) as usize + 10) {
+            orbit.update(dt);
+        }
+        assert!(orbit.transition.is_none());
+        assert!((orbit.up_down_angle - ViewPreset::Top.angles().0).abs() < 1e-6, "{}", orbit.up_down_angle);
+        assert!(orbit.direction().y > 0.999);
+    }
+}
@@ -0,0 +1,87 @@
+use cgmath::{InnerSpace, Point3, Vector3};
+
+use super::super::Camera;
+use super::{CameraController, ControllerSettings, Drag, Look, MouseButton};
+
+/// Walking on the ground: like flying, but moving stays on the horizontal plane
+/// and the eye keeps a fixed height.
+pub struct WalkController {
+    settings: ControllerSettings,
+    position: Point3<f32>,
+    eye_height: f32,
+    look: Look,
+    /// wanted movement as (right, up, forward)
+    movement: Vector3<f32>,
+    focus_distance: f32,
+}
+
+impl WalkController {
+    pub fn new(eye_height: f32) -> Self {
+        Self {
+            settings: ControllerSettings {
+                speed: 3.0,
+                sensitivity: 0.003,
+                // looking around follows the mouse directly
+                damping: ControllerSettings::MAX_DAMPING,
+            },
+            position: Point3::new(0.0, eye_height, 15.0),
+            eye_height,
+            look: Look { yaw: 0.0, pitch: 0.0 },
+            movement: Vector3::new(0.0, 0.0, 0.0),
+            focus_distance: 15.0,
+        }
+    }
+}
+
+impl CameraController for WalkController {
+    fn name(&self) -> &'static str {
+        "walk"
+    }
+
+    fn settings_mut(&mut self) -> &mut ControllerSettings {
+        &mut self.settings
+    }
+
+    fn sync(&mut self, camera: &Camera) {
+        let eye = camera.get_eye();
+        let direction = camera.focus() - eye;
+        self.position = Point3::new(eye.x, self.eye_height, eye.z);
+        self.look = Look::from_direction(direction);
+        self.focus_distance = direction.magnitude();
+    }
+
+    fn apply(&self, camera: &mut Camera) {
+        camera.set_view(self.position, self.position + self.look.forward() * self.focus_distance);
+    }
+
+    fn drag(&mut self, drag: &Drag) {
+        if drag.button == MouseButton::Left {
+            self.look(drag.dx, drag.dy);
+        }
+    }
+
+    fn look(&mut self, dx: f32, dy: f32) {
+        self.look.turn(dx, dy, self.settings.sensitivity);
+    }
+
+    fn movement(&mut self, axes: Vector3<f32>) {
+        self.movement = axes;
+    }
+
+    fn update(&mut self, dt: f32) {
+        let axes = self.movement;
+        // the up and down keys have no say on the ground
+        let direction = self.look.right() * axes.x + self.look.heading() * axes.z;
+        // keys held together don't go faster, a half tilted stick goes slower
+        let direction = if direction.magnitude2() > 1.0 {
+            direction.normalize()
+        } else {
+            direction
+        };
+        self.position += direction * self.settings.speed * dt;
+    }
+
+    fn wants_pointer_lock(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,22 @@
+use super::smoothstep;
+
+/// Curves shaping the progress of an animation, `t` going from 0.0 to 1.0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Easing {
+    Smoothstep,
+    EaseInOutCubic,
+    /// fast start and long settle, for moves that should react at once
+    EaseOutQuint,
+}
+
+impl Easing {
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Smoothstep => smoothstep(t),
+            Easing::EaseInOutCubic if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOutCubic => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Easing::EaseOutQuint => 1.0 - (1.0 - t).powi(5),
+        }
+    }
+}
@@ -0,0 +1,515 @@
+use cgmath::{Deg, InnerSpace, Point3, Quaternion, Rad, Rotation3, Vector3};
+
+use super::bounds::Aabb;
+use super::ray::Ray;
+use super::transform::ObjectTransform;
+
+/// length of the gizmo arms on screen, in pixels, whatever the distance
+pub const GIZMO_PIXELS: f32 = 110.0;
+
+// The gizmo is modelled with arms 1 long, then scaled to `GIZMO_PIXELS`.
+// Drawing and picking both follow these.
+
+/// translate arrows are a line up to there, then a cone up to 1
+pub const ARROW_SHAFT: f32 = 0.8;
+pub const ARROW_HEAD_RADIUS: f32 = 0.06;
+/// translate plane squares span from `PLANE_MIN` to `PLANE_MAX` on both of their axes
+pub const PLANE_MIN: f32 = 0.25;
+pub const PLANE_MAX: f32 = 0.45;
+/// half size of the boxes ending the scale arms
+pub const SCALE_BOX: f32 = 0.07;
+/// half size of the uniform scale box at the center
+pub const CENTER_BOX: f32 = 0.1;
+
+/// how close the ray must pass to an arm or a ring to grab it
+const GRAB_DISTANCE: f32 = 0.08;
+/// below this a ray is taken as parallel to an axis or a plane
+const PARALLEL_EPSILON: f32 = 1e-4;
+/// smallest scale a drag leaves on an axis, a zero scale could not grow back
+const MIN_SCALE: f32 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl GizmoMode {
+    pub fn next(self) -> Self {
+        match self {
+            GizmoMode::Translate => GizmoMode::Rotate,
+            GizmoMode::Rotate => GizmoMode::Scale,
+            GizmoMode::Scale => GizmoMode::Translate,
+        }
+    }
+}
+
+/// A part of the gizmo that can be dragged, the axes numbered x 0, y 1, z 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoHandle {
+    /// a translate arrow or a scale arm
+    Axis(usize),
+    /// a translate square, moving in the plane across the axis
+    Plane(usize),
+    /// a rotate ring, turning about the axis
+    Ring(usize),
+    /// the center box, scaling all the axes together
+    Uniform,
+}
+
+/// Increments drags snap to, when enabled.
+#[derive(Clone, Copy, Debug)]
+pub struct Snapping {
+    pub enabled: bool,
+    /// world units
+    pub translate: f32,
+    pub rotate: Rad<f32>,
+    /// a step of the scale factor
+    pub scale: f32,
+}
+
+impl Default for Snapping {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            translate: 0.25,
+            rotate: Deg(15.0).into(),
+            scale: 0.1,
+        }
+    }
+}
+
+impl Snapping {
+    fn snap(&self, value: f32, increment: f32) -> f32 {
+        if self.enabled {
+            (value / increment).round() * increment
+        } else {
+            value
+        }
+    }
+}
+
+/// What the renderer needs to draw the gizmo.
+#[derive(Clone, Copy, Debug)]
+pub struct GizmoView {
+    pub mode: GizmoMode,
+    pub origin: Point3<f32>,
+    /// world length of the arms
+    pub size: f32,
+    /// the handle under the pointer or being dragged
+    pub highlighted: Option<GizmoHandle>,
+}
+
+/// A handle being dragged, everything is measured from where it started.
+#[derive(Clone, Copy, Debug)]
+struct GizmoDrag {
+    pointer: i32,
+    handle: GizmoHandle,
+    origin: Point3<f32>,
+    size: f32,
+    start: ObjectTransform,
+    /// the plane uniform scaling is measured in, facing the view
+    view_normal: Vector3<f32>,
+    /// where the drag grabbed the handle, as a position along the axis or
+    /// an offset from the origin in the handle plane
+    anchor: Vector3<f32>,
+}
+
+/// Translate, rotate and scale handles around the selected object. Picks its
+/// handles with rays and turns drags into new transforms, constrained to an
+/// axis or a plane.
+#[derive(Default)]
+pub struct Gizmo {
+    mode: Option<GizmoMode>,
+    snapping: Snapping,
+    hovered: Option<GizmoHandle>,
+    drag: Option<GizmoDrag>,
+}
+
+fn axis(index: usize) -> Vector3<f32> {
+    let mut axis = Vector3::new(0.0, 0.0, 0.0);
+    axis[index] = 1.0;
+    axis
+}
+
+/// Distance along the ray and along the line through `origin` in the unit
+/// direction `axis`, where the two pass closest. `None` when parallel.
+fn closest_on_axis(ray: &Ray, origin: Point3<f32>, axis: Vector3<f32>) -> Option<(f32, f32)> {
+    let w = ray.origin - origin;
+    let b = ray.direction.dot(axis);
+    let denominator = 1.0 - b * b;
+    if denominator < PARALLEL_EPSILON {
+        return None;
+    }
+    let d = ray.direction.dot(w);
+    let e = axis.dot(w);
+    let t = (b * e - d) / denominator;
+    let s = (e - b * d) / denominator;
+    Some((t, s))
+}
+
+/// Distance along the ray to the plane through `origin` across `normal`.
+fn plane_hit(ray: &Ray, origin: Point3<f32>, normal: Vector3<f32>) -> Option<f32> {
+    let facing = ray.direction.dot(normal);
+    if facing.abs() < PARALLEL_EPSILON {
+        return None;
+    }
+    Some((origin - ray.origin).dot(normal) / facing)
+}
+
+impl Gizmo {
+    pub fn new() -> Self {
+        Self {
+            mode: Some(GizmoMode::Translate),
+            ..Self::default()
+        }
+    }
+
+    /// `None` when the gizmo is hidden.
+    pub fn mode(&self) -> Option<GizmoMode> {
+        self.mode
+    }
+
+    /// Translate, rotate, scale, then hidden.
+    pub fn next_mode(&mut self) {
+        self.cancel_drag();
+        self.mode = match self.mode {
+            Some(GizmoMode::Scale) => None,
+            Some(mode) => Some(mode.next()),
+            None => Some(GizmoMode::Translate),
+        };
+        self.hovered = None;
+    }
+
+    pub fn snapping(&self) -> &Snapping {
+        &self.snapping
+    }
+
+    pub fn toggle_snapping(&mut self) {
+        self.snapping.enabled = !self.snapping.enabled;
+    }
+
+    pub fn view(&self, origin: Point3<f32>, size: f32) -> Option<GizmoView> {
+        Some(GizmoView {
+            mode: self.mode?,
+            origin,
+            size,
+            highlighted: self.drag.map(|drag| drag.handle).or(self.hovered),
+        })
+    }
+
+    pub fn set_hovered(&mut self, handle: Option<GizmoHandle>) {
+        self.hovered = handle;
+    }
+
+    pub fn dragging(&self, pointer: i32) -> bool {
+        self.drag.is_some_and(|drag| drag.pointer == pointer)
+    }
+
+    /// Nearest handle under `ray`, for a gizmo at `origin` with arms `size` long.
+    pub fn pick_handle(&self, ray: &Ray, origin: Point3<f32>, size: f32) -> Option<GizmoHandle> {
+        let mode = self.mode?;
+        let mut nearest: Option<(f32, GizmoHandle)> = None;
+        let mut consider = |t: f32, handle: GizmoHandle| {
+            if t >= 0.0 && nearest.is_none_or(|(nearest, _)| t < nearest) {
+                nearest = Some((t, handle));
+            }
+        };
+        for index in 0..3 {
+            let normal = axis(index);
+            match mode {
+                GizmoMode::Translate | GizmoMode::Scale => {
+                    if let Some((t, s)) = closest_on_axis(ray, origin, normal) {
+                        let distance = (ray.at(t) - (origin + normal * s)).magnitude();
+                        if (0.0..=size).contains(&s) && distance < GRAB_DISTANCE * size {
+                            consider(t, GizmoHandle::Axis(index));
+                        }
+                    }
+                    if mode == GizmoMode::Scale {
+                        continue;
+                    }
+                    if let Some(t) = plane_hit(ray, origin, normal) {
+                        let offset = ray.at(t) - origin;
+                        let inside = |k: usize| (PLANE_MIN * size..=PLANE_MAX * size).contains(&offset[(index + k) % 3]);
+                        if inside(1) && inside(2) {
+                            consider(t, GizmoHandle::Plane(index));
+                        }
+                    }
+                }
+                GizmoMode::Rotate => {
+                    if let Some(t) = plane_hit(ray, origin, normal) {
+                        let radius = (ray.at(t) - origin).magnitude();
+                        if (radius - size).abs() < GRAB_DISTANCE * size {
+                            consider(t, GizmoHandle::Ring(index));
+                        }
+                    }
+                }
+            }
+        }
+        if mode == GizmoMode::Scale {
+            let half = Vector3::new(1.0, 1.0, 1.0) * CENTER_BOX * size;
+            if let Some(t) = ray.intersect_aabb(&Aabb::new(origin - half, origin + half)) {
+                consider(t, GizmoHandle::Uniform);
+            }
+        }
+        nearest.map(|(_, handle)| handle)
+    }
+
+    /// Grabs the handle under `ray`, if any, for the pointer `pointer`.
+    /// Returns whether the gizmo took the pointer.
+    pub fn begin_drag(&mut self, pointer: i32, ray: &Ray, transform: ObjectTransform, size: f32) -> bool {
+        let origin = Point3::new(transform.translation.x, transform.translation.y, transform.translation.z);
+        let handle = match self.pick_handle(ray, origin, size) {
+            Some(handle) => handle,
+            None => return false,
+        };
+        let mut drag = GizmoDrag {
+            pointer,
+            handle,
+            origin,
+            size,
+            start: transform,
+            view_normal: ray.direction,
+            anchor: Vector3::new(0.0, 0.0, 0.0),
+        };
+        drag.anchor = match Self::constrained(&drag, ray) {
+            Some(anchor) => anchor,
+            None => return false,
+        };
+        self.drag = Some(drag);
+        true
+    }
+
+    /// Where `ray` meets the constraint of the dragged handle: the position
+    /// along the axis in `x`, or the offset from the origin in the plane.
+    fn constrained(drag: &GizmoDrag, ray: &Ray) -> Option<Vector3<f32>> {
+        match drag.handle {
+            GizmoHandle::Axis(index) => {
+                let (_, s) = closest_on_axis(ray, drag.origin, axis(index))?;
+                Some(Vector3::new(s, 0.0, 0.0))
+            }
+            GizmoHandle::Plane(index) | GizmoHandle::Ring(index) => {
+                let t = plane_hit(ray, drag.origin, axis(index))?;
+                Some(ray.at(t) - drag.origin)
+            }
+            GizmoHandle::Uniform => {
+                let t = plane_hit(ray, drag.origin, drag.view_normal)?;
+                Some(ray.at(t) - drag.origin)
+            }
+        }
+    }
+
+    /// The dragged object's transform with the pointer along `ray`, `None`
+    /// when the ray misses the constraint, like a plane seen edge on.
+    pub fn drag_to(&self, ray: &Ray) -> Option<ObjectTransform> {
+        let drag = self.drag?;
+        let mode = self.mode?;
+        let current = Self::constrained(&drag, ray)?;
+        let snapping = &self.snapping;
+        let mut transform = drag.start;
+        match (mode, drag.handle) {
+            (GizmoMode::Translate, GizmoHandle::Axis(index)) => {
+                let delta = snapping.snap(current.x - drag.anchor.x, snapping.translate);
+                transform.translation += axis(index) * delta;
+            }
+            (GizmoMode::Translate, GizmoHandle::Plane(_)) => {
+                let delta = current - drag.anchor;
+                let snapped = Vector3::new(
+                    snapping.snap(delta.x, snapping.translate),
+                    snapping.snap(delta.y, snapping.translate),
+                    snapping.snap(delta.z, snapping.translate),
+                );
+                transform.translation += snapped;
+            }
+            (GizmoMode::Rotate, GizmoHandle::Ring(index)) => {
+                let normal = axis(index);
+                let angle = normal.dot(drag.anchor.cross(current)).atan2(drag.anchor.dot(current));
+                let angle = snapping.snap(angle, snapping.rotate.0);
+                transform.rotation = Quaternion::from_axis_angle(normal, Rad(angle)) * drag.start.rotation;
+            }
+            (GizmoMode::Scale, GizmoHandle::Axis(index)) => {
+                if drag.anchor.x.abs() < PARALLEL_EPSILON {
+                    return None;
+                }
+                let factor = 1.0 + snapping.snap(current.x / drag.anchor.x - 1.0, snapping.scale);
+                transform.scale[index] = (drag.start.scale[index] * factor).max(MIN_SCALE);
+            }
+            (GizmoMode::Scale, GizmoHandle::Uniform) => {
+                // up and right on screen grow, down and left shrink
+                let (right, up) = screen_axes(drag.view_normal);
+                let along = (current - drag.anchor).dot((right + up).normalize()) / drag.size;
+                let factor = 1.0 + snapping.snap(along, snapping.scale);
+                transform.scale = (drag.start.scale * factor).map(|s| s.max(MIN_SCALE));
+            }
+            _ => return None,
+        }
+        Some(transform)
+    }
+
+    /// Lets go of the handle, returns whether `pointer` was dragging it.
+    pub fn end_drag(&mut self, pointer: i32) -> bool {
+        if self.dragging(pointer) {
+            self.drag = None;
+            return true;
+        }
+        false
+    }
+
+    /// Drops the drag, returning the transform the object had before it.
+    pub fn cancel_drag(&mut self) -> Option<ObjectTransform> {
+        self.drag.take().map(|drag| drag.start)
+    }
+}
+
+/// Right and up of a view looking along `forward`, with the world y up.
+fn screen_axes(forward: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let right = forward.cross(Vector3::unit_y());
+    // looking straight down or up, any right will do
+    let right = if right.magnitude2() < PARALLEL_EPSILON {
+        Vector3::unit_x()
+    } else {
+        right.normalize()
+    };
+    (right, right.cross(forward).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Rotation;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    /// a ray from above the gizmo plane, straight down onto `x`, `z`
+    fn down_at(x: f32, z: f32) -> Ray {
+        Ray::new(Point3::new(x, 10.0, z), -Vector3::unit_y())
+    }
+
+    /// a ray from the front, straight at `x`, `y`
+    fn front_at(x: f32, y: f32) -> Ray {
+        Ray::new(Point3::new(x, y, 10.0), -Vector3::unit_z())
+    }
+
+    fn origin() -> Point3<f32> {
+        Point3::new(0.0, 0.0, 0.0)
+    }
+
+    fn gizmo(mode: GizmoMode) -> Gizmo {
+        let mut gizmo = Gizmo::new();
+        while gizmo.mode() != Some(mode) {
+            gizmo.next_mode();
+        }
+        gizmo
+    }
+
+    #[test]
+    fn picks_arrows_planes_and_nothing() {
+        let gizmo = gizmo(GizmoMode::Translate);
+        assert_eq!(gizmo.pick_handle(&front_at(0.5, 0.02), origin(), 1.0), Some(GizmoHandle::Axis(0)));
+        assert_eq!(gizmo.pick_handle(&front_at(0.01, 0.7), origin(), 1.0), Some(GizmoHandle::Axis(1)));
+        assert_eq!(gizmo.pick_handle(&front_at(0.35, 0.35), origin(), 1.0), Some(GizmoHandle::Plane(2)));
+        assert_eq!(gizmo.pick_handle(&front_at(0.7, 0.7), origin(), 1.0), None);
+        // past the arrow tip
+        assert_eq!(gizmo.pick_handle(&front_at(1.2, 0.0), origin(), 1.0), None);
+    }
+
+    #[test]
+    fn handles_scale_with_the_gizmo() {
+        let gizmo = gizmo(GizmoMode::Translate);
+        assert_eq!(gizmo.pick_handle(&front_at(3.5, 3.5), origin(), 10.0), Some(GizmoHandle::Plane(2)));
+        assert_eq!(gizmo.pick_handle(&front_at(3.5, 3.5), origin(), 1.0), None);
+    }
+
+    #[test]
+    fn picks_rings_and_the_center_box() {
+        let rotate = gizmo(GizmoMode::Rotate);
+        assert_eq!(rotate.pick_handle(&down_at(0.0, 1.0), origin(), 1.0), Some(GizmoHandle::Ring(1)));
+        assert_eq!(rotate.pick_handle(&down_at(0.0, 0.5), origin(), 1.0), None);
+        let scale = gizmo(GizmoMode::Scale);
+        assert_eq!(scale.pick_handle(&front_at(0.05, 0.05), origin(), 1.0), Some(GizmoHandle::Uniform));
+        assert_eq!(scale.pick_handle(&front_at(0.35, 0.35), origin(), 1.0), None);
+    }
+
+    #[test]
+    fn hidden_gizmo_picks_nothing() {
+        let mut gizmo = gizmo(GizmoMode::Scale);
+        gizmo.next_mode();
+        assert_eq!(gizmo.mode(), None);
+        assert_eq!(gizmo.pick_handle(&front_at(0.5, 0.0), origin(), 1.0), None);
+    }
+
+    #[test]
+    fn axis_drag_moves_along_the_axis_only() {
+        let mut gizmo = gizmo(GizmoMode::Translate);
+        assert!(gizmo.begin_drag(1, &front_at(0.5, 0.02), ObjectTransform::default(), 1.0));
+        assert!(gizmo.dragging(1));
+        let moved = gizmo.drag_to(&front_at(1.8, 0.6)).unwrap();
+        assert_close(moved.translation.x, 1.3);
+        assert_close(moved.translation.y, 0.0);
+        assert_close(moved.translation.z, 0.0);
+    }
+
+    #[test]
+    fn plane_drag_snaps_every_component() {
+        let mut gizmo = gizmo(GizmoMode::Translate);
+        gizmo.toggle_snapping();
+        assert!(gizmo.begin_drag(1, &front_at(0.3, 0.3), ObjectTransform::default(), 1.0));
+        let moved = gizmo.drag_to(&front_at(0.9, 0.1)).unwrap();
+        // 0.6 and -0.2 to increments of 0.25
+        assert_close(moved.translation.x, 0.5);
+        assert_close(moved.translation.y, -0.25);
+        assert_close(moved.translation.z, 0.0);
+    }
+
+    #[test]
+    fn ring_drag_rotates_about_its_axis() {
+        let mut gizmo = gizmo(GizmoMode::Rotate);
+        assert!(gizmo.begin_drag(1, &down_at(1.0, 0.0), ObjectTransform::default(), 1.0));
+        // a quarter turn from x to -z is a positive turn about y
+        let turned = gizmo.drag_to(&down_at(0.0, -1.0)).unwrap();
+        let x = turned.rotation.rotate_vector(Vector3::unit_x());
+        assert_close(x.x, 0.0);
+        assert_close(x.z, -1.0);
+    }
+
+    #[test]
+    fn ring_drag_snaps_the_angle() {
+        let mut gizmo = gizmo(GizmoMode::Rotate);
+        gizmo.toggle_snapping();
+        assert!(gizmo.begin_drag(1, &down_at(1.0, 0.0), ObjectTransform::default(), 1.0));
+        // 20 degrees snaps to 15
+        let angle = 20.0f32.to_radians();
+        let turned = gizmo.drag_to(&down_at(angle.cos(), -angle.sin())).unwrap();
+        let expected = Quaternion::from_axis_angle(Vector3::unit_y(), Deg(15.0));
+        assert_close(turned.rotation.dot(expected).abs(), 1.0);
+    }
+
+    #[test]
+    fn axis_scale_is_the_ratio_along_the_arm() {
+        let mut gizmo = gizmo(GizmoMode::Scale);
+        assert!(gizmo.begin_drag(1, &front_at(1.0, 0.02), ObjectTransform::default(), 1.0));
+        let scaled = gizmo.drag_to(&front_at(1.5, 0.0)).unwrap();
+        assert_close(scaled.scale.x, 1.5);
+        assert_close(scaled.scale.y, 1.0);
+        // dragging through the origin stops at the smallest scale
+        let flat = gizmo.drag_to(&front_at(-1.0, 0.0)).unwrap();
+        assert_close(flat.scale.x, MIN_SCALE);
+    }
+
+    #[test]
+    fn cancel_gives_back_the_start_transform() {
+        let mut gizmo = gizmo(GizmoMode::Translate);
+        let start = ObjectTransform {
+            translation: Vector3::new(1.0, 2.0, 3.0),
+            ..ObjectTransform::default()
+        };
+        let ray = Ray::new(Point3::new(1.5, 2.0, 13.0), -Vector3::unit_z());
+        assert!(gizmo.begin_drag(7, &ray, start, 1.0));
+        assert!(!gizmo.end_drag(8));
+        assert_eq!(gizmo.cancel_drag(), Some(start));
+        assert!(!gizmo.dragging(7));
+    }
+}
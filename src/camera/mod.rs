@@ -0,0 +1,336 @@
+//! Camera shared by the lessons: the camera and its projection, the
+//! controllers that drive it, view transitions and bookmarks, and the ray
+//! picking and transform gizmo built on top of it.
+
+use cgmath::{EuclideanSpace, InnerSpace, Matrix3, Matrix4, Point3, Quaternion, Rad, SquareMatrix, Vector3, Vector4};
+use std::f32::consts::PI;
+
+pub use bookmarks::{Bookmark, Bookmarks};
+use bounds::Aabb;
+pub use controller::{ArcballController, CameraController, Drag, FlyController, MouseButton, OrbitController, ViewPreset, WalkController};
+pub use easing::Easing;
+pub use gizmo::{
+    Gizmo, GizmoHandle, GizmoMode, GizmoView, ARROW_HEAD_RADIUS, ARROW_SHAFT, CENTER_BOX, GIZMO_PIXELS, PLANE_MAX, PLANE_MIN, SCALE_BOX,
+};
+pub use picking::{pick, PickHit, PickMesh};
+use projection::{FovPolicy, ProjectionController};
+use ray::Ray;
+pub use transform::ObjectTransform;
+pub use transition::CameraTransition;
+pub use viewport::Viewport;
+
+mod bookmarks;
+mod bounds;
+mod controller;
+mod easing;
+mod gizmo;
+mod picking;
+mod projection;
+mod ray;
+mod transform;
+mod transition;
+mod viewport;
+
+/// how long, in seconds, switching projection or jumping to a preset view takes
+pub const TRANSITION_TIME: f32 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionKind {
+    Perspective,
+    Orthographic,
+}
+
+/// Everything a camera transition interpolates.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraPose {
+    pub eye: Point3<f32>,
+    pub focus: Point3<f32>,
+    pub up: Vector3<f32>,
+    pub fov: Rad<f32>,
+}
+
+/// Where the camera is, what it looks at and which way is up, plus the
+/// projection. The active `CameraController` moves the view around.
+pub struct Camera {
+    projection: ProjectionController,
+    projection_kind: ProjectionKind,
+    /// 0.0 is fully perspective, 1.0 fully orthographic
+    ortho_blend: f32,
+    eye: Point3<f32>,
+    focus: Point3<f32>,
+    up: Vector3<f32>,
+    bounds: Aabb,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        let fovy = Rad(PI / 3.0);
+
+        Camera {
+            projection: ProjectionController::new(fovy, FovPolicy::FixedVertical),
+            projection_kind: ProjectionKind::Perspective,
+            ortho_blend: 0.0,
+            eye: Point3::new(0.0, 0.0, 15.0),
+            focus: Point3::new(0.0, 0.0, 0.0),
+            up: Vector3::unit_y(),
+            bounds: Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0)),
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        Matrix4::look_at(self.eye, self.focus, self.up)
+    }
+
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        let bounds_distance = (self.bounds.center() - self.eye).magnitude();
+        let perspective = self.projection.perspective_matrix(bounds_distance);
+        if self.ortho_blend <= 0.0 {
+            return perspective;
+        }
+        let focus_distance = (self.focus - self.eye).magnitude();
+        let ortho = self.projection.ortho_matrix(focus_distance, bounds_distance);
+        if self.ortho_blend >= 1.0 {
+            return ortho;
+        }
+        // both matrices agree on the focus plane, blending them keeps
+        // the focused object steady while the perspective flattens out
+        let t = smoothstep(self.ortho_blend);
+        perspective * (1.0 - t) + ortho * t
+    }
+
+    pub fn get_eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
+    pub fn focus(&self) -> Point3<f32> {
+        self.focus
+    }
+
+    pub fn up(&self) -> Vector3<f32> {
+        self.up
+    }
+
+    /// Looks at `focus` from `eye`, keeping the world y axis up.
+    pub fn set_view(&mut self, eye: Point3<f32>, focus: Point3<f32>) {
+        self.set_view_up(eye, focus, Vector3::unit_y());
+    }
+
+    /// Looks at `focus` from `eye` with any `up`, for views that roll or go over the top.
+    pub fn set_view_up(&mut self, eye: Point3<f32>, focus: Point3<f32>, up: Vector3<f32>) {
+        self.eye = eye;
+        self.focus = focus;
+        self.up = up;
+    }
+
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            eye: self.eye,
+            focus: self.focus,
+            up: self.up,
+            fov: self.projection.fov(),
+        }
+    }
+
+    pub fn set_pose(&mut self, pose: CameraPose) {
+        self.set_view_up(pose.eye, pose.focus, pose.up);
+        self.projection.set_fov(pose.fov);
+    }
+
+    /// Widens (positive `delta`) or narrows the field of view, in radians.
+    pub fn zoom_fov(&mut self, delta: f32) {
+        let fov = self.projection.fov().0 + delta;
+        self.projection.set_fov(Rad(fov));
+    }
+
+    /// Focus and distance from it that fit `bounds` in view, looking from
+    /// the current direction with the current field of view and aspect ratio.
+    pub fn fit_bounds(&self, bounds: &Aabb) -> (Point3<f32>, f32) {
+        let back = (self.eye - self.focus).normalize();
+        let right = self.up.cross(back).normalize();
+        let up = back.cross(right);
+        let tan_x = (self.projection.fovx().0 / 2.0).tan();
+        let tan_y = (self.projection.fovy().0 / 2.0).tan();
+        let center = bounds.center();
+        // every corner has to be inside both the horizontal and the vertical angle,
+        // the corners nearer to the eye need more room
+        let distance = bounds
+            .corners()
+            .iter()
+            .map(|&corner| {
+                let offset = corner - center;
+                let x = offset.dot(right).abs();
+                let y = offset.dot(up).abs();
+                offset.dot(back) + (x / tan_x).max(y / tan_y)
+            })
+            .fold(0.0, f32::max);
+        (center, distance)
+    }
+
+    /// Pose fitting `bounds` in view from the current direction.
+    pub fn frame_bounds(&self, bounds: &Aabb) -> CameraPose {
+        let (focus, distance) = self.fit_bounds(bounds);
+        CameraPose {
+            eye: focus + (self.eye - self.focus).normalize() * distance,
+            focus,
+            ..self.pose()
+        }
+    }
+
+    pub fn scene_bounds(&self) -> &Aabb {
+        &self.bounds
+    }
+
+    /// Keeps the aspect ratio of the projection in line with the viewport.
+    pub fn set_viewport(&mut self, width: f64, height: f64) {
+        self.projection.set_viewport(width, height);
+    }
+
+    /// Ray from the eye through the pixel `x`, `y` of `viewport`, y going down.
+    /// Unprojecting through the near and far planes works the same for every
+    /// projection, the rays of an orthographic view are parallel. `None` when
+    /// the view is degenerate, the eye on the focus for one.
+    pub fn screen_to_ray(&self, x: f32, y: f32, viewport: &Viewport) -> Option<Ray> {
+        let ndc_x = 2.0 * x / viewport.width() as f32 - 1.0;
+        let ndc_y = 1.0 - 2.0 * y / viewport.height() as f32;
+        let view_projection = self.projection_matrix() * self.view_matrix();
+        if !view_projection.is_finite() {
+            return None;
+        }
+        let inverse = view_projection.invert()?;
+        let unproject = |ndc_z: f32| {
+            let world = inverse * Vector4::new(ndc_x, ndc_y, ndc_z, 1.0);
+            Point3::from_vec(world.truncate() / world.w)
+        };
+        let near = unproject(-1.0);
+        Some(Ray::new(near, unproject(1.0) - near))
+    }
+
+    /// World length of one pixel of `viewport` at the depth of `point`, for
+    /// things drawn at a constant size on screen.
+    pub fn pixel_size_at(&self, point: Point3<f32>, viewport: &Viewport) -> f32 {
+        let projection = self.projection_matrix();
+        // clip w is the depth for a perspective and 1 for an orthographic projection
+        let w = (projection * self.view_matrix() * point.to_homogeneous()).w;
+        2.0 * w.abs() / (projection[1][1] * viewport.height() as f32)
+    }
+
+    /// Bounds of everything drawn, the depth range is fitted to the sphere around them.
+    pub fn set_scene_bounds(&mut self, bounds: Aabb) {
+        self.bounds = bounds;
+        self.projection.set_bounds_radius(bounds.bounding_radius());
+    }
+
+    pub fn next_fov_policy(&mut self) {
+        let policy = self.projection.policy().next();
+        self.projection.set_policy(policy);
+    }
+
+    pub fn toggle_projection(&mut self) {
+        self.projection_kind = match self.projection_kind {
+            ProjectionKind::Perspective => ProjectionKind::Orthographic,
+            ProjectionKind::Orthographic => ProjectionKind::Perspective,
+        };
+    }
+
+    pub fn orthographic(&self) -> bool {
+        self.projection_kind == ProjectionKind::Orthographic
+    }
+
+    pub fn set_orthographic(&mut self, orthographic: bool) {
+        self.projection_kind = if orthographic {
+            ProjectionKind::Orthographic
+        } else {
+            ProjectionKind::Perspective
+        };
+    }
+
+    /// Advances the projection switch by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        let step = dt / TRANSITION_TIME;
+        self.ortho_blend = match self.projection_kind {
+            ProjectionKind::Perspective => (self.ortho_blend - step).max(0.0),
+            ProjectionKind::Orthographic => (self.ortho_blend + step).min(1.0),
+        };
+    }
+}
+
+pub fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Camera orientation looking along `direction` with `up` roughly up, as a
+/// rotation from camera space, where the camera looks down -z, to world space.
+pub fn look_rotation(direction: Vector3<f32>, up: Vector3<f32>) -> Quaternion<f32> {
+    let back = -direction.normalize();
+    let right = up.cross(back).normalize();
+    let up = back.cross(right);
+    Quaternion::from(Matrix3::from_cols(right, up, back)).normalize()
+}
+
+/// Shortest way between two orientations, `q` and `-q` being the same rotation.
+pub fn orientation_lerp(from: Quaternion<f32>, to: Quaternion<f32>, t: f32) -> Quaternion<f32> {
+    let to = if from.dot(to) < 0.0 { -to } else { to };
+    from.slerp(to, t).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screen_center_ray_goes_through_the_focus() {
+        let mut camera = Camera::new();
+        camera.set_viewport(800.0, 600.0);
+        camera.set_view(Point3::new(3.0, 4.0, 12.0), Point3::new(1.0, 0.0, -1.0));
+        let ray = camera.screen_to_ray(400.0, 300.0, &Viewport::new(800.0, 600.0)).unwrap();
+        let to_focus = (camera.focus() - camera.get_eye()).normalize();
+        assert!((ray.direction - to_focus).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn screen_corner_ray_goes_through_the_view_corner() {
+        let mut camera = Camera::new();
+        camera.set_viewport(800.0, 600.0);
+        let ray = camera.screen_to_ray(800.0, 0.0, &Viewport::new(800.0, 600.0)).unwrap();
+        // the corner pixel projects back onto the corner of the screen
+        let point = ray.at(10.0);
+        let clip = camera.projection_matrix() * camera.view_matrix() * point.to_homogeneous();
+        assert!((clip.x / clip.w - 1.0).abs() < 1e-4);
+        assert!((clip.y / clip.w - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn pixel_size_grows_with_the_distance() {
+        let mut camera = Camera::new();
+        camera.set_viewport(800.0, 600.0);
+        camera.set_view(Point3::new(0.0, 0.0, 10.0), Point3::new(0.0, 0.0, 0.0));
+        let viewport = Viewport::new(800.0, 600.0);
+        let near = camera.pixel_size_at(Point3::new(0.0, 0.0, 5.0), &viewport);
+        let far = camera.pixel_size_at(Point3::new(0.0, 0.0, -10.0), &viewport);
+        assert!((far / near - 4.0).abs() < 1e-3);
+        // the viewport height spans the vertical field of view at the focus
+        let height = camera.pixel_size_at(camera.focus(), &viewport) * 600.0;
+        assert!((height - 2.0 * 10.0 * (PI / 6.0).tan()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let mut camera = Camera::new();
+        camera.set_viewport(800.0, 600.0);
+        camera.set_orthographic(true);
+        camera.update(TRANSITION_TIME);
+        let viewport = Viewport::new(800.0, 600.0);
+        let a = camera.screen_to_ray(100.0, 100.0, &viewport).unwrap();
+        let b = camera.screen_to_ray(700.0, 500.0, &viewport).unwrap();
+        assert!((a.direction - b.direction).magnitude() < 1e-4);
+        assert!((a.origin - b.origin).magnitude() > 1.0);
+    }
+
+    #[test]
+    fn a_degenerate_view_casts_no_ray() {
+        let mut camera = Camera::new();
+        camera.set_viewport(800.0, 600.0);
+        camera.set_view(Point3::new(1.0, 2.0, 3.0), Point3::new(1.0, 2.0, 3.0));
+        assert!(camera.screen_to_ray(400.0, 300.0, &Viewport::new(800.0, 600.0)).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+use cgmath::{InnerSpace, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+
+use super::bounds::Aabb;
+use super::ray::Ray;
+
+/// Triangles of one scene object, in object space, for picking, and the
+/// model matrix placing them in the world.
+#[derive(Clone, Debug)]
+pub struct PickMesh {
+    positions: Vec<Point3<f32>>,
+    triangles: Vec<[usize; 3]>,
+    bounds: Aabb,
+    model: Matrix4<f32>,
+    inverse_model: Matrix4<f32>,
+}
+
+impl PickMesh {
+    /// Mesh from flat `x, y, z` positions and triangle indices, placed at the origin.
+    pub fn new(positions: &[f32], indices: &[u16]) -> Self {
+        Self {
+            positions: positions.chunks(3).map(|p| Point3::new(p[0], p[1], p[2])).collect(),
+            triangles: indices.chunks(3).map(|t| [t[0] as usize, t[1] as usize, t[2] as usize]).collect(),
+            bounds: Aabb::from_positions(positions),
+            model: Matrix4::identity(),
+            inverse_model: Matrix4::identity(),
+        }
+    }
+
+    /// Places the mesh in the world, the matrix must be invertible.
+    pub fn set_model(&mut self, model: Matrix4<f32>) {
+        self.model = model;
+        self.inverse_model = model.invert().expect("model matrix not invertible");
+    }
+
+    /// Bounds in the world, around the placed object space bounds.
+    pub fn world_bounds(&self) -> Aabb {
+        self.bounds.transformed(&self.model)
+    }
+}
+
+/// The nearest triangle under a ray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PickHit {
+    /// index of the mesh in the picked list
+    pub object: usize,
+    /// index of the triangle in the mesh
+    pub triangle: usize,
+    /// weights of the triangle corners at the hit point
+    pub barycentric: Vector3<f32>,
+    pub distance: f32,
+    pub point: Point3<f32>,
+}
+
+/// Nearest hit of `ray` among `meshes`. Boxes the ray misses, or that start
+/// further than the nearest hit so far, skip their triangles.
+pub fn pick(ray: &Ray, meshes: &[PickMesh]) -> Option<PickHit> {
+    let mut nearest: Option<PickHit> = None;
+    for (object, mesh) in meshes.iter().enumerate() {
+        // the mesh is tested in object space, where its bounds are tight;
+        // distances along the local ray are not world distances once scaled
+        let local = Ray::new(
+            mesh.inverse_model.transform_point(ray.origin),
+            mesh.inverse_model.transform_vector(ray.direction),
+        );
+        let world_distance = |t: f32| (mesh.model.transform_point(local.at(t)) - ray.origin).magnitude();
+
+        // the bounding sphere is the cheapest rejection, the box the tighter one
+        if local.intersect_sphere(mesh.bounds.center(), mesh.bounds.bounding_radius()).is_none() {
+            continue;
+        }
+        match local.intersect_aabb(&mesh.bounds) {
+            Some(t) if nearest.is_none_or(|hit| world_distance(t) < hit.distance) => {}
+            _ => continue,
+        }
+        for (index, triangle) in mesh.triangles.iter().enumerate() {
+            let [a, b, c] = *triangle;
+            let hit = match local.intersect_triangle(mesh.positions[a], mesh.positions[b], mesh.positions[c]) {
+                Some(hit) => hit,
+                None => continue,
+            };
+            let distance = world_distance(hit.t);
+            if nearest.is_none_or(|nearest| distance < nearest.distance) {
+                nearest = Some(PickHit {
+                    object,
+                    triangle: index,
+                    barycentric: hit.barycentric(),
+                    distance,
+                    point: ray.at(distance),
+                });
+            }
+        }
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a unit square in the z = `z` plane, as two triangles
+    fn square(z: f32) -> PickMesh {
+        let positions = [0.0, 0.0, z, 1.0, 0.0, z, 1.0, 1.0, z, 0.0, 1.0, z];
+        PickMesh::new(&positions, &[0, 1, 2, 0, 2, 3])
+    }
+
+    #[test]
+    fn nearest_object_wins_whatever_the_order() {
+        let ray = Ray::new(Point3::new(0.75, 0.25, 10.0), -Vector3::unit_z());
+        let hit = pick(&ray, &[square(0.0), square(2.0), square(-2.0)]).unwrap();
+        assert_eq!(hit.object, 1);
+        assert!((hit.distance - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hit_reports_triangle_and_barycentrics() {
+        let ray = Ray::new(Point3::new(0.25, 0.75, 1.0), -Vector3::unit_z());
+        let hit = pick(&ray, &[square(0.0)]).unwrap();
+        assert_eq!(hit.triangle, 1);
+        // corners 0, 2 and 3 of the square
+        let expected = Vector3::new(0.25, 0.25, 0.5);
+        assert!((hit.barycentric - expected).magnitude() < 1e-5);
+        assert!((hit.point - Point3::new(0.25, 0.75, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn placed_meshes_are_hit_where_they_are() {
+        let mut moved = square(0.0);
+        moved.set_model(Matrix4::from_translation(Vector3::new(5.0, 0.0, 0.0)) * Matrix4::from_scale(2.0));
+        let ray = Ray::new(Point3::new(6.5, 1.5, 10.0), -Vector3::unit_z());
+        let bounds = moved.world_bounds();
+        assert!((bounds.min - Point3::new(5.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert!((bounds.max - Point3::new(7.0, 2.0, 0.0)).magnitude() < 1e-5);
+        let hit = pick(&ray, &[square(0.0), moved]).unwrap();
+        assert_eq!(hit.object, 1);
+        // world distance, not the one along the scaled down local ray
+        assert!((hit.distance - 10.0).abs() < 1e-4);
+        assert!((hit.point - Point3::new(6.5, 1.5, 0.0)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn nothing_under_the_ray() {
+        let ray = Ray::new(Point3::new(2.0, 2.0, 1.0), -Vector3::unit_z());
+        assert_eq!(pick(&ray, &[square(0.0)]), None);
+        assert_eq!(pick(&ray, &[]), None);
+    }
+}
@@ -0,0 +1,214 @@
+use cgmath::{Matrix4, Ortho, PerspectiveFov, Rad};
+use std::f32::consts::PI;
+
+/// narrowest and widest field of view the policies work from
+pub const MIN_FOV: f32 = 10.0 * PI / 180.0;
+pub const MAX_FOV: f32 = 120.0 * PI / 180.0;
+
+/// smallest near plane, relative to the far plane, when the eye is inside the scene bounds
+const MIN_NEAR_RATIO: f32 = 0.001;
+
+/// How the field of view follows the shape of the viewport.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FovPolicy {
+    /// the vertical angle is kept, wider windows see more on the sides
+    FixedVertical,
+    /// the horizontal angle is kept, taller windows see more above and below
+    FixedHorizontal,
+    /// the angle covers the shorter side, so anything that fits in a square
+    /// view stays fully visible whatever the window shape
+    FitBounds,
+}
+
+impl FovPolicy {
+    pub fn next(self) -> Self {
+        match self {
+            FovPolicy::FixedVertical => FovPolicy::FixedHorizontal,
+            FovPolicy::FixedHorizontal => FovPolicy::FitBounds,
+            FovPolicy::FitBounds => FovPolicy::FixedVertical,
+        }
+    }
+}
+
+/// Builds the projection from the viewport size, a fov policy and the
+/// bounding sphere of the scene.
+pub struct ProjectionController {
+    policy: FovPolicy,
+    fov: Rad<f32>,
+    aspect: f32,
+    bounds_radius: f32,
+}
+
+impl ProjectionController {
+    pub fn new(fov: Rad<f32>, policy: FovPolicy) -> Self {
+        Self {
+            policy,
+            fov: Rad(fov.0.clamp(MIN_FOV, MAX_FOV)),
+            aspect: 1.0,
+            bounds_radius: 1.0,
+        }
+    }
+
+    pub fn policy(&self) -> FovPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: FovPolicy) {
+        self.policy = policy;
+    }
+
+    /// Field of view the policy works from, before the aspect ratio is applied.
+    pub fn fov(&self) -> Rad<f32> {
+        self.fov
+    }
+
+    /// Clamped to `MIN_FOV..=MAX_FOV`.
+    pub fn set_fov(&mut self, fov: Rad<f32>) {
+        self.fov = Rad(fov.0.clamp(MIN_FOV, MAX_FOV));
+    }
+
+    pub fn set_viewport(&mut self, width: f64, height: f64) {
+        if width > 0.0 && height > 0.0 {
+            self.aspect = (width / height) as f32;
+        }
+    }
+
+    pub fn set_bounds_radius(&mut self, radius: f32) {
+        self.bounds_radius = radius;
+    }
+
+    /// Horizontal field of view for the current aspect ratio.
+    pub fn fovx(&self) -> Rad<f32> {
+        Rad(2.0 * ((self.fovy().0 / 2.0).tan() * self.aspect).atan())
+    }
+
+    /// Vertical field of view for the current aspect ratio.
+    pub fn fovy(&self) -> Rad<f32> {
+        let horizontal_to_vertical = |fovx: Rad<f32>| Rad(2.0 * ((fovx.0 / 2.0).tan() / self.aspect).atan());
+        match self.policy {
+            FovPolicy::FixedVertical => self.fov,
+            FovPolicy::FixedHorizontal => horizontal_to_vertical(self.fov),
+            FovPolicy::FitBounds if self.aspect >= 1.0 => self.fov,
+            FovPolicy::FitBounds => horizontal_to_vertical(self.fov),
+        }
+    }
+
+    /// Near and far planes hugging the scene bounds seen from `distance`
+    /// away from their center, to spend the depth precision on the scene.
+    pub fn depth_range(&self, distance: f32) -> (f32, f32) {
+        let far = distance + self.bounds_radius;
+        let near = (distance - self.bounds_radius).max(far * MIN_NEAR_RATIO);
+        (near, far)
+    }
+
+    pub fn perspective(&self, distance: f32) -> PerspectiveFov<f32> {
+        let (near, far) = self.depth_range(distance);
+        PerspectiveFov {
+            fovy: self.fovy(),
+            aspect: self.aspect,
+            near,
+            far,
+        }
+    }
+
+    pub fn perspective_matrix(&self, bounds_distance: f32) -> Matrix4<f32> {
+        self.perspective(bounds_distance).into()
+    }
+
+    /// Orthographic projection showing as much of the plane `focus_distance`
+    /// away as the perspective projection does, so switching keeps sizes on screen.
+    pub fn ortho_matrix(&self, focus_distance: f32, bounds_distance: f32) -> Matrix4<f32> {
+        let half_height = focus_distance * (self.fovy().0 / 2.0).tan();
+        let half_width = half_height * self.aspect;
+        // an orthographic view doesn't care about the eye, the near plane may be behind it
+        let far = bounds_distance + self.bounds_radius;
+        let near = bounds_distance - self.bounds_radius;
+        Ortho {
+            left: -half_width,
+            right: half_width,
+            bottom: -half_height,
+            top: half_height,
+            near,
+            far,
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(policy: FovPolicy) -> ProjectionController {
+        ProjectionController::new(Rad(PI / 3.0), policy)
+    }
+
+    fn assert_angle(actual: Rad<f32>, expected: f32) {
+        assert!((actual.0 - expected).abs() < 1e-5, "{:?} instead of {}", actual, expected);
+    }
+
+    #[test]
+    fn fixed_vertical_keeps_the_vertical_angle() {
+        let mut projection = controller(FovPolicy::FixedVertical);
+        for &(width, height) in &[(800.0, 600.0), (600.0, 800.0), (1920.0, 400.0)] {
+            projection.set_viewport(width, height);
+            assert_angle(projection.fovy(), PI / 3.0);
+        }
+        projection.set_viewport(200.0, 100.0);
+        assert!(projection.fovx().0 > PI / 3.0);
+    }
+
+    #[test]
+    fn fixed_horizontal_keeps_the_horizontal_angle() {
+        let mut projection = controller(FovPolicy::FixedHorizontal);
+        for &(width, height) in &[(800.0, 600.0), (600.0, 800.0), (1920.0, 400.0), (100.0, 1000.0)] {
+            projection.set_viewport(width, height);
+            assert_angle(projection.fovx(), PI / 3.0);
+        }
+        // the same view, square
+        projection.set_viewport(500.0, 500.0);
+        assert_angle(projection.fovy(), PI / 3.0);
+    }
+
+    #[test]
+    fn fit_bounds_covers_the_shorter_side() {
+        let mut projection = controller(FovPolicy::FitBounds);
+        projection.set_viewport(1600.0, 900.0);
+        assert_angle(projection.fovy(), PI / 3.0);
+        assert!(projection.fovx().0 > PI / 3.0);
+        projection.set_viewport(900.0, 1600.0);
+        assert_angle(projection.fovx(), PI / 3.0);
+        assert!(projection.fovy().0 > PI / 3.0);
+    }
+
+    #[test]
+    fn an_empty_viewport_keeps_the_last_aspect() {
+        let mut projection = controller(FovPolicy::FixedHorizontal);
+        projection.set_viewport(400.0, 200.0);
+        let fovy = projection.fovy();
+        projection.set_viewport(0.0, 0.0);
+        assert_angle(projection.fovy(), fovy.0);
+    }
+
+    #[test]
+    fn the_field_of_view_is_clamped() {
+        let mut projection = controller(FovPolicy::FixedVertical);
+        projection.set_fov(Rad(PI));
+        assert_angle(projection.fov(), MAX_FOV);
+        projection.set_fov(Rad(0.0));
+        assert_angle(projection.fov(), MIN_FOV);
+        projection.set_fov(Rad(-1.0));
+        assert_angle(projection.fov(), MIN_FOV);
+        assert_angle(ProjectionController::new(Rad(3.0), FovPolicy::FitBounds).fov(), MAX_FOV);
+    }
+
+    #[test]
+    fn the_near_plane_stays_in_front_of_the_eye() {
+        let mut projection = controller(FovPolicy::FixedVertical);
+        projection.set_bounds_radius(2.0);
+        assert_eq!(projection.depth_range(10.0), (8.0, 12.0));
+        let (near, far) = projection.depth_range(1.0);
+        assert_eq!(far, 3.0);
+        assert!(near > 0.0 && near < far);
+    }
+}
@@ -0,0 +1,227 @@
+use cgmath::{InnerSpace, Point3, Vector3};
+
+use super::bounds::Aabb;
+
+/// below this, a ray is taken as parallel to a triangle, or a hit as behind the origin
+const EPSILON: f32 = 1e-6;
+
+/// Half line from `origin`, along a unit `direction`.
+#[derive(Clone, Copy, Debug)]
+pub struct Ray {
+    pub origin: Point3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+/// Where a ray crosses a triangle `a, b, c`: the distance along the ray and
+/// the barycentric weights of `b` and `c`, the weight of `a` being `1 - u - v`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TriangleHit {
+    pub t: f32,
+    pub u: f32,
+    pub v: f32,
+}
+
+impl TriangleHit {
+    /// Weights of `a`, `b` and `c`.
+    pub fn barycentric(&self) -> Vector3<f32> {
+        Vector3::new(1.0 - self.u - self.v, self.u, self.v)
+    }
+}
+
+impl Ray {
+    pub fn new(origin: Point3<f32>, direction: Vector3<f32>) -> Self {
+        Self {
+            origin,
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Point3<f32> {
+        self.origin + self.direction * t
+    }
+
+    /// Distance to where the ray enters the box, 0.0 when it starts inside.
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        // slab method: the ray is inside the box where it is between the
+        // planes of all three axes at once
+        let mut t_near = f32::NEG_INFINITY;
+        let mut t_far = f32::INFINITY;
+        for axis in 0..3 {
+            let inverse = 1.0 / self.direction[axis];
+            let t0 = (aabb.min[axis] - self.origin[axis]) * inverse;
+            let t1 = (aabb.max[axis] - self.origin[axis]) * inverse;
+            t_near = t_near.max(t0.min(t1));
+            t_far = t_far.min(t0.max(t1));
+        }
+        if t_near > t_far || t_far < 0.0 {
+            None
+        } else {
+            Some(t_near.max(0.0))
+        }
+    }
+
+    /// Distance to the first crossing of the sphere surface in front of the origin.
+    pub fn intersect_sphere(&self, center: Point3<f32>, radius: f32) -> Option<f32> {
+        let offset = self.origin - center;
+        let b = offset.dot(self.direction);
+        let c = offset.magnitude2() - radius * radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        // the far crossing when the origin is inside
+        [-b - root, -b + root].iter().copied().find(|&t| t >= 0.0)
+    }
+
+    /// Möller–Trumbore intersection, hitting both sides of the triangle.
+    pub fn intersect_triangle(&self, a: Point3<f32>, b: Point3<f32>, c: Point3<f32>) -> Option<TriangleHit> {
+        let edge1 = b - a;
+        let edge2 = c - a;
+        let p = self.direction.cross(edge2);
+        let determinant = edge1.dot(p);
+        if determinant.abs() < EPSILON {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let s = self.origin - a;
+        let u = s.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = s.cross(edge1);
+        let v = self.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let t = edge2.dot(q) * inverse;
+        if t < EPSILON {
+            return None;
+        }
+        Some(TriangleHit { t, u, v })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn new_normalizes_the_direction() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 3.0, 4.0));
+        assert_close(ray.direction.magnitude(), 1.0);
+        assert_close(ray.at(5.0).y, 3.0);
+    }
+
+    #[test]
+    fn aabb_hit_from_outside() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 5.0), -Vector3::unit_z());
+        assert_close(ray.intersect_aabb(&unit_box()).unwrap(), 4.0);
+    }
+
+    #[test]
+    fn aabb_hit_along_a_diagonal() {
+        let ray = Ray::new(Point3::new(3.0, 3.0, 3.0), Vector3::new(-1.0, -1.0, -1.0));
+        assert_close(ray.intersect_aabb(&unit_box()).unwrap(), 2.0 * 3.0f32.sqrt());
+    }
+
+    #[test]
+    fn aabb_from_inside_is_at_the_origin() {
+        let ray = Ray::new(Point3::new(0.5, 0.0, 0.0), Vector3::unit_x());
+        assert_eq!(ray.intersect_aabb(&unit_box()), Some(0.0));
+    }
+
+    #[test]
+    fn aabb_miss_beside_and_behind() {
+        let beside = Ray::new(Point3::new(2.0, 0.0, 5.0), -Vector3::unit_z());
+        assert_eq!(beside.intersect_aabb(&unit_box()), None);
+        let behind = Ray::new(Point3::new(0.0, 0.0, 5.0), Vector3::unit_z());
+        assert_eq!(behind.intersect_aabb(&unit_box()), None);
+    }
+
+    #[test]
+    fn aabb_parallel_to_a_face() {
+        // a zero direction component makes infinite slab distances
+        let inside = Ray::new(Point3::new(-5.0, 0.5, 0.5), Vector3::unit_x());
+        assert_close(inside.intersect_aabb(&unit_box()).unwrap(), 4.0);
+        let outside = Ray::new(Point3::new(-5.0, 1.5, 0.5), Vector3::unit_x());
+        assert_eq!(outside.intersect_aabb(&unit_box()), None);
+    }
+
+    #[test]
+    fn sphere_hit_from_outside() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 10.0), -Vector3::unit_z());
+        assert_close(ray.intersect_sphere(Point3::new(0.0, 0.0, 0.0), 2.0).unwrap(), 8.0);
+    }
+
+    #[test]
+    fn sphere_from_inside_hits_the_far_side() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 1.0), -Vector3::unit_z());
+        assert_close(ray.intersect_sphere(Point3::new(0.0, 0.0, 0.0), 2.0).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn sphere_grazing_and_missing() {
+        let center = Point3::new(0.0, 0.0, 0.0);
+        let grazing = Ray::new(Point3::new(2.0, 0.0, 10.0), -Vector3::unit_z());
+        assert_close(grazing.intersect_sphere(center, 2.0).unwrap(), 10.0);
+        let beside = Ray::new(Point3::new(2.1, 0.0, 10.0), -Vector3::unit_z());
+        assert_eq!(beside.intersect_sphere(center, 2.0), None);
+        let behind = Ray::new(Point3::new(0.0, 0.0, 10.0), Vector3::unit_z());
+        assert_eq!(behind.intersect_sphere(center, 2.0), None);
+    }
+
+    fn triangle() -> (Point3<f32>, Point3<f32>, Point3<f32>) {
+        (Point3::new(0.0, 0.0, 0.0), Point3::new(2.0, 0.0, 0.0), Point3::new(0.0, 2.0, 0.0))
+    }
+
+    #[test]
+    fn triangle_hit_with_barycentrics() {
+        let (a, b, c) = triangle();
+        let ray = Ray::new(Point3::new(0.5, 1.0, 3.0), -Vector3::unit_z());
+        let hit = ray.intersect_triangle(a, b, c).unwrap();
+        assert_close(hit.t, 3.0);
+        assert_close(hit.u, 0.25);
+        assert_close(hit.v, 0.5);
+        let weights = hit.barycentric();
+        assert_close(weights.x + weights.y + weights.z, 1.0);
+        // the weighted corners are the hit point
+        let x = a.x * weights.x + b.x * weights.y + c.x * weights.z;
+        let y = a.y * weights.x + b.y * weights.y + c.y * weights.z;
+        assert_close(x, ray.at(hit.t).x);
+        assert_close(y, ray.at(hit.t).y);
+    }
+
+    #[test]
+    fn triangle_hit_from_the_back() {
+        let (a, b, c) = triangle();
+        let ray = Ray::new(Point3::new(0.5, 0.5, -1.0), Vector3::unit_z());
+        assert_close(ray.intersect_triangle(a, b, c).unwrap().t, 1.0);
+    }
+
+    #[test]
+    fn triangle_miss_outside_the_edges() {
+        let (a, b, c) = triangle();
+        let past_hypotenuse = Ray::new(Point3::new(1.5, 1.5, 1.0), -Vector3::unit_z());
+        assert_eq!(past_hypotenuse.intersect_triangle(a, b, c), None);
+        let negative_u = Ray::new(Point3::new(-0.5, 0.5, 1.0), -Vector3::unit_z());
+        assert_eq!(negative_u.intersect_triangle(a, b, c), None);
+    }
+
+    #[test]
+    fn triangle_miss_parallel_and_behind() {
+        let (a, b, c) = triangle();
+        let parallel = Ray::new(Point3::new(-1.0, 0.5, 0.0), Vector3::unit_x());
+        assert_eq!(parallel.intersect_triangle(a, b, c), None);
+        let behind = Ray::new(Point3::new(0.5, 0.5, 1.0), Vector3::unit_z());
+        assert_eq!(behind.intersect_triangle(a, b, c), None);
+    }
+}
@@ -0,0 +1,28 @@
+use cgmath::{Matrix4, One, Quaternion, Vector3};
+
+/// Placement of one scene object: scaled first, then rotated, then moved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectTransform {
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    /// per axis, in the object's own frame
+    pub scale: Vector3<f32>,
+}
+
+impl Default for ObjectTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Quaternion::one(),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+impl ObjectTransform {
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.translation)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
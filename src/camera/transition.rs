@@ -0,0 +1,54 @@
+use cgmath::{InnerSpace, Rad, Rotation, Vector3};
+
+use super::easing::Easing;
+use super::{look_rotation, orientation_lerp, CameraPose};
+
+/// Animates the camera from one pose to another. The focus moves in a
+/// straight line while the eye turns around it, so the view never cuts
+/// through the scene the way a straight eye path could.
+pub struct CameraTransition {
+    from: CameraPose,
+    to: CameraPose,
+    duration: f32,
+    easing: Easing,
+    elapsed: f32,
+}
+
+impl CameraTransition {
+    pub fn new(from: CameraPose, to: CameraPose, duration: f32, easing: Easing) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances by `dt` seconds and returns the pose to show.
+    pub fn step(&mut self, dt: f32) -> CameraPose {
+        self.elapsed += dt;
+        let t = self.easing.ease(self.elapsed / self.duration);
+        interpolate(&self.from, &self.to, t)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+fn interpolate(from: &CameraPose, to: &CameraPose, t: f32) -> CameraPose {
+    let from_offset = from.eye - from.focus;
+    let to_offset = to.eye - to.focus;
+    let from_rotation = look_rotation(-from_offset, from.up);
+    let to_rotation = look_rotation(-to_offset, to.up);
+    let rotation = orientation_lerp(from_rotation, to_rotation, t);
+    let distance = from_offset.magnitude() + (to_offset.magnitude() - from_offset.magnitude()) * t;
+    let focus = from.focus + (to.focus - from.focus) * t;
+    CameraPose {
+        eye: focus + rotation.rotate_vector(Vector3::unit_z()) * distance,
+        focus,
+        up: rotation.rotate_vector(Vector3::unit_y()),
+        fov: Rad(from.fov.0 + (to.fov.0 - from.fov.0) * t),
+    }
+}
@@ -0,0 +1,262 @@
+/// Something a key can be bound to. Every page supports its own subset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    ToggleHelp,
+    NextLesson,
+    PrevLesson,
+    // primitives
+    NextDrawMode,
+    PrevDrawMode,
+    RemoveLastVertex,
+    ClearVertices,
+    // camera and light
+    ToggleWireframe,
+    ToggleWireTechnique,
+    NextCameraController,
+    ToggleTurntable,
+    ToggleProjection,
+    NextFovPolicy,
+    ViewFront,
+    ViewSide,
+    ViewTop,
+    ViewIsometric,
+    FrameSelection,
+    ResetCamera,
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    SpeedUp,
+    SpeedDown,
+    SensitivityUp,
+    SensitivityDown,
+    DampingUp,
+    DampingDown,
+    RecallBookmark(u8),
+    SaveBookmark(u8),
+    ExportBookmarks,
+    ImportBookmarks,
+    // transform gizmo
+    NextGizmoMode,
+    ToggleGizmoSnapping,
+    // level of detail
+    ToggleLodDebug,
+    // instancing
+    ToggleInstancing,
+    // lighting
+    ToggleShading,
+    NextTessellation,
+    AddLight,
+    RemoveLight,
+    NextObject,
+    ExportMaterial,
+    ImportMaterial,
+    ToggleShadows,
+    // shadows
+    ToggleLightKind,
+    ToggleLightAnimation,
+    ToggleShadowDebug,
+    NextPcfRadius,
+    NextShadowResolution,
+    ToggleCascadeColors,
+    ToggleCascadeBlend,
+    // physically based shading
+    ToggleEnvironmentLighting,
+    // textures
+    NextTextureFilter,
+    NextTextureWrap,
+    NextTextureTiling,
+    ToggleSrgbDecode,
+}
+
+/// actions without a parameter, by their name in the bindings config
+const NAMED: &[Action] = &[
+    Action::ToggleHelp,
+    Action::NextLesson,
+    Action::PrevLesson,
+    Action::NextDrawMode,
+    Action::PrevDrawMode,
+    Action::RemoveLastVertex,
+    Action::ClearVertices,
+    Action::ToggleWireframe,
+    Action::ToggleWireTechnique,
+    Action::NextCameraController,
+    Action::ToggleTurntable,
+    Action::ToggleProjection,
+    Action::NextFovPolicy,
+    Action::ViewFront,
+    Action::ViewSide,
+    Action::ViewTop,
+    Action::ViewIsometric,
+    Action::FrameSelection,
+    Action::ResetCamera,
+    Action::MoveForward,
+    Action::MoveBackward,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::SpeedUp,
+    Action::SpeedDown,
+    Action::SensitivityUp,
+    Action::SensitivityDown,
+    Action::DampingUp,
+    Action::DampingDown,
+    Action::ExportBookmarks,
+    Action::ImportBookmarks,
+    Action::NextGizmoMode,
+    Action::ToggleGizmoSnapping,
+    Action::ToggleLodDebug,
+    Action::ToggleInstancing,
+    Action::ToggleShading,
+    Action::NextTessellation,
+    Action::AddLight,
+    Action::RemoveLight,
+    Action::NextObject,
+    Action::ExportMaterial,
+    Action::ImportMaterial,
+    Action::ToggleShadows,
+    Action::ToggleLightKind,
+    Action::ToggleLightAnimation,
+    Action::ToggleShadowDebug,
+    Action::NextPcfRadius,
+    Action::NextShadowResolution,
+    Action::ToggleCascadeColors,
+    Action::ToggleCascadeBlend,
+    Action::ToggleEnvironmentLighting,
+    Action::NextTextureFilter,
+    Action::NextTextureWrap,
+    Action::NextTextureTiling,
+    Action::ToggleSrgbDecode,
+];
+
+impl Action {
+    /// Name in the bindings config and help text of the actions without a
+    /// parameter, exhaustive so that a new action can't miss them.
+    fn entry(self) -> Option<(&'static str, &'static str)> {
+        Some(match self {
+            Action::ToggleHelp => ("ToggleHelp", "show or hide this help"),
+            Action::NextLesson => ("NextLesson", "go to the next lesson"),
+            Action::PrevLesson => ("PrevLesson", "go to the previous lesson"),
+            Action::NextDrawMode => ("NextDrawMode", "next draw mode"),
+            Action::PrevDrawMode => ("PrevDrawMode", "previous draw mode"),
+            Action::RemoveLastVertex => ("RemoveLastVertex", "remove the last vertex"),
+            Action::ClearVertices => ("ClearVertices", "remove every vertex"),
+            Action::ToggleWireframe => ("ToggleWireframe", "solid, wireframe or both"),
+            Action::ToggleWireTechnique => ("ToggleWireTechnique", "wires as lines or from barycentrics"),
+            Action::NextCameraController => ("NextCameraController", "orbit, arcball, fly or walk camera"),
+            Action::ToggleTurntable => ("ToggleTurntable", "arcball or turntable rotation"),
+            Action::ToggleProjection => ("ToggleProjection", "perspective or orthographic"),
+            Action::NextFovPolicy => ("NextFovPolicy", "how the field of view follows the window"),
+            Action::ViewFront => ("ViewFront", "front view"),
+            Action::ViewSide => ("ViewSide", "side view"),
+            Action::ViewTop => ("ViewTop", "top view"),
+            Action::ViewIsometric => ("ViewIsometric", "isometric view"),
+            Action::FrameSelection => ("FrameSelection", "fit the selection in view"),
+            Action::ResetCamera => ("ResetCamera", "back to the initial view"),
+            Action::MoveForward => ("MoveForward", "move forward, or dolly in"),
+            Action::MoveBackward => ("MoveBackward", "move backward, or dolly out"),
+            Action::MoveLeft => ("MoveLeft", "move or pan left"),
+            Action::MoveRight => ("MoveRight", "move or pan right"),
+            Action::MoveUp => ("MoveUp", "move or pan up"),
+            Action::MoveDown => ("MoveDown", "move or pan down"),
+            Action::SpeedUp => ("SpeedUp", "faster camera"),
+            Action::SpeedDown => ("SpeedDown", "slower camera"),
+            Action::SensitivityUp => ("SensitivityUp", "more mouse sensitivity"),
+            Action::SensitivityDown => ("SensitivityDown", "less mouse sensitivity"),
+            Action::DampingUp => ("DampingUp", "less camera inertia"),
+            Action::DampingDown => ("DampingDown", "more camera inertia"),
+            Action::ExportBookmarks => ("ExportBookmarks", "show the bookmarks as JSON"),
+            Action::ImportBookmarks => ("ImportBookmarks", "paste bookmarks JSON"),
+            Action::NextGizmoMode => ("NextGizmoMode", "move, rotate or scale gizmo, or none"),
+            Action::ToggleGizmoSnapping => ("ToggleGizmoSnapping", "snap gizmo drags to increments"),
+            Action::ToggleLodDebug => ("ToggleLodDebug", "tint meshes by level of detail"),
+            Action::ToggleInstancing => ("ToggleInstancing", "instanced or one draw call per cube"),
+            Action::ToggleShading => ("ToggleShading", "Gouraud, Phong or both side by side"),
+            Action::NextTessellation => ("NextTessellation", "finer or coarser sphere"),
+            Action::AddLight => ("AddLight", "add a point, spot or directional light"),
+            Action::RemoveLight => ("RemoveLight", "remove the selected light"),
+            Action::NextObject => ("NextObject", "select the next object"),
+            Action::ExportMaterial => ("ExportMaterial", "show the selected material as JSON"),
+            Action::ImportMaterial => ("ImportMaterial", "paste material JSON"),
+            Action::ToggleShadows => ("ToggleShadows", "point light shadows on or off"),
+            Action::ToggleLightKind => ("ToggleLightKind", "directional or spot light"),
+            Action::ToggleLightAnimation => ("ToggleLightAnimation", "pause or move the light"),
+            Action::ToggleShadowDebug => ("ToggleShadowDebug", "show the shadow map"),
+            Action::NextPcfRadius => ("NextPcfRadius", "hard, 3x3 or 5x5 filtered shadows"),
+            Action::NextShadowResolution => ("NextShadowResolution", "next shadow map size"),
+            Action::ToggleCascadeColors => ("ToggleCascadeColors", "tint by shadow cascade"),
+            Action::ToggleCascadeBlend => ("ToggleCascadeBlend", "blend between cascades or not"),
+            Action::ToggleEnvironmentLighting => ("ToggleEnvironmentLighting", "environment map or light ambient"),
+            Action::NextTextureFilter => ("NextTextureFilter", "nearest, bilinear, trilinear or anisotropic filtering"),
+            Action::NextTextureWrap => ("NextTextureWrap", "repeat, mirror or clamp the textures"),
+            Action::NextTextureTiling => ("NextTextureTiling", "tile the textures more or less"),
+            Action::ToggleSrgbDecode => ("ToggleSrgbDecode", "decode the albedo from sRGB or not"),
+            Action::RecallBookmark(_) | Action::SaveBookmark(_) => return None,
+        })
+    }
+
+    /// Name used in the bindings config, like `ToggleWireframe` or `RecallBookmark3`.
+    pub fn name(self) -> String {
+        match self {
+            Action::RecallBookmark(slot) => format!("RecallBookmark{}", slot),
+            Action::SaveBookmark(slot) => format!("SaveBookmark{}", slot),
+            _ => self.entry().map_or_else(String::new, |(name, _)| name.to_string()),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let slot = |prefix: &str| {
+            name.strip_prefix(prefix)
+                .and_then(|d| d.parse::<u8>().ok())
+                .filter(|d| (1..=9).contains(d))
+        };
+        if let Some(slot) = slot("RecallBookmark") {
+            return Some(Action::RecallBookmark(slot));
+        }
+        if let Some(slot) = slot("SaveBookmark") {
+            return Some(Action::SaveBookmark(slot));
+        }
+        NAMED.iter().copied().find(|action| action.entry().map(|(n, _)| n) == Some(name))
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            Action::RecallBookmark(slot) => format!("go to bookmark {}", slot),
+            Action::SaveBookmark(slot) => format!("save the view as bookmark {}", slot),
+            _ => self.entry().map_or_else(String::new, |(_, text)| text.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_action_has_a_name_and_a_description() {
+        let bookmarks = (1..=9).flat_map(|slot| vec![Action::RecallBookmark(slot), Action::SaveBookmark(slot)]);
+        for action in NAMED.iter().copied().chain(bookmarks) {
+            assert!(!action.name().is_empty(), "{:?}", action);
+            assert!(!action.description().is_empty(), "{:?}", action);
+            assert_eq!(Action::from_name(&action.name()), Some(action));
+        }
+    }
+
+    #[test]
+    fn the_texture_actions_are_named() {
+        for &action in [
+            Action::NextTextureFilter,
+            Action::NextTextureWrap,
+            Action::NextTextureTiling,
+            Action::ToggleSrgbDecode,
+        ]
+        .iter()
+        {
+            assert!(NAMED.contains(&action), "{:?}", action);
+            assert_eq!(Action::from_name(&action.name()), Some(action));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use super::{Action, Keyboard};
+
+/// A key with the modifiers that have to be held along, written like
+/// `KeyK` or `Shift+KeyK`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub code: String,
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Chord {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let code = parts.pop().filter(|c| !c.is_empty()).ok_or_else(|| format!("no key in '{}'", text))?;
+        let mut chord = Self {
+            code: code.to_string(),
+            shift: false,
+            ctrl: false,
+            alt: false,
+        };
+        for modifier in parts {
+            match modifier {
+                "Shift" => chord.shift = true,
+                "Ctrl" => chord.ctrl = true,
+                "Alt" => chord.alt = true,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, text)),
+            }
+        }
+        Ok(chord)
+    }
+
+    fn modifiers_match(&self, keyboard: &Keyboard) -> bool {
+        self.shift == keyboard.shift() && self.ctrl == keyboard.ctrl() && self.alt == keyboard.alt()
+    }
+
+    /// The key went down this frame with exactly these modifiers.
+    pub fn pressed(&self, keyboard: &Keyboard) -> bool {
+        keyboard.pressed(&self.code) && self.modifiers_match(keyboard)
+    }
+
+    /// The key is down, extra modifiers don't let go of a held action.
+    pub fn held(&self, keyboard: &Keyboard) -> bool {
+        keyboard.held(&self.code) && (!self.shift || keyboard.shift()) && (!self.ctrl || keyboard.ctrl()) && (!self.alt || keyboard.alt())
+    }
+
+    /// Short label for the help, like `Shift+K`.
+    pub fn label(&self) -> String {
+        let key = match self.code.as_str() {
+            "Slash" if self.shift => return "?".to_string(),
+            "BracketLeft" => "[".to_string(),
+            "BracketRight" => "]".to_string(),
+            "Comma" => ",".to_string(),
+            "Period" => ".".to_string(),
+            "Minus" => "-".to_string(),
+            "Equal" => "=".to_string(),
+            "Slash" => "/".to_string(),
+            code => {
+                if let Some(key) = code.strip_prefix("Key").or_else(|| code.strip_prefix("Digit")) {
+                    key.to_string()
+                } else if let Some(key) = code.strip_prefix("Numpad") {
+                    format!("Num {}", key)
+                } else {
+                    code.to_string()
+                }
+            }
+        };
+        let mut label = String::new();
+        for (held, name) in [(self.ctrl, "Ctrl+"), (self.alt, "Alt+"), (self.shift, "Shift+")].iter() {
+            if *held {
+                label.push_str(name);
+            }
+        }
+        label.push_str(&key);
+        label
+    }
+}
+
+/// Keys bound to the actions of one page.
+pub struct Bindings {
+    bindings: Vec<(Action, Vec<Chord>)>,
+}
+
+impl Bindings {
+    /// Bindings from `(action, keys)` pairs, the actions every page has come first.
+    pub fn new(page: &[(Action, &[&str])]) -> Self {
+        let global: &[(Action, &[&str])] = &[
+            (Action::ToggleHelp, &["Shift+Slash"]),
+            (Action::NextLesson, &["PageDown"]),
+            (Action::PrevLesson, &["PageUp"]),
+        ];
+        let mut bindings = Self { bindings: Vec::new() };
+        for (action, keys) in global.iter().chain(page) {
+            bindings.bind(*action, keys);
+        }
+        bindings
+    }
+
+    /// Binds `action` to `keys`, in place of the keys it had.
+    pub fn bind(&mut self, action: Action, keys: &[&str]) {
+        let chords = keys.iter().map(|k| Chord::parse(k).expect("invalid default binding")).collect();
+        match self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            Some(binding) => binding.1 = chords,
+            None => self.bindings.push((action, chords)),
+        }
+    }
+
+    /// Rebinds actions from a JSON object of action names to key lists, like
+    /// `{"ToggleWireframe": ["KeyX"], "ResetCamera": ["KeyR", "Home"]}`. An
+    /// empty list unbinds the action, actions this page hasn't are ignored.
+    /// Nothing changes when any of it is invalid.
+    pub fn apply_overrides(&mut self, json: &str) -> Result<(), String> {
+        let overrides: HashMap<String, Vec<String>> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut parsed = Vec::with_capacity(overrides.len());
+        for (name, keys) in overrides {
+            let action = Action::from_name(&name).ok_or_else(|| format!("unknown action '{}'", name))?;
+            let chords = keys.iter().map(|k| Chord::parse(k)).collect::<Result<Vec<_>, _>>()?;
+            parsed.push((action, chords));
+        }
+        for (action, chords) in parsed {
+            if let Some(binding) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+                binding.1 = chords;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(Action, Vec<Chord>)> {
+        self.bindings.iter()
+    }
+
+    /// Whether some action uses the key with the modifiers held now, to keep
+    /// the browser from acting on it too. Other shortcuts, like Ctrl+R, go through.
+    pub fn is_bound(&self, code: &str, keyboard: &Keyboard) -> bool {
+        self.bindings
+            .iter()
+            .any(|(_, chords)| chords.iter().any(|c| c.code == code && c.modifiers_match(keyboard)))
+    }
+}
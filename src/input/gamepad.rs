@@ -0,0 +1,330 @@
+use cgmath::{InnerSpace, Vector2, Zero};
+use wasm_bindgen::JsCast;
+
+use super::Action;
+
+/// Buttons of the standard gamepad mapping, in `Gamepad.buttons` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadButton {
+    /// A on Xbox pads, cross on PlayStation ones
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    LeftStick,
+    RightStick,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+}
+
+const BUTTONS: [GamepadButton; 16] = [
+    GamepadButton::South,
+    GamepadButton::East,
+    GamepadButton::West,
+    GamepadButton::North,
+    GamepadButton::LeftBumper,
+    GamepadButton::RightBumper,
+    GamepadButton::LeftTrigger,
+    GamepadButton::RightTrigger,
+    GamepadButton::Select,
+    GamepadButton::Start,
+    GamepadButton::LeftStick,
+    GamepadButton::RightStick,
+    GamepadButton::DpadUp,
+    GamepadButton::DpadDown,
+    GamepadButton::DpadLeft,
+    GamepadButton::DpadRight,
+];
+
+/// button value past which it counts as down, triggers are analog
+const BUTTON_THRESHOLD: f32 = 0.5;
+
+/// Raw reading of a gamepad in the standard mapping: the stick axes as
+/// left x, left y, right x, right y, with y going down, and the button values
+/// from 0 to 1.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GamepadSnapshot {
+    pub axes: Vec<f32>,
+    pub buttons: Vec<f32>,
+}
+
+impl GamepadSnapshot {
+    fn axis(&self, index: usize) -> f32 {
+        self.axes.get(index).copied().unwrap_or(0.0)
+    }
+
+    fn button(&self, button: GamepadButton) -> f32 {
+        self.buttons.get(button as usize).copied().unwrap_or(0.0)
+    }
+}
+
+/// Where the gamepad readings come from, the browser or something scripted.
+pub trait GamepadSource {
+    /// The current reading, `None` without a connected gamepad.
+    fn poll(&mut self) -> Option<GamepadSnapshot>;
+}
+
+/// The first connected gamepad of `navigator.getGamepads()`. Browsers only
+/// expose a gamepad once one of its buttons was pressed on the page.
+#[derive(Default)]
+pub struct NavigatorGamepads;
+
+impl GamepadSource for NavigatorGamepads {
+    fn poll(&mut self) -> Option<GamepadSnapshot> {
+        let navigator = web_sys::window()?.navigator();
+        let gamepads = navigator.get_gamepads().ok()?;
+        let gamepad = gamepads
+            .iter()
+            .filter_map(|g| g.dyn_into::<web_sys::Gamepad>().ok())
+            .find(|g| g.connected())?;
+        let axes = gamepad.axes().iter().map(|a| a.as_f64().unwrap_or(0.0) as f32).collect();
+        let buttons = gamepad
+            .buttons()
+            .iter()
+            .map(|b| b.dyn_into::<web_sys::GamepadButton>().map(|b| b.value() as f32).unwrap_or(0.0))
+            .collect();
+        Some(GamepadSnapshot { axes, buttons })
+    }
+}
+
+/// How a stick deflection turns into a movement: nothing inside the dead
+/// zone, then from 0 to 1 along a power curve, so small deflections give
+/// fine control and full ones full speed.
+#[derive(Clone, Copy, Debug)]
+pub struct StickResponse {
+    pub dead_zone: f32,
+    pub exponent: f32,
+}
+
+impl Default for StickResponse {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.15,
+            exponent: 2.0,
+        }
+    }
+}
+
+impl StickResponse {
+    /// The dead zone is radial, so diagonals are as easy to reach as the axes.
+    pub fn stick(&self, stick: Vector2<f32>) -> Vector2<f32> {
+        let magnitude = stick.magnitude();
+        if magnitude <= self.dead_zone {
+            return Vector2::zero();
+        }
+        stick / magnitude * self.axis(magnitude)
+    }
+
+    /// Response of a single positive axis, like a trigger.
+    pub fn axis(&self, value: f32) -> f32 {
+        let live = ((value.min(1.0) - self.dead_zone) / (1.0 - self.dead_zone)).max(0.0);
+        live.powf(self.exponent)
+    }
+}
+
+/// One frame of gamepad input, after the dead zones and response curves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadFrame {
+    /// sticks from -1 to 1, y going up
+    pub left: Vector2<f32>,
+    pub right: Vector2<f32>,
+    /// from 0 to 1
+    pub left_trigger: f32,
+    pub right_trigger: f32,
+    /// buttons that went down since the previous frame
+    pressed: Vec<GamepadButton>,
+}
+
+impl Default for GamepadFrame {
+    fn default() -> Self {
+        Self {
+            left: Vector2::zero(),
+            right: Vector2::zero(),
+            left_trigger: 0.0,
+            right_trigger: 0.0,
+            pressed: Vec::new(),
+        }
+    }
+}
+
+impl GamepadFrame {
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// Actions of the buttons pressed this frame, from `(button, action)` pairs.
+    pub fn pressed_actions(&self, bindings: &[(GamepadButton, Action)]) -> Vec<Action> {
+        bindings.iter().filter(|(b, _)| self.pressed(*b)).map(|(_, a)| *a).collect()
+    }
+}
+
+/// Polls a gamepad source once per frame and shapes its readings.
+pub struct Gamepad<S: GamepadSource> {
+    source: S,
+    sticks: StickResponse,
+    triggers: StickResponse,
+    /// buttons down at the previous poll
+    held: Vec<bool>,
+    connected: bool,
+}
+
+impl<S: GamepadSource> Gamepad<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            sticks: StickResponse::default(),
+            // triggers rest close to 0, and are already meant for fine control
+            triggers: StickResponse {
+                dead_zone: 0.05,
+                exponent: 1.0,
+            },
+            held: vec![false; BUTTONS.len()],
+            connected: false,
+        }
+    }
+
+    /// Reads the gamepad, a frame without any input when none is connected.
+    pub fn poll(&mut self) -> GamepadFrame {
+        let snapshot = self.source.poll();
+        if snapshot.is_some() != self.connected {
+            self.connected = snapshot.is_some();
+            log::info!("gamepad {}", if self.connected { "connected" } else { "disconnected" });
+        }
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => {
+                self.held.iter_mut().for_each(|h| *h = false);
+                return GamepadFrame::default();
+            }
+        };
+
+        let mut pressed = Vec::new();
+        for (button, held) in BUTTONS.iter().zip(self.held.iter_mut()) {
+            let down = snapshot.button(*button) > BUTTON_THRESHOLD;
+            if down && !*held {
+                pressed.push(*button);
+            }
+            *held = down;
+        }
+        GamepadFrame {
+            left: self.sticks.stick(Vector2::new(snapshot.axis(0), -snapshot.axis(1))),
+            right: self.sticks.stick(Vector2::new(snapshot.axis(2), -snapshot.axis(3))),
+            left_trigger: self.triggers.axis(snapshot.button(GamepadButton::LeftTrigger)),
+            right_trigger: self.triggers.axis(snapshot.button(GamepadButton::RightTrigger)),
+            pressed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Readings scripted ahead, then no gamepad.
+    #[derive(Default)]
+    struct FakeGamepads(VecDeque<Option<GamepadSnapshot>>);
+
+    impl GamepadSource for FakeGamepads {
+        fn poll(&mut self) -> Option<GamepadSnapshot> {
+            self.0.pop_front().flatten()
+        }
+    }
+
+    fn snapshot(axes: [f32; 4], down: &[GamepadButton]) -> Option<GamepadSnapshot> {
+        let mut buttons = vec![0.0; BUTTONS.len()];
+        for button in down {
+            buttons[*button as usize] = 1.0;
+        }
+        Some(GamepadSnapshot {
+            axes: axes.to_vec(),
+            buttons,
+        })
+    }
+
+    fn gamepad(readings: Vec<Option<GamepadSnapshot>>) -> Gamepad<FakeGamepads> {
+        Gamepad::new(FakeGamepads(readings.into()))
+    }
+
+    #[test]
+    fn the_dead_zone_is_radial() {
+        let response = StickResponse {
+            dead_zone: 0.2,
+            exponent: 1.0,
+        };
+        assert_eq!(response.stick(Vector2::new(0.14, 0.14)), Vector2::zero());
+        // past the dead zone on the diagonal, though under it on each axis
+        let diagonal = response.stick(Vector2::new(0.15, 0.15));
+        assert!(diagonal.x > 0.0 && (diagonal.x - diagonal.y).abs() < 1e-6);
+        // the direction is kept, only the length is remapped
+        let stick = response.stick(Vector2::new(0.0, -0.6));
+        assert!(stick.x == 0.0 && (stick.y + 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn the_response_curve_spans_the_live_zone() {
+        let response = StickResponse {
+            dead_zone: 0.2,
+            exponent: 2.0,
+        };
+        assert_eq!(response.axis(0.2), 0.0);
+        assert!((response.axis(0.6) - 0.25).abs() < 1e-6);
+        assert!((response.axis(1.0) - 1.0).abs() < 1e-6);
+        // pads reading a little over 1 still give full speed, not more
+        assert!((response.axis(1.05) - 1.0).abs() < 1e-6);
+        assert!((response.stick(Vector2::new(3.0, 4.0)).magnitude() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sticks_go_up_with_y() {
+        let mut gamepad = gamepad(vec![snapshot([0.0, -1.0, 1.0, 0.0], &[])]);
+        let frame = gamepad.poll();
+        assert!((frame.left - Vector2::new(0.0, 1.0)).magnitude() < 1e-6);
+        assert!((frame.right - Vector2::new(1.0, 0.0)).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn buttons_are_pressed_on_their_way_down_only() {
+        let south = [GamepadButton::South];
+        let mut gamepad = gamepad(vec![
+            snapshot([0.0; 4], &south),
+            snapshot([0.0; 4], &south),
+            snapshot([0.0; 4], &[]),
+            snapshot([0.0; 4], &[GamepadButton::South, GamepadButton::Start]),
+        ]);
+        let bindings = [(GamepadButton::South, Action::FrameSelection)];
+        assert_eq!(gamepad.poll().pressed_actions(&bindings), vec![Action::FrameSelection]);
+        assert!(!gamepad.poll().pressed(GamepadButton::South));
+        assert!(!gamepad.poll().pressed(GamepadButton::South));
+        let frame = gamepad.poll();
+        assert!(frame.pressed(GamepadButton::South) && frame.pressed(GamepadButton::Start));
+    }
+
+    #[test]
+    fn a_reconnected_gamepad_presses_its_held_buttons_again() {
+        let north = [GamepadButton::North];
+        let mut gamepad = gamepad(vec![snapshot([0.0; 4], &north), None, snapshot([0.0; 4], &north)]);
+        assert!(gamepad.poll().pressed(GamepadButton::North));
+        assert_eq!(gamepad.poll(), GamepadFrame::default());
+        assert!(gamepad.poll().pressed(GamepadButton::North));
+    }
+
+    #[test]
+    fn triggers_are_analog() {
+        let mut readings = snapshot([0.0; 4], &[]).unwrap();
+        readings.buttons[GamepadButton::LeftTrigger as usize] = 0.3;
+        readings.buttons[GamepadButton::RightTrigger as usize] = 0.02;
+        let frame = gamepad(vec![Some(readings)]).poll();
+        assert!(frame.left_trigger > 0.2 && frame.left_trigger < 0.3);
+        assert_eq!(frame.right_trigger, 0.0);
+        assert!(!frame.pressed(GamepadButton::LeftTrigger));
+    }
+}
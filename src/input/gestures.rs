@@ -0,0 +1,306 @@
+use cgmath::{EuclideanSpace, InnerSpace, MetricSpace, Point2};
+
+/// how far, in pixels, a pointer may wander and still count as a tap
+const TAP_SLOP: f32 = 10.0;
+/// longest press, in milliseconds, that counts as a tap
+const TAP_TIME: f64 = 300.0;
+/// longest wait, in milliseconds, between the two taps of a double tap
+const DOUBLE_TAP_TIME: f64 = 300.0;
+/// how far apart, in pixels, the two taps of a double tap may be
+const DOUBLE_TAP_SLOP: f32 = 30.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    /// From the `MouseEvent.button` value.
+    pub fn from_button(button: i16) -> Option<Self> {
+        match button {
+            0 => Some(MouseButton::Left),
+            1 => Some(MouseButton::Middle),
+            2 => Some(MouseButton::Right),
+            _ => None,
+        }
+    }
+}
+
+/// A pointer going down, from a `PointerEvent`.
+pub struct PointerInput {
+    pub id: i32,
+    pub button: MouseButton,
+    /// client position, in pixels
+    pub x: f32,
+    pub y: f32,
+    /// event timestamp, in milliseconds
+    pub time: f64,
+}
+
+impl PointerInput {
+    /// `None` for the buttons past the first three.
+    pub fn from_event(event: &web_sys::PointerEvent) -> Option<Self> {
+        Some(Self {
+            id: event.pointer_id(),
+            button: MouseButton::from_button(event.button())?,
+            x: event.client_x() as f32,
+            y: event.client_y() as f32,
+            time: event.time_stamp(),
+        })
+    }
+
+    pub fn position(&self) -> Point2<f32> {
+        Point2::new(self.x, self.y)
+    }
+}
+
+/// What the pointers are doing, in client pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    /// a single pointer moved with a button held
+    Drag {
+        button: MouseButton,
+        from: Point2<f32>,
+        to: Point2<f32>,
+    },
+    /// two pointers moved, `scale` is the ratio of their new distance to the old one
+    Pinch {
+        from_center: Point2<f32>,
+        to_center: Point2<f32>,
+        scale: f32,
+    },
+    /// a quick press and release in place, the second of a double tap only makes the `DoubleTap`
+    Tap {
+        button: MouseButton,
+        position: Point2<f32>,
+    },
+    DoubleTap(Point2<f32>),
+}
+
+struct TrackedPointer {
+    id: i32,
+    button: MouseButton,
+    position: Point2<f32>,
+    start: Point2<f32>,
+    /// timestamp of the press, in milliseconds
+    down_time: f64,
+    /// a pointer that wandered or shared the screen with another is no tap
+    tap: bool,
+}
+
+struct Tap {
+    position: Point2<f32>,
+    time: f64,
+}
+
+/// Turns raw pointer events into gestures. Knows nothing about the DOM,
+/// only pointer ids, positions and timestamps.
+#[derive(Default)]
+pub struct GestureRecognizer {
+    pointers: Vec<TrackedPointer>,
+    last_tap: Option<Tap>,
+}
+
+impl GestureRecognizer {
+    pub fn down(&mut self, id: i32, button: MouseButton, position: Point2<f32>, time: f64) {
+        self.pointers.retain(|p| p.id != id);
+        self.pointers.push(TrackedPointer {
+            id,
+            button,
+            position,
+            start: position,
+            down_time: time,
+            tap: true,
+        });
+        if self.pointers.len() > 1 {
+            for pointer in &mut self.pointers {
+                pointer.tap = false;
+            }
+        }
+    }
+
+    pub fn moved(&mut self, id: i32, position: Point2<f32>) -> Option<Gesture> {
+        let index = self.pointers.iter().position(|p| p.id == id)?;
+        let from = self.pointers[index].position;
+        let pointer = &mut self.pointers[index];
+        pointer.position = position;
+        if pointer.start.distance(position) > TAP_SLOP {
+            pointer.tap = false;
+        }
+
+        match self.pointers.len() {
+            1 => Some(Gesture::Drag {
+                button: self.pointers[0].button,
+                from,
+                to: position,
+            }),
+            // only the first two fingers count, any other just tags along
+            _ if index < 2 => {
+                let other = self.pointers[1 - index].position;
+                let from_distance = from.distance(other);
+                let to_distance = position.distance(other);
+                let scale = if from_distance > 0.0 { to_distance / from_distance } else { 1.0 };
+                Some(Gesture::Pinch {
+                    from_center: from.midpoint(other),
+                    to_center: position.midpoint(other),
+                    scale,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn up(&mut self, id: i32, time: f64) -> Option<Gesture> {
+        let index = self.pointers.iter().position(|p| p.id == id)?;
+        let pointer = self.pointers.remove(index);
+        if !pointer.tap || time - pointer.down_time > TAP_TIME {
+            self.last_tap = None;
+            return None;
+        }
+        let position = pointer.position;
+        match self.last_tap.take() {
+            Some(tap) if time - tap.time <= DOUBLE_TAP_TIME && (tap.position - position).magnitude() <= DOUBLE_TAP_SLOP => {
+                Some(Gesture::DoubleTap(position))
+            }
+            _ => {
+                self.last_tap = Some(Tap { position, time });
+                Some(Gesture::Tap {
+                    button: pointer.button,
+                    position,
+                })
+            }
+        }
+    }
+
+    /// Forgets a pointer without any gesture, when the browser takes it over.
+    /// A tap before it no longer makes a double tap with the next one. The
+    /// capture of a pointer is lost after its release too, the cancel of a
+    /// pointer already up changes nothing.
+    pub fn cancel(&mut self, id: i32) {
+        let count = self.pointers.len();
+        self.pointers.retain(|p| p.id != id);
+        if self.pointers.len() != count {
+            self.last_tap = None;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.pointers.clear();
+        self.last_tap = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f32, y: f32) -> Point2<f32> {
+        Point2::new(x, y)
+    }
+
+    #[test]
+    fn a_press_in_place_is_a_tap() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(100.0, 100.0), 0.0);
+        gestures.moved(1, at(100.0 + TAP_SLOP - 1.0, 100.0));
+        let tap = gestures.up(1, TAP_TIME - 1.0);
+        assert_eq!(
+            tap,
+            Some(Gesture::Tap {
+                button: MouseButton::Left,
+                position: at(100.0 + TAP_SLOP - 1.0, 100.0)
+            })
+        );
+    }
+
+    #[test]
+    fn moving_past_the_slop_makes_a_drag() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Right, at(100.0, 100.0), 0.0);
+        let drag = gestures.moved(1, at(100.0, 100.0 + TAP_SLOP + 1.0));
+        assert_eq!(
+            drag,
+            Some(Gesture::Drag {
+                button: MouseButton::Right,
+                from: at(100.0, 100.0),
+                to: at(100.0, 100.0 + TAP_SLOP + 1.0)
+            })
+        );
+        // coming back doesn't make it a tap again
+        gestures.moved(1, at(100.0, 100.0));
+        assert_eq!(gestures.up(1, 50.0), None);
+    }
+
+    #[test]
+    fn a_long_press_is_no_tap() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(0.0, 0.0), 0.0);
+        assert_eq!(gestures.up(1, TAP_TIME + 1.0), None);
+    }
+
+    #[test]
+    fn two_quick_taps_make_a_double_tap() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(50.0, 50.0), 0.0);
+        assert!(matches!(gestures.up(1, 80.0), Some(Gesture::Tap { .. })));
+        gestures.down(1, MouseButton::Left, at(60.0, 50.0), 200.0);
+        assert_eq!(gestures.up(1, 280.0), Some(Gesture::DoubleTap(at(60.0, 50.0))));
+    }
+
+    #[test]
+    fn a_second_pointer_turns_the_drag_into_a_pinch() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(100.0, 100.0), 0.0);
+        assert!(matches!(gestures.moved(1, at(120.0, 100.0)), Some(Gesture::Drag { .. })));
+        gestures.down(2, MouseButton::Left, at(220.0, 100.0), 10.0);
+        let pinch = gestures.moved(2, at(320.0, 100.0));
+        assert_eq!(
+            pinch,
+            Some(Gesture::Pinch {
+                from_center: at(170.0, 100.0),
+                to_center: at(220.0, 100.0),
+                scale: 2.0
+            })
+        );
+        // a third finger tags along
+        gestures.down(3, MouseButton::Left, at(0.0, 0.0), 20.0);
+        assert_eq!(gestures.moved(3, at(50.0, 50.0)), None);
+        // neither finger of a pinch is a tap
+        assert_eq!(gestures.up(1, 30.0), None);
+    }
+
+    #[test]
+    fn a_cancelled_pointer_is_forgotten() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(100.0, 100.0), 0.0);
+        gestures.down(2, MouseButton::Left, at(200.0, 100.0), 0.0);
+        gestures.cancel(2);
+        assert_eq!(gestures.moved(2, at(300.0, 100.0)), None);
+        assert_eq!(gestures.up(2, 10.0), None);
+        // the finger left is back to dragging
+        assert!(matches!(gestures.moved(1, at(150.0, 100.0)), Some(Gesture::Drag { .. })));
+    }
+
+    #[test]
+    fn a_cancel_breaks_a_double_tap() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(50.0, 50.0), 0.0);
+        gestures.up(1, 50.0);
+        gestures.down(2, MouseButton::Left, at(50.0, 50.0), 100.0);
+        gestures.cancel(2);
+        gestures.down(1, MouseButton::Left, at(50.0, 50.0), 150.0);
+        assert!(matches!(gestures.up(1, 200.0), Some(Gesture::Tap { .. })));
+    }
+
+    #[test]
+    fn losing_the_capture_after_the_release_keeps_the_double_tap() {
+        let mut gestures = GestureRecognizer::default();
+        gestures.down(1, MouseButton::Left, at(50.0, 50.0), 0.0);
+        gestures.up(1, 50.0);
+        gestures.cancel(1);
+        gestures.down(1, MouseButton::Left, at(50.0, 50.0), 150.0);
+        assert_eq!(gestures.up(1, 200.0), Some(Gesture::DoubleTap(at(50.0, 50.0))));
+    }
+}
@@ -0,0 +1,65 @@
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use super::{Bindings, BINDINGS_STORAGE_KEY};
+
+/// Panel listing the key bindings of the page, hidden until toggled.
+pub struct HelpOverlay {
+    panel: HtmlElement,
+    visible: Cell<bool>,
+}
+
+impl HelpOverlay {
+    pub fn new(bindings: &Bindings) -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("right", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("padding", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        style.set_property("background", "rgba(0, 0, 0, 0.7)")?;
+        style.set_property("white-space", "pre")?;
+        style.set_property("display", "none")?;
+
+        // the action names are the ones to use for rebinding keys
+        let mut rows: Vec<(String, String, String)> = bindings
+            .iter()
+            .filter(|(_, chords)| !chords.is_empty())
+            .map(|(action, chords)| {
+                let keys: Vec<String> = chords.iter().map(|c| c.label()).collect();
+                (keys.join(" "), action.description(), action.name())
+            })
+            .collect();
+        rows.insert(0, ("key".to_string(), "does".to_string(), "action".to_string()));
+        let keys_width = rows.iter().map(|(keys, _, _)| keys.len()).max().unwrap_or(0);
+        let text_width = rows.iter().map(|(_, text, _)| text.len()).max().unwrap_or(0);
+        let mut lines: Vec<String> = rows
+            .iter()
+            .map(|(keys, text, name)| format!("{:kw$}  {:tw$}  {}", keys, text, name, kw = keys_width, tw = text_width))
+            .collect();
+        lines.push(format!("\nrebind keys with localStorage '{}'", BINDINGS_STORAGE_KEY));
+        lines.push(r#"like {"ToggleWireframe": ["KeyX", "Shift+KeyW"]}"#.to_string());
+        panel.set_text_content(Some(&lines.join("\n")));
+        body.append_child(&panel)?;
+
+        Ok(Self {
+            panel,
+            visible: Cell::new(false),
+        })
+    }
+
+    pub fn toggle(&self) {
+        let visible = !self.visible.get();
+        self.visible.set(visible);
+        let display = if visible { "block" } else { "none" };
+        self.panel.style().set_property("display", display).unwrap();
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+/// Which keys, by `KeyboardEvent.code`, are down, and which went down since
+/// the last frame.
+#[derive(Default)]
+pub struct Keyboard {
+    held: HashSet<String>,
+    pressed: HashSet<String>,
+}
+
+impl Keyboard {
+    pub fn key_down(&mut self, code: &str) {
+        // auto repeat sends more key downs, the key is still only pressed once
+        if self.held.insert(code.to_string()) {
+            self.pressed.insert(code.to_string());
+        }
+    }
+
+    pub fn key_up(&mut self, code: &str) {
+        self.held.remove(code);
+    }
+
+    /// Went down since the last frame.
+    pub fn pressed(&self, code: &str) -> bool {
+        self.pressed.contains(code)
+    }
+
+    pub fn held(&self, code: &str) -> bool {
+        self.held.contains(code)
+    }
+
+    pub fn shift(&self) -> bool {
+        self.held("ShiftLeft") || self.held("ShiftRight")
+    }
+
+    pub fn ctrl(&self) -> bool {
+        self.held("ControlLeft") || self.held("ControlRight")
+    }
+
+    pub fn alt(&self) -> bool {
+        self.held("AltLeft") || self.held("AltRight")
+    }
+
+    /// Releases every key, when the page loses the focus their key up never comes.
+    pub fn clear(&mut self) {
+        self.held.clear();
+        self.pressed.clear();
+    }
+
+    pub fn end_frame(&mut self) {
+        self.pressed.clear();
+    }
+}
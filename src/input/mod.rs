@@ -0,0 +1,90 @@
+//! Input shared by the lessons: key state per frame and named actions bound
+//! to keys, with overrides from `localStorage`, gamepad polling, and pointer
+//! gestures.
+
+pub use action::Action;
+pub use bindings::Bindings;
+use bindings::Chord;
+pub use gamepad::{Gamepad, GamepadButton, NavigatorGamepads};
+pub use gestures::{Gesture, GestureRecognizer, MouseButton, PointerInput};
+pub use help::HelpOverlay;
+pub use keyboard::Keyboard;
+
+mod action;
+mod bindings;
+mod gamepad;
+mod gestures;
+mod help;
+mod keyboard;
+
+/// `localStorage` key of the bindings overrides, shared by every page
+const BINDINGS_STORAGE_KEY: &str = "webgl-practices:bindings";
+
+/// The keyboard and the bindings of the current page.
+pub struct Input {
+    keyboard: Keyboard,
+    bindings: Bindings,
+}
+
+impl Input {
+    /// Input with the page `bindings`, overridden by the ones in `localStorage`.
+    pub fn new(mut bindings: Bindings) -> Self {
+        let overrides = web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|s| s.get_item(BINDINGS_STORAGE_KEY).ok().flatten());
+        if let Some(json) = overrides {
+            if let Err(e) = bindings.apply_overrides(&json) {
+                log::warn!("key bindings in localStorage '{}' ignored: {}", BINDINGS_STORAGE_KEY, e);
+            }
+        }
+        Self {
+            keyboard: Keyboard::default(),
+            bindings,
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Returns whether the key is bound, the browser default should then be prevented.
+    pub fn key_down(&mut self, code: &str) -> bool {
+        self.keyboard.key_down(code);
+        self.bindings.is_bound(code, &self.keyboard)
+    }
+
+    pub fn key_up(&mut self, code: &str) {
+        self.keyboard.key_up(code);
+    }
+
+    pub fn clear(&mut self) {
+        self.keyboard.clear();
+    }
+
+    /// Actions triggered since the last frame, in binding order.
+    pub fn pressed_actions(&self) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(_, chords)| chords.iter().any(|c| c.pressed(&self.keyboard)))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    pub fn held(&self, action: Action) -> bool {
+        self.chords(action).any(|c| c.held(&self.keyboard))
+    }
+
+    fn chords(&self, action: Action) -> impl Iterator<Item = &Chord> {
+        self.bindings.iter().filter(move |(a, _)| *a == action).flat_map(|(_, chords)| chords)
+    }
+
+    /// -1, 0 or 1 from a pair of opposite actions.
+    pub fn axis(&self, positive: Action, negative: Action) -> f32 {
+        self.held(positive) as i32 as f32 - self.held(negative) as i32 as f32
+    }
+
+    /// Forgets what was pressed, call it once the frame handled the input.
+    pub fn end_frame(&mut self) {
+        self.keyboard.end_frame();
+    }
+}
@@ -1,5 +1,8 @@
+mod camera;
+mod input;
 mod pages;
 mod router;
+mod texture;
 
 use router::Router;
 use wasm_bindgen::prelude::*;
@@ -14,12 +17,17 @@ pub fn start() -> Result<(), JsValue> {
     let mut router = Router::new();
     router.register("/00-hello_world", Box::new(crate::pages::hello_world::run));
     router.register("/01-webgl/hello", Box::new(crate::pages::webgl_01_hello::run));
-    router.register("/02-webgl/triangle", Box::new(crate::pages::webgl_02_triangle::run));
-    router.register("/03-webgl/triangle_lines", Box::new(crate::pages::webgl_03_triangle_lines::run));
-    router.register("/04-webgl/triangle_points", Box::new(crate::pages::webgl_04_triangle_points::run));
+    router.register("/02-webgl/primitives", Box::new(crate::pages::webgl_02_primitives::run));
     router.register("/05-webgl/cube", Box::new(crate::pages::webgl_05_cube::run));
     router.register("/06-webgl/camera", Box::new(crate::pages::webgl_06_camera::run));
     router.register("/07-webgl/light", Box::new(crate::pages::webgl_07_light::run));
+    router.register("/08-webgl/lod", Box::new(crate::pages::webgl_08_lod::run));
+    router.register("/09-webgl/instancing", Box::new(crate::pages::webgl_09_instancing::run));
+    router.register("/10-webgl/lighting", Box::new(crate::pages::webgl_10_lighting::run));
+    router.register("/11-webgl/shadows", Box::new(crate::pages::webgl_11_shadows::run));
+    router.register("/12-webgl/cascaded-shadows", Box::new(crate::pages::webgl_12_cascades::run));
+    router.register("/13-webgl/pbr", Box::new(crate::pages::webgl_13_pbr::run));
+    router.register("/14-webgl/textures", Box::new(crate::pages::webgl_14_textures::run));
     router.route().expect("some route error");
     Ok(())
 }
@@ -1,8 +1,13 @@
 pub mod hello_world;
 pub mod webgl_01_hello;
-pub mod webgl_02_triangle;
-pub mod webgl_03_triangle_lines;
-pub mod webgl_04_triangle_points;
+pub mod webgl_02_primitives;
 pub mod webgl_05_cube;
 pub mod webgl_06_camera;
 pub mod webgl_07_light;
+pub mod webgl_08_lod;
+pub mod webgl_09_instancing;
+pub mod webgl_10_lighting;
+pub mod webgl_11_shadows;
+pub mod webgl_12_cascades;
+pub mod webgl_13_pbr;
+pub mod webgl_14_textures;
@@ -0,0 +1,84 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+/// Every primitive type `drawElements` accepts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawMode {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl DrawMode {
+    pub const ALL: [DrawMode; 7] = [
+        DrawMode::Points,
+        DrawMode::Lines,
+        DrawMode::LineStrip,
+        DrawMode::LineLoop,
+        DrawMode::Triangles,
+        DrawMode::TriangleStrip,
+        DrawMode::TriangleFan,
+    ];
+
+    pub fn gl_mode(self) -> u32 {
+        match self {
+            DrawMode::Points => GL::POINTS,
+            DrawMode::Lines => GL::LINES,
+            DrawMode::LineStrip => GL::LINE_STRIP,
+            DrawMode::LineLoop => GL::LINE_LOOP,
+            DrawMode::Triangles => GL::TRIANGLES,
+            DrawMode::TriangleStrip => GL::TRIANGLE_STRIP,
+            DrawMode::TriangleFan => GL::TRIANGLE_FAN,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DrawMode::Points => "POINTS",
+            DrawMode::Lines => "LINES",
+            DrawMode::LineStrip => "LINE_STRIP",
+            DrawMode::LineLoop => "LINE_LOOP",
+            DrawMode::Triangles => "TRIANGLES",
+            DrawMode::TriangleStrip => "TRIANGLE_STRIP",
+            DrawMode::TriangleFan => "TRIANGLE_FAN",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap();
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap();
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// Groups the indices `0..count` into the primitives the GPU assembles
+    /// for this mode. Leftover indices that can't form a primitive are dropped,
+    /// like the GL does.
+    pub fn assemble(self, count: u16) -> Vec<Vec<u16>> {
+        let n = count as usize;
+        match self {
+            DrawMode::Points => (0..count).map(|i| vec![i]).collect(),
+            DrawMode::Lines => (0..n / 2).map(|i| vec![2 * i as u16, 2 * i as u16 + 1]).collect(),
+            DrawMode::LineStrip => (1..count).map(|i| vec![i - 1, i]).collect(),
+            DrawMode::LineLoop => {
+                let mut lines = DrawMode::LineStrip.assemble(count);
+                if count > 2 {
+                    lines.push(vec![count - 1, 0]);
+                }
+                lines
+            }
+            DrawMode::Triangles => (0..n / 3).map(|i| vec![3 * i as u16, 3 * i as u16 + 1, 3 * i as u16 + 2]).collect(),
+            // every odd triangle swaps its first two vertices to keep the winding
+            DrawMode::TriangleStrip => (2..count)
+                .map(|i| if i % 2 == 0 { vec![i - 2, i - 1, i] } else { vec![i - 1, i - 2, i] })
+                .collect(),
+            DrawMode::TriangleFan => (2..count).map(|i| vec![0, i - 1, i]).collect(),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::state::Msg;
+use super::App;
+use crate::input::{Action, Bindings};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+pub fn attach_event_handlers(app: Rc<App>) -> Result<(), JsValue> {
+    attach_onresize_handler(Rc::clone(&app))?;
+    attach_mouse_down_handler(Rc::clone(&app))?;
+    attach_mouse_up_handler(Rc::clone(&app))?;
+    attach_mouse_move_handler(Rc::clone(&app))?;
+    attach_key_down_handler(Rc::clone(&app))?;
+    attach_key_up_handler(Rc::clone(&app))?;
+    attach_blur_handler(Rc::clone(&app))?;
+    Ok(())
+}
+
+/// Keys of the page, they can be overridden from `localStorage`.
+pub fn bindings() -> Bindings {
+    Bindings::new(&[
+        (Action::NextDrawMode, &["KeyM"]),
+        (Action::PrevDrawMode, &["Shift+KeyM"]),
+        (Action::RemoveLastVertex, &["Backspace"]),
+        (Action::ClearVertices, &["KeyC"]),
+    ])
+}
+
+/// Runs the actions of the keys pressed since the last frame. Called once per frame.
+pub fn handle_input(app: &App) {
+    let actions = app.input.borrow().pressed_actions();
+    for action in actions {
+        let msg = match action {
+            Action::ToggleHelp => {
+                app.help.toggle();
+                continue;
+            }
+            Action::NextLesson => return crate::router::goto_lesson(1),
+            Action::PrevLesson => return crate::router::goto_lesson(-1),
+            Action::NextDrawMode => Msg::NextDrawMode,
+            Action::PrevDrawMode => Msg::PrevDrawMode,
+            Action::RemoveLastVertex => Msg::RemoveLastVertex,
+            Action::ClearVertices => Msg::ClearVertices,
+            _ => continue,
+        };
+        app.state.borrow_mut().msg(msg);
+    }
+    app.input.borrow_mut().end_frame();
+}
+
+fn attach_onresize_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_evt: web_sys::Event| {
+        let window = web_sys::window().expect("no global `window` exists");
+        let width = window.inner_width().unwrap().as_f64().unwrap();
+        let height = window.inner_height().unwrap().as_f64().unwrap();
+        app.canvas.set_width(width as u32);
+        app.canvas.set_height(height as u32);
+        app.state.borrow_mut().msg(Msg::ViewportResize(width, height));
+        app.gl.viewport(0, 0, width as i32, height as i32);
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let body = document.body().unwrap();
+    body.set_onresize(Some(handler.as_ref().unchecked_ref()));
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_mouse_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::MouseEvent| {
+        let x = event.client_x();
+        let y = event.client_y();
+        app2.state.borrow_mut().msg(Msg::MouseDown(x, y));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("mousedown", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_mouse_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_event: web_sys::MouseEvent| {
+        app.state.borrow_mut().msg(Msg::MouseUp);
+    };
+
+    // listen on the window, so releasing outside of the canvas ends the drag too
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("mouseup", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+    Ok(())
+}
+
+fn attach_mouse_move_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::MouseEvent| {
+        event.prevent_default();
+        let x = event.client_x();
+        let y = event.client_y();
+        app2.state.borrow_mut().msg(Msg::MouseMove(x, y));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("mousemove", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        if app.input.borrow_mut().key_down(&event.code()) {
+            event.prevent_default();
+        }
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keydown", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        app.input.borrow_mut().key_up(&event.code());
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keyup", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+/// keys released outside of the page never send their up event
+fn attach_blur_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_event: web_sys::Event| {
+        app.input.borrow_mut().clear();
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("blur", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
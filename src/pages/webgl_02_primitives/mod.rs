@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use crate::input::{HelpOverlay, Input};
+use canvas::{create_canvas, create_webgl_context};
+use events::{attach_event_handlers, handle_input};
+use overlay::Overlay;
+use primitives::Primitives;
+use shader::Shader;
+use state::{Msg, State};
+
+mod canvas;
+mod draw_mode;
+mod events;
+mod overlay;
+mod primitives;
+mod shader;
+mod state;
+
+pub fn run() -> Result<(), JsValue> {
+    let app = Rc::new(App::new());
+    attach_event_handlers(Rc::clone(&app))?;
+    Rc::clone(&app).render()?;
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        app.render().unwrap();
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut()>));
+    request_animation_frame(g.borrow().as_ref().unwrap());
+    Ok(())
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+pub struct App {
+    canvas: HtmlCanvasElement,
+    gl: GL,
+    state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    primitives: Primitives,
+    overlay: Overlay,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let canvas = create_canvas().unwrap();
+        let gl = create_webgl_context(&canvas).unwrap();
+        let state = RefCell::new(State::new());
+        state.borrow_mut().msg(Msg::ViewportResize(canvas.width() as f64, canvas.height() as f64));
+        let shader = Shader::default_shader(&gl).expect("shader new error");
+        let mut primitives = Primitives::new(shader);
+        primitives.prepare_for_render(&gl);
+        let overlay = Overlay::new().unwrap();
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            primitives,
+            overlay,
+        }
+    }
+
+    pub fn render(&self) -> Result<(), JsValue> {
+        handle_input(self);
+        let state = self.state.borrow();
+        let clear_color = state.clear_color();
+        let viewport = state.viewport();
+
+        self.gl.clear_color(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT);
+        self.gl.viewport(0, 0, viewport.width() as i32, viewport.height() as i32);
+
+        self.primitives.render(&self.gl, &state);
+        self.overlay.update(&state);
+        Ok(())
+    }
+}
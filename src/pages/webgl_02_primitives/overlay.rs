@@ -0,0 +1,90 @@
+use std::cell::RefCell;
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use super::state::State;
+
+/// at most this many primitives are listed in the help text
+const MAX_LISTED_PRIMITIVES: usize = 24;
+
+/// Html layer on top of the canvas with the vertex index labels and the
+/// primitives assembled from them.
+pub struct Overlay {
+    labels: HtmlElement,
+    hud: HtmlElement,
+    last_html: RefCell<(String, String)>,
+}
+
+impl Overlay {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let labels = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = labels.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "0")?;
+        style.set_property("top", "0")?;
+        style.set_property("pointer-events", "none")?;
+        style.set_property("font", "12px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&labels)?;
+
+        let hud = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = hud.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("pointer-events", "none")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&hud)?;
+
+        Ok(Self {
+            labels,
+            hud,
+            last_html: RefCell::new((String::new(), String::new())),
+        })
+    }
+
+    pub fn update(&self, state: &State) {
+        let mut labels = String::new();
+        for (i, &pos) in state.vertex_list().positions().iter().enumerate() {
+            let (x, y) = state.to_client(pos);
+            let _ = write!(
+                labels,
+                r#"<span style="position:absolute;left:{}px;top:{}px">{}</span>"#,
+                x as i32 + 8,
+                y as i32 - 18,
+                i
+            );
+        }
+
+        let mode = state.draw_mode();
+        let primitives = mode.assemble(state.vertex_list().len() as u16);
+        let mut hud = format!("<b>{}</b> ({} primitives)<br>", mode.name(), primitives.len());
+        for primitive in primitives.iter().take(MAX_LISTED_PRIMITIVES) {
+            let indices: Vec<String> = primitive.iter().map(|i| i.to_string()).collect();
+            let _ = write!(hud, "({}) ", indices.join(" "));
+        }
+        if primitives.len() > MAX_LISTED_PRIMITIVES {
+            hud.push_str("...");
+        }
+        hud.push_str("<br><br>click: add vertex, drag: move vertex<br>");
+        hud.push_str("M / shift+M: next / previous mode<br>backspace: remove last vertex, C: clear");
+
+        // only touch the dom when something changed
+        let mut last_html = self.last_html.borrow_mut();
+        if last_html.0 != labels {
+            self.labels.set_inner_html(&labels);
+            last_html.0 = labels;
+        }
+        if last_html.1 != hud {
+            self.hud.set_inner_html(&hud);
+            last_html.1 = hud;
+        }
+    }
+}
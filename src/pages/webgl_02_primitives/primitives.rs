@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::Shader;
+use super::State;
+
+/// size of the squares drawn in `POINTS` mode
+const POINT_SIZE: f32 = 10.0;
+/// size of the rings marking every editable vertex
+const MARKER_SIZE: f32 = 18.0;
+
+pub struct Primitives {
+    shader: Shader,
+    vao: Option<WebGlVertexArrayObject>,
+    vbos: HashMap<String, WebGlBuffer>,
+}
+
+impl Primitives {
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            vao: None,
+            vbos: HashMap::new(),
+        }
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        gl.use_program(Some(&self.shader.program));
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        let vert_buffer = self.array_buffer_f32_attrib(gl, "aVertexPosition", 3);
+        let color_buffer = self.array_buffer_f32_attrib(gl, "aVertexColor", 4);
+        let indices_buffer = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&indices_buffer));
+        self.vbos.insert("vert".into(), vert_buffer);
+        self.vbos.insert("color".into(), color_buffer);
+        self.vbos.insert("index".into(), indices_buffer);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    pub fn render(&self, gl: &GL, state: &State) {
+        gl.use_program(Some(&self.shader.program));
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        // the vertex list is edited with the mouse, so upload it every frame
+        let vertex_list = state.vertex_list();
+        self.update_array_buffer(gl, "vert", &vertex_list.vertices());
+        self.update_array_buffer(gl, "color", &vertex_list.colors());
+        let indices = vertex_list.indices();
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(&indices);
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::DYNAMIC_DRAW);
+        }
+
+        let point_size = self.shader.get_uniform_location(gl, "uPointSize");
+        let round_points = self.shader.get_uniform_location(gl, "uRoundPoints");
+        gl.uniform1f(point_size.as_ref(), POINT_SIZE);
+        gl.uniform1i(round_points.as_ref(), 0);
+        gl.draw_elements_with_i32(state.draw_mode().gl_mode(), indices.len() as i32, GL::UNSIGNED_SHORT, 0);
+
+        gl.uniform1f(point_size.as_ref(), MARKER_SIZE);
+        gl.uniform1i(round_points.as_ref(), 1);
+        gl.draw_elements_with_i32(GL::POINTS, indices.len() as i32, GL::UNSIGNED_SHORT, 0);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    fn array_buffer_f32_attrib(&self, gl: &GL, attrib_name: &str, size: i32) -> WebGlBuffer {
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        let location = self.shader.get_attrib_location(gl, attrib_name);
+        gl.enable_vertex_attrib_array(location as u32);
+        gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, 0, 0);
+        vbo
+    }
+
+    fn update_array_buffer(&self, gl: &GL, name: &str, data: &[f32]) {
+        gl.bind_buffer(GL::ARRAY_BUFFER, self.vbos.get(name));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(data);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::DYNAMIC_DRAW);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+#version 300 es
+precision mediump float;
+
+uniform bool uRoundPoints;
+
+in vec4 vVertexColor;
+
+out vec4 fragColor;
+
+void main(void) {
+    // vertex markers are drawn as rings, gl_PointCoord is only defined while drawing points
+    float r = length(gl_PointCoord - vec2(0.5));
+    if (uRoundPoints && (r > 0.5 || r < 0.3)) {
+        discard;
+    }
+    fragColor = vVertexColor;
+}
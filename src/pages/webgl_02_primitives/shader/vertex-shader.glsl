@@ -1,12 +1,15 @@
 #version 300 es
 precision mediump float;
 
+uniform float uPointSize;
+
 in vec3 aVertexPosition;
 in vec4 aVertexColor;
 
 out vec4 vVertexColor;
 
 void main(void) {
+    gl_PointSize = uPointSize;
     gl_Position = vec4(aVertexPosition, 1.0);
     vVertexColor = aVertexColor;
 }
@@ -0,0 +1,99 @@
+use super::draw_mode::DrawMode;
+use clear_color::ClearColor;
+use vertex_list::VertexList;
+use viewport::Viewport;
+
+mod clear_color;
+mod vertex_list;
+mod viewport;
+
+/// how close, in pixels, a click has to be to grab a vertex
+const PICK_RADIUS: f64 = 12.0;
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    vertex_list: VertexList,
+    draw_mode: DrawMode,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::default();
+        let vertex_list = VertexList::default();
+        Self {
+            viewport,
+            clear_color,
+            vertex_list,
+            draw_mode: DrawMode::Triangles,
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn vertex_list(&self) -> &VertexList {
+        &self.vertex_list
+    }
+
+    pub fn draw_mode(&self) -> DrawMode {
+        self.draw_mode
+    }
+
+    /// Converts a client position in pixels to normalized device coordinates.
+    pub fn to_ndc(&self, x: i32, y: i32) -> [f32; 2] {
+        let x = 2.0 * x as f64 / self.viewport.width() - 1.0;
+        let y = 1.0 - 2.0 * y as f64 / self.viewport.height();
+        [x as f32, y as f32]
+    }
+
+    /// Converts normalized device coordinates back to a client position in pixels.
+    pub fn to_client(&self, pos: [f32; 2]) -> (f64, f64) {
+        let x = (pos[0] as f64 + 1.0) * 0.5 * self.viewport.width();
+        let y = (1.0 - pos[1] as f64) * 0.5 * self.viewport.height();
+        (x, y)
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::MouseDown(x, y) => {
+                let radius = [
+                    (2.0 * PICK_RADIUS / self.viewport.width()) as f32,
+                    (2.0 * PICK_RADIUS / self.viewport.height()) as f32,
+                ];
+                let pos = self.to_ndc(x, y);
+                self.vertex_list.press(pos, radius);
+            }
+            Msg::MouseUp => self.vertex_list.release(),
+            Msg::MouseMove(x, y) => {
+                let pos = self.to_ndc(x, y);
+                self.vertex_list.drag(pos);
+            }
+            Msg::NextDrawMode => self.draw_mode = self.draw_mode.next(),
+            Msg::PrevDrawMode => self.draw_mode = self.draw_mode.prev(),
+            Msg::RemoveLastVertex => self.vertex_list.remove_last(),
+            Msg::ClearVertices => self.vertex_list.clear(),
+        }
+    }
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    MouseDown(i32, i32),
+    MouseUp,
+    MouseMove(i32, i32),
+    NextDrawMode,
+    PrevDrawMode,
+    RemoveLastVertex,
+    ClearVertices,
+}
@@ -0,0 +1,93 @@
+/// colors handed out to new vertices in turn
+const PALETTE: [[f32; 4]; 6] = [
+    [1.0, 0.0, 0.0, 1.0],
+    [0.0, 1.0, 0.0, 1.0],
+    [0.0, 0.0, 1.0, 1.0],
+    [1.0, 1.0, 0.0, 1.0],
+    [0.0, 1.0, 1.0, 1.0],
+    [1.0, 0.0, 1.0, 1.0],
+];
+
+/// Editable vertices in normalized device coordinates, drawn in list order.
+pub struct VertexList {
+    positions: Vec<[f32; 2]>,
+    dragging: Option<usize>,
+}
+
+impl Default for VertexList {
+    fn default() -> Self {
+        Self {
+            positions: vec![[-0.5, -0.5], [0.5, -0.5], [0.0, 0.5]],
+            dragging: None,
+        }
+    }
+}
+
+impl VertexList {
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn positions(&self) -> &[[f32; 2]] {
+        &self.positions
+    }
+
+    pub fn vertices(&self) -> Vec<f32> {
+        self.positions.iter().flat_map(|p| vec![p[0], p[1], 0.0]).collect()
+    }
+
+    pub fn colors(&self) -> Vec<f32> {
+        (0..self.positions.len()).flat_map(|i| PALETTE[i % PALETTE.len()].to_vec()).collect()
+    }
+
+    pub fn indices(&self) -> Vec<u16> {
+        (0..self.positions.len() as u16).collect()
+    }
+
+    /// Index of the vertex closest to `pos`, if it is within `radius` on both axes.
+    pub fn hit(&self, pos: [f32; 2], radius: [f32; 2]) -> Option<usize> {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| (p[0] - pos[0]).abs() <= radius[0] && (p[1] - pos[1]).abs() <= radius[1])
+            .min_by(|(_, a), (_, b)| {
+                let da = (a[0] - pos[0]).powi(2) + (a[1] - pos[1]).powi(2);
+                let db = (b[0] - pos[0]).powi(2) + (b[1] - pos[1]).powi(2);
+                da.partial_cmp(&db).unwrap()
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Grabs the vertex under the cursor, or appends a new one there.
+    pub fn press(&mut self, pos: [f32; 2], radius: [f32; 2]) {
+        let index = match self.hit(pos, radius) {
+            Some(index) => index,
+            None if self.positions.len() < u16::MAX as usize => {
+                self.positions.push(pos);
+                self.positions.len() - 1
+            }
+            None => return,
+        };
+        self.dragging = Some(index);
+    }
+
+    pub fn drag(&mut self, pos: [f32; 2]) {
+        if let Some(index) = self.dragging {
+            self.positions[index] = pos;
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.dragging = None;
+    }
+
+    pub fn remove_last(&mut self) {
+        self.dragging = None;
+        self.positions.pop();
+    }
+
+    pub fn clear(&mut self) {
+        self.dragging = None;
+        self.positions.clear();
+    }
+}
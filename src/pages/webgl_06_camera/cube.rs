@@ -1,4 +1,4 @@
-use cgmath::{Matrix4, Transform};
+use cgmath::Matrix4;
 use std::collections::HashMap;
 use web_sys::{WebGl2RenderingContext as GL, *};
 
@@ -77,9 +77,9 @@ impl Cube {
         self.vao = gl.create_vertex_array();
         gl.bind_vertex_array(self.vao.as_ref());
 
-        let vert_buffer = self.array_buffer_f32_data(&gl, self.vertices(), "aVertexPosition", 3);
-        let color_buffer = self.array_buffer_f32_data(&gl, self.colors(), "aVertexColor", 4);
-        let indices_buffer = self.index_buffer_u16_data(&gl, self.indices());
+        let vert_buffer = self.array_buffer_f32_data(gl, self.vertices(), "aVertexPosition", 3);
+        let color_buffer = self.array_buffer_f32_data(gl, self.colors(), "aVertexColor", 4);
+        let indices_buffer = self.index_buffer_u16_data(gl, self.indices());
         self.vbos.insert("vert".into(), vert_buffer);
         self.vbos.insert("color".into(), color_buffer);
         self.vbos.insert("index".into(), indices_buffer);
@@ -92,9 +92,9 @@ impl Cube {
     pub fn render(&self, gl: &GL, state: &State) {
         gl.use_program(Some(&self.shader.program));
 
-        self.set_uniform_location_matrix4(&gl, "uModelMatrix", Matrix4::one());
-        self.set_uniform_location_matrix4(&gl, "uViewMatrix", state.camera().view_matrix());
-        self.set_uniform_location_matrix4(&gl, "uProjectiveMatrix", state.camera().projection_matrix());
+        self.set_uniform_location_matrix4(gl, "uModelMatrix", state.model_matrix(0));
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", state.camera().view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", state.camera().projection_matrix());
 
         gl.bind_vertex_array(self.vao.as_ref());
         gl.draw_elements_with_i32(GL::TRIANGLES, self.indices().len() as i32, GL::UNSIGNED_SHORT, 0);
@@ -102,7 +102,7 @@ impl Cube {
     }
 
     pub fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
-        let location = self.shader.get_uniform_location(&gl, uniform_name);
+        let location = self.shader.get_uniform_location(gl, uniform_name);
         let array: &[f32; 16] = matrix.as_ref();
         gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
     }
@@ -114,7 +114,7 @@ impl Cube {
             let data_array = js_sys::Float32Array::view(data);
             gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
         }
-        let location = self.shader.get_attrib_location(&gl, attrib_name);
+        let location = self.shader.get_attrib_location(gl, attrib_name);
         gl.enable_vertex_attrib_array(location as u32);
         gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, 0, 0);
         vbo
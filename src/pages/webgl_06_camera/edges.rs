@@ -0,0 +1,83 @@
+//! Edge data for drawing meshes as wireframes, WebGL2 has no `glPolygonMode`.
+
+use std::collections::HashSet;
+
+/// Line list indices with every edge of a triangle list exactly once,
+/// in the order the edges first appear.
+pub fn unique_edges(indices: &[u16]) -> Vec<u16> {
+    let mut seen = HashSet::new();
+    let mut lines = Vec::new();
+    for tri in indices.chunks(3) {
+        for k in 0..3 {
+            let a = tri[k];
+            let b = tri[(k + 1) % 3];
+            if a != b && seen.insert((a.min(b), a.max(b))) {
+                lines.push(a);
+                lines.push(b);
+            }
+        }
+    }
+    lines
+}
+
+/// Unrolls an indexed triangle list so that every corner owns its vertex and
+/// gets a barycentric coordinate, which the wire shader turns into the
+/// distance to the closest edge. Returns `(positions, barycentrics)`.
+pub fn barycentric_triangles(positions: &[f32], indices: &[u16]) -> (Vec<f32>, Vec<f32>) {
+    let mut unrolled = Vec::with_capacity(indices.len() * 3);
+    let mut barycentrics = Vec::with_capacity(indices.len() * 3);
+    for tri in indices.chunks(3) {
+        for (k, &index) in tri.iter().enumerate() {
+            let i = index as usize * 3;
+            unrolled.extend_from_slice(&positions[i..i + 3]);
+            let mut barycentric = [0.0; 3];
+            barycentric[k] = 1.0;
+            barycentrics.extend_from_slice(&barycentric);
+        }
+    }
+    (unrolled, barycentrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a cube on 8 shared corners, two triangles per face
+    const CUBE: [u16; 36] = [
+        0, 1, 2, 0, 2, 3, // back
+        4, 6, 5, 4, 7, 6, // front
+        0, 4, 5, 0, 5, 1, // bottom
+        3, 2, 6, 3, 6, 7, // top
+        0, 3, 7, 0, 7, 4, // left
+        1, 5, 6, 1, 6, 2, // right
+    ];
+
+    #[test]
+    fn shared_edges_are_emitted_once() {
+        let lines = unique_edges(&CUBE);
+        // 12 cube edges and a diagonal across each of the 6 faces
+        assert_eq!(lines.len(), 18 * 2);
+        let mut edges: Vec<_> = lines.chunks(2).map(|l| (l[0].min(l[1]), l[0].max(l[1]))).collect();
+        edges.sort_unstable();
+        edges.dedup();
+        assert_eq!(edges.len(), 18);
+        // the first triangle comes out in its own winding
+        assert_eq!(&lines[..6], &[0, 1, 1, 2, 2, 0]);
+    }
+
+    #[test]
+    fn collapsed_edges_are_skipped() {
+        assert_eq!(unique_edges(&[0, 0, 1]), vec![0, 1]);
+    }
+
+    #[test]
+    fn every_corner_gets_its_own_unit_coordinate() {
+        let positions = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+        let (unrolled, barycentrics) = barycentric_triangles(&positions, &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(unrolled.len(), 6 * 3);
+        assert_eq!(&unrolled[9..18], &[0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0]);
+        for triangle in barycentrics.chunks(9) {
+            assert_eq!(triangle, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        }
+    }
+}
@@ -1,18 +1,170 @@
-use super::state::Msg;
+use super::state::{Msg, PointerInput, ViewPreset};
 use super::App;
+use crate::input::{Action, Bindings, GamepadButton};
+use cgmath::{Vector3, Zero};
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
+mod bookmarks;
+
 pub fn attach_event_handlers(app: Rc<App>) -> Result<(), JsValue> {
     attach_onresize_handler(Rc::clone(&app))?;
-    attach_mouse_down_handler(Rc::clone(&app))?;
-    attach_mouse_up_handler(Rc::clone(&app))?;
-    attach_mouse_move_handler(Rc::clone(&app))?;
+    attach_pointer_down_handler(Rc::clone(&app))?;
+    attach_pointer_up_handler(Rc::clone(&app))?;
+    attach_pointer_cancel_handler(Rc::clone(&app))?;
+    attach_pointer_move_handler(Rc::clone(&app))?;
+    attach_pointer_leave_handler(Rc::clone(&app))?;
     attach_mouse_wheel_handler(Rc::clone(&app))?;
+    attach_context_menu_handler(Rc::clone(&app))?;
+    attach_key_down_handler(Rc::clone(&app))?;
+    attach_key_up_handler(Rc::clone(&app))?;
+    attach_blur_handler(Rc::clone(&app))?;
+    bookmarks::load(&app);
+    // the page must not scroll or zoom under the fingers
+    app.canvas.style().set_property("touch-action", "none")?;
     Ok(())
 }
 
+/// Keys of the page, they can be overridden from `localStorage`.
+pub fn bindings() -> Bindings {
+    let page: &[(Action, &[&str])] = &[
+        (Action::ToggleWireframe, &["KeyZ"]),
+        (Action::ToggleWireTechnique, &["KeyB"]),
+        (Action::NextCameraController, &["KeyC"]),
+        (Action::ToggleTurntable, &["KeyT"]),
+        (Action::ToggleProjection, &["KeyO", "Numpad5"]),
+        (Action::NextFovPolicy, &["KeyV"]),
+        (Action::ViewFront, &["Numpad1"]),
+        (Action::ViewSide, &["Numpad3"]),
+        (Action::ViewTop, &["Numpad7"]),
+        (Action::ViewIsometric, &["Numpad9"]),
+        (Action::FrameSelection, &["KeyF"]),
+        (Action::ResetCamera, &["KeyR"]),
+        (Action::MoveForward, &["KeyW"]),
+        (Action::MoveBackward, &["KeyS"]),
+        (Action::MoveLeft, &["KeyA"]),
+        (Action::MoveRight, &["KeyD"]),
+        (Action::MoveUp, &["KeyE"]),
+        (Action::MoveDown, &["KeyQ"]),
+        (Action::SpeedUp, &["BracketRight"]),
+        (Action::SpeedDown, &["BracketLeft"]),
+        (Action::SensitivityUp, &["Equal"]),
+        (Action::SensitivityDown, &["Minus"]),
+        (Action::DampingUp, &["Period"]),
+        (Action::DampingDown, &["Comma"]),
+        (Action::ExportBookmarks, &["KeyK"]),
+        (Action::ImportBookmarks, &["Shift+KeyK"]),
+        (Action::NextGizmoMode, &["KeyG"]),
+        (Action::ToggleGizmoSnapping, &["KeyX"]),
+    ];
+    let mut bindings = Bindings::new(page);
+    for slot in 1..=9 {
+        bindings.bind(Action::RecallBookmark(slot), &[&format!("Digit{}", slot)]);
+        bindings.bind(Action::SaveBookmark(slot), &[&format!("Shift+Digit{}", slot)]);
+    }
+    bindings
+}
+
+/// Gamepad buttons of the page, in the standard mapping.
+const GAMEPAD_BUTTONS: &[(GamepadButton, Action)] = &[
+    (GamepadButton::South, Action::FrameSelection),
+    (GamepadButton::East, Action::ResetCamera),
+    (GamepadButton::West, Action::ToggleTurntable),
+    (GamepadButton::North, Action::NextCameraController),
+    (GamepadButton::Select, Action::ToggleProjection),
+    (GamepadButton::Start, Action::ToggleHelp),
+    (GamepadButton::DpadUp, Action::ViewTop),
+    (GamepadButton::DpadDown, Action::ViewFront),
+    (GamepadButton::DpadLeft, Action::ViewSide),
+    (GamepadButton::DpadRight, Action::ViewIsometric),
+    (GamepadButton::RightBumper, Action::SpeedUp),
+    (GamepadButton::LeftBumper, Action::SpeedDown),
+];
+
+/// pixels worth of mouse movement per second, the right stick fully tilted
+const STICK_TURN_SPEED: f32 = 800.0;
+
+/// Runs the actions of the keys and gamepad buttons pressed since the last
+/// frame, and sends the held movement keys and the sticks. Called once per
+/// frame, `dt` seconds after the previous one, before the state ticks.
+pub fn handle_input(app: &App, dt: f32) {
+    let gamepad = app.gamepad.borrow_mut().poll();
+    let mut actions = app.input.borrow().pressed_actions();
+    actions.extend(gamepad.pressed_actions(GAMEPAD_BUTTONS));
+    for action in actions {
+        let msg = match action {
+            Action::ToggleHelp => {
+                app.help.toggle();
+                continue;
+            }
+            Action::NextLesson => return crate::router::goto_lesson(1),
+            Action::PrevLesson => return crate::router::goto_lesson(-1),
+            Action::RecallBookmark(slot) => Msg::RecallBookmark(slot),
+            Action::SaveBookmark(slot) => {
+                bookmarks::save(app, slot);
+                continue;
+            }
+            Action::ExportBookmarks => {
+                bookmarks::export(app);
+                continue;
+            }
+            Action::ImportBookmarks => {
+                bookmarks::import(app);
+                continue;
+            }
+            Action::NextCameraController => {
+                app.state.borrow_mut().msg(Msg::NextController);
+                if !app.state.borrow().wants_pointer_lock() && pointer_locked() {
+                    document().exit_pointer_lock();
+                }
+                continue;
+            }
+            Action::ToggleTurntable => Msg::ToggleControllerMode,
+            Action::SpeedUp => Msg::ScaleSpeed(1.25),
+            Action::SpeedDown => Msg::ScaleSpeed(0.8),
+            Action::SensitivityUp => Msg::ScaleSensitivity(1.25),
+            Action::SensitivityDown => Msg::ScaleSensitivity(0.8),
+            Action::DampingUp => Msg::ScaleDamping(1.25),
+            Action::DampingDown => Msg::ScaleDamping(0.8),
+            Action::FrameSelection => Msg::FrameSelection,
+            Action::ResetCamera => Msg::ResetView,
+            Action::ToggleWireframe => Msg::NextRenderMode,
+            Action::ToggleWireTechnique => Msg::ToggleWireTechnique,
+            Action::NextGizmoMode => Msg::NextGizmoMode,
+            Action::ToggleGizmoSnapping => Msg::ToggleGizmoSnapping,
+            Action::ToggleProjection => Msg::ToggleProjection,
+            Action::NextFovPolicy => Msg::NextFovPolicy,
+            Action::ViewFront => Msg::ViewPreset(ViewPreset::Front),
+            Action::ViewSide => Msg::ViewPreset(ViewPreset::Side),
+            Action::ViewTop => Msg::ViewPreset(ViewPreset::Top),
+            Action::ViewIsometric => Msg::ViewPreset(ViewPreset::Isometric),
+            // held actions, or the ones of other pages
+            _ => continue,
+        };
+        app.state.borrow_mut().msg(msg);
+    }
+
+    // the left stick moves like the keys, the triggers go down and up
+    let mut input = app.input.borrow_mut();
+    let keys = Vector3::new(
+        input.axis(Action::MoveRight, Action::MoveLeft),
+        input.axis(Action::MoveUp, Action::MoveDown),
+        input.axis(Action::MoveForward, Action::MoveBackward),
+    );
+    let stick = Vector3::new(gamepad.left.x, gamepad.right_trigger - gamepad.left_trigger, gamepad.left.y);
+    let clamp = |v: f32| v.clamp(-1.0, 1.0);
+    let movement = Vector3::new(clamp(keys.x + stick.x), clamp(keys.y + stick.y), clamp(keys.z + stick.z));
+    app.state.borrow_mut().msg(Msg::Movement(movement));
+    input.end_frame();
+
+    // the right stick turns like the mouse, y going down
+    if !gamepad.right.is_zero() {
+        let turn = gamepad.right * STICK_TURN_SPEED * dt;
+        app.state.borrow_mut().msg(Msg::Turn(turn.x, -turn.y));
+    }
+}
+
 fn attach_onresize_handler(app: Rc<App>) -> Result<(), JsValue> {
     let handler = move |_evt: web_sys::Event| {
         let window = web_sys::window().expect("no global `window` exists");
@@ -34,46 +186,91 @@ fn attach_onresize_handler(app: Rc<App>) -> Result<(), JsValue> {
     Ok(())
 }
 
-fn attach_mouse_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+fn attach_pointer_down_handler(app: Rc<App>) -> Result<(), JsValue> {
     let app2 = Rc::clone(&app);
-    let handler = move |event: web_sys::MouseEvent| {
-        let x = event.client_x();
-        let y = event.client_y();
-        app2.state.borrow_mut().msg(Msg::MouseDown(x, y));
+    let handler = move |event: web_sys::PointerEvent| {
+        let pointer = match PointerInput::from_event(&event) {
+            Some(pointer) => pointer,
+            None => return,
+        };
+        if event.pointer_type() == "mouse" && app2.state.borrow().wants_pointer_lock() {
+            if !pointer_locked() {
+                app2.canvas.request_pointer_lock();
+            }
+            return;
+        }
+        // keep receiving the moves and the release even outside of the canvas
+        let _ = app2.canvas.set_pointer_capture(pointer.id);
+        app2.state.borrow_mut().msg(Msg::PointerDown(pointer));
     };
 
     let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
     app.canvas
-        .add_event_listener_with_callback("mousedown", handler.as_ref().unchecked_ref())?;
+        .add_event_listener_with_callback("pointerdown", handler.as_ref().unchecked_ref())?;
     handler.forget();
 
     Ok(())
 }
 
-fn attach_mouse_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+fn attach_pointer_up_handler(app: Rc<App>) -> Result<(), JsValue> {
     let app2 = Rc::clone(&app);
-    let handler = move |_event: web_sys::MouseEvent| {
-        app2.state.borrow_mut().msg(Msg::MouseUp);
+    let handler = move |event: web_sys::PointerEvent| {
+        app2.state.borrow_mut().msg(Msg::PointerUp(event.pointer_id(), event.time_stamp()));
     };
 
     let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
-    app.canvas.add_event_listener_with_callback("mouseup", handler.as_ref().unchecked_ref())?;
+    app.canvas
+        .add_event_listener_with_callback("pointerup", handler.as_ref().unchecked_ref())?;
     handler.forget();
     Ok(())
 }
 
-fn attach_mouse_move_handler(app: Rc<App>) -> Result<(), JsValue> {
+fn attach_pointer_cancel_handler(app: Rc<App>) -> Result<(), JsValue> {
     let app2 = Rc::clone(&app);
-    let handler = move |event: web_sys::MouseEvent| {
+    let handler = move |event: web_sys::PointerEvent| {
+        app2.state.borrow_mut().msg(Msg::PointerCancel(event.pointer_id()));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointercancel", handler.as_ref().unchecked_ref())?;
+    app.canvas
+        .add_event_listener_with_callback("lostpointercapture", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+    Ok(())
+}
+
+fn attach_pointer_move_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::PointerEvent| {
         event.prevent_default();
-        let x = event.client_x();
-        let y = event.client_y();
-        app2.state.borrow_mut().msg(Msg::MouseMove(x, y));
+        if pointer_locked() {
+            app2.state.borrow_mut().msg(Msg::MouseLook(event.movement_x(), event.movement_y()));
+            return;
+        }
+        let x = event.client_x() as f32;
+        let y = event.client_y() as f32;
+        app2.state.borrow_mut().msg(Msg::PointerMove(event.pointer_id(), x, y));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointermove", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+/// nothing is hovered once the pointer is off the canvas
+fn attach_pointer_leave_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |_event: web_sys::PointerEvent| {
+        app2.state.borrow_mut().msg(Msg::PointerLeave);
     };
 
     let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
     app.canvas
-        .add_event_listener_with_callback("mousemove", handler.as_ref().unchecked_ref())?;
+        .add_event_listener_with_callback("pointerleave", handler.as_ref().unchecked_ref())?;
     handler.forget();
 
     Ok(())
@@ -84,7 +281,12 @@ fn attach_mouse_wheel_handler(app: Rc<App>) -> Result<(), JsValue> {
     let handler = move |event: web_sys::WheelEvent| {
         event.prevent_default();
         let zoom_amount = event.delta_y() / 50.;
-        app2.state.borrow_mut().msg(Msg::Zoom(zoom_amount as f32));
+        let msg = if event.shift_key() {
+            Msg::ZoomFov(zoom_amount as f32 * 0.05)
+        } else {
+            Msg::Zoom(zoom_amount as f32)
+        };
+        app2.state.borrow_mut().msg(msg);
     };
 
     let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
@@ -93,3 +295,68 @@ fn attach_mouse_wheel_handler(app: Rc<App>) -> Result<(), JsValue> {
 
     Ok(())
 }
+
+/// the right button pans, keep the browser menu out of the way
+fn attach_context_menu_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::MouseEvent| {
+        event.prevent_default();
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("contextmenu", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        if app.input.borrow_mut().key_down(&event.code()) {
+            event.prevent_default();
+        }
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keydown", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        app.input.borrow_mut().key_up(&event.code());
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keyup", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+/// keys and buttons released outside of the page never send their up event
+fn attach_blur_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_event: web_sys::Event| {
+        app.input.borrow_mut().clear();
+        app.state.borrow_mut().msg(Msg::ReleaseInput);
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("blur", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn document() -> web_sys::Document {
+    web_sys::window().unwrap().document().unwrap()
+}
+
+fn pointer_locked() -> bool {
+    document().pointer_lock_element().is_some()
+}
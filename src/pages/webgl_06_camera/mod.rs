@@ -1,20 +1,26 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::{WebGl2RenderingContext as GL, *};
 
+use crate::input::{Gamepad, HelpOverlay, Input, NavigatorGamepads};
 use canvas::{create_canvas, create_webgl_context};
 use cube::Cube;
-use events::attach_event_handlers;
+use events::{attach_event_handlers, handle_input};
+use gizmo::GizmoRenderer;
 use shader::Shader;
-use state::{Msg, State};
+use state::{Msg, PickMesh, State};
+use wireframe::Wireframe;
 
 mod canvas;
 mod cube;
+mod edges;
 mod events;
+mod gizmo;
 mod shader;
 mod state;
+mod wireframe;
 
 pub fn run() -> Result<(), JsValue> {
     let app = Rc::new(App::new());
@@ -42,7 +48,13 @@ pub struct App {
     canvas: HtmlCanvasElement,
     gl: GL,
     state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    gamepad: RefCell<Gamepad<NavigatorGamepads>>,
+    last_frame: Cell<f64>,
     cube: Cube,
+    wireframe: Wireframe,
+    gizmo: GizmoRenderer,
 }
 
 impl App {
@@ -54,10 +66,36 @@ impl App {
         let shader = Shader::default_shader(&gl).expect("shader new error");
         let mut cube = Cube::new(shader);
         cube.prepare_for_render(&gl);
-        Self { canvas, gl, state, cube }
+        state
+            .borrow_mut()
+            .msg(Msg::PickMeshesChange(vec![PickMesh::new(cube.vertices(), cube.indices())]));
+        let wire_shader = Shader::wire_shader(&gl).expect("wire shader new error");
+        let mut wireframe = Wireframe::new(wire_shader);
+        wireframe.prepare_for_render(&gl, cube.vertices(), cube.indices());
+        let mut gizmo = GizmoRenderer::new(Shader::wire_shader(&gl).expect("gizmo shader new error"));
+        gizmo.prepare_for_render(&gl);
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            gamepad: RefCell::new(Gamepad::new(NavigatorGamepads)),
+            last_frame: Cell::new(js_sys::Date::now()),
+            cube,
+            wireframe,
+            gizmo,
+        }
     }
 
     pub fn render(&self) -> Result<(), JsValue> {
+        let now = js_sys::Date::now();
+        let dt = (now - self.last_frame.replace(now)) / 1000.0;
+        handle_input(self, dt as f32);
+        self.state.borrow_mut().msg(Msg::Tick(dt as f32));
+
         let state = self.state.borrow();
         let clear_color = state.clear_color();
         let viewport = state.viewport();
@@ -66,7 +104,20 @@ impl App {
         self.gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
         self.gl.viewport(0, 0, viewport.width() as i32, viewport.height() as i32);
 
-        self.cube.render(&self.gl, &self.state.borrow());
+        let render_mode = state.render_mode();
+        if render_mode.has_solid() {
+            // push the faces back a little, so the overlaid wires win the depth test
+            if render_mode.has_wires() {
+                self.gl.enable(GL::POLYGON_OFFSET_FILL);
+                self.gl.polygon_offset(1.0, 1.0);
+            }
+            self.cube.render(&self.gl, &state);
+            self.gl.disable(GL::POLYGON_OFFSET_FILL);
+        }
+        if render_mode.has_wires() {
+            self.wireframe.render(&self.gl, &state, state.model_matrix(0));
+        }
+        self.gizmo.render(&self.gl, &state);
         Ok(())
     }
 }
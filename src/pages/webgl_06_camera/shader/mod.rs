@@ -4,8 +4,10 @@ use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 use web_sys::{WebGl2RenderingContext as GL, *};
 
-static VERT_SHADER_SRC: &'static str = include_str!("./vertex-shader.glsl");
-static FRAG_SHADER_SRC: &'static str = include_str!("./fragment-shader.glsl");
+static VERT_SHADER_SRC: &str = include_str!("./vertex-shader.glsl");
+static FRAG_SHADER_SRC: &str = include_str!("./fragment-shader.glsl");
+static WIRE_VERT_SHADER_SRC: &str = include_str!("./wire-vertex-shader.glsl");
+static WIRE_FRAG_SHADER_SRC: &str = include_str!("./wire-fragment-shader.glsl");
 
 pub struct Shader {
     pub program: WebGlProgram,
@@ -27,9 +29,9 @@ macro_rules! try_debug {
 
 impl Shader {
     pub fn new(gl: &GL, vert_shader: &str, frag_shader: &str) -> Result<Shader, JsValue> {
-        let vert_shader = try_debug!("compile vert shader", compile_shader(&gl, GL::VERTEX_SHADER, vert_shader));
-        let frag_shader = try_debug!("compile frag shader", compile_shader(&gl, GL::FRAGMENT_SHADER, frag_shader));
-        let program = try_debug!("link program", link_program(&gl, &vert_shader, &frag_shader));
+        let vert_shader = try_debug!("compile vert shader", compile_shader(gl, GL::VERTEX_SHADER, vert_shader));
+        let frag_shader = try_debug!("compile frag shader", compile_shader(gl, GL::FRAGMENT_SHADER, frag_shader));
+        let program = try_debug!("link program", link_program(gl, &vert_shader, &frag_shader));
         let uniforms = RefCell::new(HashMap::new());
         let attribs = RefCell::new(HashMap::new());
         Ok(Self { program, uniforms, attribs })
@@ -39,12 +41,16 @@ impl Shader {
         Self::new(gl, VERT_SHADER_SRC, FRAG_SHADER_SRC)
     }
 
+    pub fn wire_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, WIRE_VERT_SHADER_SRC, WIRE_FRAG_SHADER_SRC)
+    }
+
     pub fn get_attrib_location(&self, gl: &GL, name: &str) -> i32 {
         let mut attribs = self.attribs.borrow_mut();
         if attribs.get(name).is_none() {
             attribs.insert(name.into(), gl.get_attrib_location(&self.program, name));
         }
-        attribs.get(name).unwrap().clone()
+        *attribs.get(name).unwrap()
     }
 
     pub fn get_uniform_location(&self, gl: &GL, uniform_name: &str) -> Option<WebGlUniformLocation> {
@@ -53,7 +59,7 @@ impl Shader {
             uniforms.insert(
                 uniform_name.to_string(),
                 gl.get_uniform_location(&self.program, uniform_name)
-                    .expect(&format!(r#"Uniform '{}' not found"#, uniform_name)),
+                    .unwrap_or_else(|| panic!(r#"Uniform '{}' not found"#, uniform_name)),
             );
         }
         Some(uniforms.get(uniform_name).expect("loc").clone())
@@ -75,8 +81,8 @@ fn compile_shader(gl: &GL, shader_type: u32, source: &str) -> Result<WebGlShader
 
 fn link_program(gl: &GL, vert_shader: &WebGlShader, frag_shader: &WebGlShader) -> Result<WebGlProgram, String> {
     let program = gl.create_program().ok_or_else(|| "Unable to create shader program".to_string())?;
-    gl.attach_shader(&program, &vert_shader);
-    gl.attach_shader(&program, &frag_shader);
+    gl.attach_shader(&program, vert_shader);
+    gl.attach_shader(&program, frag_shader);
     gl.link_program(&program);
     if gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
         Ok(program)
@@ -0,0 +1,25 @@
+#version 300 es
+precision mediump float;
+
+uniform bool uBarycentric;
+uniform vec4 uWireColor;
+uniform float uWireWidth;
+
+in vec3 vBarycentric;
+
+out vec4 fragColor;
+
+void main(void) {
+    if (!uBarycentric) {
+        fragColor = uWireColor;
+        return;
+    }
+    // distance to the closest edge, in pixels thanks to the screen space derivatives
+    vec3 d = fwidth(vBarycentric);
+    vec3 edge = smoothstep(d * (uWireWidth - 1.0), d * (uWireWidth + 1.0), vBarycentric);
+    float coverage = 1.0 - min(min(edge.x, edge.y), edge.z);
+    if (coverage < 0.01) {
+        discard;
+    }
+    fragColor = vec4(uWireColor.rgb, uWireColor.a * coverage);
+}
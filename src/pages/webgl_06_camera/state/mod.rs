@@ -1,31 +1,84 @@
-use camera::Camera;
+use crate::camera::{
+    pick, ArcballController, Bookmark, Camera, CameraController, CameraPose, CameraTransition, Drag, Easing, FlyController, Gizmo, ObjectTransform,
+    OrbitController, Viewport, WalkController, GIZMO_PIXELS,
+};
+pub use crate::camera::{
+    Bookmarks, GizmoHandle, GizmoMode, GizmoView, MouseButton, PickHit, PickMesh, ViewPreset, ARROW_HEAD_RADIUS, ARROW_SHAFT, CENTER_BOX, PLANE_MAX,
+    PLANE_MIN, SCALE_BOX,
+};
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use cgmath::{Matrix4, Point2, Point3, Vector2, Vector3, Zero};
 use clear_color::ClearColor;
-use mouse::Mouse;
-use viewport::Viewport;
+pub use render_mode::{RenderMode, WireTechnique};
 
-mod camera;
 mod clear_color;
-mod mouse;
-mod viewport;
+mod render_mode;
+
+/// how long, in seconds, framing the selection takes
+const FRAME_TIME: f32 = 0.6;
+/// how long, in seconds, going back to the initial view takes
+const RESET_TIME: f32 = 0.8;
+/// how long, in seconds, going to a bookmarked view takes
+const BOOKMARK_TIME: f32 = 0.6;
 
 pub struct State {
     viewport: Viewport,
     clear_color: ClearColor,
     camera: Camera,
-    mouse: Mouse,
+    controllers: Vec<Box<dyn CameraController>>,
+    active_controller: usize,
+    /// initial pose, where reset view goes back to
+    home: CameraPose,
+    /// while it runs, the transition drives the camera instead of the controller
+    transition: Option<CameraTransition>,
+    bookmarks: Bookmarks,
+    gestures: GestureRecognizer,
+    /// what can be hovered and selected
+    pick_meshes: Vec<PickMesh>,
+    /// last client position of a pointer over the canvas
+    pointer: Option<Point2<f32>>,
+    hovered: Option<PickHit>,
+    selected: Option<PickHit>,
+    /// placement of every pickable object, in the same order
+    transforms: Vec<ObjectTransform>,
+    gizmo: Gizmo,
+    render_mode: RenderMode,
+    wire_technique: WireTechnique,
 }
 
 impl State {
     pub fn new() -> Self {
         let viewport = Viewport::default();
         let clear_color = ClearColor::default();
-        let camera = Camera::new();
-        let mouse = Mouse::default();
+        let mut camera = Camera::new();
+        let controllers: Vec<Box<dyn CameraController>> = vec![
+            Box::new(OrbitController::new()),
+            Box::new(ArcballController::new()),
+            Box::new(FlyController::new()),
+            // eye level of someone standing on the plane under the cube
+            Box::new(WalkController::new(0.7)),
+        ];
+        controllers[0].apply(&mut camera);
+        let home = camera.pose();
         Self {
             viewport,
             clear_color,
             camera,
-            mouse,
+            controllers,
+            active_controller: 0,
+            home,
+            transition: None,
+            bookmarks: Bookmarks::default(),
+            gestures: GestureRecognizer::default(),
+            pick_meshes: Vec::new(),
+            pointer: None,
+            hovered: None,
+            selected: None,
+            transforms: Vec::new(),
+            gizmo: Gizmo::new(),
+            render_mode: RenderMode::Solid,
+            wire_technique: WireTechnique::Lines,
         }
     }
 
@@ -41,37 +94,429 @@ impl State {
         &self.camera
     }
 
+    pub fn bookmarks(&self) -> &Bookmarks {
+        &self.bookmarks
+    }
+
+    /// Where the object `object` is in the world, the identity for an unknown object.
+    pub fn model_matrix(&self, object: usize) -> Matrix4<f32> {
+        self.transforms.get(object).copied().unwrap_or_default().matrix()
+    }
+
+    fn set_transform(&mut self, object: usize, transform: ObjectTransform) {
+        self.transforms[object] = transform;
+        self.pick_meshes[object].set_model(transform.matrix());
+        self.fit_scene_bounds();
+    }
+
+    /// Fits the depth range and the framing to the objects where they are now.
+    fn fit_scene_bounds(&mut self) {
+        let mut bounds = self.pick_meshes.iter().map(PickMesh::world_bounds);
+        if let Some(first) = bounds.next() {
+            let bounds = bounds.fold(first, |all, object| all.union(&object));
+            self.camera.set_scene_bounds(bounds);
+        }
+    }
+
+    /// Origin and world arm length of the gizmo around the selected object,
+    /// sized to stay the same on screen.
+    fn gizmo_placement(&self) -> Option<(usize, Point3<f32>, f32)> {
+        let object = self.selected?.object;
+        let t = self.transforms[object].translation;
+        let origin = Point3::new(t.x, t.y, t.z);
+        let size = self.camera.pixel_size_at(origin, &self.viewport) * GIZMO_PIXELS;
+        Some((object, origin, size))
+    }
+
+    /// The gizmo to draw, `None` without a selection or with the gizmo hidden.
+    pub fn gizmo(&self) -> Option<GizmoView> {
+        let (_, origin, size) = self.gizmo_placement()?;
+        self.gizmo.view(origin, size)
+    }
+
+    /// Grabs a gizmo handle with a left press on it, returns whether it did.
+    fn begin_gizmo_drag(&mut self, pointer: &PointerInput) -> bool {
+        if pointer.button != MouseButton::Left {
+            return false;
+        }
+        let (object, _, size) = match self.gizmo_placement() {
+            Some(placement) => placement,
+            None => return false,
+        };
+        let ray = match self.camera.screen_to_ray(pointer.x, pointer.y, &self.viewport) {
+            Some(ray) => ray,
+            None => return false,
+        };
+        let transform = self.transforms[object];
+        self.gizmo.begin_drag(pointer.id, &ray, transform, size)
+    }
+
+    /// Puts the dragged object back where it was before the drag.
+    fn cancel_gizmo_drag(&mut self) {
+        if let (Some(start), Some((object, _, _))) = (self.gizmo.cancel_drag(), self.gizmo_placement()) {
+            self.set_transform(object, start);
+        }
+    }
+
+    /// Nearest triangle under the client position `position`.
+    fn pick(&self, position: Point2<f32>) -> Option<PickHit> {
+        let ray = self.camera.screen_to_ray(position.x, position.y, &self.viewport)?;
+        pick(&ray, &self.pick_meshes)
+    }
+
+    /// Whether the active camera controller wants the mouse captured.
+    pub fn wants_pointer_lock(&self) -> bool {
+        self.controller().wants_pointer_lock()
+    }
+
+    /// Pointer position relative to the viewport center, the shorter side spanning -1..1.
+    fn pointer_coords(&self, position: Point2<f32>) -> Vector2<f32> {
+        let width = self.viewport.width() as f32;
+        let height = self.viewport.height() as f32;
+        let scale = width.min(height);
+        Vector2::new((2.0 * position.x - width) / scale, (height - 2.0 * position.y) / scale)
+    }
+
+    fn gesture(&mut self, gesture: Gesture) {
+        match gesture {
+            Gesture::Drag { button, from, to } => {
+                self.interrupt_transition();
+                let drag = Drag {
+                    button,
+                    dx: to.x - from.x,
+                    dy: to.y - from.y,
+                    from: self.pointer_coords(from),
+                    to: self.pointer_coords(to),
+                };
+                self.controller_mut().drag(&drag);
+            }
+            Gesture::Pinch {
+                from_center,
+                to_center,
+                scale,
+            } => {
+                self.interrupt_transition();
+                // the fingers pan together, like a middle button drag
+                let drag = Drag {
+                    button: MouseButton::Middle,
+                    dx: to_center.x - from_center.x,
+                    dy: to_center.y - from_center.y,
+                    from: self.pointer_coords(from_center),
+                    to: self.pointer_coords(to_center),
+                };
+                self.controller_mut().drag(&drag);
+                self.controller_mut().pinch(scale);
+            }
+            Gesture::Tap { button, position } => {
+                if button == MouseButton::Left {
+                    self.selected = self.pick(position);
+                }
+            }
+            Gesture::DoubleTap(_) => self.msg(Msg::FrameSelection),
+        }
+    }
+
+    fn controller(&self) -> &dyn CameraController {
+        self.controllers[self.active_controller].as_ref()
+    }
+
+    fn controller_mut(&mut self) -> &mut dyn CameraController {
+        self.controllers[self.active_controller].as_mut()
+    }
+
+    fn start_transition(&mut self, to: CameraPose, duration: f32, easing: Easing) {
+        self.transition = Some(CameraTransition::new(self.camera.pose(), to, duration, easing));
+    }
+
+    /// Input takes the camera back, the controller goes on from where the transition stopped.
+    fn interrupt_transition(&mut self) {
+        if self.transition.take().is_some() {
+            let camera = &self.camera;
+            self.controllers[self.active_controller].sync(camera);
+        }
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+
+    pub fn wire_technique(&self) -> WireTechnique {
+        self.wire_technique
+    }
+
     pub fn msg(&mut self, msg: Msg) {
         match msg {
-            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ViewportResize(width, height) => {
+                self.viewport.set(width, height);
+                self.camera.set_viewport(width, height);
+            }
+            Msg::PickMeshesChange(meshes) => {
+                self.gizmo.cancel_drag();
+                self.transforms = vec![ObjectTransform::default(); meshes.len()];
+                self.pick_meshes = meshes;
+                self.hovered = None;
+                self.selected = None;
+                self.fit_scene_bounds();
+            }
             Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
-            Msg::MouseDown(x, y) => {
-                self.mouse.set_pressed(true);
-                self.mouse.set_pos(x, y);
+            Msg::PointerDown(pointer) => {
+                // a press on a gizmo handle is no camera gesture
+                if self.begin_gizmo_drag(&pointer) {
+                    return;
+                }
+                self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time);
             }
-            Msg::MouseUp => self.mouse.set_pressed(false),
-            Msg::MouseMove(x, y) => {
-                if !self.mouse.pressed() {
+            Msg::PointerMove(id, x, y) => {
+                self.pointer = Some(Point2::new(x, y));
+                if self.gizmo.dragging(id) {
+                    let transform = self.camera.screen_to_ray(x, y, &self.viewport).and_then(|ray| self.gizmo.drag_to(&ray));
+                    if let (Some(transform), Some((object, _, _))) = (transform, self.gizmo_placement()) {
+                        self.set_transform(object, transform);
+                    }
                     return;
                 }
-                let (old_x, old_y) = self.mouse.pos();
-                let dx = old_x as i32 - x;
-                let dy = y - old_y as i32;
-                self.camera.orbit_left_right(dx as f32);
-                self.camera.orbit_up_down(dy as f32);
-                self.mouse.set_pos(x, y);
-            }
-            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+                if let Some(gesture) = self.gestures.moved(id, Point2::new(x, y)) {
+                    self.gesture(gesture);
+                }
+            }
+            Msg::PointerUp(id, time) => {
+                if self.gizmo.end_drag(id) {
+                    return;
+                }
+                if let Some(gesture) = self.gestures.up(id, time) {
+                    self.gesture(gesture);
+                }
+            }
+            Msg::PointerCancel(id) => {
+                if self.gizmo.dragging(id) {
+                    self.cancel_gizmo_drag();
+                }
+                self.gestures.cancel(id);
+            }
+            Msg::PointerLeave => self.pointer = None,
+            Msg::MouseLook(dx, dy) => {
+                self.interrupt_transition();
+                self.controller_mut().look(dx as f32, dy as f32);
+            }
+            Msg::Turn(dx, dy) => {
+                self.interrupt_transition();
+                self.controller_mut().turn(dx, dy);
+            }
+            Msg::Zoom(zoom) => {
+                self.interrupt_transition();
+                self.controller_mut().wheel(zoom);
+            }
+            Msg::ZoomFov(delta) => self.camera.zoom_fov(delta),
+            Msg::Movement(axes) => {
+                if !axes.is_zero() {
+                    self.interrupt_transition();
+                }
+                self.controller_mut().movement(axes);
+            }
+            Msg::ReleaseInput => {
+                self.cancel_gizmo_drag();
+                self.gestures.clear();
+            }
+            Msg::NextController => {
+                self.interrupt_transition();
+                self.controller_mut().movement(Vector3::zero());
+                self.active_controller = (self.active_controller + 1) % self.controllers.len();
+                let camera = &self.camera;
+                self.controllers[self.active_controller].sync(camera);
+                log::info!("camera controller: {}", self.controller().name());
+            }
+            Msg::ToggleControllerMode => self.controller_mut().toggle_mode(),
+            Msg::ScaleSpeed(factor) => {
+                let settings = self.controller_mut().settings_mut();
+                settings.speed *= factor;
+                log::info!("camera speed: {}", settings.speed);
+            }
+            Msg::ScaleSensitivity(factor) => {
+                let settings = self.controller_mut().settings_mut();
+                settings.sensitivity *= factor;
+                log::info!("camera sensitivity: {}", settings.sensitivity);
+            }
+            Msg::ScaleDamping(factor) => {
+                let settings = self.controller_mut().settings_mut();
+                settings.scale_damping(factor);
+                log::info!("camera damping: {}", settings.damping);
+            }
+            Msg::FrameSelection => {
+                // the selected object, or the whole scene without a selection
+                let bounds = match self.selected {
+                    Some(hit) => self.pick_meshes[hit.object].world_bounds(),
+                    None => *self.camera.scene_bounds(),
+                };
+                let to = self.camera.frame_bounds(&bounds);
+                self.start_transition(to, FRAME_TIME, Easing::EaseInOutCubic);
+            }
+            Msg::ResetView => {
+                let to = self.home;
+                self.start_transition(to, RESET_TIME, Easing::EaseOutQuint);
+            }
+            Msg::SaveBookmark(slot, name) => {
+                let bookmark = Bookmark::new(slot, name, self.camera.pose(), self.camera.orthographic());
+                self.bookmarks.set(bookmark);
+            }
+            Msg::RecallBookmark(slot) => {
+                let (pose, orthographic) = match self.bookmarks.get(slot) {
+                    Some(bookmark) => (bookmark.pose(), bookmark.orthographic),
+                    None => return,
+                };
+                self.camera.set_orthographic(orthographic);
+                self.start_transition(pose, BOOKMARK_TIME, Easing::EaseInOutCubic);
+            }
+            Msg::LoadBookmarks(bookmarks) => self.bookmarks = bookmarks,
+            Msg::ToggleProjection => self.camera.toggle_projection(),
+            Msg::NextFovPolicy => self.camera.next_fov_policy(),
+            Msg::ViewPreset(preset) => {
+                self.interrupt_transition();
+                self.controller_mut().view_preset(preset);
+            }
+            Msg::Tick(dt) => {
+                if let Some(transition) = self.transition.as_mut() {
+                    self.camera.set_pose(transition.step(dt));
+                    if transition.finished() {
+                        self.interrupt_transition();
+                    }
+                } else {
+                    let controller = &mut self.controllers[self.active_controller];
+                    controller.update(dt);
+                    controller.apply(&mut self.camera);
+                }
+                self.camera.update(dt);
+                // the camera may have moved under a still pointer
+                self.hovered = self.pointer.and_then(|position| self.pick(position));
+                let handle = match (self.pointer, self.gizmo_placement()) {
+                    (Some(position), Some((_, origin, size))) => self
+                        .camera
+                        .screen_to_ray(position.x, position.y, &self.viewport)
+                        .and_then(|ray| self.gizmo.pick_handle(&ray, origin, size)),
+                    _ => None,
+                };
+                self.gizmo.set_hovered(handle);
+            }
+            Msg::NextRenderMode => self.render_mode = self.render_mode.next(),
+            Msg::ToggleWireTechnique => self.wire_technique = self.wire_technique.toggle(),
+            Msg::NextGizmoMode => {
+                self.gizmo.next_mode();
+                log::info!("gizmo: {:?}", self.gizmo.mode());
+            }
+            Msg::ToggleGizmoSnapping => {
+                self.gizmo.toggle_snapping();
+                log::info!("gizmo snapping: {}", self.gizmo.snapping().enabled);
+            }
         }
     }
 }
 
 pub enum Msg {
     ViewportResize(f64, f64),
+    /// objects that can be hovered, selected and moved, in object space, all
+    /// at the origin, the scene bounds are fitted around them
+    PickMeshesChange(Vec<PickMesh>),
     #[allow(dead_code)]
     ClearColorChange(f32, f32, f32, f32),
-    MouseDown(i32, i32),
-    MouseUp,
-    MouseMove(i32, i32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the browser took the pointer over, or the capture was lost
+    PointerCancel(i32),
+    /// no pointer is over the canvas anymore
+    PointerLeave,
+    /// mouse movement while the pointer is locked, there is no position then
+    MouseLook(i32, i32),
+    /// turns the view by pixels worth of mouse movement, from a gamepad stick
+    Turn(f32, f32),
     Zoom(f32),
+    /// widens or narrows the field of view, in radians
+    ZoomFov(f32),
+    /// wanted movement as (right, up, forward), each from -1 to 1, sent every frame
+    Movement(Vector3<f32>),
+    /// the page lost the focus, no pointer is down anymore
+    ReleaseInput,
+    NextController,
+    /// like arcball or turntable rotation
+    ToggleControllerMode,
+    ScaleSpeed(f32),
+    ScaleSensitivity(f32),
+    ScaleDamping(f32),
+    /// eases the camera to fit the selection in view
+    FrameSelection,
+    /// eases the camera back to its initial pose
+    ResetView,
+    /// stores the current view in a numbered slot, with a name
+    SaveBookmark(u8, String),
+    RecallBookmark(u8),
+    LoadBookmarks(Bookmarks),
+    ToggleProjection,
+    NextFovPolicy,
+    ViewPreset(ViewPreset),
+    /// a frame went by, with the seconds elapsed since the previous one
+    Tick(f32),
+    NextRenderMode,
+    ToggleWireTechnique,
+    /// translate, rotate or scale gizmo, or none
+    NextGizmoMode,
+    ToggleGizmoSnapping,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{EuclideanSpace, InnerSpace};
+
+    /// corners of a 1 by 1 by 1 cube at the origin, the bounds are all that counts
+    fn cube() -> PickMesh {
+        let positions = [
+            -0.5, -0.5, -0.5, 0.5, -0.5, -0.5, 0.5, 0.5, -0.5, -0.5, 0.5, -0.5, -0.5, -0.5, 0.5, 0.5, -0.5, 0.5, 0.5, 0.5, 0.5, -0.5, 0.5, 0.5,
+        ];
+        PickMesh::new(&positions, &[0, 1, 2, 4, 6, 5])
+    }
+
+    fn in_depth_range(state: &State, point: Point3<f32>) -> bool {
+        let clip = state.camera.projection_matrix() * state.camera.view_matrix() * point.to_homogeneous();
+        clip.w > 0.0 && clip.z.abs() <= clip.w
+    }
+
+    #[test]
+    fn moved_objects_stay_in_the_depth_range() {
+        let mut state = State::new();
+        state.msg(Msg::ViewportResize(800.0, 600.0));
+        state.msg(Msg::PickMeshesChange(vec![cube(), cube()]));
+        let far_away = Point3::new(0.0, 0.0, -60.0);
+        assert!(!in_depth_range(&state, far_away));
+
+        state.set_transform(
+            1,
+            ObjectTransform {
+                translation: far_away.to_vec(),
+                ..ObjectTransform::default()
+            },
+        );
+        assert!(in_depth_range(&state, far_away));
+        assert!(in_depth_range(&state, Point3::new(0.0, 0.0, 0.0)));
+        let bounds = state.camera.scene_bounds();
+        assert!((bounds.min - Point3::new(-0.5, -0.5, -60.5)).magnitude() < 1e-4);
+        assert!((bounds.max - Point3::new(0.5, 0.5, 0.5)).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn scaled_objects_stay_in_the_depth_range() {
+        let mut state = State::new();
+        state.msg(Msg::ViewportResize(800.0, 600.0));
+        state.msg(Msg::PickMeshesChange(vec![cube()]));
+        let corner = Point3::new(0.0, 0.0, -40.0);
+        assert!(!in_depth_range(&state, corner));
+        state.set_transform(
+            0,
+            ObjectTransform {
+                scale: Vector3::new(1.0, 1.0, 80.0),
+                ..ObjectTransform::default()
+            },
+        );
+        assert!(in_depth_range(&state, corner));
+    }
 }
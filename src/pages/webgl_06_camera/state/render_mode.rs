@@ -0,0 +1,42 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    Solid,
+    Wireframe,
+    SolidWireframe,
+}
+
+impl RenderMode {
+    pub fn next(self) -> Self {
+        match self {
+            RenderMode::Solid => RenderMode::Wireframe,
+            RenderMode::Wireframe => RenderMode::SolidWireframe,
+            RenderMode::SolidWireframe => RenderMode::Solid,
+        }
+    }
+
+    pub fn has_solid(self) -> bool {
+        self != RenderMode::Wireframe
+    }
+
+    pub fn has_wires(self) -> bool {
+        self != RenderMode::Solid
+    }
+}
+
+/// How the wires are produced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireTechnique {
+    /// `GL::LINES` over an index buffer of the unique edges
+    Lines,
+    /// triangles shaded by their distance to the edges, anti-aliased with `fwidth`
+    Barycentric,
+}
+
+impl WireTechnique {
+    pub fn toggle(self) -> Self {
+        match self {
+            WireTechnique::Lines => WireTechnique::Barycentric,
+            WireTechnique::Barycentric => WireTechnique::Lines,
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use cgmath::{Matrix, Matrix4, SquareMatrix, Transform, Vector3, Vector4};
+use cgmath::{Matrix, Matrix4, SquareMatrix, Vector3, Vector4};
 use std::collections::HashMap;
 use std::mem::size_of;
 use web_sys::{WebGl2RenderingContext as GL, *};
 
+use super::state::PickHit;
 use super::Shader;
 use super::State;
 
@@ -66,10 +67,19 @@ impl Cube {
         }
     }
 
+    /// Face of a triangle of `indices`, faces are two triangles each.
+    pub fn face_of_triangle(triangle: usize) -> usize {
+        triangle / 2
+    }
+
     pub fn vertices(&self) -> &[f32] {
         &self.vertices
     }
 
+    pub fn positions(&self) -> Vec<f32> {
+        self.vertices.chunks(6).flat_map(|v| v[..3].to_vec()).collect()
+    }
+
     pub fn indices(&self) -> &[u16] {
         &self.indices
     }
@@ -79,10 +89,10 @@ impl Cube {
         self.vao = gl.create_vertex_array();
         gl.bind_vertex_array(self.vao.as_ref());
 
-        let vert_buffer = self.array_buffer_f32_data(&gl, self.vertices(), GL::STATIC_DRAW);
-        self.set_attrib_location_f32(&gl, "aVertexPosition", 3, 6 * size_of::<f32>(), 0);
-        self.set_attrib_location_f32(&gl, "aVertexNormal", 3, 6 * size_of::<f32>(), 3 * size_of::<f32>());
-        let indices_buffer = self.index_buffer_u16_data(&gl, self.indices());
+        let vert_buffer = self.array_buffer_f32_data(gl, self.vertices(), GL::STATIC_DRAW);
+        self.set_attrib_location_f32(gl, "aVertexPosition", 3, 6 * size_of::<f32>(), 0);
+        self.set_attrib_location_f32(gl, "aVertexNormal", 3, 6 * size_of::<f32>(), 3 * size_of::<f32>());
+        let indices_buffer = self.index_buffer_u16_data(gl, self.indices());
         self.vbos.insert("vert".into(), vert_buffer);
         self.vbos.insert("index".into(), indices_buffer);
 
@@ -94,18 +104,25 @@ impl Cube {
     pub fn render(&self, gl: &GL, state: &State) {
         gl.use_program(Some(&self.shader.program));
 
-        let model_matrix: Matrix4<f32> = Matrix4::one();
+        let model_matrix = state.model_matrix(0);
         let normal_matrix: Matrix4<f32> = model_matrix.invert().unwrap().transpose();
-        self.set_uniform_location_matrix4(&gl, "uModelMatrix", model_matrix);
-        self.set_uniform_location_matrix4(&gl, "uViewMatrix", state.camera().view_matrix());
-        self.set_uniform_location_matrix4(&gl, "uProjectiveMatrix", state.camera().projection_matrix());
-        self.set_uniform_location_matrix4(&gl, "uNormalMatrix", normal_matrix);
-
-        self.set_uniform_location_vector3(&gl, "uLightDirection", Vector3::new(10.0, 25.0, 10.0));
-        self.set_uniform_location_vector4(&gl, "uLightAmbient", Vector4::new(1.0, 1.0, 1.0, 1.0));
-        self.set_uniform_location_vector4(&gl, "uLightDiffuse", Vector4::new(1.0, 1.0, 1.0, 1.0));
-        self.set_uniform_location_vector4(&gl, "uMaterialAmbient", Vector4::new(0.4, 0.4, 0.4, 1.0));
-        self.set_uniform_location_vector4(&gl, "uMaterialDiffuse", Vector4::new(0.2, 0.2, 0.2, 1.0));
+        self.set_uniform_location_matrix4(gl, "uModelMatrix", model_matrix);
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", state.camera().view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", state.camera().projection_matrix());
+        self.set_uniform_location_matrix4(gl, "uNormalMatrix", normal_matrix);
+
+        self.set_uniform_location_vector3(gl, "uLightDirection", Vector3::new(10.0, 25.0, 10.0));
+        self.set_uniform_location_vector4(gl, "uLightAmbient", Vector4::new(1.0, 1.0, 1.0, 1.0));
+        self.set_uniform_location_vector4(gl, "uLightDiffuse", Vector4::new(1.0, 1.0, 1.0, 1.0));
+        self.set_uniform_location_vector4(gl, "uMaterialAmbient", Vector4::new(0.4, 0.4, 0.4, 1.0));
+        self.set_uniform_location_vector4(gl, "uMaterialDiffuse", Vector4::new(0.2, 0.2, 0.2, 1.0));
+
+        // the cube is the only pickable object
+        let face = |hit: Option<&PickHit>| hit.map_or(-1, |hit| Self::face_of_triangle(hit.triangle) as i32);
+        self.set_uniform_location_i32(gl, "uHoveredFace", face(state.hovered()));
+        self.set_uniform_location_i32(gl, "uSelectedFace", face(state.selected()));
+        self.set_uniform_location_vector4(gl, "uHoverColor", Vector4::new(1.0, 1.0, 1.0, 0.25));
+        self.set_uniform_location_vector4(gl, "uSelectColor", Vector4::new(1.0, 0.6, 0.1, 0.6));
 
         gl.bind_vertex_array(self.vao.as_ref());
         gl.draw_elements_with_i32(GL::TRIANGLES, self.indices().len() as i32, GL::UNSIGNED_SHORT, 0);
@@ -113,19 +130,24 @@ impl Cube {
     }
 
     pub fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
-        let location = self.shader.get_uniform_location(&gl, uniform_name);
+        let location = self.shader.get_uniform_location(gl, uniform_name);
         let array: &[f32; 16] = matrix.as_ref();
         gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
     }
 
+    pub fn set_uniform_location_i32(&self, gl: &GL, uniform_name: &str, value: i32) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        gl.uniform1i(location.as_ref(), value);
+    }
+
     pub fn set_uniform_location_vector3(&self, gl: &GL, uniform_name: &str, vector: Vector3<f32>) {
-        let location = self.shader.get_uniform_location(&gl, uniform_name);
+        let location = self.shader.get_uniform_location(gl, uniform_name);
         let array: &[f32; 3] = vector.as_ref();
         gl.uniform3fv_with_f32_array(location.as_ref(), &array[..]);
     }
 
     pub fn set_uniform_location_vector4(&self, gl: &GL, uniform_name: &str, vector: Vector4<f32>) {
-        let location = self.shader.get_uniform_location(&gl, uniform_name);
+        let location = self.shader.get_uniform_location(gl, uniform_name);
         let array: &[f32; 4] = vector.as_ref();
         gl.uniform4fv_with_f32_array(location.as_ref(), &array[..]);
     }
@@ -141,7 +163,7 @@ impl Cube {
     }
 
     pub fn set_attrib_location_f32(&self, gl: &GL, attrib_name: &str, size: i32, stride: usize, offset: usize) {
-        let location = self.shader.get_attrib_location(&gl, attrib_name);
+        let location = self.shader.get_attrib_location(gl, attrib_name);
         gl.enable_vertex_attrib_array(location as u32);
         gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, stride as i32, offset as i32);
     }
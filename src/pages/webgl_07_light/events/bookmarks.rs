@@ -0,0 +1,77 @@
+use super::super::state::{Bookmarks, Msg};
+use super::super::App;
+
+/// Bookmarks go to `localStorage`, under a key of their own for each lesson page.
+fn storage_key() -> String {
+    let pathname = web_sys::window().unwrap().location().pathname().unwrap_or_default();
+    format!("webgl-practices:{}:bookmarks", pathname)
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window().unwrap().local_storage().ok().flatten()
+}
+
+/// Restores the bookmarks saved by a previous visit.
+pub fn load(app: &App) {
+    let json = match storage().and_then(|s| s.get_item(&storage_key()).ok().flatten()) {
+        Some(json) => json,
+        None => return,
+    };
+    match Bookmarks::from_json(&json) {
+        Ok(bookmarks) => app.state.borrow_mut().msg(Msg::LoadBookmarks(bookmarks)),
+        Err(e) => log::warn!("saved bookmarks ignored: {}", e),
+    }
+}
+
+fn persist(app: &App) {
+    let json = app.state.borrow().bookmarks().to_json();
+    if let Some(storage) = storage() {
+        if storage.set_item(&storage_key(), &json).is_err() {
+            log::warn!("bookmarks could not be saved");
+        }
+    }
+}
+
+/// Asks for a name and saves the current view in `slot`.
+pub fn save(app: &App, slot: u8) {
+    let window = web_sys::window().unwrap();
+    let default_name = app
+        .state
+        .borrow()
+        .bookmarks()
+        .get(slot)
+        .map(|b| b.name.clone())
+        .unwrap_or_else(|| format!("View {}", slot));
+    let name = match window.prompt_with_message_and_default(&format!("Bookmark {} name", slot), &default_name) {
+        Ok(Some(name)) => name,
+        _ => return,
+    };
+    app.state.borrow_mut().msg(Msg::SaveBookmark(slot, name));
+    persist(app);
+}
+
+/// Shows the bookmarks as JSON, ready to be copied into a bug report.
+pub fn export(app: &App) {
+    let json = app.state.borrow().bookmarks().to_json();
+    log::info!("bookmarks:\n{}", json);
+    let window = web_sys::window().unwrap();
+    let _ = window.prompt_with_message_and_default("Bookmarks, copy them with Ctrl+C", &json.replace('\n', ""));
+}
+
+/// Replaces the bookmarks with pasted JSON.
+pub fn import(app: &App) {
+    let window = web_sys::window().unwrap();
+    let json = match window.prompt_with_message("Paste the bookmarks JSON") {
+        Ok(Some(json)) if !json.trim().is_empty() => json,
+        _ => return,
+    };
+    match Bookmarks::from_json(&json) {
+        Ok(bookmarks) => {
+            app.state.borrow_mut().msg(Msg::LoadBookmarks(bookmarks));
+            persist(app);
+        }
+        Err(e) => {
+            let _ = window.alert_with_message(&format!("Invalid bookmarks: {}", e));
+        }
+    }
+}
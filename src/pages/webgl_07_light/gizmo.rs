@@ -0,0 +1,202 @@
+use cgmath::{EuclideanSpace, Matrix4, Vector3};
+use std::f32::consts::PI;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::state::{GizmoHandle, GizmoMode, ARROW_HEAD_RADIUS, ARROW_SHAFT, CENTER_BOX, PLANE_MAX, PLANE_MIN, SCALE_BOX};
+use super::Shader;
+use super::State;
+
+/// segments of the rings and of the arrow cones
+const SEGMENTS: usize = 48;
+const AXIS_COLORS: [[f32; 4]; 3] = [[0.9, 0.2, 0.2, 1.0], [0.3, 0.8, 0.2, 1.0], [0.2, 0.4, 0.9, 1.0]];
+const UNIFORM_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.85, 0.1, 1.0];
+/// the plane squares are see-through, so the arrows stay visible behind them
+const PLANE_ALPHA: f32 = 0.4;
+
+/// One draw call of the gizmo, all with the same handle and color.
+struct GizmoPart {
+    mode: GizmoMode,
+    handle: GizmoHandle,
+    primitive: u32,
+    first: i32,
+    count: i32,
+}
+
+/// Draws the translate, rotate and scale handles of the selected object,
+/// over everything else and at the same size on screen at any distance.
+pub struct GizmoRenderer {
+    shader: Shader,
+    vao: Option<WebGlVertexArrayObject>,
+    vbo: Option<WebGlBuffer>,
+    parts: Vec<GizmoPart>,
+}
+
+/// Accumulates the positions of every part, in gizmo units.
+#[derive(Default)]
+struct GizmoGeometry {
+    positions: Vec<f32>,
+    parts: Vec<GizmoPart>,
+}
+
+impl GizmoGeometry {
+    fn part(&mut self, mode: GizmoMode, handle: GizmoHandle, primitive: u32, points: &[Vector3<f32>]) {
+        let first = (self.positions.len() / 3) as i32;
+        for p in points {
+            self.positions.extend_from_slice(&[p.x, p.y, p.z]);
+        }
+        self.parts.push(GizmoPart {
+            mode,
+            handle,
+            primitive,
+            first,
+            count: points.len() as i32,
+        });
+    }
+}
+
+fn unit(axis: usize) -> Vector3<f32> {
+    let mut unit = Vector3::new(0.0, 0.0, 0.0);
+    unit[axis] = 1.0;
+    unit
+}
+
+/// `SEGMENTS` points around `axis`, in the plane across it.
+fn circle(axis: usize, center: Vector3<f32>, radius: f32) -> Vec<Vector3<f32>> {
+    let (u, v) = (unit((axis + 1) % 3), unit((axis + 2) % 3));
+    (0..SEGMENTS)
+        .map(|i| {
+            let angle = i as f32 / SEGMENTS as f32 * 2.0 * PI;
+            center + (u * angle.cos() + v * angle.sin()) * radius
+        })
+        .collect()
+}
+
+/// Triangles of an axis aligned box.
+fn solid_box(center: Vector3<f32>, half: f32) -> Vec<Vector3<f32>> {
+    let corner = |i: usize| center + Vector3::new(half * sign(i & 1), half * sign(i & 2), half * sign(i & 4));
+    // two triangles per face, over the corner numbering of `corner`
+    let faces: [[usize; 4]; 6] = [[0, 2, 3, 1], [4, 5, 7, 6], [0, 1, 5, 4], [2, 6, 7, 3], [0, 4, 6, 2], [1, 3, 7, 5]];
+    faces
+        .iter()
+        .flat_map(|f| vec![corner(f[0]), corner(f[1]), corner(f[2]), corner(f[0]), corner(f[2]), corner(f[3])])
+        .collect()
+}
+
+fn sign(bit: usize) -> f32 {
+    if bit == 0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+fn build_geometry() -> GizmoGeometry {
+    let mut geometry = GizmoGeometry::default();
+    let origin = Vector3::new(0.0, 0.0, 0.0);
+    for axis in 0..3 {
+        let tip = unit(axis);
+
+        let handle = GizmoHandle::Axis(axis);
+        geometry.part(GizmoMode::Translate, handle, GL::LINES, &[origin, tip * ARROW_SHAFT]);
+        let base = circle(axis, tip * ARROW_SHAFT, ARROW_HEAD_RADIUS);
+        let cone: Vec<_> = (0..SEGMENTS).flat_map(|i| vec![tip, base[i], base[(i + 1) % SEGMENTS]]).collect();
+        geometry.part(GizmoMode::Translate, handle, GL::TRIANGLES, &cone);
+
+        let (u, v) = (unit((axis + 1) % 3), unit((axis + 2) % 3));
+        let corner = |a: f32, b: f32| u * a + v * b;
+        let square = [
+            corner(PLANE_MIN, PLANE_MIN),
+            corner(PLANE_MAX, PLANE_MIN),
+            corner(PLANE_MAX, PLANE_MAX),
+            corner(PLANE_MIN, PLANE_MIN),
+            corner(PLANE_MAX, PLANE_MAX),
+            corner(PLANE_MIN, PLANE_MAX),
+        ];
+        geometry.part(GizmoMode::Translate, GizmoHandle::Plane(axis), GL::TRIANGLES, &square);
+
+        geometry.part(GizmoMode::Rotate, GizmoHandle::Ring(axis), GL::LINE_LOOP, &circle(axis, origin, 1.0));
+
+        geometry.part(GizmoMode::Scale, handle, GL::LINES, &[origin, tip * (1.0 - SCALE_BOX)]);
+        geometry.part(GizmoMode::Scale, handle, GL::TRIANGLES, &solid_box(tip, SCALE_BOX));
+    }
+    geometry.part(GizmoMode::Scale, GizmoHandle::Uniform, GL::TRIANGLES, &solid_box(origin, CENTER_BOX));
+    geometry
+}
+
+impl GizmoRenderer {
+    /// `shader` is a wire shader, its plain color lines suit the handles.
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            vao: None,
+            vbo: None,
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        let geometry = build_geometry();
+        gl.use_program(Some(&self.shader.program));
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(&geometry.positions);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        let location = self.shader.get_attrib_location(gl, "aVertexPosition");
+        gl.enable_vertex_attrib_array(location as u32);
+        gl.vertex_attrib_pointer_with_i32(location as u32, 3, GL::FLOAT, false, 0, 0);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        self.vbo = Some(vbo);
+        self.parts = geometry.parts;
+    }
+
+    pub fn render(&self, gl: &GL, state: &State) {
+        let gizmo = match state.gizmo() {
+            Some(gizmo) => gizmo,
+            None => return,
+        };
+        gl.use_program(Some(&self.shader.program));
+
+        let model_matrix = Matrix4::from_translation(gizmo.origin.to_vec()) * Matrix4::from_scale(gizmo.size);
+        self.set_uniform_location_matrix4(gl, "uModelMatrix", model_matrix);
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", state.camera().view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", state.camera().projection_matrix());
+        let barycentric = self.shader.get_uniform_location(gl, "uBarycentric");
+        gl.uniform1i(barycentric.as_ref(), 0);
+        let color = self.shader.get_uniform_location(gl, "uWireColor");
+
+        // a gizmo hidden inside its object could not be grabbed
+        gl.disable(GL::DEPTH_TEST);
+        gl.enable(GL::BLEND);
+        gl.blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
+        gl.bind_vertex_array(self.vao.as_ref());
+        for part in self.parts.iter().filter(|p| p.mode == gizmo.mode) {
+            let mut rgba = match part.handle {
+                _ if gizmo.highlighted == Some(part.handle) => HIGHLIGHT_COLOR,
+                GizmoHandle::Axis(axis) | GizmoHandle::Plane(axis) | GizmoHandle::Ring(axis) => AXIS_COLORS[axis],
+                GizmoHandle::Uniform => UNIFORM_COLOR,
+            };
+            if let GizmoHandle::Plane(_) = part.handle {
+                rgba[3] = PLANE_ALPHA;
+            }
+            gl.uniform4fv_with_f32_array(color.as_ref(), &rgba);
+            gl.draw_arrays(part.primitive, part.first, part.count);
+        }
+        gl.bind_vertex_array(None);
+        gl.disable(GL::BLEND);
+        gl.enable(GL::DEPTH_TEST);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+}
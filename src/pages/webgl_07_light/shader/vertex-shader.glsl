@@ -12,16 +12,32 @@ uniform vec4 uLightDiffuse;
 uniform vec4 uMaterialAmbient;
 uniform vec4 uMaterialDiffuse;
 
+// faces to tint, -1 for none
+uniform int uHoveredFace;
+uniform int uSelectedFace;
+uniform vec4 uHoverColor;
+uniform vec4 uSelectColor;
+
 in vec3 aVertexPosition;
 in vec3 aVertexNormal;
 
 out vec4 vVertexColor;
 
 void main(void) {
-    vec4 transformedNormal =  uNormalMatrix * vec4(aVertexNormal, 1.0);
+    // w = 0, a normal is a direction and must not pick up the translation
+    vec4 transformedNormal = uNormalMatrix * vec4(aVertexNormal, 0.0);
     float lambertTerm = dot(normalize(transformedNormal.xyz), normalize(uLightDirection));
     vVertexColor = uLightAmbient * uMaterialAmbient + uLightDiffuse * uMaterialDiffuse * lambertTerm;
     vVertexColor = vec4(vVertexColor.xyz, 1.0);
 
+    // every face has four vertices of its own
+    int face = gl_VertexID / 4;
+    if (face == uSelectedFace) {
+        vVertexColor = mix(vVertexColor, uSelectColor, uSelectColor.a);
+    } else if (face == uHoveredFace) {
+        vVertexColor = mix(vVertexColor, uHoverColor, uHoverColor.a);
+    }
+    vVertexColor.a = 1.0;
+
     gl_Position = uProjectiveMatrix * uViewMatrix * uModelMatrix * vec4(aVertexPosition, 1.0);
 }
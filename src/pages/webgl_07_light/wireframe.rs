@@ -0,0 +1,126 @@
+use cgmath::Matrix4;
+use std::collections::HashMap;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::edges::{barycentric_triangles, unique_edges};
+use super::state::WireTechnique;
+use super::Shader;
+use super::State;
+
+/// Draws the edges of a triangle mesh, either as `GL::LINES` over the unique
+/// edges or as a barycentric shaded copy of the triangles.
+pub struct Wireframe {
+    shader: Shader,
+    line_index_count: i32,
+    triangle_vertex_count: i32,
+    lines_vao: Option<WebGlVertexArrayObject>,
+    barycentric_vao: Option<WebGlVertexArrayObject>,
+    vbos: HashMap<String, WebGlBuffer>,
+}
+
+impl Wireframe {
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            line_index_count: 0,
+            triangle_vertex_count: 0,
+            lines_vao: None,
+            barycentric_vao: None,
+            vbos: HashMap::new(),
+        }
+    }
+
+    /// `positions` holds three floats per vertex, `indices` is a triangle list.
+    pub fn prepare_for_render(&mut self, gl: &GL, positions: &[f32], indices: &[u16]) {
+        gl.use_program(Some(&self.shader.program));
+
+        let line_indices = unique_edges(indices);
+        self.line_index_count = line_indices.len() as i32;
+        self.lines_vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.lines_vao.as_ref());
+        let vert_buffer = self.array_buffer_f32_data(gl, positions, "aVertexPosition", 3);
+        let line_buffer = self.index_buffer_u16_data(gl, &line_indices);
+        self.vbos.insert("vert".into(), vert_buffer);
+        self.vbos.insert("line_index".into(), line_buffer);
+
+        let (unrolled, barycentrics) = barycentric_triangles(positions, indices);
+        self.triangle_vertex_count = (unrolled.len() / 3) as i32;
+        self.barycentric_vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.barycentric_vao.as_ref());
+        let unrolled_buffer = self.array_buffer_f32_data(gl, &unrolled, "aVertexPosition", 3);
+        let barycentric_buffer = self.array_buffer_f32_data(gl, &barycentrics, "aBarycentric", 3);
+        self.vbos.insert("unrolled_vert".into(), unrolled_buffer);
+        self.vbos.insert("barycentric".into(), barycentric_buffer);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    pub fn render(&self, gl: &GL, state: &State, model_matrix: Matrix4<f32>) {
+        gl.use_program(Some(&self.shader.program));
+
+        self.set_uniform_location_matrix4(gl, "uModelMatrix", model_matrix);
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", state.camera().view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", state.camera().projection_matrix());
+        let wire_color = self.shader.get_uniform_location(gl, "uWireColor");
+        gl.uniform4fv_with_f32_array(wire_color.as_ref(), &[1.0, 1.0, 1.0, 1.0]);
+        let wire_width = self.shader.get_uniform_location(gl, "uWireWidth");
+        gl.uniform1f(wire_width.as_ref(), 1.0);
+
+        // wires sit exactly on the faces, so they must pass an equal depth
+        // and blend over what is already drawn without hiding each other
+        gl.enable(GL::BLEND);
+        gl.blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
+        gl.depth_func(GL::LEQUAL);
+        gl.depth_mask(false);
+
+        let barycentric = self.shader.get_uniform_location(gl, "uBarycentric");
+        match state.wire_technique() {
+            WireTechnique::Lines => {
+                gl.uniform1i(barycentric.as_ref(), 0);
+                gl.bind_vertex_array(self.lines_vao.as_ref());
+                gl.draw_elements_with_i32(GL::LINES, self.line_index_count, GL::UNSIGNED_SHORT, 0);
+            }
+            WireTechnique::Barycentric => {
+                gl.uniform1i(barycentric.as_ref(), 1);
+                gl.bind_vertex_array(self.barycentric_vao.as_ref());
+                gl.draw_arrays(GL::TRIANGLES, 0, self.triangle_vertex_count);
+            }
+        }
+        gl.bind_vertex_array(None);
+
+        gl.depth_mask(true);
+        gl.depth_func(GL::LESS);
+        gl.disable(GL::BLEND);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+
+    fn array_buffer_f32_data(&self, gl: &GL, data: &[f32], attrib_name: &str, size: i32) -> WebGlBuffer {
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(data);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        let location = self.shader.get_attrib_location(gl, attrib_name);
+        gl.enable_vertex_attrib_array(location as u32);
+        gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, 0, 0);
+        vbo
+    }
+
+    fn index_buffer_u16_data(&self, gl: &GL, data: &[u16]) -> WebGlBuffer {
+        let ebo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(data);
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        ebo
+    }
+}
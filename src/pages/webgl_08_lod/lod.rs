@@ -0,0 +1,74 @@
+use super::simplify::simplify;
+
+/// Index buffers of one mesh at decreasing triangle counts, all of them
+/// referencing the same vertex buffer.
+pub struct LodChain {
+    levels: Vec<Vec<u16>>,
+    /// a level `i + 1` is used once the projected radius drops below `thresholds[i]` pixels
+    thresholds: Vec<f32>,
+}
+
+impl LodChain {
+    /// `ratios` are fractions of the source triangle count, one per extra level.
+    /// Every level is simplified from the previous one, so a coarser level
+    /// never brings back detail removed from a finer one.
+    pub fn build(positions: &[[f32; 3]], indices: &[u16], ratios: &[f32], thresholds: &[f32]) -> Self {
+        assert_eq!(ratios.len(), thresholds.len(), "one screen size threshold per extra level");
+        let mut levels = vec![indices.to_vec()];
+        for &ratio in ratios {
+            let target = ((indices.len() / 3) as f32 * ratio) as usize * 3;
+            let coarser = simplify(positions, levels.last().unwrap(), target);
+            levels.push(coarser);
+        }
+        Self {
+            levels,
+            thresholds: thresholds.to_vec(),
+        }
+    }
+
+    pub fn levels(&self) -> &[Vec<u16>] {
+        &self.levels
+    }
+
+    pub fn level(&self, lod: usize) -> &[u16] {
+        &self.levels[lod]
+    }
+
+    /// Picks a level from the radius of the object projected on screen, in pixels.
+    pub fn select(&self, screen_radius: f32) -> usize {
+        let lod = self.thresholds.iter().take_while(|&&t| screen_radius < t).count();
+        lod.min(self.levels.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pages::webgl_08_lod::mesh::Mesh;
+
+    fn chain() -> LodChain {
+        let torus = Mesh::torus(1.0, 0.4, 32, 16);
+        LodChain::build(&torus.positions(), torus.indices(), &[0.5, 0.25], &[100.0, 40.0])
+    }
+
+    #[test]
+    fn every_level_is_coarser_than_the_previous() {
+        let chain = chain();
+        assert_eq!(chain.levels().len(), 3);
+        let source = chain.level(0).len();
+        assert!(chain.level(1).len() <= source / 2);
+        assert!(chain.level(2).len() <= source / 4);
+        assert!(!chain.level(2).is_empty());
+    }
+
+    #[test]
+    fn thresholds_pick_the_level() {
+        let chain = chain();
+        assert_eq!(chain.select(500.0), 0);
+        assert_eq!(chain.select(100.0), 0);
+        assert_eq!(chain.select(99.0), 1);
+        assert_eq!(chain.select(40.0), 1);
+        assert_eq!(chain.select(39.0), 2);
+        assert_eq!(chain.select(0.0), 2);
+    }
+}
@@ -0,0 +1,148 @@
+use cgmath::{Matrix, Matrix4, Point3, SquareMatrix, Vector3, Vector4};
+use std::collections::HashMap;
+use std::mem::size_of;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::lod::LodChain;
+use super::mesh::{Mesh, VERTEX_SIZE};
+use super::Shader;
+use super::State;
+
+/// tint of each level in the lod debug view, finest first
+const LOD_TINTS: [[f32; 4]; 5] = [
+    [1.0, 1.0, 1.0, 1.0],
+    [0.3, 1.0, 0.3, 1.0],
+    [0.3, 0.6, 1.0, 1.0],
+    [1.0, 0.9, 0.2, 1.0],
+    [1.0, 0.3, 0.3, 1.0],
+];
+
+pub struct LodMesh {
+    mesh: Mesh,
+    lods: LodChain,
+    bounding_center: Point3<f32>,
+    bounding_radius: f32,
+    shader: Shader,
+    vao: Option<WebGlVertexArrayObject>,
+    vbos: HashMap<String, WebGlBuffer>,
+    ebos: Vec<WebGlBuffer>,
+}
+
+impl LodMesh {
+    pub fn new(shader: Shader) -> Self {
+        let mesh = Mesh::torus(1.0, 0.35, 128, 48);
+        let lods = LodChain::build(&mesh.positions(), mesh.indices(), &[0.5, 0.2, 0.08, 0.02], &[180.0, 90.0, 45.0, 20.0]);
+        for (i, level) in lods.levels().iter().enumerate() {
+            log::debug!("lod {}: {} triangles", i, level.len() / 3);
+        }
+        let (center, radius) = mesh.bounding_sphere();
+        Self {
+            mesh,
+            lods,
+            bounding_center: Point3::new(center[0], center[1], center[2]),
+            bounding_radius: radius,
+            shader,
+            vao: None,
+            vbos: HashMap::new(),
+            ebos: Vec::new(),
+        }
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        gl.use_program(Some(&self.shader.program));
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        let stride = VERTEX_SIZE * size_of::<f32>();
+        let vert_buffer = self.array_buffer_f32_data(gl, self.mesh.vertices(), GL::STATIC_DRAW);
+        self.set_attrib_location_f32(gl, "aVertexPosition", 3, stride, 0);
+        self.set_attrib_location_f32(gl, "aVertexNormal", 3, stride, 3 * size_of::<f32>());
+        self.vbos.insert("vert".into(), vert_buffer);
+
+        // every level shares the vertex buffer, only the element buffer is swapped
+        self.ebos = self.lods.levels().iter().map(|level| self.index_buffer_u16_data(gl, level)).collect();
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    pub fn render(&self, gl: &GL, state: &State, offsets: &[Vector3<f32>]) {
+        gl.use_program(Some(&self.shader.program));
+
+        let camera = state.camera();
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", camera.view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", camera.projection_matrix());
+        self.set_uniform_location_vector3(gl, "uLightDirection", Vector3::new(10.0, 25.0, 10.0));
+        self.set_uniform_location_vector4(gl, "uLightAmbient", Vector4::new(1.0, 1.0, 1.0, 1.0));
+        self.set_uniform_location_vector4(gl, "uLightDiffuse", Vector4::new(1.0, 1.0, 1.0, 1.0));
+        self.set_uniform_location_vector4(gl, "uMaterialAmbient", Vector4::new(0.2, 0.2, 0.2, 1.0));
+        self.set_uniform_location_vector4(gl, "uMaterialDiffuse", Vector4::new(0.8, 0.8, 0.8, 1.0));
+
+        gl.bind_vertex_array(self.vao.as_ref());
+        for &offset in offsets {
+            let model_matrix = Matrix4::from_translation(offset);
+            let normal_matrix = model_matrix.invert().unwrap().transpose();
+            self.set_uniform_location_matrix4(gl, "uModelMatrix", model_matrix);
+            self.set_uniform_location_matrix4(gl, "uNormalMatrix", normal_matrix);
+
+            let center = self.bounding_center + offset;
+            let screen_radius = camera.screen_radius(center, self.bounding_radius, state.viewport().height());
+            let lod = self.lods.select(screen_radius);
+            let tint = if state.lod_debug() {
+                LOD_TINTS[lod.min(LOD_TINTS.len() - 1)]
+            } else {
+                LOD_TINTS[0]
+            };
+            self.set_uniform_location_vector4(gl, "uLodTint", Vector4::from(tint));
+
+            gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&self.ebos[lod]));
+            gl.draw_elements_with_i32(GL::TRIANGLES, self.lods.level(lod).len() as i32, GL::UNSIGNED_SHORT, 0);
+        }
+        gl.bind_vertex_array(None);
+    }
+
+    pub fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+
+    pub fn set_uniform_location_vector3(&self, gl: &GL, uniform_name: &str, vector: Vector3<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 3] = vector.as_ref();
+        gl.uniform3fv_with_f32_array(location.as_ref(), &array[..]);
+    }
+
+    pub fn set_uniform_location_vector4(&self, gl: &GL, uniform_name: &str, vector: Vector4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 4] = vector.as_ref();
+        gl.uniform4fv_with_f32_array(location.as_ref(), &array[..]);
+    }
+
+    pub fn array_buffer_f32_data(&self, gl: &GL, data: &[f32], usage: u32) -> WebGlBuffer {
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(data);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, usage);
+        }
+        vbo
+    }
+
+    pub fn set_attrib_location_f32(&self, gl: &GL, attrib_name: &str, size: i32, stride: usize, offset: usize) {
+        let location = self.shader.get_attrib_location(gl, attrib_name);
+        gl.enable_vertex_attrib_array(location as u32);
+        gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, stride as i32, offset as i32);
+    }
+
+    pub fn index_buffer_u16_data(&self, gl: &GL, data: &[u16]) -> WebGlBuffer {
+        let ebo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(data);
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        ebo
+    }
+}
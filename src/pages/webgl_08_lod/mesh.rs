@@ -0,0 +1,81 @@
+use std::f32::consts::PI;
+
+/// floats per vertex: position(3) + normal(3) + uv(2)
+pub const VERTEX_SIZE: usize = 8;
+
+pub struct Mesh {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// Torus around the y axis. The first and last ring/column are duplicated,
+    /// so the mesh has a uv seam in both directions.
+    pub fn torus(major_radius: f32, minor_radius: f32, major_segments: usize, minor_segments: usize) -> Self {
+        let mut vertices = Vec::with_capacity((major_segments + 1) * (minor_segments + 1) * VERTEX_SIZE);
+        for i in 0..=major_segments {
+            let u = i as f32 / major_segments as f32;
+            // wrap the angle so the seam vertices get bit-identical positions
+            let (sin_u, cos_u) = ((i % major_segments) as f32 / major_segments as f32 * 2.0 * PI).sin_cos();
+            for j in 0..=minor_segments {
+                let v = j as f32 / minor_segments as f32;
+                let (sin_v, cos_v) = ((j % minor_segments) as f32 / minor_segments as f32 * 2.0 * PI).sin_cos();
+                let ring = major_radius + minor_radius * cos_v;
+                vertices.extend_from_slice(&[
+                    ring * cos_u,
+                    minor_radius * sin_v,
+                    ring * sin_u,
+                    cos_v * cos_u,
+                    sin_v,
+                    cos_v * sin_u,
+                    u,
+                    v,
+                ]);
+            }
+        }
+
+        let columns = minor_segments + 1;
+        let mut indices = Vec::with_capacity(major_segments * minor_segments * 6);
+        for i in 0..major_segments {
+            for j in 0..minor_segments {
+                let a = (i * columns + j) as u16;
+                let b = ((i + 1) * columns + j) as u16;
+                let c = ((i + 1) * columns + j + 1) as u16;
+                let d = (i * columns + j + 1) as u16;
+                indices.extend_from_slice(&[a, d, b, b, d, c]);
+            }
+        }
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+
+    pub fn positions(&self) -> Vec<[f32; 3]> {
+        self.vertices.chunks(VERTEX_SIZE).map(|v| [v[0], v[1], v[2]]).collect()
+    }
+
+    /// center and radius of a sphere enclosing every vertex
+    pub fn bounding_sphere(&self) -> ([f32; 3], f32) {
+        let positions = self.positions();
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in &positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        let center = [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5];
+        let radius = positions
+            .iter()
+            .map(|p| ((p[0] - center[0]).powi(2) + (p[1] - center[1]).powi(2) + (p[2] - center[2]).powi(2)).sqrt())
+            .fold(0.0, f32::max);
+        (center, radius)
+    }
+}
@@ -0,0 +1,501 @@
+//! Quadric error metric simplification (Garland & Heckbert, 1997).
+//!
+//! Edges are removed with half-edge collapses: a vertex is merged into one of
+//! its neighbours instead of a new optimal position, so every level of detail
+//! keeps indexing the original vertex buffer and no attribute is interpolated.
+//! Vertices that share a position with another vertex (uv/normal seams) and
+//! vertices on open borders are never moved, which keeps seams and outlines
+//! intact. The result only depends on the input, never on hash ordering.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexKind {
+    /// interior vertex that owns all of its attributes, free to collapse
+    Manifold,
+    /// vertex on an open edge of the surface
+    Border,
+    /// vertex sharing its position with another vertex of different attributes
+    Seam,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Quadric {
+    a2: f64,
+    ab: f64,
+    ac: f64,
+    ad: f64,
+    b2: f64,
+    bc: f64,
+    bd: f64,
+    c2: f64,
+    cd: f64,
+    d2: f64,
+}
+
+impl Quadric {
+    fn from_plane(a: f64, b: f64, c: f64, d: f64, weight: f64) -> Self {
+        Self {
+            a2: a * a * weight,
+            ab: a * b * weight,
+            ac: a * c * weight,
+            ad: a * d * weight,
+            b2: b * b * weight,
+            bc: b * c * weight,
+            bd: b * d * weight,
+            c2: c * c * weight,
+            cd: c * d * weight,
+            d2: d * d * weight,
+        }
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        self.a2 += other.a2;
+        self.ab += other.ab;
+        self.ac += other.ac;
+        self.ad += other.ad;
+        self.b2 += other.b2;
+        self.bc += other.bc;
+        self.bd += other.bd;
+        self.c2 += other.c2;
+        self.cd += other.cd;
+        self.d2 += other.d2;
+    }
+
+    fn error(&self, p: [f64; 3]) -> f64 {
+        let [x, y, z] = p;
+        let e = x * x * self.a2
+            + y * y * self.b2
+            + z * z * self.c2
+            + 2.0 * (x * y * self.ab + x * z * self.ac + y * z * self.bc)
+            + 2.0 * (x * self.ad + y * self.bd + z * self.cd)
+            + self.d2;
+        e.abs()
+    }
+}
+
+struct Collapse {
+    cost: f64,
+    from: u32,
+    to: u32,
+    stamp_from: u32,
+    stamp_to: u32,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    // `BinaryHeap` is a max-heap: the cheapest collapse must compare greatest,
+    // ties are broken by vertex index then by stamp, so no two collapses
+    // compare equal and the pop order never depends on the push order
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.from.cmp(&self.from))
+            .then_with(|| other.to.cmp(&self.to))
+            .then_with(|| self.stamp_from.cmp(&other.stamp_from))
+            .then_with(|| self.stamp_to.cmp(&other.stamp_to))
+    }
+}
+
+/// Classifies every vertex by how much it may be moved.
+pub fn classify_vertices(positions: &[[f32; 3]], indices: &[u16]) -> Vec<VertexKind> {
+    let canonical = canonical_vertices(positions);
+    let mut group_size = vec![0u32; positions.len()];
+    for &c in &canonical {
+        group_size[c as usize] += 1;
+    }
+
+    let mut edge_count: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+    for tri in indices.chunks(3) {
+        for k in 0..3 {
+            let a = canonical[tri[k] as usize];
+            let b = canonical[tri[(k + 1) % 3] as usize];
+            *edge_count.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    let mut on_border = vec![false; positions.len()];
+    for (&(a, b), &count) in &edge_count {
+        if count == 1 {
+            on_border[a as usize] = true;
+            on_border[b as usize] = true;
+        }
+    }
+
+    canonical
+        .iter()
+        .map(|&c| {
+            if group_size[c as usize] > 1 {
+                VertexKind::Seam
+            } else if on_border[c as usize] {
+                VertexKind::Border
+            } else {
+                VertexKind::Manifold
+            }
+        })
+        .collect()
+}
+
+/// Reduces `indices` to at most `target_index_count` indices when possible.
+/// The returned indices reference the same vertices as the input.
+pub fn simplify(positions: &[[f32; 3]], indices: &[u16], target_index_count: usize) -> Vec<u16> {
+    assert_eq!(indices.len() % 3, 0, "indices should be a triangle list");
+    let target_triangles = target_index_count / 3;
+    let mut triangles: Vec<[u32; 3]> = indices.chunks(3).map(|t| [u32::from(t[0]), u32::from(t[1]), u32::from(t[2])]).collect();
+    if triangles.len() <= target_triangles {
+        return indices.to_vec();
+    }
+
+    let kinds = classify_vertices(positions, indices);
+    let canonical = canonical_vertices(positions);
+    let points: Vec<[f64; 3]> = positions.iter().map(|p| [f64::from(p[0]), f64::from(p[1]), f64::from(p[2])]).collect();
+
+    // quadrics and triangle adjacency are kept per position, so that all the
+    // wedges of a seam vertex see the same neighbourhood
+    let mut quadrics = vec![Quadric::default(); positions.len()];
+    let mut adjacency: Vec<Vec<u32>> = vec![Vec::new(); positions.len()];
+    for (t, tri) in triangles.iter().enumerate() {
+        if let Some(quadric) = triangle_quadric(&points, tri) {
+            for &v in tri {
+                quadrics[canonical[v as usize] as usize].add(&quadric);
+            }
+        }
+        for &v in tri {
+            adjacency[canonical[v as usize] as usize].push(t as u32);
+        }
+    }
+
+    let mut alive = vec![true; triangles.len()];
+    let mut alive_count = triangles.len();
+    let mut stamps = vec![0u32; positions.len()];
+    let mut heap = BinaryHeap::new();
+    for tri in &triangles {
+        for k in 0..3 {
+            push_collapses(&mut heap, &kinds, &canonical, &quadrics, &points, &stamps, tri[k], tri[(k + 1) % 3]);
+        }
+    }
+
+    while alive_count > target_triangles {
+        let collapse = match heap.pop() {
+            Some(collapse) => collapse,
+            None => break,
+        };
+        let from = canonical[collapse.from as usize] as usize;
+        let to = canonical[collapse.to as usize] as usize;
+        if stamps[from] != collapse.stamp_from || stamps[to] != collapse.stamp_to {
+            continue;
+        }
+        if !is_collapse_valid(&triangles, &alive, &adjacency, &canonical, &points, collapse.from, collapse.to) {
+            continue;
+        }
+
+        for &t in &adjacency[from].clone() {
+            let t = t as usize;
+            if !alive[t] {
+                continue;
+            }
+            let tri = &mut triangles[t];
+            if tri.iter().any(|&v| canonical[v as usize] as usize == to) {
+                alive[t] = false;
+                alive_count -= 1;
+                continue;
+            }
+            for v in tri.iter_mut() {
+                if *v == collapse.from {
+                    *v = collapse.to;
+                }
+            }
+            adjacency[to].push(t as u32);
+        }
+        adjacency[from].clear();
+        let merged = quadrics[from];
+        quadrics[to].add(&merged);
+        stamps[from] += 1;
+        stamps[to] += 1;
+        adjacency[to].retain(|&t| alive[t as usize]);
+
+        for &t in &adjacency[to] {
+            let tri = triangles[t as usize];
+            for k in 0..3 {
+                push_collapses(&mut heap, &kinds, &canonical, &quadrics, &points, &stamps, tri[k], tri[(k + 1) % 3]);
+            }
+        }
+    }
+
+    triangles
+        .iter()
+        .zip(alive.iter())
+        .filter(|(_, &alive)| alive)
+        .flat_map(|(tri, _)| tri.iter().map(|&v| v as u16))
+        .collect()
+}
+
+/// Maps each vertex to the lowest index of a vertex with the same position.
+fn canonical_vertices(positions: &[[f32; 3]]) -> Vec<u32> {
+    let mut first_seen: HashMap<[u32; 3], u32> = HashMap::new();
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            // adding zero folds -0.0 into 0.0
+            let key = [(p[0] + 0.0).to_bits(), (p[1] + 0.0).to_bits(), (p[2] + 0.0).to_bits()];
+            *first_seen.entry(key).or_insert(i as u32)
+        })
+        .collect()
+}
+
+fn triangle_quadric(points: &[[f64; 3]], tri: &[u32; 3]) -> Option<Quadric> {
+    let p0 = points[tri[0] as usize];
+    let normal = triangle_normal(points[tri[0] as usize], points[tri[1] as usize], points[tri[2] as usize]);
+    let length = dot(normal, normal).sqrt();
+    if length <= f64::EPSILON {
+        return None;
+    }
+    let n = [normal[0] / length, normal[1] / length, normal[2] / length];
+    let d = -dot(n, p0);
+    // area weighted, so large faces dominate the error
+    Some(Quadric::from_plane(n[0], n[1], n[2], d, length * 0.5))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_collapses(
+    heap: &mut BinaryHeap<Collapse>,
+    kinds: &[VertexKind],
+    canonical: &[u32],
+    quadrics: &[Quadric],
+    points: &[[f64; 3]],
+    stamps: &[u32],
+    a: u32,
+    b: u32,
+) {
+    let ca = canonical[a as usize] as usize;
+    let cb = canonical[b as usize] as usize;
+    if ca == cb {
+        return;
+    }
+    let mut quadric = quadrics[ca];
+    quadric.add(&quadrics[cb]);
+    for &(from, to, cf, ct) in &[(a, b, ca, cb), (b, a, cb, ca)] {
+        if kinds[from as usize] != VertexKind::Manifold {
+            continue;
+        }
+        heap.push(Collapse {
+            cost: quadric.error(points[to as usize]),
+            from,
+            to,
+            stamp_from: stamps[cf],
+            stamp_to: stamps[ct],
+        });
+    }
+}
+
+fn is_collapse_valid(
+    triangles: &[[u32; 3]],
+    alive: &[bool],
+    adjacency: &[Vec<u32>],
+    canonical: &[u32],
+    points: &[[f64; 3]],
+    from: u32,
+    to: u32,
+) -> bool {
+    let cf = canonical[from as usize] as usize;
+    let ct = canonical[to as usize] as usize;
+    let neighbours = |c: usize| {
+        let mut ring: Vec<u32> = adjacency[c]
+            .iter()
+            .filter(|&&t| alive[t as usize])
+            .flat_map(|&t| triangles[t as usize].iter().map(|&v| canonical[v as usize]))
+            .filter(|&v| v as usize != c)
+            .collect();
+        ring.sort_unstable();
+        ring.dedup();
+        ring
+    };
+
+    // link condition: an interior edge shares exactly two neighbours,
+    // any more would pinch the surface into a non-manifold fan
+    let ring_from = neighbours(cf);
+    let ring_to = neighbours(ct);
+    let shared = ring_from.iter().filter(|v| ring_to.binary_search(v).is_ok()).count();
+    if shared != 2 {
+        return false;
+    }
+
+    // no remaining triangle may flip or degenerate
+    for &t in &adjacency[cf] {
+        if !alive[t as usize] {
+            continue;
+        }
+        let tri = triangles[t as usize];
+        if tri.iter().any(|&v| canonical[v as usize] as usize == ct) {
+            continue;
+        }
+        let corners = tri.map(|v| points[v as usize]);
+        let moved = tri.map(|v| if v == from { points[to as usize] } else { points[v as usize] });
+        let before = triangle_normal(corners[0], corners[1], corners[2]);
+        let after = triangle_normal(moved[0], moved[1], moved[2]);
+        if dot(before, after) <= 1e-4 * dot(before, before).sqrt() * dot(after, after).sqrt() {
+            return false;
+        }
+        if dot(after, after) <= f64::EPSILON {
+            return false;
+        }
+    }
+    true
+}
+
+fn triangle_normal(a: [f64; 3], b: [f64; 3], c: [f64; 3]) -> [f64; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pages::webgl_08_lod::mesh::Mesh;
+
+    /// `n` by `n` quads in the z = 0 plane, an open surface
+    fn grid(n: u16) -> (Vec<[f32; 3]>, Vec<u16>) {
+        let mut positions = Vec::new();
+        for y in 0..=n {
+            for x in 0..=n {
+                positions.push([f32::from(x), f32::from(y), 0.0]);
+            }
+        }
+        let mut indices = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let a = y * (n + 1) + x;
+                let b = a + 1;
+                let c = a + n + 2;
+                let d = a + n + 1;
+                indices.extend_from_slice(&[a, b, c, a, c, d]);
+            }
+        }
+        (positions, indices)
+    }
+
+    fn area(positions: &[[f32; 3]], indices: &[u16]) -> f64 {
+        let points: Vec<[f64; 3]> = positions.iter().map(|p| [f64::from(p[0]), f64::from(p[1]), f64::from(p[2])]).collect();
+        indices
+            .chunks(3)
+            .map(|t| {
+                let n = triangle_normal(points[t[0] as usize], points[t[1] as usize], points[t[2] as usize]);
+                dot(n, n).sqrt() * 0.5
+            })
+            .sum()
+    }
+
+    #[test]
+    fn reduces_to_the_target_triangle_count() {
+        let torus = Mesh::torus(1.0, 0.4, 32, 16);
+        let positions = torus.positions();
+        let source = torus.indices();
+        for &target in &[source.len() / 2, source.len() / 4] {
+            let simplified = simplify(&positions, source, target);
+            assert_eq!(simplified.len() % 3, 0);
+            assert!(simplified.len() <= target, "{} indices for a target of {}", simplified.len(), target);
+            assert!(simplified.iter().all(|&v| (v as usize) < positions.len()));
+        }
+        assert_eq!(simplify(&positions, source, source.len()), source);
+    }
+
+    #[test]
+    fn the_same_mesh_simplifies_the_same_way() {
+        let (first, second) = (Mesh::torus(1.0, 0.4, 32, 16), Mesh::torus(1.0, 0.4, 32, 16));
+        assert_eq!(first.positions(), second.positions());
+        assert_eq!(first.indices(), second.indices());
+        let source = first.indices();
+        for &target in &[source.len() / 2, source.len() / 4, 0] {
+            assert_eq!(
+                simplify(&first.positions(), source, target),
+                simplify(&second.positions(), second.indices(), target)
+            );
+        }
+        // a flat grid costs nothing to collapse anywhere, only the tie-break orders it
+        let (positions, indices) = grid(8);
+        let simplified = simplify(&positions, &indices, indices.len() / 2);
+        for _ in 0..4 {
+            assert_eq!(simplify(&positions, &indices, indices.len() / 2), simplified);
+        }
+    }
+
+    #[test]
+    fn a_flat_plane_has_no_quadric_error() {
+        let (positions, indices) = grid(4);
+        let points: Vec<[f64; 3]> = positions.iter().map(|p| [f64::from(p[0]), f64::from(p[1]), f64::from(p[2])]).collect();
+        let mut quadric = Quadric::default();
+        for t in indices.chunks(3) {
+            quadric.add(&triangle_quadric(&points, &[u32::from(t[0]), u32::from(t[1]), u32::from(t[2])]).unwrap());
+        }
+        for p in &points {
+            assert!(quadric.error(*p) < 1e-9);
+        }
+        // area weighted: 16 unit squares, one unit off the plane
+        assert!((quadric.error([2.0, 2.0, 1.0]) - 16.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_simplified_plane_stays_flat_and_keeps_its_outline() {
+        let (positions, indices) = grid(6);
+        let kinds = classify_vertices(&positions, &indices);
+        let simplified = simplify(&positions, &indices, 0);
+        assert!(simplified.len() < indices.len());
+        // no triangle flipped over or overlaps another
+        assert!((area(&positions, &simplified) - 36.0).abs() < 1e-6);
+        for (v, kind) in kinds.iter().enumerate() {
+            if *kind == VertexKind::Border {
+                assert!(simplified.contains(&(v as u16)), "border vertex {} collapsed", v);
+            }
+        }
+    }
+
+    #[test]
+    fn classifies_borders_and_seams() {
+        let (mut positions, indices) = grid(2);
+        let kinds = classify_vertices(&positions, &indices);
+        assert_eq!(kinds.iter().filter(|&&k| k == VertexKind::Manifold).count(), 1);
+        assert_eq!(kinds[4], VertexKind::Manifold);
+        // a second vertex at the center, as a uv seam would add
+        positions.push([1.0, 1.0, 0.0]);
+        let kinds = classify_vertices(&positions, &indices);
+        assert_eq!(kinds[4], VertexKind::Seam);
+        assert_eq!(kinds[9], VertexKind::Seam);
+    }
+
+    #[test]
+    fn degenerate_triangles_add_no_error() {
+        let (mut positions, mut indices) = grid(4);
+        let points: Vec<[f64; 3]> = positions.iter().map(|p| [f64::from(p[0]), f64::from(p[1]), f64::from(p[2])]).collect();
+        assert!(triangle_quadric(&points, &[0, 1, 2]).is_none());
+        assert!(triangle_quadric(&points, &[6, 6, 7]).is_none());
+
+        // a sliver along the bottom border, off to the side of the grid
+        positions.extend_from_slice(&[[5.0, 0.0, 0.0], [6.0, 0.0, 0.0], [7.0, 0.0, 0.0]]);
+        indices.extend_from_slice(&[25, 26, 27]);
+        let simplified = simplify(&positions, &indices, 0);
+        assert!(simplified.len() < indices.len());
+        for t in simplified.chunks(3) {
+            assert!(t[0] != t[1] && t[1] != t[2] && t[2] != t[0], "{:?}", t);
+        }
+    }
+}
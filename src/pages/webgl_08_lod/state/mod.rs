@@ -0,0 +1,89 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+use cgmath::Point2;
+use clear_color::ClearColor;
+use viewport::Viewport;
+
+mod camera;
+mod clear_color;
+mod viewport;
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    lod_debug: bool,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::default();
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            lod_debug: false,
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn lod_debug(&self) -> bool {
+        self.lod_debug
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer) => self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time),
+            Msg::PointerMove(id, x, y) => match self.gestures.moved(id, Point2::new(x, y)) {
+                Some(Gesture::Drag { from, to, .. }) => {
+                    self.camera.orbit_left_right(from.x - to.x);
+                    self.camera.orbit_up_down(to.y - from.y);
+                }
+                Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                _ => {}
+            },
+            Msg::PointerUp(id, time) => {
+                self.gestures.up(id, time);
+            }
+            Msg::PointerCancel(id) => self.gestures.cancel(id),
+            Msg::ReleaseInput => self.gestures.clear(),
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::ToggleLodDebug => self.lod_debug = !self.lod_debug,
+        }
+    }
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    ToggleLodDebug,
+}
@@ -0,0 +1,60 @@
+/// Unit cube with a normal per face, positions and normals interleaved.
+pub struct Cube {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Cube {
+    pub fn new() -> Self {
+        let vertices = vec![
+            // position    // normals
+            1.0, 1.0, 1.0, 0.0, 0.0, 1.0, //  top
+            -1.0, 1.0, 1.0, 0.0, 0.0, 1.0, //
+            -1.0, -1.0, 1.0, 0.0, 0.0, 1.0, //
+            1.0, -1.0, 1.0, 0.0, 0.0, 1.0, //
+            1.0, 1.0, -1.0, 0.0, 0.0, -1.0, // bottom
+            -1.0, 1.0, -1.0, 0.0, 0.0, -1.0, //
+            -1.0, -1.0, -1.0, 0.0, 0.0, -1.0, //
+            1.0, -1.0, -1.0, 0.0, 0.0, -1.0, //
+            -1.0, -1.0, 1.0, -1.0, 0.0, 0.0, // left
+            -1.0, 1.0, 1.0, -1.0, 0.0, 0.0, //
+            -1.0, 1.0, -1.0, -1.0, 0.0, 0.0, //
+            -1.0, -1.0, -1.0, -1.0, 0.0, 0.0, //
+            1.0, -1.0, 1.0, 1.0, 0.0, 0.0, // right
+            1.0, 1.0, 1.0, 1.0, 0.0, 0.0, //
+            1.0, 1.0, -1.0, 1.0, 0.0, 0.0, //
+            1.0, -1.0, -1.0, 1.0, 0.0, 0.0, //
+            -1.0, -1.0, 1.0, 0.0, 1.0, 0.0, // front
+            -1.0, -1.0, -1.0, 0.0, 1.0, 0.0, //
+            1.0, -1.0, -1.0, 0.0, 1.0, 0.0, //
+            1.0, -1.0, 1.0, 0.0, 1.0, 0.0, //
+            -1.0, 1.0, 1.0, 0.0, -1.0, 0.0, // back
+            -1.0, 1.0, -1.0, 0.0, -1.0, 0.0, //
+            1.0, 1.0, -1.0, 0.0, -1.0, 0.0, //
+            1.0, 1.0, 1.0, 0.0, -1.0, 0.0, //
+        ];
+        let indices = vec![
+            0, 1, 2, // top
+            0, 2, 3, //
+            4, 6, 5, // bottom
+            4, 7, 6, //
+            8, 9, 10, // left
+            8, 10, 11, //
+            12, 15, 14, // right
+            12, 14, 13, //
+            16, 17, 18, // front
+            16, 18, 19, //
+            20, 23, 22, // back
+            20, 22, 21, //
+        ];
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+}
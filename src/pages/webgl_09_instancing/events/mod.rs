@@ -0,0 +1,209 @@
+use super::state::{Msg, PointerInput};
+use super::App;
+use crate::input::{Action, Bindings};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+pub fn attach_event_handlers(app: Rc<App>) -> Result<(), JsValue> {
+    attach_onresize_handler(Rc::clone(&app))?;
+    attach_pointer_down_handler(Rc::clone(&app))?;
+    attach_pointer_up_handler(Rc::clone(&app))?;
+    attach_pointer_cancel_handler(Rc::clone(&app))?;
+    attach_pointer_move_handler(Rc::clone(&app))?;
+    attach_mouse_wheel_handler(Rc::clone(&app))?;
+    attach_key_down_handler(Rc::clone(&app))?;
+    attach_key_up_handler(Rc::clone(&app))?;
+    attach_blur_handler(Rc::clone(&app))?;
+    attach_color_picker_handler(Rc::clone(&app))?;
+    // the page must not scroll or zoom under the fingers
+    app.canvas.style().set_property("touch-action", "none")?;
+    Ok(())
+}
+
+/// Keys of the page, they can be overridden from `localStorage`.
+pub fn bindings() -> Bindings {
+    Bindings::new(&[(Action::ToggleInstancing, &["KeyI"])])
+}
+
+/// Runs the actions of the keys pressed since the last frame. Called once per frame.
+pub fn handle_input(app: &App) {
+    let actions = app.input.borrow().pressed_actions();
+    for action in actions {
+        let msg = match action {
+            Action::ToggleHelp => {
+                app.help.toggle();
+                continue;
+            }
+            Action::NextLesson => return crate::router::goto_lesson(1),
+            Action::PrevLesson => return crate::router::goto_lesson(-1),
+            Action::ToggleInstancing => Msg::ToggleInstancing,
+            _ => continue,
+        };
+        app.state.borrow_mut().msg(msg);
+    }
+    app.input.borrow_mut().end_frame();
+}
+
+fn attach_onresize_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_evt: web_sys::Event| {
+        let window = web_sys::window().expect("no global `window` exists");
+        let width = window.inner_width().unwrap().as_f64().unwrap();
+        let height = window.inner_height().unwrap().as_f64().unwrap();
+        app.canvas.set_width(width as u32);
+        app.canvas.set_height(height as u32);
+        app.state.borrow_mut().msg(Msg::ViewportResize(width, height));
+        app.gl.viewport(0, 0, width as i32, height as i32);
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let body = document.body().unwrap();
+    body.set_onresize(Some(handler.as_ref().unchecked_ref()));
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_pointer_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::PointerEvent| {
+        let pointer = match PointerInput::from_event(&event) {
+            Some(pointer) => pointer,
+            None => return,
+        };
+        // keep receiving the moves and the release even outside of the canvas
+        let _ = app2.canvas.set_pointer_capture(pointer.id);
+        app2.state.borrow_mut().msg(Msg::PointerDown(pointer, event.shift_key()));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointerdown", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_pointer_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::PointerEvent| {
+        app2.state.borrow_mut().msg(Msg::PointerUp(event.pointer_id(), event.time_stamp()));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointerup", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+    Ok(())
+}
+
+fn attach_pointer_cancel_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::PointerEvent| {
+        app2.state.borrow_mut().msg(Msg::PointerCancel(event.pointer_id()));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointercancel", handler.as_ref().unchecked_ref())?;
+    app.canvas
+        .add_event_listener_with_callback("lostpointercapture", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+    Ok(())
+}
+
+fn attach_pointer_move_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::PointerEvent| {
+        event.prevent_default();
+        let x = event.client_x() as f32;
+        let y = event.client_y() as f32;
+        app2.state.borrow_mut().msg(Msg::PointerMove(event.pointer_id(), x, y));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas
+        .add_event_listener_with_callback("pointermove", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_mouse_wheel_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |event: web_sys::WheelEvent| {
+        event.prevent_default();
+        let zoom_amount = event.delta_y() / 50.;
+        app2.state.borrow_mut().msg(Msg::Zoom(zoom_amount as f32));
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.canvas.add_event_listener_with_callback("wheel", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_down_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        if app.input.borrow_mut().key_down(&event.code()) {
+            event.prevent_default();
+        }
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keydown", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_key_up_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |event: web_sys::KeyboardEvent| {
+        app.input.borrow_mut().key_up(&event.code());
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("keyup", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+/// keys and buttons released outside of the page never send their up event
+fn attach_blur_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let handler = move |_event: web_sys::Event| {
+        app.input.borrow_mut().clear();
+        app.state.borrow_mut().msg(Msg::ReleaseInput);
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    let window = web_sys::window().unwrap();
+    window.add_event_listener_with_callback("blur", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+fn attach_color_picker_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |_event: web_sys::Event| {
+        let (instance, color) = app2.hud.picked_color();
+        let mut batch = app2.batch.borrow_mut();
+        if instance < batch.instance_count() {
+            batch.set("aInstanceColor", instance, &color);
+        }
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.hud
+        .color_input()
+        .add_event_listener_with_callback("input", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
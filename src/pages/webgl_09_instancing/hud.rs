@@ -0,0 +1,126 @@
+use cgmath::Point2;
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+/// how often, in milliseconds, the frame rate is refreshed
+const FPS_INTERVAL: f64 = 500.0;
+
+/// Frame rate readout, the per-instance color picker and the selection box.
+pub struct Hud {
+    stats: HtmlElement,
+    selection_box: HtmlElement,
+    instance_input: HtmlInputElement,
+    color_input: HtmlInputElement,
+    frames: Cell<u32>,
+    last_time: Cell<f64>,
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&panel)?;
+
+        let stats = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        panel.append_child(&stats)?;
+
+        let picker = document.create_element("div")?;
+        picker.set_inner_html("instance ");
+        let instance_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        instance_input.set_type("number");
+        instance_input.set_min("0");
+        instance_input.set_value("0");
+        picker.append_child(&instance_input)?;
+        let color_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        color_input.set_type("color");
+        color_input.set_value("#ffffff");
+        picker.append_child(&color_input)?;
+        panel.append_child(&picker)?;
+
+        let help = document.create_element("div")?;
+        help.set_inner_html("?: keys, click: select, shift+click: toggle, shift+drag: box select");
+        panel.append_child(&help)?;
+
+        let selection_box = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = selection_box.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("border", "1px dashed white")?;
+        style.set_property("background", "rgba(255, 255, 255, 0.1)")?;
+        // the canvas under the box keeps the mouse events
+        style.set_property("pointer-events", "none")?;
+        style.set_property("display", "none")?;
+        body.append_child(&selection_box)?;
+
+        Ok(Self {
+            stats,
+            selection_box,
+            instance_input,
+            color_input,
+            frames: Cell::new(0),
+            last_time: Cell::new(js_sys::Date::now()),
+        })
+    }
+
+    pub fn color_input(&self) -> &HtmlInputElement {
+        &self.color_input
+    }
+
+    /// The instance index and the color currently entered in the picker.
+    pub fn picked_color(&self) -> (usize, [f32; 4]) {
+        let instance = self.instance_input.value().parse().unwrap_or(0);
+        let hex = self.color_input.value();
+        let channel = |i: usize| {
+            let value = hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+            f32::from(value.unwrap_or(255)) / 255.0
+        };
+        (instance, [channel(1), channel(3), channel(5), 1.0])
+    }
+
+    /// Shows the box between two corners in client coordinates, or hides it.
+    pub fn set_selection_box(&self, corners: Option<(Point2<f32>, Point2<f32>)>) {
+        let style = self.selection_box.style();
+        let (from, to) = match corners {
+            Some(corners) => corners,
+            None => {
+                style.set_property("display", "none").unwrap();
+                return;
+            }
+        };
+        style.set_property("display", "block").unwrap();
+        style.set_property("left", &format!("{}px", from.x.min(to.x))).unwrap();
+        style.set_property("top", &format!("{}px", from.y.min(to.y))).unwrap();
+        style.set_property("width", &format!("{}px", (to.x - from.x).abs())).unwrap();
+        style.set_property("height", &format!("{}px", (to.y - from.y).abs())).unwrap();
+    }
+
+    /// Counts a rendered frame and refreshes the statistics now and then.
+    pub fn frame(&self, instanced: bool, instance_count: usize, selected: usize) {
+        self.frames.set(self.frames.get() + 1);
+        let now = js_sys::Date::now();
+        let elapsed = now - self.last_time.get();
+        if elapsed < FPS_INTERVAL {
+            return;
+        }
+        let fps = f64::from(self.frames.get()) * 1000.0 / elapsed;
+        self.frames.set(0);
+        self.last_time.set(now);
+
+        let draw_calls = if instanced { 1 } else { instance_count };
+        let mode = if instanced { "instanced" } else { "non-instanced loop" };
+        self.stats.set_inner_html(&format!(
+            "{:.1} fps<br>{} cubes, {}, {} draw calls<br>{} selected",
+            fps, instance_count, mode, draw_calls, selected
+        ));
+    }
+}
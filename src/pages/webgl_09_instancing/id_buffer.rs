@@ -0,0 +1,198 @@
+use cgmath::{Matrix4, Point2};
+use std::collections::BTreeSet;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::instance_batch::InstanceBatch;
+use super::Shader;
+
+/// Offscreen framebuffer holding, for every pixel, the id of the instance
+/// drawn there: `instance + 1`, 0 being the background. Reading it back picks
+/// among thousands of instances without any ray cast.
+pub struct IdBuffer {
+    shader: Shader,
+    framebuffer: WebGlFramebuffer,
+    ids: WebGlTexture,
+    depth: WebGlRenderbuffer,
+    width: i32,
+    height: i32,
+}
+
+impl IdBuffer {
+    pub fn new(gl: &GL, width: i32, height: i32) -> Result<Self, JsValue> {
+        let shader = Shader::pick_shader(gl)?;
+        let framebuffer = gl.create_framebuffer().ok_or("create framebuffer error")?;
+        let ids = gl.create_texture().ok_or("create texture error")?;
+        let depth = gl.create_renderbuffer().ok_or("create renderbuffer error")?;
+        let mut buffer = Self {
+            shader,
+            framebuffer,
+            ids,
+            depth,
+            width: 0,
+            height: 0,
+        };
+        buffer.resize(gl, width, height)?;
+        Ok(buffer)
+    }
+
+    /// Reallocates the attachments when the canvas size changed.
+    pub fn resize(&mut self, gl: &GL, width: i32, height: i32) -> Result<(), JsValue> {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height) == (self.width, self.height) {
+            return Ok(());
+        }
+        // the storage of a texture is immutable, a new size needs a new texture
+        if self.width != 0 {
+            gl.delete_texture(Some(&self.ids));
+            self.ids = gl.create_texture().ok_or("create texture error")?;
+        }
+        self.width = width;
+        self.height = height;
+
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.ids));
+        // integer textures can't be filtered, and there is no need to
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+        gl.tex_storage_2d(GL::TEXTURE_2D, 1, GL::R32UI, width, height);
+        gl.bind_texture(GL::TEXTURE_2D, None);
+
+        gl.bind_renderbuffer(GL::RENDERBUFFER, Some(&self.depth));
+        gl.renderbuffer_storage(GL::RENDERBUFFER, GL::DEPTH_COMPONENT24, width, height);
+        gl.bind_renderbuffer(GL::RENDERBUFFER, None);
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(&self.ids), 0);
+        gl.framebuffer_renderbuffer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, GL::RENDERBUFFER, Some(&self.depth));
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            return Err(format!("id framebuffer incomplete: {:#x}", status).into());
+        }
+        Ok(())
+    }
+
+    /// Draws the instance ids of `batch`, always in one instanced call so
+    /// `gl_InstanceID` numbers them.
+    pub fn render(&self, gl: &GL, batch: &InstanceBatch, view: Matrix4<f32>, projection: Matrix4<f32>) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.width, self.height);
+        gl.clear_bufferuiv_with_u32_array(GL::COLOR, 0, &[0, 0, 0, 0]);
+        gl.clear(GL::DEPTH_BUFFER_BIT);
+
+        gl.use_program(Some(&self.shader.program));
+        for (name, matrix) in [("uViewMatrix", view), ("uProjectiveMatrix", projection)].iter() {
+            let location = self.shader.get_uniform_location(gl, name);
+            let array: &[f32; 16] = matrix.as_ref();
+            gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+        }
+        batch.draw_instanced_with(gl, &self.shader);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    /// Instance under the client pixel at `position`.
+    pub fn read_id(&self, gl: &GL, position: Point2<f32>) -> Option<usize> {
+        self.read_region(gl, position, position).into_iter().next()
+    }
+
+    /// Every instance with a pixel in the client rectangle between the two
+    /// corners, sorted and once each.
+    pub fn read_region(&self, gl: &GL, from: Point2<f32>, to: Point2<f32>) -> Vec<usize> {
+        let (left, bottom, width, height) = match framebuffer_rect(from, to, self.width, self.height) {
+            Some(rect) => rect,
+            None => return Vec::new(),
+        };
+
+        // RGBA_INTEGER with UNSIGNED_INT is the read format every implementation supports
+        let mut pixels = vec![0u32; (width * height * 4) as usize];
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        unsafe {
+            let view = js_sys::Uint32Array::view_mut_raw(pixels.as_mut_ptr(), pixels.len());
+            if let Err(e) = gl.read_pixels_with_opt_array_buffer_view(left, bottom, width, height, GL::RGBA_INTEGER, GL::UNSIGNED_INT, Some(&view)) {
+                log::warn!("id buffer read error: {:?}", e);
+            }
+        }
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        unique_ids(&pixels)
+    }
+}
+
+/// Pixels covered by the client rectangle between two corners, in any order,
+/// as the `(x, y, width, height)` framebuffer rectangle `read_pixels` takes.
+/// Equal corners cover the one pixel under them. The rectangle is clipped to
+/// the `width` by `height` framebuffer, `None` when it lies outside.
+fn framebuffer_rect(from: Point2<f32>, to: Point2<f32>, width: i32, height: i32) -> Option<(i32, i32, i32, i32)> {
+    let left = from.x.min(to.x).floor() as i32;
+    let right = from.x.max(to.x).floor() as i32;
+    let top = from.y.min(to.y).floor() as i32;
+    let bottom = from.y.max(to.y).floor() as i32;
+    if right < 0 || bottom < 0 || left >= width || top >= height {
+        return None;
+    }
+    let (left, right) = (left.max(0), right.min(width - 1));
+    let (top, bottom) = (top.max(0), bottom.min(height - 1));
+    // client rows go down, framebuffer rows go up
+    Some((left, height - 1 - bottom, right - left + 1, bottom - top + 1))
+}
+
+/// Instances of the red channel of RGBA texels, without the background.
+fn unique_ids(pixels: &[u32]) -> Vec<usize> {
+    let ids: BTreeSet<u32> = pixels.chunks(4).map(|p| p[0]).filter(|&id| id > 0).collect();
+    ids.into_iter().map(|id| id as usize - 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x0: f32, y0: f32, x1: f32, y1: f32) -> Option<(i32, i32, i32, i32)> {
+        framebuffer_rect(Point2::new(x0, y0), Point2::new(x1, y1), 100, 50)
+    }
+
+    #[test]
+    fn client_rows_flip_into_framebuffer_rows() {
+        assert_eq!(rect(10.0, 0.0, 19.0, 9.0), Some((10, 40, 10, 10)));
+        assert_eq!(rect(0.0, 40.0, 99.0, 49.0), Some((0, 0, 100, 10)));
+        // a fraction of a pixel still covers the whole pixel
+        assert_eq!(rect(10.5, 0.25, 19.75, 9.5), Some((10, 40, 10, 10)));
+    }
+
+    #[test]
+    fn a_zero_size_rectangle_covers_the_pixel_under_it() {
+        assert_eq!(rect(3.5, 7.5, 3.5, 7.5), Some((3, 42, 1, 1)));
+        assert_eq!(rect(0.0, 0.0, 0.0, 0.0), Some((0, 49, 1, 1)));
+        assert_eq!(rect(99.0, 49.0, 99.0, 49.0), Some((99, 0, 1, 1)));
+    }
+
+    #[test]
+    fn reversed_corners_cover_the_same_pixels() {
+        let rect_down = rect(10.0, 5.0, 30.0, 25.0);
+        assert_eq!(rect(30.0, 25.0, 10.0, 5.0), rect_down);
+        assert_eq!(rect(10.0, 25.0, 30.0, 5.0), rect_down);
+        assert_eq!(rect(30.0, 5.0, 10.0, 25.0), rect_down);
+    }
+
+    #[test]
+    fn rectangles_are_clipped_to_the_canvas() {
+        assert_eq!(rect(-20.0, -20.0, 200.0, 200.0), Some((0, 0, 100, 50)));
+        assert_eq!(rect(90.0, 45.0, 150.0, 80.0), Some((90, 0, 10, 5)));
+        assert_eq!(rect(-5.0, -5.0, 0.0, 0.0), Some((0, 49, 1, 1)));
+    }
+
+    #[test]
+    fn rectangles_outside_of_the_canvas_cover_nothing() {
+        assert_eq!(rect(-20.0, 10.0, -1.0, 20.0), None);
+        assert_eq!(rect(100.0, 10.0, 120.0, 20.0), None);
+        assert_eq!(rect(10.0, -20.0, 20.0, -0.5), None);
+        assert_eq!(rect(10.0, 50.0, 20.0, 60.0), None);
+        assert_eq!(rect(-20.0, -20.0, -20.0, -20.0), None);
+    }
+
+    #[test]
+    fn unique_ids_skip_the_background() {
+        let pixels = [3, 0, 0, 1, 0, 0, 0, 1, 1, 7, 7, 1, 3, 0, 0, 1, 2, 0, 0, 1];
+        assert_eq!(unique_ids(&pixels), vec![0, 1, 2]);
+        assert_eq!(unique_ids(&[0, 5, 5, 5, 0, 0, 0, 0]), Vec::<usize>::new());
+        assert_eq!(unique_ids(&[]), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,228 @@
+use cgmath::{Matrix4, Vector3};
+use std::collections::HashMap;
+use std::mem::size_of;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::Shader;
+
+/// One per-instance vertex attribute backed by its own dynamic buffer.
+struct InstanceAttribute {
+    name: String,
+    /// floats per instance, a `mat4` takes 16 and spans four attribute slots
+    components: usize,
+    data: Vec<f32>,
+    buffer: WebGlBuffer,
+    dirty: bool,
+}
+
+impl InstanceAttribute {
+    fn slots(&self) -> usize {
+        self.components.div_ceil(4)
+    }
+
+    fn slot_size(&self, slot: usize) -> usize {
+        (self.components - slot * 4).min(4)
+    }
+}
+
+/// Draws one mesh many times, every instance reading its own model matrix,
+/// color and custom values from instance buffers.
+pub struct InstanceBatch {
+    shader: Shader,
+    vao: Option<WebGlVertexArrayObject>,
+    vbos: HashMap<String, WebGlBuffer>,
+    index_count: i32,
+    instance_count: usize,
+    attributes: Vec<InstanceAttribute>,
+}
+
+impl InstanceBatch {
+    /// `vertices` interleaves position and normal, `indices` is a triangle list.
+    pub fn new(gl: &GL, shader: Shader, vertices: &[f32], indices: &[u16]) -> Self {
+        let mut batch = Self {
+            shader,
+            vao: None,
+            vbos: HashMap::new(),
+            index_count: indices.len() as i32,
+            instance_count: 0,
+            attributes: Vec::new(),
+        };
+
+        gl.use_program(Some(&batch.shader.program));
+        batch.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(batch.vao.as_ref());
+
+        let vert_buffer = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vert_buffer));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(vertices);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        let stride = 6 * size_of::<f32>();
+        batch.set_attrib_location_f32(gl, "aVertexPosition", 3, stride, 0);
+        batch.set_attrib_location_f32(gl, "aVertexNormal", 3, stride, 3 * size_of::<f32>());
+
+        let index_buffer = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&index_buffer));
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(indices);
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        batch.vbos.insert("vert".into(), vert_buffer);
+        batch.vbos.insert("index".into(), index_buffer);
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        batch
+    }
+
+    /// Declares a per-instance attribute of the shader, `components` floats wide.
+    pub fn add_attribute(&mut self, gl: &GL, name: &str, components: usize) {
+        let buffer = gl.create_buffer().unwrap();
+        let attribute = InstanceAttribute {
+            name: name.into(),
+            components,
+            data: vec![0.0; components * self.instance_count],
+            buffer,
+            dirty: true,
+        };
+
+        gl.bind_vertex_array(self.vao.as_ref());
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&attribute.buffer));
+        let location = self.shader.get_attrib_location(gl, name) as u32;
+        let stride = (components * size_of::<f32>()) as i32;
+        for slot in 0..attribute.slots() {
+            let offset = (slot * 4 * size_of::<f32>()) as i32;
+            let size = attribute.slot_size(slot) as i32;
+            gl.enable_vertex_attrib_array(location + slot as u32);
+            gl.vertex_attrib_pointer_with_i32(location + slot as u32, size, GL::FLOAT, false, stride, offset);
+            // advance once per instance instead of once per vertex
+            gl.vertex_attrib_divisor(location + slot as u32, 1);
+        }
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        self.attributes.push(attribute);
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instance_count
+    }
+
+    /// Resizes every instance buffer, new instances start zeroed.
+    pub fn set_instance_count(&mut self, count: usize) {
+        self.instance_count = count;
+        for attribute in &mut self.attributes {
+            attribute.data.resize(attribute.components * count, 0.0);
+            attribute.dirty = true;
+        }
+    }
+
+    /// Mutable access to all the values of one attribute, marking it for upload.
+    pub fn attribute_mut(&mut self, name: &str) -> &mut [f32] {
+        let attribute = self
+            .attributes
+            .iter_mut()
+            .find(|a| a.name == name)
+            .unwrap_or_else(|| panic!("instance attribute '{}' not found", name));
+        attribute.dirty = true;
+        &mut attribute.data
+    }
+
+    /// Sets all the components of one attribute for one instance.
+    pub fn set(&mut self, name: &str, instance: usize, values: &[f32]) {
+        let data = self.attribute_mut(name);
+        let start = instance * values.len();
+        data[start..start + values.len()].copy_from_slice(values);
+    }
+
+    /// Sends the attributes changed since the last upload to the gpu.
+    pub fn upload(&mut self, gl: &GL) {
+        for attribute in self.attributes.iter_mut().filter(|a| a.dirty) {
+            gl.bind_buffer(GL::ARRAY_BUFFER, Some(&attribute.buffer));
+            unsafe {
+                let data_array = js_sys::Float32Array::view(&attribute.data);
+                gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::DYNAMIC_DRAW);
+            }
+            attribute.dirty = false;
+        }
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    }
+
+    pub fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        gl.use_program(Some(&self.shader.program));
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+
+    pub fn set_uniform_location_vector3(&self, gl: &GL, uniform_name: &str, vector: Vector3<f32>) {
+        gl.use_program(Some(&self.shader.program));
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 3] = vector.as_ref();
+        gl.uniform3fv_with_f32_array(location.as_ref(), &array[..]);
+    }
+
+    pub fn set_uniform_location_f32(&self, gl: &GL, uniform_name: &str, value: f32) {
+        gl.use_program(Some(&self.shader.program));
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        gl.uniform1f(location.as_ref(), value);
+    }
+
+    /// Draws every instance with a single draw call.
+    pub fn draw_instanced(&self, gl: &GL) {
+        self.draw_instanced_with(gl, &self.shader);
+    }
+
+    /// Draws every instance with another program, whose attributes must use
+    /// the same locations as the batch shader.
+    pub fn draw_instanced_with(&self, gl: &GL, shader: &Shader) {
+        gl.use_program(Some(&shader.program));
+        gl.bind_vertex_array(self.vao.as_ref());
+        gl.draw_elements_instanced_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0, self.instance_count as i32);
+        gl.bind_vertex_array(None);
+    }
+
+    /// Draws the instances one call each, feeding the instance attributes as
+    /// constant vertex attributes. Only there to compare with `draw_instanced`.
+    pub fn draw_looped(&self, gl: &GL) {
+        gl.use_program(Some(&self.shader.program));
+        gl.bind_vertex_array(self.vao.as_ref());
+        let locations: Vec<u32> = self
+            .attributes
+            .iter()
+            .map(|a| self.shader.get_attrib_location(gl, &a.name) as u32)
+            .collect();
+        for (attribute, &location) in self.attributes.iter().zip(&locations) {
+            for slot in 0..attribute.slots() {
+                gl.disable_vertex_attrib_array(location + slot as u32);
+            }
+        }
+
+        for instance in 0..self.instance_count {
+            for (attribute, &location) in self.attributes.iter().zip(&locations) {
+                let values = &attribute.data[instance * attribute.components..(instance + 1) * attribute.components];
+                for (slot, chunk) in values.chunks(4).enumerate() {
+                    let mut value = [0.0, 0.0, 0.0, 1.0];
+                    value[..chunk.len()].copy_from_slice(chunk);
+                    gl.vertex_attrib4fv_with_f32_array(location + slot as u32, &value);
+                }
+            }
+            gl.draw_elements_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0);
+        }
+
+        for (attribute, &location) in self.attributes.iter().zip(&locations) {
+            for slot in 0..attribute.slots() {
+                gl.enable_vertex_attrib_array(location + slot as u32);
+            }
+        }
+        gl.bind_vertex_array(None);
+    }
+
+    fn set_attrib_location_f32(&self, gl: &GL, attrib_name: &str, size: i32, stride: usize, offset: usize) {
+        let location = self.shader.get_attrib_location(gl, attrib_name);
+        gl.enable_vertex_attrib_array(location as u32);
+        gl.vertex_attrib_pointer_with_i32(location as u32, size, GL::FLOAT, false, stride as i32, offset as i32);
+    }
+}
@@ -0,0 +1,194 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use crate::input::{HelpOverlay, Input};
+use canvas::{create_canvas, create_webgl_context};
+use cgmath::{Matrix4, Rad, Vector3};
+use cube::Cube;
+use events::{attach_event_handlers, handle_input};
+use hud::Hud;
+use id_buffer::IdBuffer;
+use instance_batch::InstanceBatch;
+use shader::Shader;
+use state::{Msg, PickRequest, State};
+
+mod canvas;
+mod cube;
+mod events;
+mod hud;
+mod id_buffer;
+mod instance_batch;
+mod shader;
+mod state;
+
+/// the cubes are laid out on a `GRID_SIZE` x `GRID_SIZE` grid
+const GRID_SIZE: usize = 110;
+const GRID_SPACING: f32 = 1.0;
+
+pub fn run() -> Result<(), JsValue> {
+    let app = Rc::new(App::new());
+    attach_event_handlers(Rc::clone(&app))?;
+    Rc::clone(&app).render()?;
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        app.render().unwrap();
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut()>));
+    request_animation_frame(g.borrow().as_ref().unwrap());
+    Ok(())
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+pub struct App {
+    canvas: HtmlCanvasElement,
+    gl: GL,
+    state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    batch: RefCell<InstanceBatch>,
+    id_buffer: RefCell<IdBuffer>,
+    hud: Hud,
+    start_time: f64,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let canvas = create_canvas().unwrap();
+        let gl = create_webgl_context(&canvas).unwrap();
+        let state = RefCell::new(State::new());
+        state.borrow_mut().msg(Msg::ViewportResize(canvas.width() as f64, canvas.height() as f64));
+        let shader = Shader::default_shader(&gl).expect("shader new error");
+        let cube = Cube::new();
+        let mut batch = InstanceBatch::new(&gl, shader, cube.vertices(), cube.indices());
+        batch.add_attribute(&gl, "aInstanceMatrix", 16);
+        batch.add_attribute(&gl, "aInstanceColor", 4);
+        batch.add_attribute(&gl, "aInstancePulse", 1);
+        batch.add_attribute(&gl, "aInstanceSelected", 1);
+        batch.set_instance_count(GRID_SIZE * GRID_SIZE);
+        for (i, color) in batch.attribute_mut("aInstanceColor").chunks_mut(4).enumerate() {
+            let (x, z) = grid_coords(i);
+            color.copy_from_slice(&[x, 0.4, z, 1.0]);
+        }
+        for (i, pulse) in batch.attribute_mut("aInstancePulse").iter_mut().enumerate() {
+            // golden ratio steps spread the phases evenly without a rng
+            *pulse = (i as f32 * 0.618_034).fract() * 2.0 * std::f32::consts::PI;
+        }
+        let id_buffer = IdBuffer::new(&gl, canvas.width() as i32, canvas.height() as i32).expect("id buffer error");
+        let hud = Hud::new().unwrap();
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            batch: RefCell::new(batch),
+            id_buffer: RefCell::new(id_buffer),
+            hud,
+            start_time: js_sys::Date::now(),
+        }
+    }
+
+    pub fn render(&self) -> Result<(), JsValue> {
+        handle_input(self);
+        let state = self.state.borrow();
+        let clear_color = state.clear_color();
+        let viewport = state.viewport();
+
+        self.gl.clear_color(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+        self.gl.viewport(0, 0, viewport.width() as i32, viewport.height() as i32);
+
+        let time = ((js_sys::Date::now() - self.start_time) / 1000.0) as f32;
+        let mut batch = self.batch.borrow_mut();
+        animate(&mut batch, time);
+        batch.upload(&self.gl);
+
+        let camera = state.camera();
+        batch.set_uniform_location_matrix4(&self.gl, "uViewMatrix", camera.view_matrix());
+        batch.set_uniform_location_matrix4(&self.gl, "uProjectiveMatrix", camera.projection_matrix());
+        batch.set_uniform_location_vector3(&self.gl, "uLightDirection", Vector3::new(10.0, 25.0, 10.0));
+        batch.set_uniform_location_f32(&self.gl, "uTime", time);
+
+        if state.instanced() {
+            batch.draw_instanced(&self.gl);
+        } else {
+            batch.draw_looped(&self.gl);
+        }
+        self.hud.set_selection_box(state.selection_box());
+        self.hud.frame(state.instanced(), batch.instance_count(), state.selection().len());
+        drop(batch);
+        drop(state);
+
+        let request = self.state.borrow_mut().take_pick_request();
+        if let Some(request) = request {
+            self.pick(request)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the id buffer with this frame's instances and selects what is
+    /// under the click or inside the box.
+    fn pick(&self, request: PickRequest) -> Result<(), JsValue> {
+        let msg = {
+            let state = self.state.borrow();
+            let viewport = state.viewport();
+            let camera = state.camera();
+            let mut id_buffer = self.id_buffer.borrow_mut();
+            id_buffer.resize(&self.gl, viewport.width() as i32, viewport.height() as i32)?;
+            id_buffer.render(&self.gl, &self.batch.borrow(), camera.view_matrix(), camera.projection_matrix());
+            match request {
+                PickRequest::Point { position, toggle: false } => Msg::Select(id_buffer.read_id(&self.gl, position).into_iter().collect()),
+                PickRequest::Point { position, toggle: true } => match id_buffer.read_id(&self.gl, position) {
+                    Some(instance) => Msg::ToggleSelected(instance),
+                    None => return Ok(()),
+                },
+                PickRequest::Rect { from, to } => Msg::Select(id_buffer.read_region(&self.gl, from, to)),
+            }
+        };
+        self.state.borrow_mut().msg(msg);
+
+        let state = self.state.borrow();
+        let mut batch = self.batch.borrow_mut();
+        let selected = batch.attribute_mut("aInstanceSelected");
+        selected.iter_mut().for_each(|s| *s = 0.0);
+        for &instance in state.selection() {
+            selected[instance] = 1.0;
+        }
+        Ok(())
+    }
+}
+
+/// Position of an instance on the grid, both coordinates in `0..1`.
+fn grid_coords(instance: usize) -> (f32, f32) {
+    let x = (instance % GRID_SIZE) as f32 / (GRID_SIZE - 1) as f32;
+    let z = (instance / GRID_SIZE) as f32 / (GRID_SIZE - 1) as f32;
+    (x, z)
+}
+
+/// Moves every cube along a wave running out from the center of the grid.
+fn animate(batch: &mut InstanceBatch, time: f32) {
+    let half = (GRID_SIZE as f32 - 1.0) * GRID_SPACING * 0.5;
+    for (i, matrix) in batch.attribute_mut("aInstanceMatrix").chunks_mut(16).enumerate() {
+        let (u, v) = grid_coords(i);
+        let x = u * (GRID_SIZE - 1) as f32 * GRID_SPACING - half;
+        let z = v * (GRID_SIZE - 1) as f32 * GRID_SPACING - half;
+        let distance = (x * x + z * z).sqrt();
+        let y = (distance * 0.3 - time * 2.0).sin() * 2.0;
+        let model = Matrix4::from_translation(Vector3::new(x, y, z)) * Matrix4::from_angle_y(Rad(time + distance * 0.1)) * Matrix4::from_scale(0.3);
+        let array: &[f32; 16] = model.as_ref();
+        matrix.copy_from_slice(array);
+    }
+}
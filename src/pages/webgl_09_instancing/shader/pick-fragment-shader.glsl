@@ -0,0 +1,10 @@
+#version 300 es
+precision highp float;
+
+flat in uint vId;
+
+out uint fragId;
+
+void main(void) {
+    fragId = vId;
+}
@@ -0,0 +1,17 @@
+#version 300 es
+precision highp float;
+
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+
+// same locations as the color shader, both draw from the same vertex array
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 2) in mat4 aInstanceMatrix;
+
+flat out uint vId;
+
+void main(void) {
+    // 0 is the background, instance ids start at 1
+    vId = uint(gl_InstanceID) + 1u;
+    gl_Position = uProjectiveMatrix * uViewMatrix * aInstanceMatrix * vec4(aVertexPosition, 1.0);
+}
@@ -0,0 +1,30 @@
+#version 300 es
+precision mediump float;
+
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+uniform vec3 uLightDirection;
+uniform float uTime;
+
+// fixed locations, the picking shader draws from the same vertex array
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+
+// per instance, the matrix takes locations 2 to 5
+layout(location = 2) in mat4 aInstanceMatrix;
+layout(location = 6) in vec4 aInstanceColor;
+layout(location = 7) in float aInstancePulse;
+layout(location = 8) in float aInstanceSelected;
+
+out vec4 vVertexColor;
+
+void main(void) {
+    // instances are only rotated and uniformly scaled, no inverse transpose needed
+    vec3 normal = normalize(mat3(aInstanceMatrix) * aVertexNormal);
+    float lambertTerm = max(dot(normal, normalize(uLightDirection)), 0.0);
+    float pulse = 0.8 + 0.2 * sin(uTime * 3.0 + aInstancePulse);
+    vVertexColor = vec4(aInstanceColor.rgb * (0.3 + 0.7 * lambertTerm) * pulse, 1.0);
+    vVertexColor.rgb = mix(vVertexColor.rgb, vec3(1.0, 0.9, 0.2), aInstanceSelected * 0.7);
+
+    gl_Position = uProjectiveMatrix * uViewMatrix * aInstanceMatrix * vec4(aVertexPosition, 1.0);
+}
@@ -1,6 +1,10 @@
 use cgmath::{Matrix4, PerspectiveFov, Point3, Rad, Vector3};
 use std::f32::consts::PI;
 
+/// closest and farthest the eye gets from the focus
+const MIN_DISTANCE: f32 = 0.1;
+const MAX_DISTANCE: f32 = 200.0;
+
 pub struct Camera {
     projection: PerspectiveFov<f32>,
     up_down_angle: f32,    // pitch
@@ -21,11 +25,11 @@ impl Camera {
                 fovy,
                 aspect: 1.0,
                 near: 0.1,
-                far: 50.0,
+                far: 300.0,
             },
             left_right_angle: 45.0f32.to_radians(),
             up_down_angle: 80.0f32.to_radians(),
-            r: 15.0,
+            r: 60.0,
             focus: Point3::new(0.0, 0.0, 0.0),
             sensitivity: 0.02,
         }
@@ -33,7 +37,7 @@ impl Camera {
 
     pub fn view_matrix(&self) -> Matrix4<f32> {
         let eye = self.get_eye();
-        Matrix4::look_at(eye, self.focus.clone(), Vector3::unit_y())
+        Matrix4::look_at(eye, self.focus, Vector3::unit_y())
     }
 
     pub fn projection_matrix(&self) -> Matrix4<f32> {
@@ -57,21 +61,15 @@ impl Camera {
         self.up_down_angle += delta * self.sensitivity;
         // protect some weird camera movements
         // -pi/2 < up_down_angle < pi/2
-        if self.up_down_angle < -(PI / 1.9) {
-            self.up_down_angle = -(PI / 1.9);
-        }
-        if self.up_down_angle > (PI / 2.1) {
-            self.up_down_angle = PI / 2.1;
-        }
+        self.up_down_angle = self.up_down_angle.clamp(-(PI / 1.9), PI / 2.1);
     }
 
     pub fn zoom(&mut self, zoom: f32) {
-        self.r += zoom;
+        self.r = (self.r + zoom).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
 
-        if self.r > 50.0 {
-            self.r = 50.0;
-        } else if self.r < 0.1 {
-            self.r = 0.1;
-        }
+    /// Closes in as two fingers spread apart, keeping the scene under them.
+    pub fn pinch(&mut self, scale: f32) {
+        self.r = (self.r / scale).clamp(MIN_DISTANCE, MAX_DISTANCE);
     }
 }
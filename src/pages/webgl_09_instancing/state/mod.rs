@@ -0,0 +1,241 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+use cgmath::Point2;
+use clear_color::ClearColor;
+use viewport::Viewport;
+
+mod camera;
+mod clear_color;
+mod viewport;
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    instanced: bool,
+    /// shift held when the pointer went down, its drag draws a selection box
+    boxing: Option<SelectionBox>,
+    pick_request: Option<PickRequest>,
+    selection: Vec<usize>,
+}
+
+/// Pixels the id buffer has to be read at, in client coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PickRequest {
+    /// a click, `toggle` adds or removes the instance instead of selecting only it
+    Point { position: Point2<f32>, toggle: bool },
+    /// a box drag, selecting every instance with a visible pixel inside
+    Rect { from: Point2<f32>, to: Point2<f32> },
+}
+
+/// The box of a shift drag, in client coordinates.
+#[derive(Clone, Copy, Debug)]
+struct SelectionBox {
+    pointer: i32,
+    from: Point2<f32>,
+    to: Point2<f32>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::default();
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            instanced: true,
+            boxing: None,
+            pick_request: None,
+            selection: Vec::new(),
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn instanced(&self) -> bool {
+        self.instanced
+    }
+
+    /// Selected instances, sorted.
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    /// Corners of the box being dragged, in client coordinates.
+    pub fn selection_box(&self) -> Option<(Point2<f32>, Point2<f32>)> {
+        self.boxing.filter(|b| b.from != b.to).map(|b| (b.from, b.to))
+    }
+
+    /// The click or box to resolve against the id buffer, once.
+    pub fn take_pick_request(&mut self) -> Option<PickRequest> {
+        self.pick_request.take()
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer, shift) => {
+                self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time);
+                if shift {
+                    let position = pointer.position();
+                    self.boxing = Some(SelectionBox {
+                        pointer: pointer.id,
+                        from: position,
+                        to: position,
+                    });
+                }
+            }
+            Msg::PointerMove(id, x, y) => match self.gestures.moved(id, Point2::new(x, y)) {
+                Some(Gesture::Drag { from, to, .. }) => match self.boxing.as_mut() {
+                    Some(selection_box) if selection_box.pointer == id => selection_box.to = to,
+                    _ => {
+                        self.camera.orbit_left_right(from.x - to.x);
+                        self.camera.orbit_up_down(to.y - from.y);
+                    }
+                },
+                Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                _ => {}
+            },
+            Msg::PointerUp(id, time) => {
+                let boxing = self.boxing.filter(|b| b.pointer == id);
+                if boxing.is_some() {
+                    self.boxing = None;
+                }
+                self.pick_request = match self.gestures.up(id, time) {
+                    Some(Gesture::Tap { position, .. }) | Some(Gesture::DoubleTap(position)) => Some(PickRequest::Point {
+                        position,
+                        toggle: boxing.is_some(),
+                    }),
+                    _ => boxing.map(|b| PickRequest::Rect { from: b.from, to: b.to }),
+                };
+            }
+            Msg::PointerCancel(id) => {
+                self.gestures.cancel(id);
+                self.boxing = self.boxing.filter(|b| b.pointer != id);
+            }
+            Msg::ReleaseInput => {
+                self.gestures.clear();
+                self.boxing = None;
+            }
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::ToggleInstancing => self.instanced = !self.instanced,
+            Msg::Select(instances) => self.selection = instances,
+            Msg::ToggleSelected(instance) => match self.selection.binary_search(&instance) {
+                Ok(index) => {
+                    self.selection.remove(index);
+                }
+                Err(index) => self.selection.insert(index, instance),
+            },
+        }
+    }
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    /// the pointer, and whether shift is held
+    PointerDown(PointerInput, bool),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    ToggleInstancing,
+    /// replaces the selection, the instances sorted
+    Select(Vec<usize>),
+    ToggleSelected(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::MouseButton;
+
+    fn down(state: &mut State, x: f32, y: f32, shift: bool) {
+        let pointer = PointerInput {
+            id: 1,
+            button: MouseButton::Left,
+            x,
+            y,
+            time: 0.0,
+        };
+        state.msg(Msg::PointerDown(pointer, shift));
+    }
+
+    #[test]
+    fn a_click_picks_the_point_under_it() {
+        let mut state = State::new();
+        down(&mut state, 10.5, 20.25, false);
+        state.msg(Msg::PointerUp(1, 50.0));
+        let position = Point2::new(10.5, 20.25);
+        assert_eq!(state.take_pick_request(), Some(PickRequest::Point { position, toggle: false }));
+        assert_eq!(state.take_pick_request(), None);
+    }
+
+    #[test]
+    fn a_shift_drag_selects_the_box_and_leaves_the_camera() {
+        let mut state = State::new();
+        let view = state.camera().view_matrix();
+        down(&mut state, 10.0, 20.0, true);
+        state.msg(Msg::PointerMove(1, 60.0, 25.0));
+        state.msg(Msg::PointerMove(1, 80.0, 90.0));
+        assert_eq!(state.selection_box(), Some((Point2::new(10.0, 20.0), Point2::new(80.0, 90.0))));
+        state.msg(Msg::PointerUp(1, 500.0));
+        let request = PickRequest::Rect {
+            from: Point2::new(10.0, 20.0),
+            to: Point2::new(80.0, 90.0),
+        };
+        assert_eq!(state.take_pick_request(), Some(request));
+        assert_eq!(state.selection_box(), None);
+        assert_eq!(state.camera().view_matrix(), view);
+    }
+
+    #[test]
+    fn a_shift_click_toggles_and_a_plain_drag_orbits() {
+        let mut state = State::new();
+        down(&mut state, 10.0, 20.0, true);
+        state.msg(Msg::PointerUp(1, 50.0));
+        let position = Point2::new(10.0, 20.0);
+        assert_eq!(state.take_pick_request(), Some(PickRequest::Point { position, toggle: true }));
+
+        let view = state.camera().view_matrix();
+        down(&mut state, 10.0, 20.0, false);
+        state.msg(Msg::PointerMove(1, 80.0, 90.0));
+        state.msg(Msg::PointerUp(1, 500.0));
+        assert_eq!(state.take_pick_request(), None);
+        assert_eq!(state.selection_box(), None);
+        assert_ne!(state.camera().view_matrix(), view);
+    }
+
+    #[test]
+    fn losing_the_pointer_drops_the_box() {
+        let mut state = State::new();
+        down(&mut state, 10.0, 20.0, true);
+        state.msg(Msg::PointerMove(1, 80.0, 90.0));
+        state.msg(Msg::PointerCancel(1));
+        assert_eq!(state.selection_box(), None);
+        state.msg(Msg::PointerUp(1, 500.0));
+        assert_eq!(state.take_pick_request(), None);
+    }
+}
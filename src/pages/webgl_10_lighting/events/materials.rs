@@ -0,0 +1,80 @@
+use super::super::state::{materials_from_json, materials_to_json, Material, MaterialValue, Msg, BLINN_PHONG};
+use super::super::App;
+
+/// Imported materials go to `localStorage`, under a key of their own for each lesson page.
+fn storage_key() -> String {
+    let pathname = web_sys::window().unwrap().location().pathname().unwrap_or_default();
+    format!("webgl-practices:{}:materials", pathname)
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window().unwrap().local_storage().ok().flatten()
+}
+
+/// Restores the materials imported by a previous visit.
+pub fn load(app: &App) {
+    let json = match storage().and_then(|s| s.get_item(&storage_key()).ok().flatten()) {
+        Some(json) => json,
+        None => return,
+    };
+    match materials_from_json(&json).and_then(|materials| check(app, materials)) {
+        Ok(materials) => app.state.borrow_mut().msg(Msg::LoadMaterials(materials)),
+        Err(e) => log::warn!("saved materials ignored: {}", e),
+    }
+}
+
+fn persist(app: &App) {
+    let json = materials_to_json(&app.state.borrow().custom_materials());
+    if let Some(storage) = storage() {
+        if storage.set_item(&storage_key(), &json).is_err() {
+            log::warn!("materials could not be saved");
+        }
+    }
+}
+
+/// Only materials of the lit program can be drawn here.
+fn check(app: &App, materials: Vec<Material>) -> Result<Vec<Material>, String> {
+    if let Some(other) = materials.iter().find(|m| m.shader != BLINN_PHONG) {
+        return Err(format!("{} is a {} material, not {}", other.name, other.shader, BLINN_PHONG));
+    }
+    for material in &materials {
+        for value in material.params.values() {
+            if let MaterialValue::Texture(name) = value {
+                if !app.sphere.borrow().has_texture(name) {
+                    log::warn!("material {}: no texture {}, drawn white", material.name, name);
+                }
+            }
+        }
+    }
+    Ok(materials)
+}
+
+/// Shows the material of the selected object, overrides included, as JSON
+/// to copy and share.
+pub fn export(app: &App) {
+    let json = {
+        let state = app.state.borrow();
+        state.material_of(state.selected_object()).to_json()
+    };
+    log::info!("material:\n{}", json);
+    let window = web_sys::window().unwrap();
+    let _ = window.prompt_with_message_and_default("Material, copy it with Ctrl+C", &json.replace('\n', ""));
+}
+
+/// Adds pasted materials to the library, the selected object takes the first.
+pub fn import(app: &App) {
+    let window = web_sys::window().unwrap();
+    let json = match window.prompt_with_message("Paste the material JSON") {
+        Ok(Some(json)) if !json.trim().is_empty() => json,
+        _ => return,
+    };
+    match materials_from_json(&json).and_then(|materials| check(app, materials)) {
+        Ok(materials) => {
+            app.state.borrow_mut().msg(Msg::ImportMaterials(materials));
+            persist(app);
+        }
+        Err(e) => {
+            let _ = window.alert_with_message(&format!("Invalid material: {}", e));
+        }
+    }
+}
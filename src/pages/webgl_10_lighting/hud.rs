@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement, HtmlSelectElement};
+
+use super::state::{Shading, State, MAX_LIGHTS, SHININESS_RANGE};
+
+/// The shading switch, the material of the selected object, and the line
+/// between the two halves of the side by side view.
+pub struct Hud {
+    info: HtmlElement,
+    shading_inputs: Vec<(Shading, HtmlInputElement)>,
+    object_label: HtmlElement,
+    material_select: HtmlSelectElement,
+    /// names in `material_select`, refilled when the library changes
+    material_names: RefCell<Vec<String>>,
+    diffuse_input: HtmlInputElement,
+    shininess_input: HtmlInputElement,
+    shininess_label: HtmlElement,
+    divider: HtmlElement,
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&panel)?;
+
+        let switch = document.create_element("div")?;
+        let mut shading_inputs = Vec::new();
+        for shading in Shading::ALL.iter() {
+            let label = document.create_element("label")?;
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("radio");
+            input.set_name("shading");
+            label.append_child(&input)?;
+            label.append_with_str_1(shading.label())?;
+            switch.append_child(&label)?;
+            shading_inputs.push((*shading, input));
+        }
+        panel.append_child(&switch)?;
+
+        let picker = document.create_element("div")?;
+        let object_label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+        picker.append_child(&object_label)?;
+        let material_select = document.create_element("select")?.dyn_into::<HtmlSelectElement>()?;
+        picker.append_child(&material_select)?;
+        let diffuse_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        diffuse_input.set_type("color");
+        picker.append_child(&diffuse_input)?;
+        panel.append_child(&picker)?;
+
+        let slider = document.create_element("div")?;
+        slider.set_inner_html("shininess ");
+        let shininess_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        shininess_input.set_type("range");
+        shininess_input.set_min(&SHININESS_RANGE.0.to_string());
+        shininess_input.set_max(&SHININESS_RANGE.1.to_string());
+        slider.append_child(&shininess_input)?;
+        let shininess_label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+        slider.append_child(&shininess_label)?;
+        panel.append_child(&slider)?;
+
+        let info = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        panel.append_child(&info)?;
+
+        let divider = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = divider.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "50%")?;
+        style.set_property("top", "0")?;
+        style.set_property("bottom", "0")?;
+        style.set_property("border-left", "1px solid rgba(255, 255, 255, 0.5)")?;
+        // the canvas under the line keeps the mouse events
+        style.set_property("pointer-events", "none")?;
+        body.append_child(&divider)?;
+
+        Ok(Self {
+            info,
+            shading_inputs,
+            object_label,
+            material_select,
+            material_names: RefCell::new(Vec::new()),
+            diffuse_input,
+            shininess_input,
+            shininess_label,
+            divider,
+        })
+    }
+
+    pub fn shading_inputs(&self) -> impl Iterator<Item = &HtmlInputElement> {
+        self.shading_inputs.iter().map(|(_, input)| input)
+    }
+
+    pub fn material_select(&self) -> &HtmlSelectElement {
+        &self.material_select
+    }
+
+    pub fn diffuse_input(&self) -> &HtmlInputElement {
+        &self.diffuse_input
+    }
+
+    /// The color entered in the diffuse picker.
+    pub fn diffuse(&self) -> Option<[f32; 3]> {
+        from_hex(&self.diffuse_input.value())
+    }
+
+    pub fn shininess_input(&self) -> &HtmlInputElement {
+        &self.shininess_input
+    }
+
+    /// The shading of the checked radio button.
+    pub fn checked_shading(&self) -> Option<Shading> {
+        self.shading_inputs.iter().find(|(_, input)| input.checked()).map(|(shading, _)| *shading)
+    }
+
+    pub fn shininess(&self) -> Option<f32> {
+        self.shininess_input.value().parse().ok()
+    }
+
+    /// Follows the state, which the keys change too.
+    pub fn sync(&self, state: &State) {
+        for (shading, input) in &self.shading_inputs {
+            input.set_checked(*shading == state.shading());
+        }
+        self.sync_material(state);
+
+        let (stacks, slices) = state.tessellation();
+        let halves = if state.shading() == Shading::Split { "<br>Gouraud | Phong" } else { "" };
+        let lights: Vec<_> = state.lights().iter().map(|light| light.name()).collect();
+        let selected = match state.selected_light() {
+            Some(index) => format!(", {} {} selected", state.lights()[index].name(), index + 1),
+            None => String::new(),
+        };
+        let shadows = if !state.shadows() {
+            "off".to_string()
+        } else {
+            let casters: Vec<_> = state.shadow_casters().iter().map(|index| (index + 1).to_string()).collect();
+            match casters.len() {
+                0 => "no point light".to_string(),
+                _ => format!("from light {}", casters.join(", ")),
+            }
+        };
+        self.info.set_inner_html(&format!(
+            "spheres {}x{}, {} triangles{}<br>{} of {} lights: {}{}<br>shadows {}<br>?: keys, drag a light to move it, click a sphere to select it",
+            stacks,
+            slices,
+            stacks * slices * 2,
+            halves,
+            lights.len(),
+            MAX_LIGHTS,
+            lights.join(", "),
+            selected,
+            shadows
+        ));
+        let display = if state.shading() == Shading::Split { "block" } else { "none" };
+        self.divider.style().set_property("display", display).unwrap();
+    }
+
+    fn sync_material(&self, state: &State) {
+        let names = state.material_names();
+        if *self.material_names.borrow() != names {
+            let options: String = names.iter().map(|name| format!("<option>{}</option>", name)).collect();
+            self.material_select.set_inner_html(&options);
+            *self.material_names.borrow_mut() = names;
+        }
+
+        let object = state.selected_object();
+        let material = state.material_of(object);
+        let overridden = if state.objects()[object].overrides.is_empty() { "" } else { "*" };
+        self.object_label.set_inner_html(&format!("sphere {}{} ", object + 1, overridden));
+        if self.material_select.value() != material.name {
+            self.material_select.set_value(&material.name);
+        }
+        if let Some(diffuse) = material.color("diffuse") {
+            // the picker rounds to 8 bits, compare what it would show
+            let hex = to_hex(diffuse);
+            if self.diffuse_input.value() != hex {
+                self.diffuse_input.set_value(&hex);
+            }
+        }
+        if let Some(shininess) = material.scalar("shininess") {
+            if self.shininess() != Some(shininess) {
+                self.shininess_input.set_value(&shininess.to_string());
+            }
+            self.shininess_label.set_inner_html(&format!(" {}", shininess));
+        }
+    }
+}
+
+/// `#rrggbb` of a color of the color input.
+fn to_hex(rgb: [f32; 3]) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(rgb[0]), channel(rgb[1]), channel(rgb[2]))
+}
+
+fn from_hex(hex: &str) -> Option<[f32; 3]> {
+    let channel = |i: usize| {
+        let value = hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok())?;
+        Some(f32::from(value) / 255.0)
+    };
+    Some([channel(1)?, channel(3)?, channel(5)?])
+}
@@ -0,0 +1,292 @@
+use cgmath::{EuclideanSpace, Matrix, Matrix3, Matrix4, Point3, Transform, Vector3};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::size_of;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::mesh::{Mesh, VERTEX_SIZE};
+use super::point_shadows::PointShadows;
+use super::state::{
+    floor_model_matrix, uniform_name, Light, Material, MaterialValue, CHECKER_TEXTURE, MAX_LIGHTS, MAX_SHADOWED_LIGHTS, POINT_SHADOW_FAR,
+    WHITE_TEXTURE,
+};
+use super::Shader;
+use super::State;
+use crate::texture::{Mipmaps, Sampler, SamplerOptions, Texture, TextureFormat, TextureUnits};
+
+/// `aVertexPosition`, `aVertexNormal` and `aTextureCoord`, fixed in both
+/// shaders so one vertex array serves them both
+const POSITION_LOCATION: u32 = 0;
+const NORMAL_LOCATION: u32 = 1;
+const TEXTURE_COORD_LOCATION: u32 = 2;
+/// rows and columns of the floor, lit per vertex too
+const FLOOR_DIVISIONS: usize = 32;
+/// side of the checker texture, and squares along it
+const CHECKER_SIZE: usize = 64;
+const CHECKER_SQUARES: usize = 8;
+
+/// The spheres and the floor, drawn lit per vertex or per fragment.
+pub struct LitMesh {
+    gouraud: Shader,
+    phong: Shader,
+    /// textures the materials refer to by name
+    textures: HashMap<String, Texture>,
+    /// repeated and mipmapped, for all of them
+    sampler: Sampler,
+    /// the shadow cube maps first, kept for the whole pass, then the
+    /// textures of every material
+    units: RefCell<TextureUnits>,
+    tessellation: (usize, usize),
+    index_count: i32,
+    vao: Option<WebGlVertexArrayObject>,
+    vbo: Option<WebGlBuffer>,
+    ebo: Option<WebGlBuffer>,
+    floor_index_count: i32,
+    floor_vao: Option<WebGlVertexArrayObject>,
+}
+
+impl LitMesh {
+    pub fn new(gl: &GL, gouraud: Shader, phong: Shader) -> Result<Self, JsValue> {
+        let mut textures = HashMap::new();
+        let white = Texture::new_2d(gl, TextureFormat::Rgba8, 1, 1, Mipmaps::None)?;
+        white.upload(gl, 0, 0, &[255; 4])?;
+        textures.insert(WHITE_TEXTURE.to_string(), white);
+        let size = CHECKER_SIZE as u32;
+        let checker = Texture::new_2d(gl, TextureFormat::Srgb8Alpha8, size, size, Mipmaps::Full)?;
+        checker.upload(gl, 0, 0, &checker_texels(CHECKER_SIZE, CHECKER_SQUARES))?;
+        checker.generate_mipmaps(gl)?;
+        textures.insert(CHECKER_TEXTURE.to_string(), checker);
+        Ok(Self {
+            gouraud,
+            phong,
+            textures,
+            sampler: Sampler::new(gl, SamplerOptions::default())?,
+            units: RefCell::new(TextureUnits::new(gl)),
+            tessellation: (0, 0),
+            index_count: 0,
+            vao: None,
+            vbo: None,
+            ebo: None,
+            floor_index_count: 0,
+            floor_vao: None,
+        })
+    }
+
+    pub fn has_texture(&self, name: &str) -> bool {
+        self.textures.contains_key(name)
+    }
+
+    /// Stacks and slices of the sphere in the buffers.
+    pub fn tessellation(&self) -> (usize, usize) {
+        self.tessellation
+    }
+
+    /// (Re)builds the buffers for a sphere of `stacks` and `slices`, and the
+    /// floor the first time.
+    pub fn prepare_for_render(&mut self, gl: &GL, (stacks, slices): (usize, usize)) {
+        gl.delete_vertex_array(self.vao.as_ref());
+        gl.delete_buffer(self.vbo.as_ref());
+        gl.delete_buffer(self.ebo.as_ref());
+
+        let mesh = Mesh::uv_sphere(1.0, stacks, slices);
+        let (vao, vbo, ebo) = upload(gl, &mesh);
+        self.vao = vao;
+        self.vbo = Some(vbo);
+        self.ebo = Some(ebo);
+        self.index_count = mesh.indices().len() as i32;
+        self.tessellation = (stacks, slices);
+
+        if self.floor_vao.is_none() {
+            let floor = Mesh::grid(FLOOR_DIVISIONS);
+            self.floor_vao = upload(gl, &floor).0;
+            self.floor_index_count = floor.indices().len() as i32;
+        }
+    }
+
+    /// Draws every object and the floor with only their model matrix set,
+    /// for the shadow passes.
+    pub fn draw_depth(&self, gl: &GL, shader: &Shader, state: &State) {
+        gl.bind_vertex_array(self.vao.as_ref());
+        for object in state.objects() {
+            set_matrix4(gl, shader, "uModelMatrix", object.model_matrix());
+            gl.draw_elements_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0);
+        }
+        gl.bind_vertex_array(self.floor_vao.as_ref());
+        set_matrix4(gl, shader, "uModelMatrix", floor_model_matrix());
+        gl.draw_elements_with_i32(GL::TRIANGLES, self.floor_index_count, GL::UNSIGNED_SHORT, 0);
+        gl.bind_vertex_array(None);
+    }
+
+    /// Draws with the Gouraud or the Phong shader into the current viewport,
+    /// of width / height `aspect`.
+    pub fn render(&self, gl: &GL, state: &State, point_shadows: &PointShadows, phong: bool, aspect: f32) -> Result<(), JsValue> {
+        let shader = if phong { &self.phong } else { &self.gouraud };
+        gl.use_program(Some(&shader.program));
+
+        let camera = state.camera();
+        let view_matrix = camera.view_matrix();
+        set_matrix4(gl, shader, "uViewMatrix", view_matrix);
+        set_matrix4(gl, shader, "uProjectiveMatrix", camera.projection_matrix(aspect));
+
+        // the lights are given in world space, the shaders light in eye space
+        let lights = state.lights();
+        let casters = state.shadow_casters();
+        let location = shader.get_uniform_location(gl, "uLightCount");
+        gl.uniform1i(location.as_ref(), lights.len().min(MAX_LIGHTS) as i32);
+        for (i, light) in lights.iter().take(MAX_LIGHTS).enumerate() {
+            let uniform = |member: &str| format!("uLights[{}].{}", i, member);
+            let location = shader.get_uniform_location(gl, &uniform("kind"));
+            gl.uniform1i(location.as_ref(), light.kind());
+            let (position, direction) = match *light {
+                Light::Directional { direction, .. } => (Point3::origin(), direction),
+                Light::Point { position, .. } => (position, Vector3::unit_z()),
+                Light::Spot { position, direction, .. } => (position, direction),
+            };
+            set_vector3(gl, shader, &uniform("position"), view_matrix.transform_point(position).into());
+            set_vector3(gl, shader, &uniform("direction"), camera.to_eye_direction(direction).into());
+            let color = light.color();
+            set_vector3(gl, shader, &uniform("ambient"), color.ambient);
+            set_vector3(gl, shader, &uniform("diffuse"), color.diffuse);
+            set_vector3(gl, shader, &uniform("specular"), color.specular);
+            let attenuation = match *light {
+                Light::Point { attenuation, .. } | Light::Spot { attenuation, .. } => attenuation.to_uniform(),
+                // never read for a directional light
+                Light::Directional { .. } => [1.0, 0.0, 0.0, 0.0],
+            };
+            let location = shader.get_uniform_location(gl, &uniform("attenuation"));
+            gl.uniform4fv_with_f32_array(location.as_ref(), &attenuation);
+            let location = shader.get_uniform_location(gl, &uniform("cone"));
+            gl.uniform2fv_with_f32_array(location.as_ref(), &light.cone_cosines());
+            let slot = casters.iter().position(|&caster| caster == i).map_or(-1, |slot| slot as i32);
+            let location = shader.get_uniform_location(gl, &uniform("shadow"));
+            gl.uniform1i(location.as_ref(), slot);
+        }
+        let mut units = self.units.borrow_mut();
+        units.release();
+        self.bind_point_shadows(gl, shader, point_shadows, &mut units)?;
+        let eye_to_world = Matrix3::from_cols(view_matrix.x.truncate(), view_matrix.y.truncate(), view_matrix.z.truncate()).transpose();
+        let location = shader.get_uniform_location(gl, "uEyeToWorld");
+        let array: &[f32; 9] = eye_to_world.as_ref();
+        gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+        gl.bind_vertex_array(self.vao.as_ref());
+        for (index, object) in state.objects().iter().enumerate() {
+            let model_matrix = object.model_matrix();
+            set_matrix4(gl, shader, "uModelMatrix", model_matrix);
+            let normal_matrix: Matrix3<f32> = camera.normal_matrix(model_matrix);
+            let location = shader.get_uniform_location(gl, "uNormalMatrix");
+            let array: &[f32; 9] = normal_matrix.as_ref();
+            gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+            self.apply_material(gl, shader, &state.material_of(index), &mut units)?;
+            gl.draw_elements_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0);
+        }
+
+        let model_matrix = floor_model_matrix();
+        set_matrix4(gl, shader, "uModelMatrix", model_matrix);
+        let normal_matrix: Matrix3<f32> = camera.normal_matrix(model_matrix);
+        let location = shader.get_uniform_location(gl, "uNormalMatrix");
+        let array: &[f32; 9] = normal_matrix.as_ref();
+        gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+        self.apply_material(gl, shader, &state.floor_material(), &mut units)?;
+        gl.bind_vertex_array(self.floor_vao.as_ref());
+        gl.draw_elements_with_i32(GL::TRIANGLES, self.floor_index_count, GL::UNSIGNED_SHORT, 0);
+        gl.bind_vertex_array(None);
+        Ok(())
+    }
+
+    /// Binds every shadow cube map to units reserved for the whole pass,
+    /// used or not: samplers of two types may not share a unit, and the
+    /// unset ones would all be on unit 0.
+    fn bind_point_shadows(&self, gl: &GL, shader: &Shader, point_shadows: &PointShadows, units: &mut TextureUnits) -> Result<(), JsValue> {
+        let slots = (0..MAX_SHADOWED_LIGHTS)
+            .map(|slot| units.bind(gl, point_shadows.cube(slot), point_shadows.sampler()))
+            .collect::<Result<Vec<_>, _>>()?;
+        units.reserve();
+        let location = shader.get_uniform_location(gl, "uPointShadowMaps");
+        gl.uniform1iv_with_i32_array(location.as_ref(), &slots);
+        let location = shader.get_uniform_location(gl, "uPointShadowFar");
+        gl.uniform1f(location.as_ref(), POINT_SHADOW_FAR);
+        Ok(())
+    }
+
+    /// Sets the uniform of every parameter of `material` the program takes,
+    /// textures on the units left free by the shadow cube maps.
+    fn apply_material(&self, gl: &GL, shader: &Shader, material: &Material, units: &mut TextureUnits) -> Result<(), JsValue> {
+        units.reset();
+        for (param, value) in &material.params {
+            // a material may carry parameters for other programs
+            let location = match shader.find_uniform_location(gl, &uniform_name(param)) {
+                Some(location) => location,
+                None => continue,
+            };
+            match value {
+                MaterialValue::Color(rgb) => gl.uniform3fv_with_f32_array(Some(&location), rgb),
+                MaterialValue::Scalar(value) => gl.uniform1f(Some(&location), *value),
+                MaterialValue::Texture(name) => {
+                    // reported on import, drawn white
+                    let texture = self.textures.get(name).unwrap_or(&self.textures[WHITE_TEXTURE]);
+                    let unit = units.bind(gl, texture, &self.sampler)?;
+                    gl.uniform1i(Some(&location), unit);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Vertex array of `mesh`, with its vertex and index buffers.
+fn upload(gl: &GL, mesh: &Mesh) -> (Option<WebGlVertexArrayObject>, WebGlBuffer, WebGlBuffer) {
+    let vao = gl.create_vertex_array();
+    gl.bind_vertex_array(vao.as_ref());
+
+    let vbo = gl.create_buffer().unwrap();
+    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+    unsafe {
+        let data_array = js_sys::Float32Array::view(mesh.vertices());
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+    }
+    let stride = (VERTEX_SIZE * size_of::<f32>()) as i32;
+    gl.enable_vertex_attrib_array(POSITION_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(POSITION_LOCATION, 3, GL::FLOAT, false, stride, 0);
+    gl.enable_vertex_attrib_array(NORMAL_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(NORMAL_LOCATION, 3, GL::FLOAT, false, stride, 3 * size_of::<f32>() as i32);
+    gl.enable_vertex_attrib_array(TEXTURE_COORD_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(TEXTURE_COORD_LOCATION, 2, GL::FLOAT, false, stride, 6 * size_of::<f32>() as i32);
+
+    let ebo = gl.create_buffer().unwrap();
+    gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+    unsafe {
+        let data_array = js_sys::Uint16Array::view(mesh.indices());
+        gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+    }
+
+    gl.bind_vertex_array(None);
+    gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+    gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    (vao, vbo, ebo)
+}
+
+/// RGBA8 texels of a `size` wide checkerboard of `squares` light and dark
+/// gray squares a side.
+fn checker_texels(size: usize, squares: usize) -> Vec<u8> {
+    let square = size / squares;
+    let mut texels = Vec::with_capacity(size * size * 4);
+    for row in 0..size {
+        for column in 0..size {
+            let gray = if (row / square + column / square).is_multiple_of(2) { 255 } else { 150 };
+            texels.extend_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+    texels
+}
+
+fn set_matrix4(gl: &GL, shader: &Shader, uniform_name: &str, matrix: Matrix4<f32>) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    let array: &[f32; 16] = matrix.as_ref();
+    gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+}
+
+fn set_vector3(gl: &GL, shader: &Shader, uniform_name: &str, vector: [f32; 3]) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    gl.uniform3fv_with_f32_array(location.as_ref(), &vector);
+}
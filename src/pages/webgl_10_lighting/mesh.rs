@@ -0,0 +1,148 @@
+use std::f32::consts::PI;
+
+/// floats per vertex: position(3) + normal(3) + texture coordinates(2)
+pub const VERTEX_SIZE: usize = 8;
+
+pub struct Mesh {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// Sphere around the origin, `stacks` rings from pole to pole and
+    /// `slices` around the y axis. The seam column is duplicated, for the
+    /// texture coordinates to go from 0 to 1 around.
+    pub fn uv_sphere(radius: f32, stacks: usize, slices: usize) -> Self {
+        let mut vertices = Vec::with_capacity((stacks + 1) * (slices + 1) * VERTEX_SIZE);
+        for i in 0..=stacks {
+            let (sin_theta, cos_theta) = (i as f32 / stacks as f32 * PI).sin_cos();
+            for j in 0..=slices {
+                let (sin_phi, cos_phi) = ((j % slices) as f32 / slices as f32 * 2.0 * PI).sin_cos();
+                // on a sphere around the origin the normal is the position direction
+                let normal = [sin_theta * cos_phi, cos_theta, sin_theta * sin_phi];
+                vertices.extend_from_slice(&[normal[0] * radius, normal[1] * radius, normal[2] * radius]);
+                vertices.extend_from_slice(&normal);
+                vertices.extend_from_slice(&[j as f32 / slices as f32, i as f32 / stacks as f32]);
+            }
+        }
+
+        let columns = slices + 1;
+        let mut indices = Vec::with_capacity(stacks * slices * 6);
+        for i in 0..stacks {
+            for j in 0..slices {
+                let a = (i * columns + j) as u16;
+                let b = ((i + 1) * columns + j) as u16;
+                let c = ((i + 1) * columns + j + 1) as u16;
+                let d = (i * columns + j + 1) as u16;
+                indices.extend_from_slice(&[a, d, b, b, d, c]);
+            }
+        }
+        Self { vertices, indices }
+    }
+
+    /// Square from -1 to 1 in the y = 0 plane, facing up, cut in
+    /// `divisions` rows and columns: lit per vertex, a single quad would
+    /// miss a point light above its middle.
+    pub fn grid(divisions: usize) -> Self {
+        let mut vertices = Vec::with_capacity((divisions + 1) * (divisions + 1) * VERTEX_SIZE);
+        for i in 0..=divisions {
+            let z = i as f32 / divisions as f32 * 2.0 - 1.0;
+            for j in 0..=divisions {
+                let x = j as f32 / divisions as f32 * 2.0 - 1.0;
+                let (u, v) = (j as f32 / divisions as f32, i as f32 / divisions as f32);
+                vertices.extend_from_slice(&[x, 0.0, z, 0.0, 1.0, 0.0, u, v]);
+            }
+        }
+
+        let columns = divisions + 1;
+        let mut indices = Vec::with_capacity(divisions * divisions * 6);
+        for i in 0..divisions {
+            for j in 0..divisions {
+                let a = (i * columns + j) as u16;
+                let b = ((i + 1) * columns + j) as u16;
+                let c = ((i + 1) * columns + j + 1) as u16;
+                let d = (i * columns + j + 1) as u16;
+                indices.extend_from_slice(&[a, b, d, d, b, c]);
+            }
+        }
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_normals_are_unit_and_outward() {
+        let sphere = Mesh::uv_sphere(2.0, 8, 12);
+        assert_eq!(sphere.vertices().len(), 9 * 13 * VERTEX_SIZE);
+        assert_eq!(sphere.indices().len(), 8 * 12 * 6);
+        for v in sphere.vertices().chunks(VERTEX_SIZE) {
+            let length = (v[3] * v[3] + v[4] * v[4] + v[5] * v[5]).sqrt();
+            assert!((length - 1.0).abs() < 1e-5);
+            for k in 0..3 {
+                assert!((v[k] - v[k + 3] * 2.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn triangles_wind_counter_clockwise_from_outside() {
+        let sphere = Mesh::uv_sphere(1.0, 8, 12);
+        let position = |i: u16| {
+            let v = &sphere.vertices()[i as usize * VERTEX_SIZE..];
+            [v[0], v[1], v[2]]
+        };
+        for t in sphere.indices().chunks(3) {
+            let (a, b, c) = (position(t[0]), position(t[1]), position(t[2]));
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            // triangles at the poles are degenerate
+            let area = (cross[0].powi(2) + cross[1].powi(2) + cross[2].powi(2)).sqrt();
+            if area < 1e-6 {
+                continue;
+            }
+            let centroid = [a[0] + b[0] + c[0], a[1] + b[1] + c[1], a[2] + b[2] + c[2]];
+            assert!(cross[0] * centroid[0] + cross[1] * centroid[1] + cross[2] * centroid[2] > 0.0);
+        }
+    }
+
+    #[test]
+    fn texture_coordinates_span_the_unit_square() {
+        let sphere = Mesh::uv_sphere(1.0, 8, 12);
+        let grid = Mesh::grid(4);
+        for mesh in [sphere, grid].iter() {
+            let uvs: Vec<_> = mesh.vertices().chunks(VERTEX_SIZE).map(|v| (v[6], v[7])).collect();
+            assert!(uvs.iter().all(|&(u, v)| (0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)));
+            assert_eq!(uvs.first(), Some(&(0.0, 0.0)));
+            assert_eq!(uvs.last(), Some(&(1.0, 1.0)));
+        }
+    }
+
+    #[test]
+    fn grid_faces_up() {
+        let grid = Mesh::grid(4);
+        assert_eq!(grid.vertices().len(), 5 * 5 * VERTEX_SIZE);
+        assert_eq!(grid.indices().len(), 4 * 4 * 6);
+        let position = |i: u16| {
+            let v = &grid.vertices()[i as usize * VERTEX_SIZE..];
+            [v[0], v[1], v[2]]
+        };
+        for t in grid.indices().chunks(3) {
+            let (a, b, c) = (position(t[0]), position(t[1]), position(t[2]));
+            // y of the cross product of the edges, positive when counter clockwise seen from above
+            let cross_y = (c[0] - a[0]) * (b[2] - a[2]) - (c[2] - a[2]) * (b[0] - a[0]);
+            assert!(cross_y > 0.0, "{:?}", t);
+        }
+    }
+}
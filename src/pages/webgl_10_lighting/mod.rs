@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use crate::input::{HelpOverlay, Input};
+use canvas::{create_canvas, create_webgl_context};
+use events::{attach_event_handlers, handle_input};
+use hud::Hud;
+use light_gizmos::LightGizmos;
+use lit_mesh::LitMesh;
+use point_shadows::PointShadows;
+use shader::Shader;
+use state::{Msg, Shading, State};
+
+mod canvas;
+mod events;
+mod hud;
+mod light_gizmos;
+mod lit_mesh;
+mod mesh;
+mod point_shadows;
+mod shader;
+mod state;
+
+pub fn run() -> Result<(), JsValue> {
+    let app = Rc::new(App::new());
+    attach_event_handlers(Rc::clone(&app))?;
+    Rc::clone(&app).render()?;
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        app.render().unwrap();
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut()>));
+    request_animation_frame(g.borrow().as_ref().unwrap());
+    Ok(())
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+pub struct App {
+    canvas: HtmlCanvasElement,
+    gl: GL,
+    state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    hud: Hud,
+    sphere: RefCell<LitMesh>,
+    point_shadows: PointShadows,
+    light_gizmos: LightGizmos,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let canvas = create_canvas().unwrap();
+        let gl = create_webgl_context(&canvas).unwrap();
+        let state = RefCell::new(State::new());
+        state.borrow_mut().msg(Msg::ViewportResize(canvas.width() as f64, canvas.height() as f64));
+        let gouraud = Shader::gouraud_shader(&gl).expect("gouraud shader error");
+        let phong = Shader::phong_shader(&gl).expect("phong shader error");
+        let mut sphere = LitMesh::new(&gl, gouraud, phong).expect("lit mesh textures error");
+        sphere.prepare_for_render(&gl, state.borrow().tessellation());
+        let point_shadows = PointShadows::new(&gl, Shader::point_depth_shader(&gl).expect("point depth shader error")).expect("point shadows error");
+        let mut light_gizmos = LightGizmos::new(Shader::gizmo_shader(&gl).expect("gizmo shader error"));
+        light_gizmos.prepare_for_render(&gl);
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        let hud = Hud::new().expect("hud error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            hud,
+            sphere: RefCell::new(sphere),
+            point_shadows,
+            light_gizmos,
+        }
+    }
+
+    pub fn render(&self) -> Result<(), JsValue> {
+        handle_input(self);
+        let state = self.state.borrow();
+        let clear_color = state.clear_color();
+
+        if self.sphere.borrow().tessellation() != state.tessellation() {
+            self.sphere.borrow_mut().prepare_for_render(&self.gl, state.tessellation());
+        }
+
+        let sphere = self.sphere.borrow();
+        // the cube maps first, into their own framebuffer
+        self.point_shadows.render(&self.gl, &state, &sphere);
+
+        self.gl.clear_color(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+
+        let panes = state.panes();
+        for (i, pane) in panes.iter().enumerate() {
+            self.gl.viewport(pane.left as i32, 0, pane.width as i32, pane.height as i32);
+            // side by side, the same view twice: Gouraud on the left, Phong on the right
+            let phong = match state.shading() {
+                Shading::Split => i == 1,
+                shading => shading == Shading::Phong,
+            };
+            sphere.render(&self.gl, &state, &self.point_shadows, phong, pane.aspect())?;
+            self.light_gizmos.render(&self.gl, &state, pane);
+        }
+        self.hud.sync(&state);
+        Ok(())
+    }
+}
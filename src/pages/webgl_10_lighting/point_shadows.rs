@@ -0,0 +1,97 @@
+use cgmath::Matrix4;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::lit_mesh::LitMesh;
+use super::state::{face_view_projections, MAX_SHADOWED_LIGHTS, POINT_SHADOW_FAR};
+use super::Shader;
+use super::State;
+use crate::texture::{Filter, Mipmaps, Sampler, SamplerOptions, Texture, TextureFormat, Wrap};
+
+/// side of every face of the cube maps
+const CUBE_SIZE: i32 = 512;
+
+/// One depth cube map per shadowed point light, holding in every direction
+/// the distance to the nearest surface.
+pub struct PointShadows {
+    shader: Shader,
+    framebuffer: WebGlFramebuffer,
+    cubes: Vec<Texture>,
+    /// compared lookups through a `samplerCubeShadow`, filtered between the comparisons
+    sampler: Sampler,
+}
+
+impl PointShadows {
+    pub fn new(gl: &GL, shader: Shader) -> Result<Self, JsValue> {
+        let framebuffer = gl.create_framebuffer().ok_or("create framebuffer error")?;
+        let mut cubes = Vec::with_capacity(MAX_SHADOWED_LIGHTS);
+        for _ in 0..MAX_SHADOWED_LIGHTS {
+            cubes.push(Texture::new_cube(gl, TextureFormat::Depth24, CUBE_SIZE as u32, Mipmaps::None)?);
+        }
+        let sampler = Sampler::new(
+            gl,
+            SamplerOptions {
+                wrap: Wrap::ClampToEdge,
+                filter: Filter::Linear,
+                compare: true,
+                ..SamplerOptions::default()
+            },
+        )?;
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            GL::FRAMEBUFFER,
+            GL::DEPTH_ATTACHMENT,
+            GL::TEXTURE_CUBE_MAP_POSITIVE_X,
+            Some(cubes[0].raw()),
+            0,
+        );
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            return Err(format!("shadow framebuffer incomplete: {:#x}", status).into());
+        }
+        Ok(Self {
+            shader,
+            framebuffer,
+            cubes,
+            sampler,
+        })
+    }
+
+    pub fn cube(&self, slot: usize) -> &Texture {
+        &self.cubes[slot]
+    }
+
+    pub fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+
+    /// Renders the six faces of the cube map of every shadow casting light.
+    pub fn render(&self, gl: &GL, state: &State, mesh: &LitMesh) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, CUBE_SIZE, CUBE_SIZE);
+        gl.use_program(Some(&self.shader.program));
+        let location = self.shader.get_uniform_location(gl, "uPointShadowFar");
+        gl.uniform1f(location.as_ref(), POINT_SHADOW_FAR);
+        for (cube, &index) in self.cubes.iter().zip(state.shadow_casters().iter()) {
+            let position = state.lights()[index].position().expect("a point light has a position");
+            let location = self.shader.get_uniform_location(gl, "uLightPosition");
+            gl.uniform3fv_with_f32_array(location.as_ref(), &[position.x, position.y, position.z]);
+            for (face, view_projection) in face_view_projections(position).iter().enumerate() {
+                let target = GL::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32;
+                gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, target, Some(cube.raw()), 0);
+                gl.clear(GL::DEPTH_BUFFER_BIT);
+                self.set_uniform_location_matrix4(gl, "uLightViewProjection", *view_projection);
+                mesh.draw_depth(gl, &self.shader, state);
+            }
+        }
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+}
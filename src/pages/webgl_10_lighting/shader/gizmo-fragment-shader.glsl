@@ -1,10 +1,10 @@
 #version 300 es
 precision mediump float;
 
-in vec4 vVertexColor;
+uniform vec4 uColor;
 
 out vec4 fragColor;
 
 void main(void) {
-    fragColor = vVertexColor;
+    fragColor = uColor;
 }
@@ -0,0 +1,10 @@
+#version 300 es
+precision mediump float;
+
+in vec3 vVertexColor;
+
+out vec4 fragColor;
+
+void main(void) {
+    fragColor = vec4(vVertexColor, 1.0);
+}
@@ -0,0 +1,27 @@
+#version 300 es
+precision mediump float;
+
+uniform mat4 uModelMatrix;
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+// inverse transpose of the model view matrix, a normal is no position
+uniform mat3 uNormalMatrix;
+
+#include "lighting.glsl"
+
+// both shading models draw from the same vertex array
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+layout(location = 2) in vec2 aTextureCoord;
+
+out vec3 vVertexColor;
+
+void main(void) {
+    vec4 position = uViewMatrix * uModelMatrix * vec4(aVertexPosition, 1.0);
+    vec3 normal = normalize(uNormalMatrix * aVertexNormal);
+    // lit once per vertex, the colors are interpolated across the triangles,
+    // and so is the diffuse map: a texel only shows where a vertex samples it
+    vVertexColor = blinnPhong(position.xyz, normal, aTextureCoord);
+
+    gl_Position = uProjectiveMatrix * position;
+}
@@ -0,0 +1,132 @@
+// Blinn-Phong lighting, shared by the Gouraud and Phong shaders. Everything
+// is in eye space, where the eye sits at the origin. MAX_LIGHTS and
+// MAX_SHADOWED_LIGHTS are defined from the Rust side.
+
+precision highp samplerCubeShadow;
+
+const int DIRECTIONAL_LIGHT = 0;
+const int POINT_LIGHT = 1;
+const int SPOT_LIGHT = 2;
+
+struct Light {
+    int kind;
+    vec3 position;
+    // toward the light for a directional light, along the cone for a spot
+    vec3 direction;
+    vec3 ambient;
+    vec3 diffuse;
+    vec3 specular;
+    // constant, linear, quadratic, and a radius replacing them when not 0
+    vec4 attenuation;
+    // cosines of the inner and outer half angles of a spot
+    vec2 cone;
+    // slot of its shadow cube map, -1 without
+    int shadow;
+};
+
+uniform Light uLights[MAX_LIGHTS];
+uniform int uLightCount;
+
+// distances from each shadowed point light divided by uPointShadowFar, compared with the reference
+uniform samplerCubeShadow uPointShadowMaps[MAX_SHADOWED_LIGHTS];
+uniform float uPointShadowFar;
+// the cube maps are in world space, this turns eye space directions back
+uniform mat3 uEyeToWorld;
+
+// offsets around the lookup direction, the percentage closer filtering kernel
+const int PCF_SAMPLES = 20;
+const vec3 PCF_KERNEL[PCF_SAMPLES] = vec3[PCF_SAMPLES](
+    vec3(1.0, 1.0, 1.0), vec3(1.0, -1.0, 1.0), vec3(-1.0, -1.0, 1.0), vec3(-1.0, 1.0, 1.0),
+    vec3(1.0, 1.0, -1.0), vec3(1.0, -1.0, -1.0), vec3(-1.0, -1.0, -1.0), vec3(-1.0, 1.0, -1.0),
+    vec3(1.0, 1.0, 0.0), vec3(1.0, -1.0, 0.0), vec3(-1.0, -1.0, 0.0), vec3(-1.0, 1.0, 0.0),
+    vec3(1.0, 0.0, 1.0), vec3(-1.0, 0.0, 1.0), vec3(1.0, 0.0, -1.0), vec3(-1.0, 0.0, -1.0),
+    vec3(0.0, 1.0, 1.0), vec3(0.0, -1.0, 1.0), vec3(0.0, -1.0, -1.0), vec3(0.0, 1.0, -1.0)
+);
+// radius of the kernel per unit of distance to the light, the shadows soften away from their casters
+const float PCF_SPREAD = 0.015;
+
+uniform vec3 uMaterialAmbient;
+uniform vec3 uMaterialDiffuse;
+uniform vec3 uMaterialSpecular;
+// light of its own, added whatever the lights
+uniform vec3 uMaterialEmissive;
+uniform float uMaterialShininess;
+// multiplies the diffuse color, white for the plain materials
+uniform sampler2D uMaterialDiffuseMap;
+
+// samplers may only be indexed by constants, one branch per slot; their
+// units are reserved on the Rust side, apart from the ones of the materials
+#if MAX_SHADOWED_LIGHTS != 2
+#error shadowLookup has a branch for 2 shadow cube maps
+#endif
+float shadowLookup(int slot, vec4 coords) {
+    if (slot == 0) {
+        return texture(uPointShadowMaps[0], coords);
+    }
+    return texture(uPointShadowMaps[1], coords);
+}
+
+// 1 where point light `slot` reaches, along `fromLight` in world space, 0 in its shadow
+float pointShadow(int slot, vec3 fromLight, float bias) {
+    float distance = length(fromLight);
+    if (distance >= uPointShadowFar) {
+        return 1.0;
+    }
+    float reference = (distance - bias) / uPointShadowFar;
+    float radius = distance * PCF_SPREAD;
+    float lit = 0.0;
+    for (int i = 0; i < PCF_SAMPLES; i++) {
+        lit += shadowLookup(slot, vec4(fromLight + PCF_KERNEL[i] * radius, reference));
+    }
+    return lit / float(PCF_SAMPLES);
+}
+
+float attenuate(vec4 attenuation, float distance) {
+    float radius = attenuation.w;
+    if (radius > 0.0) {
+        // inverse square, windowed to reach exactly 0 at the radius
+        float ratio = distance / radius;
+        float falloff = clamp(1.0 - ratio * ratio * ratio * ratio, 0.0, 1.0);
+        return falloff * falloff / (distance * distance + 1.0);
+    }
+    return 1.0 / (attenuation.x + attenuation.y * distance + attenuation.z * distance * distance);
+}
+
+vec3 blinnPhong(vec3 position, vec3 normal, vec2 textureCoord) {
+    vec3 toEye = normalize(-position);
+    vec3 diffuse = uMaterialDiffuse * texture(uMaterialDiffuseMap, textureCoord).rgb;
+    vec3 color = uMaterialEmissive;
+    for (int i = 0; i < MAX_LIGHTS; i++) {
+        if (i >= uLightCount) {
+            break;
+        }
+        Light light = uLights[i];
+
+        vec3 toLight = normalize(light.direction);
+        float intensity = 1.0;
+        if (light.kind != DIRECTIONAL_LIGHT) {
+            vec3 offset = light.position - position;
+            float distance = length(offset);
+            toLight = offset / distance;
+            intensity = attenuate(light.attenuation, distance);
+        }
+        if (light.kind == SPOT_LIGHT) {
+            float cosine = dot(-toLight, normalize(light.direction));
+            intensity *= smoothstep(light.cone.y, light.cone.x, cosine);
+        }
+
+        float lambertTerm = max(dot(normal, toLight), 0.0);
+        if (light.shadow >= 0 && lambertTerm > 0.0) {
+            // more bias on the slopes, where a texel spans more depth
+            float bias = mix(0.02, 0.08, 1.0 - lambertTerm);
+            intensity *= pointShadow(light.shadow, uEyeToWorld * (position - light.position), bias);
+        }
+        // the halfway vector is cheaper than reflecting, and keeps a round highlight at grazing angles
+        vec3 halfway = normalize(toLight + toEye);
+        // faces turned away from the light get no highlight
+        float specularTerm = lambertTerm > 0.0 ? pow(max(dot(normal, halfway), 0.0), uMaterialShininess) : 0.0;
+        color += light.ambient * uMaterialAmbient
+            + intensity * (light.diffuse * diffuse * lambertTerm + light.specular * uMaterialSpecular * specularTerm);
+    }
+    return color;
+}
@@ -0,0 +1,125 @@
+use log::debug;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::state::{MAX_LIGHTS, MAX_SHADOWED_LIGHTS};
+
+static LIGHTING_SRC: &str = include_str!("./lighting.glsl");
+static GOURAUD_VERT_SHADER_SRC: &str = include_str!("./gouraud-vertex-shader.glsl");
+static GOURAUD_FRAG_SHADER_SRC: &str = include_str!("./gouraud-fragment-shader.glsl");
+static PHONG_VERT_SHADER_SRC: &str = include_str!("./phong-vertex-shader.glsl");
+static PHONG_FRAG_SHADER_SRC: &str = include_str!("./phong-fragment-shader.glsl");
+static GIZMO_VERT_SHADER_SRC: &str = include_str!("./gizmo-vertex-shader.glsl");
+static GIZMO_FRAG_SHADER_SRC: &str = include_str!("./gizmo-fragment-shader.glsl");
+static POINT_DEPTH_VERT_SHADER_SRC: &str = include_str!("./point-depth-vertex-shader.glsl");
+static POINT_DEPTH_FRAG_SHADER_SRC: &str = include_str!("./point-depth-fragment-shader.glsl");
+
+/// GLSL has no includes, the shared lighting code replaces this line
+const LIGHTING_INCLUDE: &str = "#include \"lighting.glsl\"";
+
+pub struct Shader {
+    pub program: WebGlProgram,
+    uniforms: RefCell<HashMap<String, WebGlUniformLocation>>,
+}
+
+macro_rules! try_debug {
+    ($target: expr, $e: expr) => {
+        match $e {
+            Ok(result) => result,
+            Err(msg) => {
+                debug!("{} error: {}", $target, msg);
+                return Err(msg.into());
+            }
+        }
+    };
+}
+
+impl Shader {
+    pub fn new(gl: &GL, vert_shader: &str, frag_shader: &str) -> Result<Shader, JsValue> {
+        let vert_shader = try_debug!("compile vert shader", compile_shader(gl, GL::VERTEX_SHADER, vert_shader));
+        let frag_shader = try_debug!("compile frag shader", compile_shader(gl, GL::FRAGMENT_SHADER, frag_shader));
+        let program = try_debug!("link program", link_program(gl, &vert_shader, &frag_shader));
+        let uniforms = RefCell::new(HashMap::new());
+        Ok(Self { program, uniforms })
+    }
+
+    /// Lit per vertex, the colors are interpolated.
+    pub fn gouraud_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, &with_lighting(GOURAUD_VERT_SHADER_SRC), GOURAUD_FRAG_SHADER_SRC)
+    }
+
+    /// Lit per fragment, the normals are interpolated.
+    pub fn phong_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, PHONG_VERT_SHADER_SRC, &with_lighting(PHONG_FRAG_SHADER_SRC))
+    }
+
+    /// Plain colored lines, for the light gizmos.
+    pub fn gizmo_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, GIZMO_VERT_SHADER_SRC, GIZMO_FRAG_SHADER_SRC)
+    }
+
+    /// Distances to a point light, for its shadow cube map.
+    pub fn point_depth_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, POINT_DEPTH_VERT_SHADER_SRC, POINT_DEPTH_FRAG_SHADER_SRC)
+    }
+
+    /// The location of a uniform the program may not declare, for parameters
+    /// that only some programs take.
+    pub fn find_uniform_location(&self, gl: &GL, uniform_name: &str) -> Option<WebGlUniformLocation> {
+        if let Some(location) = self.uniforms.borrow().get(uniform_name) {
+            return Some(location.clone());
+        }
+        let location = gl.get_uniform_location(&self.program, uniform_name)?;
+        self.uniforms.borrow_mut().insert(uniform_name.to_string(), location.clone());
+        Some(location)
+    }
+
+    pub fn get_uniform_location(&self, gl: &GL, uniform_name: &str) -> Option<WebGlUniformLocation> {
+        let mut uniforms = self.uniforms.borrow_mut();
+        if uniforms.get(uniform_name).is_none() {
+            uniforms.insert(
+                uniform_name.to_string(),
+                gl.get_uniform_location(&self.program, uniform_name)
+                    .unwrap_or_else(|| panic!(r#"Uniform '{}' not found"#, uniform_name)),
+            );
+        }
+        Some(uniforms.get(uniform_name).expect("loc").clone())
+    }
+}
+
+fn with_lighting(source: &str) -> String {
+    let lighting = format!(
+        "#define MAX_LIGHTS {}\n#define MAX_SHADOWED_LIGHTS {}\n{}",
+        MAX_LIGHTS, MAX_SHADOWED_LIGHTS, LIGHTING_SRC
+    );
+    source.replace(LIGHTING_INCLUDE, &lighting)
+}
+
+fn compile_shader(gl: &GL, shader_type: u32, source: &str) -> Result<WebGlShader, String> {
+    let shader = gl.create_shader(shader_type).ok_or_else(|| "Could not create shader".to_string())?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        Ok(shader)
+    } else {
+        Err(gl
+            .get_shader_info_log(&shader)
+            .unwrap_or_else(|| "Unknown error creating shader".to_string()))
+    }
+}
+
+fn link_program(gl: &GL, vert_shader: &WebGlShader, frag_shader: &WebGlShader) -> Result<WebGlProgram, String> {
+    let program = gl.create_program().ok_or_else(|| "Unable to create shader program".to_string())?;
+    gl.attach_shader(&program, vert_shader);
+    gl.attach_shader(&program, frag_shader);
+    gl.link_program(&program);
+    if gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+        Ok(program)
+    } else {
+        Err(gl
+            .get_program_info_log(&program)
+            .unwrap_or_else(|| "Unknown error creating program".to_string()))
+    }
+}
@@ -0,0 +1,15 @@
+#version 300 es
+precision mediump float;
+
+#include "lighting.glsl"
+
+in vec3 vPosition;
+in vec3 vNormal;
+in vec2 vTextureCoord;
+
+out vec4 fragColor;
+
+void main(void) {
+    // interpolated normals are shorter than 1 between the vertices
+    fragColor = vec4(blinnPhong(vPosition, normalize(vNormal), vTextureCoord), 1.0);
+}
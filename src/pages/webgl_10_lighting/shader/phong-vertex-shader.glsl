@@ -0,0 +1,27 @@
+#version 300 es
+precision mediump float;
+
+uniform mat4 uModelMatrix;
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+// inverse transpose of the model view matrix, a normal is no position
+uniform mat3 uNormalMatrix;
+
+// both shading models draw from the same vertex array
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+layout(location = 2) in vec2 aTextureCoord;
+
+// eye space, interpolated for the fragment shader to light
+out vec3 vPosition;
+out vec3 vNormal;
+out vec2 vTextureCoord;
+
+void main(void) {
+    vec4 position = uViewMatrix * uModelMatrix * vec4(aVertexPosition, 1.0);
+    vPosition = position.xyz;
+    vNormal = uNormalMatrix * aVertexNormal;
+    vTextureCoord = aTextureCoord;
+
+    gl_Position = uProjectiveMatrix * position;
+}
@@ -0,0 +1,13 @@
+#version 300 es
+precision highp float;
+
+uniform vec3 uLightPosition;
+uniform float uPointShadowFar;
+
+in vec3 vWorldPosition;
+
+// the distance to the light instead of the depth of the face, the same
+// whichever face a direction falls in
+void main(void) {
+    gl_FragDepth = length(vWorldPosition - uLightPosition) / uPointShadowFar;
+}
@@ -0,0 +1,16 @@
+#version 300 es
+precision highp float;
+
+uniform mat4 uModelMatrix;
+// of the cube face being rendered
+uniform mat4 uLightViewProjection;
+
+layout(location = 0) in vec3 aVertexPosition;
+
+out vec3 vWorldPosition;
+
+void main(void) {
+    vec4 position = uModelMatrix * vec4(aVertexPosition, 1.0);
+    vWorldPosition = position.xyz;
+    gl_Position = uLightViewProjection * position;
+}
@@ -0,0 +1,181 @@
+use cgmath::{EuclideanSpace, InnerSpace, Matrix, Matrix3, Matrix4, PerspectiveFov, Point3, Rad, SquareMatrix, Vector3, Vector4};
+use std::f32::consts::PI;
+
+/// closest and farthest the eye gets from the focus, staying outside of the unit sphere
+const MIN_DISTANCE: f32 = 1.5;
+const MAX_DISTANCE: f32 = 20.0;
+
+pub struct Camera {
+    projection: PerspectiveFov<f32>,
+    up_down_angle: f32,    // pitch
+    left_right_angle: f32, // yaw
+    r: f32,
+    focus: Point3<f32>,
+    sensitivity: f32,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        let fovy = Rad(PI / 4.0);
+
+        Camera {
+            projection: PerspectiveFov {
+                fovy,
+                aspect: 1.0,
+                near: 0.1,
+                far: 100.0,
+            },
+            left_right_angle: 30.0f32.to_radians(),
+            up_down_angle: 20.0f32.to_radians(),
+            r: 7.0,
+            focus: Point3::new(0.0, 0.0, 0.0),
+            sensitivity: 0.01,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let eye = self.get_eye();
+        Matrix4::look_at(eye, self.focus, Vector3::unit_y())
+    }
+
+    /// Projection for a viewport of width / height `aspect`, the side by
+    /// side view draws two half width ones.
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4<f32> {
+        PerspectiveFov { aspect, ..self.projection }.into()
+    }
+
+    /// Takes the normals of a mesh placed by `model` to eye space. It is the
+    /// inverse transpose of the model view matrix: under a non-uniform scale
+    /// the model view matrix itself would tilt the normals off their faces.
+    /// Only the upper 3x3 part is kept, normals are not moved.
+    pub fn normal_matrix(&self, model: Matrix4<f32>) -> Matrix3<f32> {
+        let model_view = self.view_matrix() * model;
+        let linear = Matrix3::from_cols(model_view.x.truncate(), model_view.y.truncate(), model_view.z.truncate());
+        linear.invert().expect("model matrix not invertible").transpose()
+    }
+
+    /// A world space direction in eye space, where the lighting is done.
+    pub fn to_eye_direction(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        (self.view_matrix() * direction.extend(0.0)).truncate()
+    }
+
+    /// Normalized device coordinates of a world point, none behind the eye.
+    pub fn project(&self, point: Point3<f32>, aspect: f32) -> Option<(f32, f32)> {
+        let clip = self.projection_matrix(aspect) * self.view_matrix() * point.to_homogeneous();
+        if clip.w <= 0.0 {
+            return None;
+        }
+        Some((clip.x / clip.w, clip.y / clip.w))
+    }
+
+    /// The eye and the unit direction through the normalized device
+    /// coordinates `ndc_x`, `ndc_y`.
+    pub fn ray(&self, ndc_x: f32, ndc_y: f32, aspect: f32) -> (Point3<f32>, Vector3<f32>) {
+        let inverse = (self.projection_matrix(aspect) * self.view_matrix())
+            .invert()
+            .expect("view projection not invertible");
+        let far = inverse * Vector4::new(ndc_x, ndc_y, 1.0, 1.0);
+        let far = Point3::from_vec(far.truncate() / far.w);
+        let eye = self.get_eye();
+        (eye, (far - eye).normalize())
+    }
+
+    /// Where the pointer ray through `ndc_x`, `ndc_y` crosses the plane facing
+    /// the eye through `point`, to drag things at a constant depth.
+    pub fn drag_point(&self, point: Point3<f32>, ndc_x: f32, ndc_y: f32, aspect: f32) -> Point3<f32> {
+        let (origin, direction) = self.ray(ndc_x, ndc_y, aspect);
+        let forward = (self.focus - self.get_eye()).normalize();
+        let t = (point - origin).dot(forward) / direction.dot(forward);
+        origin + direction * t
+    }
+
+    /// World length of one pixel at the depth of `point`, in a pane
+    /// `viewport_height` pixels high.
+    pub fn pixel_size_at(&self, point: Point3<f32>, viewport_height: f64) -> f32 {
+        let depth = -(self.view_matrix() * point.to_homogeneous()).z;
+        // projection[1][1] is cot(fovy / 2), whatever the aspect
+        let projection = self.projection_matrix(1.0);
+        2.0 * depth.abs() / (projection[1][1] * viewport_height as f32)
+    }
+
+    /// World directions of the screen right and up, billboards lie across them.
+    pub fn right_up(&self) -> (Vector3<f32>, Vector3<f32>) {
+        let view = self.view_matrix();
+        (Vector3::new(view.x.x, view.y.x, view.z.x), Vector3::new(view.x.y, view.y.y, view.z.y))
+    }
+
+    pub fn focus(&self) -> Point3<f32> {
+        self.focus
+    }
+
+    pub fn get_eye(&self) -> Point3<f32> {
+        let pitch = self.up_down_angle;
+        let yaw = self.left_right_angle;
+        let y = self.r * pitch.sin();
+        let x = self.r * pitch.cos() * yaw.sin();
+        let z = self.r * pitch.cos() * yaw.cos();
+        Point3::new(x, y, z)
+    }
+
+    pub fn orbit_left_right(&mut self, delta: f32) {
+        self.left_right_angle += delta * self.sensitivity;
+    }
+
+    pub fn orbit_up_down(&mut self, delta: f32) {
+        self.up_down_angle += delta * self.sensitivity;
+        // protect some weird camera movements
+        // -pi/2 < up_down_angle < pi/2
+        self.up_down_angle = self.up_down_angle.clamp(-(PI / 2.1), PI / 2.1);
+    }
+
+    pub fn zoom(&mut self, zoom: f32) {
+        self.r = (self.r + zoom * 0.2).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Closes in as two fingers spread apart, keeping the scene under them.
+    pub fn pinch(&mut self, scale: f32) {
+        self.r = (self.r / scale).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::InnerSpace;
+
+    #[test]
+    fn normals_stay_perpendicular_under_non_uniform_scale() {
+        let camera = Camera::new();
+        let model = Matrix4::from_nonuniform_scale(3.0, 1.0, 0.5);
+        // a face along the x = y diagonal, and its normal
+        let (tangent, normal) = (Vector3::new(1.0, -1.0, 0.0), Vector3::new(1.0, 1.0, 0.0));
+        let eye_tangent = (camera.view_matrix() * model * tangent.extend(0.0)).truncate();
+        let eye_normal = camera.normal_matrix(model) * normal;
+        assert!(eye_tangent.dot(eye_normal).abs() < 1e-5);
+        // the model view matrix alone would not keep it perpendicular
+        let wrong = (camera.view_matrix() * model * normal.extend(0.0)).truncate();
+        assert!(eye_tangent.dot(wrong).abs() > 0.1);
+    }
+
+    #[test]
+    fn rays_go_through_projected_points() {
+        let camera = Camera::new();
+        let point = Point3::new(0.5, -0.25, 0.75);
+        let (ndc_x, ndc_y) = camera.project(point, 1.5).unwrap();
+        let (origin, direction) = camera.ray(ndc_x, ndc_y, 1.5);
+        let to_point = (point - origin).normalize();
+        assert!((to_point - direction).magnitude() < 1e-4);
+        // dragged at its own depth, a point stays where it is
+        let dragged = camera.drag_point(point, ndc_x, ndc_y, 1.5);
+        assert!((dragged - point).magnitude() < 1e-4);
+    }
+
+    #[test]
+    fn translation_does_not_move_normals() {
+        let camera = Camera::new();
+        let moved = Matrix4::from_translation(Vector3::new(5.0, -2.0, 7.0));
+        let normal = Vector3::new(0.0, 1.0, 0.0);
+        let expected = camera.to_eye_direction(normal);
+        assert!((camera.normal_matrix(moved) * normal - expected).magnitude() < 1e-5);
+    }
+}
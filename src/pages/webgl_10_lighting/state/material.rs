@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// minimum and maximum of the specular exponent
+pub const SHININESS_RANGE: (f32, f32) = (1.0, 256.0);
+/// program of the lit materials, both the Gouraud and the Phong shaders take its parameters
+pub const BLINN_PHONG: &str = "blinn-phong";
+/// textures the lesson draws with, for the `diffuseMap` of the materials
+pub const WHITE_TEXTURE: &str = "white";
+pub const CHECKER_TEXTURE: &str = "checker";
+/// version written in exported materials, bumped when the format changes
+const FORMAT_VERSION: u32 = 1;
+
+/// Value of one material parameter, as the shader uniform takes it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaterialValue {
+    /// linear rgb, a `vec3`
+    Color([f32; 3]),
+    /// a `float`
+    Scalar(f32),
+    /// name of a texture for a `sampler2D`
+    Texture(String),
+}
+
+/// Parameters by name, the shader uniform of `diffuse` is `uMaterialDiffuse`.
+pub type MaterialParams = BTreeMap<String, MaterialValue>;
+
+/// A named parameter set for one shader program.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    pub name: String,
+    /// the program drawing with the material
+    pub shader: String,
+    pub params: MaterialParams,
+}
+
+impl Material {
+    pub fn new(name: &str, shader: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            shader: shader.to_string(),
+            params: MaterialParams::new(),
+        }
+    }
+
+    pub fn with(mut self, param: &str, value: MaterialValue) -> Self {
+        self.params.insert(param.to_string(), value);
+        self
+    }
+
+    pub fn color(&self, param: &str) -> Option<[f32; 3]> {
+        match self.params.get(param) {
+            Some(MaterialValue::Color(rgb)) => Some(*rgb),
+            _ => None,
+        }
+    }
+
+    pub fn scalar(&self, param: &str) -> Option<f32> {
+        match self.params.get(param) {
+            Some(MaterialValue::Scalar(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The material with `overrides` replacing its parameters of the same name.
+    pub fn overridden(&self, overrides: &MaterialParams) -> Material {
+        let mut material = self.clone();
+        for (param, value) in overrides {
+            material.params.insert(param.clone(), value.clone());
+        }
+        material
+    }
+
+    pub fn to_json(&self) -> String {
+        materials_to_json(std::slice::from_ref(self))
+    }
+}
+
+/// Uniform of the parameter `param`: `shininess` is `uMaterialShininess`.
+pub fn uniform_name(param: &str) -> String {
+    let mut chars = param.chars();
+    match chars.next() {
+        Some(first) => format!("uMaterial{}{}", first.to_ascii_uppercase(), chars.as_str()),
+        None => "uMaterial".to_string(),
+    }
+}
+
+/// Every parameter of the Blinn-Phong program, so a material missing some
+/// doesn't draw with the values of the previous object.
+pub fn blinn_phong_defaults() -> Material {
+    Material::new("default", BLINN_PHONG)
+        .with("ambient", MaterialValue::Color([0.05, 0.05, 0.05]))
+        .with("diffuse", MaterialValue::Color([0.8, 0.8, 0.8]))
+        .with("specular", MaterialValue::Color([0.5, 0.5, 0.5]))
+        .with("emissive", MaterialValue::Color([0.0, 0.0, 0.0]))
+        .with("shininess", MaterialValue::Scalar(32.0))
+        // multiplies the diffuse color, so the plain materials are left as they are
+        .with("diffuseMap", MaterialValue::Texture(WHITE_TEXTURE.to_string()))
+}
+
+/// Plastic, metal, rubber and emissive.
+pub fn presets() -> Vec<Material> {
+    use MaterialValue::{Color, Scalar};
+    vec![
+        // a colored diffuse coat under a white, fairly sharp highlight
+        Material::new("plastic", BLINN_PHONG)
+            .with("ambient", Color([0.08, 0.01, 0.01]))
+            .with("diffuse", Color([0.8, 0.1, 0.1]))
+            .with("specular", Color([0.6, 0.6, 0.6]))
+            .with("shininess", Scalar(32.0)),
+        // little diffuse, a bright highlight tinted by the metal
+        Material::new("metal", BLINN_PHONG)
+            .with("ambient", Color([0.04, 0.04, 0.04]))
+            .with("diffuse", Color([0.25, 0.25, 0.27]))
+            .with("specular", Color([0.95, 0.9, 0.8]))
+            .with("shininess", Scalar(128.0)),
+        // dull, with a wide and faint highlight
+        Material::new("rubber", BLINN_PHONG)
+            .with("ambient", Color([0.02, 0.02, 0.02]))
+            .with("diffuse", Color([0.15, 0.15, 0.15]))
+            .with("specular", Color([0.1, 0.1, 0.1]))
+            .with("shininess", Scalar(8.0)),
+        // glows on its own, lit or not
+        Material::new("emissive", BLINN_PHONG)
+            .with("ambient", Color([0.0, 0.0, 0.0]))
+            .with("diffuse", Color([0.2, 0.1, 0.05]))
+            .with("specular", Color([0.3, 0.3, 0.3]))
+            .with("emissive", Color([1.0, 0.5, 0.1]))
+            .with("shininess", Scalar(16.0)),
+    ]
+}
+
+#[derive(Serialize, Deserialize)]
+struct MaterialFile {
+    version: u32,
+    materials: Vec<Material>,
+}
+
+pub fn materials_to_json(materials: &[Material]) -> String {
+    let file = MaterialFile {
+        version: FORMAT_VERSION,
+        materials: materials.to_vec(),
+    };
+    serde_json::to_string_pretty(&file).expect("materials serialize error")
+}
+
+pub fn materials_from_json(json: &str) -> Result<Vec<Material>, String> {
+    let file: MaterialFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if file.version != FORMAT_VERSION {
+        return Err(format!("unsupported materials version {}", file.version));
+    }
+    Ok(file.materials)
+}
+
+/// Materials by name: the presets, and the ones imported.
+pub struct MaterialLibrary {
+    materials: Vec<Material>,
+}
+
+impl Default for MaterialLibrary {
+    fn default() -> Self {
+        Self { materials: presets() }
+    }
+}
+
+impl MaterialLibrary {
+    pub fn get(&self, name: &str) -> Option<&Material> {
+        self.materials.iter().find(|m| m.name == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.materials.iter().map(|m| m.name.as_str())
+    }
+
+    /// Adds a material, replacing the one of the same name.
+    pub fn set(&mut self, material: Material) {
+        match self.materials.iter_mut().find(|m| m.name == material.name) {
+            Some(existing) => *existing = material,
+            None => self.materials.push(material),
+        }
+    }
+
+    /// The materials that are not presets, or differ from them: what is worth saving.
+    pub fn custom(&self) -> Vec<Material> {
+        let presets = presets();
+        self.materials.iter().filter(|m| !presets.contains(m)).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_only_their_parameters() {
+        let plastic = presets().remove(0);
+        let mut overrides = MaterialParams::new();
+        overrides.insert("diffuse".into(), MaterialValue::Color([0.1, 0.8, 0.1]));
+        let green = plastic.overridden(&overrides);
+        assert_eq!(green.color("diffuse"), Some([0.1, 0.8, 0.1]));
+        assert_eq!(green.scalar("shininess"), plastic.scalar("shininess"));
+        assert_eq!(green.name, "plastic");
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let metal = presets().remove(1).with("roughnessMap", MaterialValue::Texture("brushed".into()));
+        let json = metal.to_json();
+        assert!(json.contains("\"texture\": \"brushed\""));
+        assert_eq!(materials_from_json(&json), Ok(vec![metal]));
+        assert!(materials_from_json("{\"version\": 9, \"materials\": []}").is_err());
+    }
+
+    #[test]
+    fn uniform_names_follow_the_shader() {
+        assert_eq!(uniform_name("shininess"), "uMaterialShininess");
+        assert_eq!(uniform_name("diffuseMap"), "uMaterialDiffuseMap");
+    }
+
+    #[test]
+    fn library_saves_only_custom_materials() {
+        let mut library = MaterialLibrary::default();
+        assert!(library.custom().is_empty());
+        let shiny = presets().remove(2).with("shininess", MaterialValue::Scalar(64.0));
+        library.set(shiny.clone());
+        library.set(Material::new("chalk", BLINN_PHONG));
+        assert_eq!(library.custom(), vec![shiny, Material::new("chalk", BLINN_PHONG)]);
+        assert_eq!(library.names().count(), 5);
+    }
+}
@@ -0,0 +1,434 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+use cgmath::{Deg, EuclideanSpace, InnerSpace, Point2, Point3, Vector3};
+use clear_color::ClearColor;
+pub use light::{Attenuation, Light, LightColor, MAX_LIGHTS};
+use material::{blinn_phong_defaults, MaterialLibrary};
+pub use material::{
+    materials_from_json, materials_to_json, uniform_name, Material, MaterialValue, BLINN_PHONG, CHECKER_TEXTURE, SHININESS_RANGE, WHITE_TEXTURE,
+};
+pub use point_shadow::{face_view_projections, MAX_SHADOWED_LIGHTS, POINT_SHADOW_FAR};
+pub use scene::floor_model_matrix;
+use scene::SceneObject;
+pub use shading::Shading;
+pub use viewport::Pane;
+use viewport::Viewport;
+
+mod camera;
+mod clear_color;
+mod light;
+mod material;
+mod point_shadow;
+mod scene;
+mod shading;
+mod viewport;
+
+/// stacks and slices of the sphere, coarse enough for Gouraud to show its facets first
+pub const TESSELLATIONS: [(usize, usize); 3] = [(8, 12), (16, 24), (48, 64)];
+/// radius on screen of the light gizmos
+pub const LIGHT_GIZMO_PIXELS: f32 = 10.0;
+/// distance from the focus at which the gizmo of a directional light is shown
+pub const DIRECTIONAL_GIZMO_DISTANCE: f32 = 2.5;
+/// colors of the added lights, in turn
+const LIGHT_PALETTE: [[f32; 3]; 4] = [[1.0, 0.6, 0.2], [0.3, 0.7, 1.0], [0.6, 1.0, 0.4], [1.0, 0.4, 0.8]];
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    lights: Vec<Light>,
+    /// light under the pointer
+    hovered_light: Option<usize>,
+    /// pointer id and light it drags, the pointer then doesn't orbit
+    dragged_light: Option<(i32, usize)>,
+    /// last light clicked, the one removed first
+    selected_light: Option<usize>,
+    materials: MaterialLibrary,
+    objects: Vec<SceneObject>,
+    /// object the material controls edit
+    selected_object: usize,
+    shading: Shading,
+    tessellation: usize,
+    /// whether the first point lights cast shadows
+    shadows: bool,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::default();
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            lights: default_lights(),
+            hovered_light: None,
+            dragged_light: None,
+            selected_light: None,
+            materials: MaterialLibrary::default(),
+            objects: default_objects(),
+            selected_object: 0,
+            shading: Shading::Split,
+            tessellation: 0,
+            shadows: true,
+        }
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Hovered or dragged light, drawn highlighted.
+    pub fn highlighted_light(&self) -> Option<usize> {
+        self.dragged_light.map(|(_, index)| index).or(self.hovered_light)
+    }
+
+    pub fn selected_light(&self) -> Option<usize> {
+        self.selected_light
+    }
+
+    /// Where the gizmo of light `index` is drawn and grabbed.
+    pub fn light_anchor(&self, index: usize) -> Point3<f32> {
+        match self.lights[index] {
+            Light::Directional { direction, .. } => self.camera.focus() + direction * DIRECTIONAL_GIZMO_DISTANCE,
+            light => light.position().unwrap(),
+        }
+    }
+
+    /// The panes drawn, two in the side by side view.
+    pub fn panes(&self) -> Vec<Pane> {
+        self.viewport.panes(self.shading == Shading::Split)
+    }
+
+    pub fn objects(&self) -> &[SceneObject] {
+        &self.objects
+    }
+
+    pub fn selected_object(&self) -> usize {
+        self.selected_object
+    }
+
+    pub fn material_names(&self) -> Vec<String> {
+        self.materials.names().map(String::from).collect()
+    }
+
+    /// Every parameter `object` is drawn with: the defaults, under its
+    /// material, under its overrides.
+    pub fn material_of(&self, object: usize) -> Material {
+        let object = &self.objects[object];
+        let mut material = blinn_phong_defaults();
+        if let Some(base) = self.materials.get(&object.material) {
+            material = material.overridden(&base.params);
+            material.name = base.name.clone();
+        }
+        material.overridden(&object.overrides)
+    }
+
+    /// The floor, a gray checkerboard under every light.
+    pub fn floor_material(&self) -> Material {
+        blinn_phong_defaults()
+            .with("diffuse", MaterialValue::Color([0.6, 0.6, 0.6]))
+            .with("specular", MaterialValue::Color([0.1, 0.1, 0.1]))
+            .with("diffuseMap", MaterialValue::Texture(CHECKER_TEXTURE.to_string()))
+    }
+
+    pub fn shadows(&self) -> bool {
+        self.shadows
+    }
+
+    /// Indices of the lights with a shadow cube map: the first point lights.
+    pub fn shadow_casters(&self) -> Vec<usize> {
+        if !self.shadows {
+            return Vec::new();
+        }
+        self.lights
+            .iter()
+            .enumerate()
+            .filter(|(_, light)| matches!(light, Light::Point { .. }))
+            .map(|(index, _)| index)
+            .take(MAX_SHADOWED_LIGHTS)
+            .collect()
+    }
+
+    /// Materials worth saving, the presets are always there.
+    pub fn custom_materials(&self) -> Vec<Material> {
+        self.materials.custom()
+    }
+
+    pub fn shading(&self) -> Shading {
+        self.shading
+    }
+
+    /// Stacks and slices of the sphere.
+    pub fn tessellation(&self) -> (usize, usize) {
+        TESSELLATIONS[self.tessellation]
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer) => {
+                // a press on a light grabs it, the other presses orbit
+                if let Some(index) = self.light_at(pointer.x, pointer.y) {
+                    self.dragged_light = Some((pointer.id, index));
+                    self.selected_light = Some(index);
+                    return;
+                }
+                if let Some(object) = self.object_at(pointer.x, pointer.y) {
+                    self.selected_object = object;
+                }
+                self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time);
+            }
+            Msg::PointerMove(id, x, y) => match self.dragged_light {
+                Some((dragging, index)) if dragging == id => self.drag_light(index, x, y),
+                _ => match self.gestures.moved(id, Point2::new(x, y)) {
+                    Some(Gesture::Drag { from, to, .. }) => {
+                        self.camera.orbit_left_right(from.x - to.x);
+                        self.camera.orbit_up_down(to.y - from.y);
+                    }
+                    Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                    Some(_) => {}
+                    None => self.hovered_light = self.light_at(x, y),
+                },
+            },
+            Msg::PointerUp(id, time) => {
+                self.release_light(id);
+                self.gestures.up(id, time);
+            }
+            Msg::PointerCancel(id) => {
+                self.release_light(id);
+                self.gestures.cancel(id);
+            }
+            Msg::ReleaseInput => {
+                self.dragged_light = None;
+                self.gestures.clear();
+            }
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::ShadingChange(shading) => self.shading = shading,
+            Msg::ToggleShading => self.shading = self.shading.next(),
+            Msg::ShininessChange(shininess) => {
+                let shininess = shininess.clamp(SHININESS_RANGE.0, SHININESS_RANGE.1);
+                self.set_override("shininess", MaterialValue::Scalar(shininess));
+            }
+            Msg::DiffuseChange(rgb) => self.set_override("diffuse", MaterialValue::Color(rgb)),
+            Msg::MaterialChange(name) => {
+                let object = &mut self.objects[self.selected_object];
+                object.material = name;
+                object.overrides.clear();
+            }
+            Msg::NextObject => self.selected_object = (self.selected_object + 1) % self.objects.len(),
+            Msg::LoadMaterials(materials) => {
+                for material in materials {
+                    self.materials.set(material);
+                }
+            }
+            Msg::ImportMaterials(materials) => {
+                // the selected object shows the first of them
+                if let Some(first) = materials.first() {
+                    let object = &mut self.objects[self.selected_object];
+                    object.material = first.name.clone();
+                    object.overrides.clear();
+                }
+                for material in materials {
+                    self.materials.set(material);
+                }
+            }
+            Msg::NextTessellation => self.tessellation = (self.tessellation + 1) % TESSELLATIONS.len(),
+            Msg::AddLight => self.add_light(),
+            Msg::RemoveLight => self.remove_light(),
+            Msg::ToggleShadows => self.shadows = !self.shadows,
+        }
+    }
+
+    /// The light whose gizmo is nearest to the client pixel `x`, `y`, if
+    /// close enough to grab.
+    fn light_at(&self, x: f32, y: f32) -> Option<usize> {
+        let (x, y) = (f64::from(x), f64::from(y));
+        let pane = self.viewport.pane_at(x, self.shading == Shading::Split);
+        let reach = f64::from(LIGHT_GIZMO_PIXELS) * 1.5;
+        (0..self.lights.len())
+            .filter_map(|index| {
+                let (ndc_x, ndc_y) = self.camera.project(self.light_anchor(index), pane.aspect())?;
+                let (light_x, light_y) = pane.to_client(ndc_x, ndc_y);
+                let distance = ((light_x - x).powi(2) + (light_y - y).powi(2)).sqrt();
+                Some((index, distance))
+            })
+            .filter(|&(_, distance)| distance <= reach)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// The nearest object under the client pixel `x`, `y`.
+    fn object_at(&self, x: f32, y: f32) -> Option<usize> {
+        let (x, y) = (f64::from(x), f64::from(y));
+        let pane = self.viewport.pane_at(x, self.shading == Shading::Split);
+        let (ndc_x, ndc_y) = pane.to_ndc(x, y);
+        let (origin, direction) = self.camera.ray(ndc_x, ndc_y, pane.aspect());
+        self.objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| Some((index, object.intersect(origin, direction)?)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// Changes one parameter of the selected object only.
+    fn set_override(&mut self, param: &str, value: MaterialValue) {
+        self.objects[self.selected_object].overrides.insert(param.to_string(), value);
+    }
+
+    /// Moves light `index` under the pointer, at the depth it was grabbed at.
+    fn drag_light(&mut self, index: usize, x: f32, y: f32) {
+        let pane = self.viewport.pane_at(f64::from(x), self.shading == Shading::Split);
+        let (ndc_x, ndc_y) = pane.to_ndc(f64::from(x), f64::from(y));
+        let to = self.camera.drag_point(self.light_anchor(index), ndc_x, ndc_y, pane.aspect());
+        let focus = self.camera.focus();
+        let light = &mut self.lights[index];
+        match light {
+            // the gizmo stays on its sphere around the focus, only the direction changes
+            Light::Directional { .. } => light.set_direction(to - focus),
+            _ => light.move_to(to, focus),
+        }
+    }
+
+    /// Drops the light dragged by pointer `id`, if any.
+    fn release_light(&mut self, id: i32) {
+        if matches!(self.dragged_light, Some((dragging, _)) if dragging == id) {
+            self.dragged_light = None;
+        }
+    }
+
+    /// Adds a point, a spot or a directional light in turn, up to `MAX_LIGHTS`.
+    fn add_light(&mut self) {
+        if self.lights.len() >= MAX_LIGHTS {
+            return;
+        }
+        let count = self.lights.len();
+        let color = LightColor::new(LIGHT_PALETTE[count % LIGHT_PALETTE.len()]);
+        // spread around the sphere by the golden angle
+        let angle = Deg(137.5 * count as f32);
+        let around = Vector3::new(angle.0.to_radians().cos(), 0.6, angle.0.to_radians().sin()) * 2.0;
+        let position = self.camera.focus() + around;
+        let light = match count % 3 {
+            0 => Light::Point {
+                position,
+                color,
+                attenuation: Attenuation::Radius(6.0),
+            },
+            1 => Light::Spot {
+                position,
+                direction: (self.camera.focus() - position).normalize(),
+                color,
+                attenuation: Attenuation::Radius(8.0),
+                inner: Deg(12.0).into(),
+                outer: Deg(20.0).into(),
+            },
+            _ => Light::Directional {
+                direction: around.normalize(),
+                color,
+            },
+        };
+        self.lights.push(light);
+        self.selected_light = Some(count);
+    }
+
+    /// Removes the selected light, or the last one.
+    fn remove_light(&mut self) {
+        let index = match self.selected_light.or_else(|| self.lights.len().checked_sub(1)) {
+            Some(index) => index,
+            None => return,
+        };
+        self.lights.remove(index);
+        self.selected_light = None;
+        self.hovered_light = None;
+        self.dragged_light = None;
+    }
+}
+
+/// Four spheres in a square facing the camera, one per preset.
+fn default_objects() -> Vec<SceneObject> {
+    vec![
+        SceneObject::new(Point3::new(-1.1, 1.1, 0.0), 0.9, "plastic"),
+        SceneObject::new(Point3::new(1.1, 1.1, 0.0), 0.9, "metal"),
+        SceneObject::new(Point3::new(-1.1, -1.1, 0.0), 0.9, "rubber"),
+        SceneObject::new(Point3::new(1.1, -1.1, 0.0), 0.9, "emissive"),
+    ]
+}
+
+/// A dim white sun, an orange point light and a blue spot.
+fn default_lights() -> Vec<Light> {
+    let spot_position = Point3::new(-1.5, 2.5, 1.5);
+    vec![
+        Light::Directional {
+            direction: Vector3::new(1.0, 1.5, 2.0).normalize(),
+            color: LightColor {
+                ambient: [0.1, 0.1, 0.1],
+                diffuse: [0.5, 0.5, 0.5],
+                specular: [0.5, 0.5, 0.5],
+            },
+        },
+        Light::Point {
+            position: Point3::new(2.5, 0.5, 2.0),
+            color: LightColor::new([1.0, 0.6, 0.2]),
+            attenuation: Attenuation::Radius(6.0),
+        },
+        Light::Spot {
+            position: spot_position,
+            direction: (Point3::origin() - spot_position).normalize(),
+            color: LightColor::new([0.3, 0.7, 1.0]),
+            attenuation: Attenuation::Coefficients {
+                constant: 1.0,
+                linear: 0.09,
+                quadratic: 0.032,
+            },
+            inner: Deg(12.0).into(),
+            outer: Deg(20.0).into(),
+        },
+    ]
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    ShadingChange(Shading),
+    ToggleShading,
+    /// for the selected object
+    ShininessChange(f32),
+    /// for the selected object
+    DiffuseChange([f32; 3]),
+    /// the material of the selected object, dropping its overrides
+    MaterialChange(String),
+    NextObject,
+    /// adds saved materials to the library
+    LoadMaterials(Vec<Material>),
+    /// adds materials to the library, the selected object takes the first
+    ImportMaterials(Vec<Material>),
+    NextTessellation,
+    AddLight,
+    RemoveLight,
+    ToggleShadows,
+}
@@ -0,0 +1,101 @@
+use cgmath::{Deg, Matrix4, Point3, Vector3};
+
+/// point lights casting shadows, one cube map each
+pub const MAX_SHADOWED_LIGHTS: usize = 2;
+/// distance from a point light past which nothing is shadowed, the cube
+/// maps hold the distances divided by it
+pub const POINT_SHADOW_FAR: f32 = 20.0;
+const POINT_SHADOW_NEAR: f32 = 0.05;
+
+/// Where every face looks and its up, in the order of
+/// `TEXTURE_CUBE_MAP_POSITIVE_X + face`. Cube maps are laid out as seen
+/// from outside of the cube, hence the ups mostly pointing down.
+const FACES: [(Vector3<f32>, Vector3<f32>); 6] = [
+    (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+    (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+    (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+];
+
+/// World to clip space of the six faces of a cube map around `position`,
+/// each a 90° frustum so that together they see every direction.
+pub fn face_view_projections(position: Point3<f32>) -> [Matrix4<f32>; 6] {
+    let projection = cgmath::perspective(Deg(90.0), 1.0, POINT_SHADOW_NEAR, POINT_SHADOW_FAR);
+    let mut matrices = [Matrix4::from_scale(1.0); 6];
+    for (matrix, (forward, up)) in matrices.iter_mut().zip(FACES.iter()) {
+        *matrix = projection * Matrix4::look_at_dir(position, *forward, *up);
+    }
+    matrices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::InnerSpace;
+
+    /// Face and texture coordinates a cube map lookup along `r` reads, as
+    /// the OpenGL ES 3.0 specification selects them.
+    fn cube_lookup(r: Vector3<f32>) -> (usize, f32, f32) {
+        let (face, sc, tc, ma) = if r.x.abs() >= r.y.abs() && r.x.abs() >= r.z.abs() {
+            if r.x > 0.0 {
+                (0, -r.z, -r.y, r.x)
+            } else {
+                (1, r.z, -r.y, r.x)
+            }
+        } else if r.y.abs() >= r.z.abs() {
+            if r.y > 0.0 {
+                (2, r.x, r.z, r.y)
+            } else {
+                (3, r.x, -r.z, r.y)
+            }
+        } else if r.z > 0.0 {
+            (4, r.x, -r.y, r.z)
+        } else {
+            (5, -r.x, -r.y, r.z)
+        };
+        (face, (sc / ma.abs() + 1.0) / 2.0, (tc / ma.abs() + 1.0) / 2.0)
+    }
+
+    #[test]
+    fn faces_are_rendered_where_the_lookups_read() {
+        let light = Point3::new(1.0, 2.0, -3.0);
+        let matrices = face_view_projections(light);
+        let directions = [
+            Vector3::new(1.0, 0.3, -0.2),
+            Vector3::new(-1.0, -0.5, 0.4),
+            Vector3::new(0.2, 1.0, 0.7),
+            Vector3::new(-0.6, -1.0, 0.1),
+            Vector3::new(0.3, -0.4, 1.0),
+            Vector3::new(0.5, 0.2, -1.0),
+        ];
+        for direction in directions.iter() {
+            let (face, s, t) = cube_lookup(*direction);
+            let point = light + direction.normalize() * 5.0;
+            let clip = matrices[face] * point.to_homogeneous();
+            // texture coordinates run from the bottom left of the rendered face
+            let (x, y) = ((clip.x / clip.w + 1.0) / 2.0, (clip.y / clip.w + 1.0) / 2.0);
+            assert!(
+                (x - s).abs() < 1e-4 && (y - t).abs() < 1e-4,
+                "face {}: {:?} vs {:?}",
+                face,
+                (x, y),
+                (s, t)
+            );
+        }
+    }
+
+    #[test]
+    fn faces_see_every_direction() {
+        let matrices = face_view_projections(Point3::new(0.0, 0.0, 0.0));
+        for &(x, y, z) in &[(1.0, 1.0, 1.0), (-1.0, 0.2, 0.0), (0.0, 0.0, -3.0), (0.3, -2.0, 0.3)] {
+            let point = Point3::new(x, y, z);
+            let seen = matrices.iter().any(|matrix| {
+                let clip = matrix * point.to_homogeneous();
+                clip.w > 0.0 && clip.x.abs() <= clip.w && clip.y.abs() <= clip.w && clip.z.abs() <= clip.w
+            });
+            assert!(seen, "{:?} in no face", point);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use cgmath::{InnerSpace, Matrix4, Point3, Vector3};
+
+use super::material::MaterialParams;
+
+/// height and half side of the floor under the spheres, where their shadows fall
+const FLOOR_HEIGHT: f32 = -2.2;
+const FLOOR_HALF_SIZE: f32 = 6.0;
+
+/// Places the unit floor mesh.
+pub fn floor_model_matrix() -> Matrix4<f32> {
+    Matrix4::from_translation(Vector3::new(0.0, FLOOR_HEIGHT, 0.0)) * Matrix4::from_scale(FLOOR_HALF_SIZE)
+}
+
+/// A sphere of the scene, drawn with a material of the library and its own
+/// changes to it.
+#[derive(Clone, Debug)]
+pub struct SceneObject {
+    pub center: Point3<f32>,
+    pub radius: f32,
+    /// name in the material library
+    pub material: String,
+    /// parameters of this object only, over the ones of the material
+    pub overrides: MaterialParams,
+}
+
+impl SceneObject {
+    pub fn new(center: Point3<f32>, radius: f32, material: &str) -> Self {
+        Self {
+            center,
+            radius,
+            material: material.to_string(),
+            overrides: MaterialParams::new(),
+        }
+    }
+
+    /// Places the unit sphere mesh.
+    pub fn model_matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.center - Point3::new(0.0, 0.0, 0.0)) * Matrix4::from_scale(self.radius)
+    }
+
+    /// Distance along the unit `direction` from `origin` to the sphere, if hit.
+    pub fn intersect(&self, origin: Point3<f32>, direction: Vector3<f32>) -> Option<f32> {
+        let offset = origin - self.center;
+        let b = offset.dot(direction);
+        let c = offset.magnitude2() - self.radius * self.radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let t = -b - discriminant.sqrt();
+        // from inside, the far side is the hit
+        let t = if t >= 0.0 { t } else { -b + discriminant.sqrt() };
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rays_hit_the_near_side() {
+        let sphere = SceneObject::new(Point3::new(0.0, 0.0, -5.0), 1.0, "plastic");
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        assert_eq!(sphere.intersect(origin, -Vector3::unit_z()), Some(4.0));
+        assert_eq!(sphere.intersect(origin, Vector3::unit_z()), None);
+        assert_eq!(sphere.intersect(origin, Vector3::unit_y()), None);
+        assert_eq!(sphere.intersect(Point3::new(0.0, 0.0, -5.0), Vector3::unit_x()), Some(1.0));
+    }
+}
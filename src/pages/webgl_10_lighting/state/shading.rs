@@ -0,0 +1,30 @@
+/// Where the lighting is computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shading {
+    /// per vertex
+    Gouraud,
+    /// per fragment
+    Phong,
+    /// Gouraud on the left half, Phong on the right one
+    Split,
+}
+
+impl Shading {
+    pub const ALL: [Shading; 3] = [Shading::Gouraud, Shading::Phong, Shading::Split];
+
+    pub fn next(self) -> Self {
+        match self {
+            Shading::Gouraud => Shading::Phong,
+            Shading::Phong => Shading::Split,
+            Shading::Split => Shading::Gouraud,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Shading::Gouraud => "Gouraud",
+            Shading::Phong => "Phong",
+            Shading::Split => "side by side",
+        }
+    }
+}
@@ -0,0 +1,83 @@
+pub struct Viewport {
+    width: f64,
+    height: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new(200.0, 300.0)
+    }
+}
+
+impl Viewport {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+
+    pub fn set(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+/// Part of the viewport drawn with its own projection, the side by side
+/// view has two.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pane {
+    pub left: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Pane {
+    pub fn aspect(&self) -> f32 {
+        (self.width / self.height.max(1.0)) as f32
+    }
+
+    /// Normalized device coordinates of the client pixel `x`, `y`.
+    pub fn to_ndc(self, x: f64, y: f64) -> (f32, f32) {
+        let ndc_x = (x - self.left) / self.width.max(1.0) * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / self.height.max(1.0) * 2.0;
+        (ndc_x as f32, ndc_y as f32)
+    }
+
+    /// Client pixel of the normalized device coordinates.
+    pub fn to_client(self, ndc_x: f32, ndc_y: f32) -> (f64, f64) {
+        let x = self.left + (f64::from(ndc_x) + 1.0) * 0.5 * self.width;
+        let y = (1.0 - f64::from(ndc_y)) * 0.5 * self.height;
+        (x, y)
+    }
+}
+
+impl Viewport {
+    /// The whole viewport, or its left and right halves when `split`.
+    pub fn panes(&self, split: bool) -> Vec<Pane> {
+        if !split {
+            return vec![Pane {
+                left: 0.0,
+                width: self.width,
+                height: self.height,
+            }];
+        }
+        // whole pixels, the halves are gl viewports
+        let half = (self.width / 2.0).floor();
+        vec![
+            Pane {
+                left: 0.0,
+                width: half,
+                height: self.height,
+            },
+            Pane {
+                left: half,
+                width: self.width - half,
+                height: self.height,
+            },
+        ]
+    }
+
+    /// The pane under the client `x`.
+    pub fn pane_at(&self, x: f64, split: bool) -> Pane {
+        let panes = self.panes(split);
+        panes.iter().rev().find(|pane| x >= pane.left).copied().unwrap_or(panes[0])
+    }
+}
@@ -0,0 +1,45 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+use super::shadow_map::ShadowMap;
+use super::Shader;
+use super::State;
+
+/// side of the debug view, in pixels
+const DEBUG_SIZE: i32 = 256;
+const DEBUG_MARGIN: i32 = 8;
+
+/// The shadow map in the bottom right corner: near is black, far is white.
+pub struct DepthDebugView {
+    shader: Shader,
+}
+
+impl DepthDebugView {
+    pub fn new(shader: Shader) -> Self {
+        Self { shader }
+    }
+
+    pub fn render(&self, gl: &GL, state: &State, shadow_map: &ShadowMap) {
+        let width = state.viewport().width() as i32;
+        gl.viewport(width - DEBUG_SIZE - DEBUG_MARGIN, DEBUG_MARGIN, DEBUG_SIZE, DEBUG_SIZE);
+        gl.use_program(Some(&self.shader.program));
+
+        let range = state.light().depth_range();
+        let location = self.shader.get_uniform_location(gl, "uLinearize");
+        gl.uniform1i(location.as_ref(), range.is_some() as i32);
+        let (near, far) = range.unwrap_or((0.0, 1.0));
+        let location = self.shader.get_uniform_location(gl, "uDepthRange");
+        gl.uniform2f(location.as_ref(), near, far);
+
+        // a compared texture can't be read as plain depths
+        shadow_map.set_compare(gl, false);
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, Some(shadow_map.texture()));
+        let location = self.shader.get_uniform_location(gl, "uDepthMap");
+        gl.uniform1i(location.as_ref(), 0);
+        gl.disable(GL::DEPTH_TEST);
+        gl.draw_arrays(GL::TRIANGLES, 0, 3);
+        gl.enable(GL::DEPTH_TEST);
+        gl.bind_texture(GL::TEXTURE_2D, None);
+        shadow_map.set_compare(gl, true);
+    }
+}
@@ -0,0 +1,105 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+use super::state::{LightKind, State, MAX_BIAS, MAX_NORMAL_OFFSET};
+
+/// The bias and normal offset sliders, and the shadow settings.
+pub struct Hud {
+    info: HtmlElement,
+    bias_input: HtmlInputElement,
+    bias_label: HtmlElement,
+    normal_offset_input: HtmlInputElement,
+    normal_offset_label: HtmlElement,
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        style.set_property("text-shadow", "0 0 2px black")?;
+        body.append_child(&panel)?;
+
+        let slider = |name: &str, max: f32, step: f32| -> Result<(HtmlInputElement, HtmlElement), JsValue> {
+            let row = document.create_element("div")?;
+            row.set_inner_html(name);
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("range");
+            input.set_min("0");
+            input.set_max(&max.to_string());
+            input.set_step(&step.to_string());
+            row.append_child(&input)?;
+            let label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+            row.append_child(&label)?;
+            panel.append_child(&row)?;
+            Ok((input, label))
+        };
+        let (bias_input, bias_label) = slider("depth bias ", MAX_BIAS, 0.0001)?;
+        let (normal_offset_input, normal_offset_label) = slider("normal offset ", MAX_NORMAL_OFFSET, 0.005)?;
+
+        let info = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        panel.append_child(&info)?;
+
+        Ok(Self {
+            info,
+            bias_input,
+            bias_label,
+            normal_offset_input,
+            normal_offset_label,
+        })
+    }
+
+    pub fn bias_input(&self) -> &HtmlInputElement {
+        &self.bias_input
+    }
+
+    pub fn normal_offset_input(&self) -> &HtmlInputElement {
+        &self.normal_offset_input
+    }
+
+    pub fn bias(&self) -> Option<f32> {
+        self.bias_input.value().parse().ok()
+    }
+
+    pub fn normal_offset(&self) -> Option<f32> {
+        self.normal_offset_input.value().parse().ok()
+    }
+
+    /// Follows the state, which the keys change too.
+    pub fn sync(&self, state: &State) {
+        let shadows = state.shadows();
+        if self.bias() != Some(shadows.bias) {
+            self.bias_input.set_value(&shadows.bias.to_string());
+        }
+        self.bias_label.set_inner_html(&format!(" {:.4}", shadows.bias));
+        if self.normal_offset() != Some(shadows.normal_offset) {
+            self.normal_offset_input.set_value(&shadows.normal_offset.to_string());
+        }
+        self.normal_offset_label.set_inner_html(&format!(" {:.3}", shadows.normal_offset));
+
+        let light = match state.light().kind() {
+            LightKind::Directional => "directional, orthographic map",
+            LightKind::Spot => "spot, perspective map",
+        };
+        let taps = 2 * shadows.pcf_radius + 1;
+        let paused = if state.light().animated() { "" } else { ", paused" };
+        self.info.set_inner_html(&format!(
+            "{}{}<br>{}x{} map, pcf {}x{}<br>?: keys",
+            light,
+            paused,
+            shadows.resolution(),
+            shadows.resolution(),
+            taps,
+            taps
+        ));
+    }
+}
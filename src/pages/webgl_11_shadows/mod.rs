@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use crate::input::{HelpOverlay, Input};
+use canvas::{create_canvas, create_webgl_context};
+use depth_debug::DepthDebugView;
+use events::{attach_event_handlers, handle_input};
+use hud::Hud;
+use scene_meshes::SceneMeshes;
+use scene_renderer::SceneRenderer;
+use shader::Shader;
+use shadow_map::ShadowMap;
+use state::{Msg, State};
+
+mod canvas;
+mod depth_debug;
+mod events;
+mod hud;
+mod mesh;
+mod scene_meshes;
+mod scene_renderer;
+mod shader;
+mod shadow_map;
+mod state;
+
+pub fn run() -> Result<(), JsValue> {
+    let app = Rc::new(App::new());
+    attach_event_handlers(Rc::clone(&app))?;
+    Rc::clone(&app).render()?;
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        app.render().unwrap();
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut()>));
+    request_animation_frame(g.borrow().as_ref().unwrap());
+    Ok(())
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+pub struct App {
+    canvas: HtmlCanvasElement,
+    gl: GL,
+    state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    hud: Hud,
+    meshes: SceneMeshes,
+    shadow_map: RefCell<ShadowMap>,
+    scene: SceneRenderer,
+    depth_debug: DepthDebugView,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let canvas = create_canvas().unwrap();
+        let gl = create_webgl_context(&canvas).unwrap();
+        let state = RefCell::new(State::new());
+        state.borrow_mut().msg(Msg::ViewportResize(canvas.width() as f64, canvas.height() as f64));
+        let meshes = SceneMeshes::new(&gl);
+        let shadow_map = ShadowMap::new(&gl, state.borrow().shadows().resolution()).expect("shadow map error");
+        let scene = SceneRenderer::new(Shader::scene_shader(&gl).expect("scene shader error"));
+        let depth_debug = DepthDebugView::new(Shader::debug_shader(&gl).expect("debug shader error"));
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        let hud = Hud::new().expect("hud error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            hud,
+            meshes,
+            shadow_map: RefCell::new(shadow_map),
+            scene,
+            depth_debug,
+        }
+    }
+
+    pub fn render(&self) -> Result<(), JsValue> {
+        handle_input(self);
+        self.state.borrow_mut().msg(Msg::Tick(js_sys::Date::now()));
+        let state = self.state.borrow();
+
+        let resolution = state.shadows().resolution();
+        if self.shadow_map.borrow().size() != resolution {
+            self.shadow_map.borrow_mut().resize(&self.gl, resolution)?;
+        }
+        let shadow_map = self.shadow_map.borrow();
+        // the shadow pass first, into its own framebuffer
+        shadow_map.render(&self.gl, &state, &self.meshes);
+
+        let clear_color = state.clear_color();
+        let viewport = state.viewport();
+        self.gl.viewport(0, 0, viewport.width() as i32, viewport.height() as i32);
+        self.gl.clear_color(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+        let aspect = (viewport.width() / viewport.height().max(1.0)) as f32;
+        self.scene.render(&self.gl, &state, &self.meshes, &shadow_map, aspect);
+
+        if state.shadow_debug() {
+            self.depth_debug.render(&self.gl, &state, &shadow_map);
+        }
+        self.hud.sync(&state);
+        Ok(())
+    }
+}
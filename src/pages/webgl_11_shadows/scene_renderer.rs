@@ -0,0 +1,84 @@
+use cgmath::{Matrix, Matrix3, Matrix4, SquareMatrix, Vector3};
+use web_sys::WebGl2RenderingContext as GL;
+
+use super::scene_meshes::SceneMeshes;
+use super::shadow_map::ShadowMap;
+use super::state::LightKind;
+use super::Shader;
+use super::State;
+
+/// texture unit of the shadow map
+const SHADOW_UNIT: u32 = 0;
+
+/// Draws the scene lit by the shadow light, looking its shadows up in the map.
+pub struct SceneRenderer {
+    shader: Shader,
+}
+
+impl SceneRenderer {
+    pub fn new(shader: Shader) -> Self {
+        Self { shader }
+    }
+
+    pub fn render(&self, gl: &GL, state: &State, meshes: &SceneMeshes, shadow_map: &ShadowMap, aspect: f32) {
+        let shader = &self.shader;
+        gl.use_program(Some(&shader.program));
+
+        let camera = state.camera();
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", camera.view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", camera.projection_matrix(aspect));
+        let eye = camera.get_eye();
+        self.set_uniform_location_vector3(gl, "uEyePosition", Vector3::new(eye.x, eye.y, eye.z));
+
+        // everything in world space, the lit pass and the shadow pass agree on it
+        let light = state.light();
+        let (kind, direction) = match light.kind() {
+            LightKind::Directional => (0, light.direction()),
+            LightKind::Spot => (1, light.spot_axis()),
+        };
+        let location = shader.get_uniform_location(gl, "uLightKind");
+        gl.uniform1i(location.as_ref(), kind);
+        self.set_uniform_location_vector3(gl, "uLightDirection", direction);
+        let position = light.position();
+        self.set_uniform_location_vector3(gl, "uLightPosition", Vector3::new(position.x, position.y, position.z));
+        let location = shader.get_uniform_location(gl, "uLightCone");
+        gl.uniform2fv_with_f32_array(location.as_ref(), &light.cone_cosines());
+
+        let shadows = state.shadows();
+        self.set_uniform_location_matrix4(gl, "uLightViewProjection", light.view_projection());
+        let location = shader.get_uniform_location(gl, "uShadowBias");
+        gl.uniform1f(location.as_ref(), shadows.bias);
+        let location = shader.get_uniform_location(gl, "uNormalOffset");
+        gl.uniform1f(location.as_ref(), shadows.normal_offset);
+        let location = shader.get_uniform_location(gl, "uPcfRadius");
+        gl.uniform1i(location.as_ref(), shadows.pcf_radius);
+        gl.active_texture(GL::TEXTURE0 + SHADOW_UNIT);
+        gl.bind_texture(GL::TEXTURE_2D, Some(shadow_map.texture()));
+        let location = shader.get_uniform_location(gl, "uShadowMap");
+        gl.uniform1i(location.as_ref(), SHADOW_UNIT as i32);
+
+        for object in state.objects() {
+            self.set_uniform_location_matrix4(gl, "uModelMatrix", object.model);
+            let linear = Matrix3::from_cols(object.model.x.truncate(), object.model.y.truncate(), object.model.z.truncate());
+            let normal_matrix = linear.invert().expect("model matrix not invertible").transpose();
+            let location = shader.get_uniform_location(gl, "uNormalMatrix");
+            let array: &[f32; 9] = normal_matrix.as_ref();
+            gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+            self.set_uniform_location_vector3(gl, "uColor", Vector3::from(object.color));
+            meshes.draw(gl, object.shape);
+        }
+        gl.bind_texture(GL::TEXTURE_2D, None);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+
+    fn set_uniform_location_vector3(&self, gl: &GL, uniform_name: &str, vector: Vector3<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 3] = vector.as_ref();
+        gl.uniform3fv_with_f32_array(location.as_ref(), &array[..]);
+    }
+}
@@ -0,0 +1,22 @@
+#version 300 es
+precision highp float;
+
+uniform sampler2D uDepthMap;
+// a perspective map stores 1 / z, it is brought back to linear between near and far
+uniform bool uLinearize;
+uniform vec2 uDepthRange;
+
+in vec2 vUv;
+
+out vec4 fragColor;
+
+void main(void) {
+    float depth = texture(uDepthMap, vUv).r;
+    if (uLinearize) {
+        float near = uDepthRange.x;
+        float far = uDepthRange.y;
+        float z = 2.0 * near * far / (far + near - (depth * 2.0 - 1.0) * (far - near));
+        depth = (z - near) / (far - near);
+    }
+    fragColor = vec4(vec3(depth), 1.0);
+}
@@ -0,0 +1,11 @@
+#version 300 es
+precision mediump float;
+
+out vec2 vUv;
+
+// one triangle covering the viewport, without any vertex buffer
+void main(void) {
+    vec2 corner = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    vUv = corner;
+    gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,11 @@
+#version 300 es
+precision highp float;
+
+uniform mat4 uModelMatrix;
+uniform mat4 uLightViewProjection;
+
+layout(location = 0) in vec3 aVertexPosition;
+
+void main(void) {
+    gl_Position = uLightViewProjection * uModelMatrix * vec4(aVertexPosition, 1.0);
+}
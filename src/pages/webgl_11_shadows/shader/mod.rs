@@ -0,0 +1,93 @@
+use log::debug;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+static SCENE_VERT_SHADER_SRC: &str = include_str!("./scene-vertex-shader.glsl");
+static SCENE_FRAG_SHADER_SRC: &str = include_str!("./scene-fragment-shader.glsl");
+static DEPTH_VERT_SHADER_SRC: &str = include_str!("./depth-vertex-shader.glsl");
+static DEPTH_FRAG_SHADER_SRC: &str = include_str!("./depth-fragment-shader.glsl");
+static DEBUG_VERT_SHADER_SRC: &str = include_str!("./debug-vertex-shader.glsl");
+static DEBUG_FRAG_SHADER_SRC: &str = include_str!("./debug-fragment-shader.glsl");
+
+pub struct Shader {
+    pub program: WebGlProgram,
+    uniforms: RefCell<HashMap<String, WebGlUniformLocation>>,
+}
+
+macro_rules! try_debug {
+    ($target: expr, $e: expr) => {
+        match $e {
+            Ok(result) => result,
+            Err(msg) => {
+                debug!("{} error: {}", $target, msg);
+                return Err(msg.into());
+            }
+        }
+    };
+}
+
+impl Shader {
+    pub fn new(gl: &GL, vert_shader: &str, frag_shader: &str) -> Result<Shader, JsValue> {
+        let vert_shader = try_debug!("compile vert shader", compile_shader(gl, GL::VERTEX_SHADER, vert_shader));
+        let frag_shader = try_debug!("compile frag shader", compile_shader(gl, GL::FRAGMENT_SHADER, frag_shader));
+        let program = try_debug!("link program", link_program(gl, &vert_shader, &frag_shader));
+        let uniforms = RefCell::new(HashMap::new());
+        Ok(Self { program, uniforms })
+    }
+
+    /// Lit, with the shadows of the shadow map.
+    pub fn scene_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, SCENE_VERT_SHADER_SRC, SCENE_FRAG_SHADER_SRC)
+    }
+
+    /// Depths only, from the light.
+    pub fn depth_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, DEPTH_VERT_SHADER_SRC, DEPTH_FRAG_SHADER_SRC)
+    }
+
+    /// The shadow map as gray levels.
+    pub fn debug_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, DEBUG_VERT_SHADER_SRC, DEBUG_FRAG_SHADER_SRC)
+    }
+
+    pub fn get_uniform_location(&self, gl: &GL, uniform_name: &str) -> Option<WebGlUniformLocation> {
+        let mut uniforms = self.uniforms.borrow_mut();
+        if uniforms.get(uniform_name).is_none() {
+            uniforms.insert(
+                uniform_name.to_string(),
+                gl.get_uniform_location(&self.program, uniform_name)
+                    .unwrap_or_else(|| panic!(r#"Uniform '{}' not found"#, uniform_name)),
+            );
+        }
+        Some(uniforms.get(uniform_name).expect("loc").clone())
+    }
+}
+
+fn compile_shader(gl: &GL, shader_type: u32, source: &str) -> Result<WebGlShader, String> {
+    let shader = gl.create_shader(shader_type).ok_or_else(|| "Could not create shader".to_string())?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        Ok(shader)
+    } else {
+        Err(gl
+            .get_shader_info_log(&shader)
+            .unwrap_or_else(|| "Unknown error creating shader".to_string()))
+    }
+}
+
+fn link_program(gl: &GL, vert_shader: &WebGlShader, frag_shader: &WebGlShader) -> Result<WebGlProgram, String> {
+    let program = gl.create_program().ok_or_else(|| "Unable to create shader program".to_string())?;
+    gl.attach_shader(&program, vert_shader);
+    gl.attach_shader(&program, frag_shader);
+    gl.link_program(&program);
+    if gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+        Ok(program)
+    } else {
+        Err(gl
+            .get_program_info_log(&program)
+            .unwrap_or_else(|| "Unknown error creating program".to_string()))
+    }
+}
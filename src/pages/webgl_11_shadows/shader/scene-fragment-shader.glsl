@@ -0,0 +1,67 @@
+#version 300 es
+precision highp float;
+precision highp sampler2DShadow;
+
+const int DIRECTIONAL_LIGHT = 0;
+const int SPOT_LIGHT = 1;
+
+uniform int uLightKind;
+// toward the light for the sun, along the cone for the spot
+uniform vec3 uLightDirection;
+uniform vec3 uLightPosition;
+// cosines of the inner and outer half angles of the spot
+uniform vec2 uLightCone;
+uniform vec3 uEyePosition;
+uniform vec3 uColor;
+
+uniform mat4 uLightViewProjection;
+// compares with the reference depth, linear filtering makes each lookup a 2x2 filter
+uniform sampler2DShadow uShadowMap;
+uniform float uShadowBias;
+uniform float uNormalOffset;
+uniform int uPcfRadius;
+
+in vec3 vPosition;
+in vec3 vNormal;
+
+out vec4 fragColor;
+
+// 1 where the light reaches the fragment, 0 in the shadow, in between on the edges
+float shadow(vec3 normal) {
+    // pushed off the surface, so it doesn't shadow itself on the slopes
+    vec4 lightClip = uLightViewProjection * vec4(vPosition + normal * uNormalOffset, 1.0);
+    vec3 coords = lightClip.xyz / lightClip.w * 0.5 + 0.5;
+    // outside of the map nothing casts a shadow
+    if (any(lessThan(coords, vec3(0.0))) || any(greaterThan(coords, vec3(1.0)))) {
+        return 1.0;
+    }
+    vec2 texel = 1.0 / vec2(textureSize(uShadowMap, 0));
+    float depth = coords.z - uShadowBias;
+    float lit = 0.0;
+    for (int x = -uPcfRadius; x <= uPcfRadius; x++) {
+        for (int y = -uPcfRadius; y <= uPcfRadius; y++) {
+            lit += texture(uShadowMap, vec3(coords.xy + vec2(x, y) * texel, depth));
+        }
+    }
+    float size = float(2 * uPcfRadius + 1);
+    return lit / (size * size);
+}
+
+void main(void) {
+    vec3 normal = normalize(vNormal);
+    vec3 toLight = normalize(uLightDirection);
+    float intensity = 1.0;
+    if (uLightKind == SPOT_LIGHT) {
+        toLight = normalize(uLightPosition - vPosition);
+        intensity = smoothstep(uLightCone.y, uLightCone.x, dot(-toLight, normalize(uLightDirection)));
+    }
+
+    float lambertTerm = max(dot(normal, toLight), 0.0);
+    vec3 halfway = normalize(toLight + normalize(uEyePosition - vPosition));
+    float specularTerm = lambertTerm > 0.0 ? pow(max(dot(normal, halfway), 0.0), 32.0) : 0.0;
+    // the map is only sampled where it matters
+    float lit = intensity * lambertTerm > 0.0 ? intensity * shadow(normal) : 0.0;
+
+    vec3 color = uColor * 0.15 + lit * (uColor * lambertTerm + vec3(0.3) * specularTerm);
+    fragColor = vec4(color, 1.0);
+}
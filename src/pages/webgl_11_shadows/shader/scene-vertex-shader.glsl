@@ -0,0 +1,23 @@
+#version 300 es
+precision highp float;
+
+uniform mat4 uModelMatrix;
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+// inverse transpose of the model matrix, the lighting is done in world space
+uniform mat3 uNormalMatrix;
+
+// the depth pass draws from the same vertex arrays
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+
+out vec3 vPosition;
+out vec3 vNormal;
+
+void main(void) {
+    vec4 position = uModelMatrix * vec4(aVertexPosition, 1.0);
+    vPosition = position.xyz;
+    vNormal = uNormalMatrix * aVertexNormal;
+
+    gl_Position = uProjectiveMatrix * uViewMatrix * position;
+}
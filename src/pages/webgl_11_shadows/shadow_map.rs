@@ -0,0 +1,107 @@
+use cgmath::Matrix4;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::scene_meshes::SceneMeshes;
+use super::Shader;
+use super::State;
+
+/// Depth texture render target holding, for every texel, the depth of the
+/// nearest surface seen from the light.
+pub struct ShadowMap {
+    shader: Shader,
+    framebuffer: WebGlFramebuffer,
+    depth: WebGlTexture,
+    size: i32,
+}
+
+impl ShadowMap {
+    pub fn new(gl: &GL, size: i32) -> Result<Self, JsValue> {
+        let shader = Shader::depth_shader(gl)?;
+        let framebuffer = gl.create_framebuffer().ok_or("create framebuffer error")?;
+        let depth = gl.create_texture().ok_or("create texture error")?;
+        let mut map = Self {
+            shader,
+            framebuffer,
+            depth,
+            size: 0,
+        };
+        map.resize(gl, size)?;
+        Ok(map)
+    }
+
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn texture(&self) -> &WebGlTexture {
+        &self.depth
+    }
+
+    /// Reallocates the depth texture when the resolution changed.
+    pub fn resize(&mut self, gl: &GL, size: i32) -> Result<(), JsValue> {
+        if size == self.size {
+            return Ok(());
+        }
+        // the storage of a texture is immutable, a new size needs a new texture
+        if self.size != 0 {
+            gl.delete_texture(Some(&self.depth));
+            self.depth = gl.create_texture().ok_or("create texture error")?;
+        }
+        self.size = size;
+
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.depth));
+        gl.tex_storage_2d(GL::TEXTURE_2D, 1, GL::DEPTH_COMPONENT24, size, size);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(GL::TEXTURE_2D, None);
+        self.set_compare(gl, true);
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, GL::TEXTURE_2D, Some(&self.depth), 0);
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            return Err(format!("shadow framebuffer incomplete: {:#x}", status).into());
+        }
+        Ok(())
+    }
+
+    /// Compared lookups through a `sampler2DShadow`, filtered between the
+    /// comparisons; or raw depths for a `sampler2D`, which depth textures only
+    /// allow unfiltered.
+    pub fn set_compare(&self, gl: &GL, compare: bool) {
+        let (mode, filter) = if compare {
+            (GL::COMPARE_REF_TO_TEXTURE, GL::LINEAR)
+        } else {
+            (GL::NONE, GL::NEAREST)
+        };
+        gl.bind_texture(GL::TEXTURE_2D, Some(&self.depth));
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_COMPARE_MODE, mode as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_COMPARE_FUNC, GL::LEQUAL as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, filter as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, filter as i32);
+        gl.bind_texture(GL::TEXTURE_2D, None);
+    }
+
+    /// Renders the depths of every object from the light.
+    pub fn render(&self, gl: &GL, state: &State, meshes: &SceneMeshes) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.size, self.size);
+        gl.clear(GL::DEPTH_BUFFER_BIT);
+
+        gl.use_program(Some(&self.shader.program));
+        self.set_uniform_location_matrix4(gl, "uLightViewProjection", state.light().view_projection());
+        for object in state.objects() {
+            self.set_uniform_location_matrix4(gl, "uModelMatrix", object.model);
+            meshes.draw(gl, object.shape);
+        }
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+}
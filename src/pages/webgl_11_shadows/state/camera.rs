@@ -0,0 +1,73 @@
+use cgmath::{Matrix4, PerspectiveFov, Point3, Rad, Vector3};
+use std::f32::consts::PI;
+
+/// closest and farthest the eye gets from the focus
+const MIN_DISTANCE: f32 = 2.0;
+const MAX_DISTANCE: f32 = 50.0;
+
+pub struct Camera {
+    projection: PerspectiveFov<f32>,
+    up_down_angle: f32,    // pitch
+    left_right_angle: f32, // yaw
+    r: f32,
+    focus: Point3<f32>,
+    sensitivity: f32,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        let fovy = Rad(PI / 4.0);
+
+        Camera {
+            projection: PerspectiveFov {
+                fovy,
+                aspect: 1.0,
+                near: 0.1,
+                far: 100.0,
+            },
+            left_right_angle: 20.0f32.to_radians(),
+            up_down_angle: 35.0f32.to_radians(),
+            r: 16.0,
+            focus: Point3::new(0.0, 0.0, 0.0),
+            sensitivity: 0.01,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let eye = self.get_eye();
+        Matrix4::look_at(eye, self.focus, Vector3::unit_y())
+    }
+
+    /// Projection for a viewport of width / height `aspect`.
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4<f32> {
+        PerspectiveFov { aspect, ..self.projection }.into()
+    }
+
+    pub fn get_eye(&self) -> Point3<f32> {
+        let pitch = self.up_down_angle;
+        let yaw = self.left_right_angle;
+        let y = self.r * pitch.sin();
+        let x = self.r * pitch.cos() * yaw.sin();
+        let z = self.r * pitch.cos() * yaw.cos();
+        Point3::new(x, y, z)
+    }
+
+    pub fn orbit_left_right(&mut self, delta: f32) {
+        self.left_right_angle += delta * self.sensitivity;
+    }
+
+    pub fn orbit_up_down(&mut self, delta: f32) {
+        self.up_down_angle += delta * self.sensitivity;
+        // stay above the ground
+        self.up_down_angle = self.up_down_angle.clamp(0.05, PI / 2.1);
+    }
+
+    pub fn zoom(&mut self, zoom: f32) {
+        self.r = (self.r + zoom).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Closes in as two fingers spread apart, keeping the scene under them.
+    pub fn pinch(&mut self, scale: f32) {
+        self.r = (self.r / scale).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+}
@@ -0,0 +1,139 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+use cgmath::Point2;
+use clear_color::ClearColor;
+use scene::{default_scene, SCENE_CENTER, SCENE_RADIUS};
+pub use scene::{SceneObject, Shape};
+pub use shadow_light::{LightKind, ShadowLight};
+pub use shadow_settings::{ShadowSettings, MAX_BIAS, MAX_NORMAL_OFFSET};
+use viewport::Viewport;
+
+mod camera;
+mod clear_color;
+mod scene;
+mod shadow_light;
+mod shadow_settings;
+mod viewport;
+
+/// longest step of the animation, a frame after the tab was hidden would jump
+const MAX_TICK_SECONDS: f64 = 0.1;
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    objects: Vec<SceneObject>,
+    light: ShadowLight,
+    shadows: ShadowSettings,
+    shadow_debug: bool,
+    /// time of the previous tick, in milliseconds
+    last_tick: Option<f64>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::new(0.55, 0.7, 0.85, 1.0);
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            objects: default_scene(),
+            light: ShadowLight::new(SCENE_CENTER, SCENE_RADIUS),
+            shadows: ShadowSettings::default(),
+            shadow_debug: false,
+            last_tick: None,
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn objects(&self) -> &[SceneObject] {
+        &self.objects
+    }
+
+    pub fn light(&self) -> &ShadowLight {
+        &self.light
+    }
+
+    pub fn shadows(&self) -> &ShadowSettings {
+        &self.shadows
+    }
+
+    pub fn shadow_debug(&self) -> bool {
+        self.shadow_debug
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer) => self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time),
+            Msg::PointerMove(id, x, y) => match self.gestures.moved(id, Point2::new(x, y)) {
+                Some(Gesture::Drag { from, to, .. }) => {
+                    self.camera.orbit_left_right(from.x - to.x);
+                    self.camera.orbit_up_down(to.y - from.y);
+                }
+                Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                _ => {}
+            },
+            Msg::PointerUp(id, time) => {
+                self.gestures.up(id, time);
+            }
+            Msg::PointerCancel(id) => self.gestures.cancel(id),
+            Msg::ReleaseInput => self.gestures.clear(),
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::Tick(now) => {
+                let seconds = self.last_tick.map_or(0.0, |last| ((now - last) / 1000.0).clamp(0.0, MAX_TICK_SECONDS));
+                self.last_tick = Some(now);
+                self.light.update(seconds as f32);
+            }
+            Msg::ToggleLightKind => self.light.toggle_kind(),
+            Msg::ToggleLightAnimation => self.light.toggle_animation(),
+            Msg::ToggleShadowDebug => self.shadow_debug = !self.shadow_debug,
+            Msg::NextPcfRadius => self.shadows.next_pcf_radius(),
+            Msg::NextShadowResolution => self.shadows.next_resolution(),
+            Msg::BiasChange(bias) => self.shadows.set_bias(bias),
+            Msg::NormalOffsetChange(offset) => self.shadows.set_normal_offset(offset),
+        }
+    }
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    /// a frame at `performance.now()`-like milliseconds
+    Tick(f64),
+    ToggleLightKind,
+    ToggleLightAnimation,
+    ToggleShadowDebug,
+    NextPcfRadius,
+    NextShadowResolution,
+    BiasChange(f32),
+    NormalOffsetChange(f32),
+}
@@ -0,0 +1,48 @@
+use cgmath::{Deg, Matrix4, Point3, Vector3};
+
+/// center and radius of a sphere around the whole scene, the sun's shadow map covers it
+pub const SCENE_CENTER: Point3<f32> = Point3::new(0.0, 1.0, 0.0);
+pub const SCENE_RADIUS: f32 = 14.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Cube,
+    Plane,
+}
+
+pub struct SceneObject {
+    pub shape: Shape,
+    pub model: Matrix4<f32>,
+    pub color: [f32; 3],
+}
+
+impl SceneObject {
+    fn new(shape: Shape, model: Matrix4<f32>, color: [f32; 3]) -> Self {
+        Self { shape, model, color }
+    }
+}
+
+/// A ground, cubes standing on it and one floating, so some shadows touch
+/// their caster and one doesn't.
+pub fn default_scene() -> Vec<SceneObject> {
+    let cube = |x: f32, z: f32, half: f32, turn: f32| {
+        Matrix4::from_translation(Vector3::new(x, half, z)) * Matrix4::from_angle_y(Deg(turn)) * Matrix4::from_scale(half)
+    };
+    vec![
+        SceneObject::new(Shape::Plane, Matrix4::from_scale(10.0), [0.6, 0.6, 0.55]),
+        SceneObject::new(Shape::Cube, cube(0.0, 0.0, 1.0, 20.0), [0.8, 0.3, 0.2]),
+        SceneObject::new(Shape::Cube, cube(-4.0, 2.0, 0.6, 45.0), [0.2, 0.5, 0.8]),
+        SceneObject::new(Shape::Cube, cube(3.5, -3.0, 0.8, -10.0), [0.3, 0.7, 0.3]),
+        SceneObject::new(
+            Shape::Cube,
+            Matrix4::from_translation(Vector3::new(-5.0, 2.0, -6.0)) * Matrix4::from_nonuniform_scale(0.4, 2.0, 0.4),
+            [0.8, 0.8, 0.3],
+        ),
+        // floating, its shadow is apart from it
+        SceneObject::new(
+            Shape::Cube,
+            Matrix4::from_translation(Vector3::new(3.0, 3.0, 3.0)) * Matrix4::from_angle_x(Deg(30.0)) * Matrix4::from_scale(0.7),
+            [0.7, 0.4, 0.8],
+        ),
+    ]
+}
@@ -0,0 +1,183 @@
+use cgmath::{Deg, InnerSpace, Matrix4, Point3, Rad, Vector3};
+
+/// height of the sun above the horizon
+const SUN_ELEVATION: Deg<f32> = Deg(50.0);
+/// height and distance from the center of the spot light
+const SPOT_HEIGHT: f32 = 7.0;
+const SPOT_DISTANCE: f32 = 5.0;
+/// half angles of the spot cone, lit fully inside the inner one
+const SPOT_INNER: Deg<f32> = Deg(25.0);
+const SPOT_OUTER: Deg<f32> = Deg(35.0);
+const SPOT_NEAR: f32 = 0.5;
+/// radians per second of the light orbit
+const ORBIT_SPEED: f32 = 0.3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    /// the sun, shadows through an orthographic projection
+    Directional,
+    /// shadows through a perspective projection as wide as the cone
+    Spot,
+}
+
+/// The one light casting shadows, orbiting around the scene center.
+pub struct ShadowLight {
+    kind: LightKind,
+    /// around the y axis
+    angle: Rad<f32>,
+    animated: bool,
+    /// sphere around everything casting or receiving shadows
+    scene_center: Point3<f32>,
+    scene_radius: f32,
+}
+
+impl ShadowLight {
+    pub fn new(scene_center: Point3<f32>, scene_radius: f32) -> Self {
+        Self {
+            kind: LightKind::Directional,
+            angle: Rad(0.8),
+            animated: true,
+            scene_center,
+            scene_radius,
+        }
+    }
+
+    pub fn kind(&self) -> LightKind {
+        self.kind
+    }
+
+    pub fn animated(&self) -> bool {
+        self.animated
+    }
+
+    pub fn toggle_kind(&mut self) {
+        self.kind = match self.kind {
+            LightKind::Directional => LightKind::Spot,
+            LightKind::Spot => LightKind::Directional,
+        };
+    }
+
+    pub fn toggle_animation(&mut self) {
+        self.animated = !self.animated;
+    }
+
+    /// Moves the light along its orbit by `seconds` of animation.
+    pub fn update(&mut self, seconds: f32) {
+        if self.animated {
+            self.angle += Rad(seconds * ORBIT_SPEED);
+        }
+    }
+
+    /// World direction from the scene toward a directional light.
+    pub fn direction(&self) -> Vector3<f32> {
+        let elevation = Rad::from(SUN_ELEVATION).0;
+        Vector3::new(
+            self.angle.0.cos() * elevation.cos(),
+            elevation.sin(),
+            self.angle.0.sin() * elevation.cos(),
+        )
+    }
+
+    /// World position of a spot light, aiming at the scene center.
+    pub fn position(&self) -> Point3<f32> {
+        self.scene_center + Vector3::new(self.angle.0.cos() * SPOT_DISTANCE, SPOT_HEIGHT, self.angle.0.sin() * SPOT_DISTANCE)
+    }
+
+    /// World direction of the spot cone, from the light.
+    pub fn spot_axis(&self) -> Vector3<f32> {
+        (self.scene_center - self.position()).normalize()
+    }
+
+    /// Cosines of the inner and outer half angles of the spot.
+    pub fn cone_cosines(&self) -> [f32; 2] {
+        [Rad::from(SPOT_INNER).0.cos(), Rad::from(SPOT_OUTER).0.cos()]
+    }
+
+    /// Where the shadow map is rendered from.
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let eye = match self.kind {
+            // far enough back that the whole scene sphere is in front
+            LightKind::Directional => self.scene_center + self.direction() * self.scene_radius * 2.0,
+            LightKind::Spot => self.position(),
+        };
+        let forward = (self.scene_center - eye).normalize();
+        // any up not along the view direction
+        let up = if forward.y.abs() > 0.99 { Vector3::unit_z() } else { Vector3::unit_y() };
+        Matrix4::look_at(eye, self.scene_center, up)
+    }
+
+    /// Light space projection: the box around the scene sphere for the sun,
+    /// the cone for the spot.
+    pub fn projection_matrix(&self) -> Matrix4<f32> {
+        let r = self.scene_radius;
+        match self.kind {
+            LightKind::Directional => cgmath::ortho(-r, r, -r, r, r, 3.0 * r),
+            LightKind::Spot => {
+                let distance = (self.scene_center - self.position()).magnitude();
+                cgmath::perspective(Rad::from(SPOT_OUTER) * 2.0, 1.0, SPOT_NEAR, distance + r)
+            }
+        }
+    }
+
+    pub fn view_projection(&self) -> Matrix4<f32> {
+        self.projection_matrix() * self.view_matrix()
+    }
+
+    /// Near and far planes of a perspective shadow map, to linearize its
+    /// depths in the debug view; none for the orthographic one.
+    pub fn depth_range(&self) -> Option<(f32, f32)> {
+        match self.kind {
+            LightKind::Directional => None,
+            LightKind::Spot => Some((SPOT_NEAR, (self.scene_center - self.position()).magnitude() + self.scene_radius)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector4;
+
+    /// normalized device coordinates of a world point
+    fn ndc(light: &ShadowLight, point: Point3<f32>) -> Vector3<f32> {
+        let clip = light.view_projection() * Vector4::new(point.x, point.y, point.z, 1.0);
+        clip.truncate() / clip.w
+    }
+
+    #[test]
+    fn the_sun_sees_the_whole_scene() {
+        let mut light = ShadowLight::new(Point3::new(0.0, 1.0, 0.0), 10.0);
+        for _ in 0..8 {
+            light.update(2.0);
+            // points on the scene sphere along each axis
+            for axis in 0..3 {
+                for &sign in &[-1.0, 1.0] {
+                    let mut offset = Vector3::new(0.0, 0.0, 0.0);
+                    offset[axis] = sign * 10.0;
+                    let p = ndc(&light, light.scene_center + offset);
+                    assert!(p.x.abs() <= 1.0 + 1e-4 && p.y.abs() <= 1.0 + 1e-4 && p.z.abs() <= 1.0 + 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_spot_aims_at_the_center() {
+        let mut light = ShadowLight::new(Point3::new(0.0, 0.0, 0.0), 10.0);
+        light.toggle_kind();
+        let p = ndc(&light, Point3::new(0.0, 0.0, 0.0));
+        assert!(p.x.abs() < 1e-5 && p.y.abs() < 1e-5);
+        assert!(p.z > -1.0 && p.z < 1.0);
+        let (near, far) = light.depth_range().unwrap();
+        assert!(near < far);
+    }
+
+    #[test]
+    fn paused_lights_stay() {
+        let mut light = ShadowLight::new(Point3::new(0.0, 0.0, 0.0), 10.0);
+        light.toggle_animation();
+        let before = light.direction();
+        light.update(5.0);
+        assert_eq!(light.direction(), before);
+    }
+}
@@ -0,0 +1,52 @@
+/// shadow map sizes to pick from
+pub const RESOLUTIONS: [i32; 4] = [256, 512, 1024, 2048];
+/// largest depth bias of the slider
+pub const MAX_BIAS: f32 = 0.01;
+/// largest normal offset of the slider, in world units
+pub const MAX_NORMAL_OFFSET: f32 = 0.2;
+/// largest percentage closer filtering radius, in texels
+const MAX_PCF_RADIUS: i32 = 2;
+
+/// Knobs against shadow acne and jagged edges.
+pub struct ShadowSettings {
+    /// subtracted from the depth of a point before comparing it to the map
+    pub bias: f32,
+    /// world distance a point is pushed along its normal before the lookup
+    pub normal_offset: f32,
+    /// the map is sampled over (2r + 1)² texels, 0 for a single hard lookup
+    pub pcf_radius: i32,
+    resolution: usize,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            bias: 0.002,
+            normal_offset: 0.05,
+            pcf_radius: 1,
+            resolution: 2,
+        }
+    }
+}
+
+impl ShadowSettings {
+    pub fn resolution(&self) -> i32 {
+        RESOLUTIONS[self.resolution]
+    }
+
+    pub fn next_resolution(&mut self) {
+        self.resolution = (self.resolution + 1) % RESOLUTIONS.len();
+    }
+
+    pub fn next_pcf_radius(&mut self) {
+        self.pcf_radius = (self.pcf_radius + 1) % (MAX_PCF_RADIUS + 1);
+    }
+
+    pub fn set_bias(&mut self, bias: f32) {
+        self.bias = bias.clamp(0.0, MAX_BIAS);
+    }
+
+    pub fn set_normal_offset(&mut self, offset: f32) {
+        self.normal_offset = offset.clamp(0.0, MAX_NORMAL_OFFSET);
+    }
+}
@@ -0,0 +1,97 @@
+use cgmath::Matrix4;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::scene_meshes::SceneMeshes;
+use super::state::{Cascade, CASCADE_COUNT};
+use super::Shader;
+use super::State;
+
+/// Depth texture array with one layer per cascade, each holding the depths
+/// of the nearest surfaces seen from the sun over its slice of the view.
+pub struct CascadedShadowMap {
+    shader: Shader,
+    framebuffer: WebGlFramebuffer,
+    depth: WebGlTexture,
+    size: i32,
+}
+
+impl CascadedShadowMap {
+    pub fn new(gl: &GL, size: i32) -> Result<Self, JsValue> {
+        let shader = Shader::depth_shader(gl)?;
+        let framebuffer = gl.create_framebuffer().ok_or("create framebuffer error")?;
+        let depth = gl.create_texture().ok_or("create texture error")?;
+        let mut map = Self {
+            shader,
+            framebuffer,
+            depth,
+            size: 0,
+        };
+        map.resize(gl, size)?;
+        Ok(map)
+    }
+
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    pub fn texture(&self) -> &WebGlTexture {
+        &self.depth
+    }
+
+    /// Reallocates the layers when the resolution changed.
+    pub fn resize(&mut self, gl: &GL, size: i32) -> Result<(), JsValue> {
+        if size == self.size {
+            return Ok(());
+        }
+        // the storage of a texture is immutable, a new size needs a new texture
+        if self.size != 0 {
+            gl.delete_texture(Some(&self.depth));
+            self.depth = gl.create_texture().ok_or("create texture error")?;
+        }
+        self.size = size;
+
+        gl.bind_texture(GL::TEXTURE_2D_ARRAY, Some(&self.depth));
+        gl.tex_storage_3d(GL::TEXTURE_2D_ARRAY, 1, GL::DEPTH_COMPONENT24, size, size, CASCADE_COUNT as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+        // compared lookups, filtered between the comparisons
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_COMPARE_MODE, GL::COMPARE_REF_TO_TEXTURE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_COMPARE_FUNC, GL::LEQUAL as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D_ARRAY, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+        gl.bind_texture(GL::TEXTURE_2D_ARRAY, None);
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.framebuffer_texture_layer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, Some(&self.depth), 0, 0);
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            return Err(format!("shadow framebuffer incomplete: {:#x}", status).into());
+        }
+        Ok(())
+    }
+
+    /// Renders the depths of every object into the layer of every cascade.
+    pub fn render(&self, gl: &GL, state: &State, cascades: &[Cascade], meshes: &SceneMeshes) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.viewport(0, 0, self.size, self.size);
+        gl.use_program(Some(&self.shader.program));
+        for (layer, cascade) in cascades.iter().enumerate() {
+            gl.framebuffer_texture_layer(GL::FRAMEBUFFER, GL::DEPTH_ATTACHMENT, Some(&self.depth), 0, layer as i32);
+            gl.clear(GL::DEPTH_BUFFER_BIT);
+            self.set_uniform_location_matrix4(gl, "uLightViewProjection", cascade.view_projection);
+            for object in state.objects() {
+                self.set_uniform_location_matrix4(gl, "uModelMatrix", object.model);
+                meshes.draw(gl, object.shape);
+            }
+        }
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+}
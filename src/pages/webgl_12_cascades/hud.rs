@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement};
+
+use super::state::{Cascade, State, MAX_SHADOW_DISTANCE, MIN_SHADOW_DISTANCE};
+
+/// The split and distance sliders, and where the cascades end.
+pub struct Hud {
+    info: HtmlElement,
+    lambda_input: HtmlInputElement,
+    lambda_label: HtmlElement,
+    distance_input: HtmlInputElement,
+    distance_label: HtmlElement,
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        style.set_property("text-shadow", "0 0 2px black")?;
+        body.append_child(&panel)?;
+
+        let slider = |name: &str, min: f32, max: f32, step: f32| -> Result<(HtmlInputElement, HtmlElement), JsValue> {
+            let row = document.create_element("div")?;
+            row.set_inner_html(name);
+            let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+            input.set_type("range");
+            input.set_min(&min.to_string());
+            input.set_max(&max.to_string());
+            input.set_step(&step.to_string());
+            row.append_child(&input)?;
+            let label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+            row.append_child(&label)?;
+            panel.append_child(&row)?;
+            Ok((input, label))
+        };
+        let (lambda_input, lambda_label) = slider("uniform / log split ", 0.0, 1.0, 0.05)?;
+        let (distance_input, distance_label) = slider("shadow distance ", MIN_SHADOW_DISTANCE, MAX_SHADOW_DISTANCE, 10.0)?;
+
+        let info = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        panel.append_child(&info)?;
+
+        Ok(Self {
+            info,
+            lambda_input,
+            lambda_label,
+            distance_input,
+            distance_label,
+        })
+    }
+
+    pub fn lambda_input(&self) -> &HtmlInputElement {
+        &self.lambda_input
+    }
+
+    pub fn distance_input(&self) -> &HtmlInputElement {
+        &self.distance_input
+    }
+
+    pub fn lambda(&self) -> Option<f32> {
+        self.lambda_input.value().parse().ok()
+    }
+
+    pub fn distance(&self) -> Option<f32> {
+        self.distance_input.value().parse().ok()
+    }
+
+    pub fn sync(&self, state: &State, cascades: &[Cascade]) {
+        let settings = state.settings();
+        if self.lambda() != Some(settings.lambda) {
+            self.lambda_input.set_value(&settings.lambda.to_string());
+        }
+        self.lambda_label.set_inner_html(&format!(" {:.2}", settings.lambda));
+        if self.distance() != Some(settings.shadow_distance) {
+            self.distance_input.set_value(&settings.shadow_distance.to_string());
+        }
+        self.distance_label.set_inner_html(&format!(" {:.0}", settings.shadow_distance));
+
+        let splits: Vec<String> = cascades.iter().map(|cascade| format!("{:.1}", cascade.far)).collect();
+        let texels: Vec<String> = cascades.iter().map(|cascade| format!("{:.2}", cascade.texel_size)).collect();
+        let taps = 2 * settings.pcf_radius + 1;
+        self.info.set_inner_html(&format!(
+            "cascades end at {}<br>texels of {} units<br>{}x{} per cascade, pcf {}x{}<br>blend {}, sun {}<br>?: keys",
+            splits.join(", "),
+            texels.join(", "),
+            settings.resolution(),
+            settings.resolution(),
+            taps,
+            taps,
+            if settings.blend { "on" } else { "off" },
+            if state.sun().animated() { "moving" } else { "paused" },
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+/// floats per vertex: position(3) + normal(3)
+pub const VERTEX_SIZE: usize = 6;
+
+pub struct Mesh {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// Cube from -1 to 1, four vertices per face so each face has its own
+    /// normal.
+    pub fn cube() -> Self {
+        let mut mesh = Self {
+            vertices: Vec::with_capacity(24 * VERTEX_SIZE),
+            indices: Vec::with_capacity(36),
+        };
+        for axis in 0..3 {
+            for &sign in &[1.0f32, -1.0] {
+                let mut normal = [0.0; 3];
+                normal[axis] = sign;
+                // u x v = normal, so the corners below go counter-clockwise from outside
+                let mut u = [0.0; 3];
+                u[(axis + 1) % 3] = sign;
+                let mut v = [0.0; 3];
+                v[(axis + 2) % 3] = 1.0;
+                mesh.quad(normal, u, v);
+            }
+        }
+        mesh
+    }
+
+    /// Square from -1 to 1 in the y = 0 plane, facing up.
+    pub fn plane() -> Self {
+        let mut mesh = Self {
+            vertices: Vec::with_capacity(4 * VERTEX_SIZE),
+            indices: Vec::with_capacity(6),
+        };
+        mesh.quad([0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]);
+        // the quad is centered on its normal, the plane goes through the origin
+        for vertex in mesh.vertices.chunks_mut(VERTEX_SIZE) {
+            vertex[1] = 0.0;
+        }
+        mesh
+    }
+
+    /// Face at `normal` spanning `u` and `v`, with `u` x `v` = `normal`.
+    fn quad(&mut self, normal: [f32; 3], u: [f32; 3], v: [f32; 3]) {
+        let first = (self.vertices.len() / VERTEX_SIZE) as u16;
+        for &(a, b) in &[(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            for k in 0..3 {
+                self.vertices.push(normal[k] + u[k] * a + v[k] * b);
+            }
+            self.vertices.extend_from_slice(&normal);
+        }
+        self.indices
+            .extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// every triangle turns counter-clockwise around its vertex normal
+    fn assert_wound_outward(mesh: &Mesh) {
+        let vertex = |i: u16| &mesh.vertices()[i as usize * VERTEX_SIZE..(i as usize + 1) * VERTEX_SIZE];
+        for t in mesh.indices().chunks(3) {
+            let (a, b, c) = (vertex(t[0]), vertex(t[1]), vertex(t[2]));
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            assert!(cross[0] * a[3] + cross[1] * a[4] + cross[2] * a[5] > 0.0);
+        }
+    }
+
+    #[test]
+    fn cube_faces_point_outward() {
+        let cube = Mesh::cube();
+        assert_eq!(cube.vertices().len(), 24 * VERTEX_SIZE);
+        assert_eq!(cube.indices().len(), 36);
+        assert_wound_outward(&cube);
+        for v in cube.vertices().chunks(VERTEX_SIZE) {
+            // on the face the normal points out of
+            let along = v[0] * v[3] + v[1] * v[4] + v[2] * v[5];
+            assert_eq!(along, 1.0);
+        }
+    }
+
+    #[test]
+    fn plane_faces_up() {
+        let plane = Mesh::plane();
+        assert_wound_outward(&plane);
+        assert!(plane.vertices().chunks(VERTEX_SIZE).all(|v| v[1] == 0.0 && v[4] == 1.0));
+    }
+}
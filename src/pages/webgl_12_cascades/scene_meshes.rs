@@ -0,0 +1,73 @@
+use std::mem::size_of;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::mesh::{Mesh, VERTEX_SIZE};
+use super::state::Shape;
+
+/// `aVertexPosition` and `aVertexNormal`, fixed in the shaders so every pass
+/// draws from the same vertex arrays
+const POSITION_LOCATION: u32 = 0;
+const NORMAL_LOCATION: u32 = 1;
+
+struct MeshBuffers {
+    vao: Option<WebGlVertexArrayObject>,
+    index_count: i32,
+}
+
+/// The vertex arrays of every shape, shared by the shadow and the lit pass.
+pub struct SceneMeshes {
+    cube: MeshBuffers,
+    plane: MeshBuffers,
+}
+
+impl SceneMeshes {
+    pub fn new(gl: &GL) -> Self {
+        Self {
+            cube: upload(gl, &Mesh::cube()),
+            plane: upload(gl, &Mesh::plane()),
+        }
+    }
+
+    /// Draws `shape` with the program and uniforms in use.
+    pub fn draw(&self, gl: &GL, shape: Shape) {
+        let buffers = match shape {
+            Shape::Cube => &self.cube,
+            Shape::Plane => &self.plane,
+        };
+        gl.bind_vertex_array(buffers.vao.as_ref());
+        gl.draw_elements_with_i32(GL::TRIANGLES, buffers.index_count, GL::UNSIGNED_SHORT, 0);
+        gl.bind_vertex_array(None);
+    }
+}
+
+fn upload(gl: &GL, mesh: &Mesh) -> MeshBuffers {
+    let vao = gl.create_vertex_array();
+    gl.bind_vertex_array(vao.as_ref());
+
+    let vbo = gl.create_buffer().unwrap();
+    gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+    unsafe {
+        let data_array = js_sys::Float32Array::view(mesh.vertices());
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+    }
+    let stride = (VERTEX_SIZE * size_of::<f32>()) as i32;
+    gl.enable_vertex_attrib_array(POSITION_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(POSITION_LOCATION, 3, GL::FLOAT, false, stride, 0);
+    gl.enable_vertex_attrib_array(NORMAL_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(NORMAL_LOCATION, 3, GL::FLOAT, false, stride, 3 * size_of::<f32>() as i32);
+
+    let ebo = gl.create_buffer().unwrap();
+    gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+    unsafe {
+        let data_array = js_sys::Uint16Array::view(mesh.indices());
+        gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+    }
+
+    gl.bind_vertex_array(None);
+    gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+    gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    MeshBuffers {
+        vao,
+        index_count: mesh.indices().len() as i32,
+    }
+}
@@ -0,0 +1,90 @@
+use cgmath::{Matrix, Matrix3, Matrix4, SquareMatrix, Vector3};
+use web_sys::WebGl2RenderingContext as GL;
+
+use super::cascaded_shadow_map::CascadedShadowMap;
+use super::scene_meshes::SceneMeshes;
+use super::state::Cascade;
+use super::Shader;
+use super::State;
+
+/// texture unit of the shadow map array
+const SHADOW_UNIT: u32 = 0;
+/// subtracted from the depths before the comparison, the normal offset does the rest
+const SHADOW_BIAS: f32 = 0.0005;
+
+/// Draws the city lit by the sun, every fragment shadowed by its cascade.
+pub struct SceneRenderer {
+    shader: Shader,
+}
+
+impl SceneRenderer {
+    pub fn new(shader: Shader) -> Self {
+        Self { shader }
+    }
+
+    pub fn render(&self, gl: &GL, state: &State, meshes: &SceneMeshes, shadow_map: &CascadedShadowMap, cascades: &[Cascade]) {
+        let shader = &self.shader;
+        gl.use_program(Some(&shader.program));
+
+        let camera = state.camera();
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", camera.view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", camera.projection_matrix(state.aspect()));
+        let eye = camera.get_eye();
+        self.set_uniform_location_vector3(gl, "uEyePosition", Vector3::new(eye.x, eye.y, eye.z));
+        self.set_uniform_location_vector3(gl, "uLightDirection", state.sun().direction());
+
+        let mut matrices = Vec::with_capacity(cascades.len() * 16);
+        for cascade in cascades {
+            let array: &[f32; 16] = cascade.view_projection.as_ref();
+            matrices.extend_from_slice(array);
+        }
+        let location = shader.get_uniform_location(gl, "uCascadeViewProjections");
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &matrices);
+        let fars: Vec<f32> = cascades.iter().map(|c| c.far).collect();
+        let location = shader.get_uniform_location(gl, "uCascadeFar");
+        gl.uniform1fv_with_f32_array(location.as_ref(), &fars);
+        let texel_sizes: Vec<f32> = cascades.iter().map(|c| c.texel_size).collect();
+        let location = shader.get_uniform_location(gl, "uCascadeTexelSizes");
+        gl.uniform1fv_with_f32_array(location.as_ref(), &texel_sizes);
+        let location = shader.get_uniform_location(gl, "uCascadeNear");
+        gl.uniform1f(location.as_ref(), camera.near());
+
+        let settings = state.settings();
+        let location = shader.get_uniform_location(gl, "uShadowBias");
+        gl.uniform1f(location.as_ref(), SHADOW_BIAS);
+        let location = shader.get_uniform_location(gl, "uPcfRadius");
+        gl.uniform1i(location.as_ref(), settings.pcf_radius);
+        let location = shader.get_uniform_location(gl, "uBlendCascades");
+        gl.uniform1i(location.as_ref(), settings.blend as i32);
+        let location = shader.get_uniform_location(gl, "uCascadeColors");
+        gl.uniform1i(location.as_ref(), settings.debug_colors as i32);
+        gl.active_texture(GL::TEXTURE0 + SHADOW_UNIT);
+        gl.bind_texture(GL::TEXTURE_2D_ARRAY, Some(shadow_map.texture()));
+        let location = shader.get_uniform_location(gl, "uShadowMap");
+        gl.uniform1i(location.as_ref(), SHADOW_UNIT as i32);
+
+        for object in state.objects() {
+            self.set_uniform_location_matrix4(gl, "uModelMatrix", object.model);
+            let linear = Matrix3::from_cols(object.model.x.truncate(), object.model.y.truncate(), object.model.z.truncate());
+            let normal_matrix = linear.invert().expect("model matrix not invertible").transpose();
+            let location = shader.get_uniform_location(gl, "uNormalMatrix");
+            let array: &[f32; 9] = normal_matrix.as_ref();
+            gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+            self.set_uniform_location_vector3(gl, "uColor", Vector3::from(object.color));
+            meshes.draw(gl, object.shape);
+        }
+        gl.bind_texture(GL::TEXTURE_2D_ARRAY, None);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+
+    fn set_uniform_location_vector3(&self, gl: &GL, uniform_name: &str, vector: Vector3<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 3] = vector.as_ref();
+        gl.uniform3fv_with_f32_array(location.as_ref(), &array[..]);
+    }
+}
@@ -0,0 +1,6 @@
+#version 300 es
+precision mediump float;
+
+// only the depth is written
+void main(void) {
+}
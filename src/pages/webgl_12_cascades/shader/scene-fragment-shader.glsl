@@ -0,0 +1,98 @@
+#version 300 es
+precision highp float;
+precision highp sampler2DArrayShadow;
+
+// CASCADE_COUNT is defined from Rust
+
+// last part of a cascade cross faded with the next one
+const float BLEND_BAND = 0.15;
+// texels a point is pushed along its normal, whatever the cascade
+const float NORMAL_OFFSET_TEXELS = 1.5;
+const vec3 CASCADE_TINTS[4] = vec3[4](vec3(1.0, 0.35, 0.35), vec3(0.35, 1.0, 0.35), vec3(0.35, 0.5, 1.0), vec3(1.0, 1.0, 0.35));
+
+// toward the sun
+uniform vec3 uLightDirection;
+uniform vec3 uEyePosition;
+uniform vec3 uColor;
+
+uniform mat4 uCascadeViewProjections[CASCADE_COUNT];
+// view depths where the cascades end
+uniform float uCascadeFar[CASCADE_COUNT];
+uniform float uCascadeNear;
+// world size of a texel of every cascade
+uniform float uCascadeTexelSizes[CASCADE_COUNT];
+// one layer per cascade, compared with the reference depth
+uniform sampler2DArrayShadow uShadowMap;
+uniform float uShadowBias;
+uniform int uPcfRadius;
+uniform bool uBlendCascades;
+uniform bool uCascadeColors;
+
+in vec3 vPosition;
+in vec3 vNormal;
+in float vViewDepth;
+
+out vec4 fragColor;
+
+// 1 where the sun reaches the fragment in the map of `cascade`, 0 in the shadow
+float shadow(int cascade, vec3 normal) {
+    // the texels are larger in the far cascades, so is the offset against acne
+    float offset = uCascadeTexelSizes[cascade] * NORMAL_OFFSET_TEXELS;
+    // orthographic, w is 1
+    vec3 coords = (uCascadeViewProjections[cascade] * vec4(vPosition + normal * offset, 1.0)).xyz * 0.5 + 0.5;
+    if (any(lessThan(coords, vec3(0.0))) || any(greaterThan(coords, vec3(1.0)))) {
+        return 1.0;
+    }
+    vec2 texel = 1.0 / vec2(textureSize(uShadowMap, 0).xy);
+    float depth = coords.z - uShadowBias;
+    float lit = 0.0;
+    for (int x = -uPcfRadius; x <= uPcfRadius; x++) {
+        for (int y = -uPcfRadius; y <= uPcfRadius; y++) {
+            lit += texture(uShadowMap, vec4(coords.xy + vec2(x, y) * texel, float(cascade), depth));
+        }
+    }
+    float size = float(2 * uPcfRadius + 1);
+    return lit / (size * size);
+}
+
+void main(void) {
+    vec3 normal = normalize(vNormal);
+    vec3 toLight = normalize(uLightDirection);
+    float lambertTerm = max(dot(normal, toLight), 0.0);
+    vec3 halfway = normalize(toLight + normalize(uEyePosition - vPosition));
+    float specularTerm = lambertTerm > 0.0 ? pow(max(dot(normal, halfway), 0.0), 32.0) : 0.0;
+
+    int cascade = CASCADE_COUNT;
+    for (int i = 0; i < CASCADE_COUNT; i++) {
+        if (vViewDepth < uCascadeFar[i]) {
+            cascade = i;
+            break;
+        }
+    }
+
+    float lit = 1.0;
+    vec3 tint = vec3(1.0);
+    // past the last cascade nothing casts shadows
+    if (cascade < CASCADE_COUNT) {
+        lit = lambertTerm > 0.0 ? shadow(cascade, normal) : 0.0;
+        tint = CASCADE_TINTS[cascade % 4];
+        float start = cascade == 0 ? uCascadeNear : uCascadeFar[cascade - 1];
+        // 0 at the end of the cascade, 1 where the band starts
+        float band = (uCascadeFar[cascade] - vViewDepth) / ((uCascadeFar[cascade] - start) * BLEND_BAND);
+        if (uBlendCascades && band < 1.0 && lambertTerm > 0.0) {
+            if (cascade + 1 < CASCADE_COUNT) {
+                lit = mix(shadow(cascade + 1, normal), lit, band);
+                tint = mix(CASCADE_TINTS[(cascade + 1) % 4], tint, band);
+            } else {
+                // the last one fades out instead of ending on a line
+                lit = mix(1.0, lit, band);
+            }
+        }
+    }
+
+    vec3 color = uColor * 0.25 + lit * (uColor * lambertTerm * 0.9 + vec3(0.2) * specularTerm);
+    if (uCascadeColors) {
+        color *= tint;
+    }
+    fragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,103 @@
+use cgmath::{Matrix4, PerspectiveFov, Point3, Rad, Vector2, Vector3};
+use std::f32::consts::PI;
+
+/// closest and farthest the eye gets from the focus
+const MIN_DISTANCE: f32 = 2.0;
+const MAX_DISTANCE: f32 = 300.0;
+
+use super::cascades::Frustum;
+
+/// focus speed, in camera distances per second
+const PAN_SPEED: f32 = 0.8;
+
+/// Orbits around a focus on the ground, which the keys move around the city.
+pub struct Camera {
+    projection: PerspectiveFov<f32>,
+    up_down_angle: f32,    // pitch
+    left_right_angle: f32, // yaw
+    r: f32,
+    focus: Point3<f32>,
+    sensitivity: f32,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        let fovy = Rad(PI / 4.0);
+
+        Camera {
+            projection: PerspectiveFov {
+                fovy,
+                aspect: 1.0,
+                near: 0.5,
+                far: 600.0,
+            },
+            left_right_angle: 30.0f32.to_radians(),
+            up_down_angle: 12.0f32.to_radians(),
+            r: 40.0,
+            focus: Point3::new(0.0, 0.0, 0.0),
+            sensitivity: 0.01,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        let eye = self.get_eye();
+        Matrix4::look_at(eye, self.focus, Vector3::unit_y())
+    }
+
+    /// Projection for a viewport of width / height `aspect`.
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4<f32> {
+        PerspectiveFov { aspect, ..self.projection }.into()
+    }
+
+    pub fn near(&self) -> f32 {
+        self.projection.near
+    }
+
+    /// The frustum from the near plane up to `distance`, or the far plane.
+    pub fn frustum(&self, aspect: f32, distance: f32) -> Frustum {
+        Frustum {
+            view: self.view_matrix(),
+            fovy: self.projection.fovy,
+            aspect,
+            near: self.projection.near,
+            far: distance.min(self.projection.far),
+        }
+    }
+
+    pub fn get_eye(&self) -> Point3<f32> {
+        let pitch = self.up_down_angle;
+        let yaw = self.left_right_angle;
+        let y = self.r * pitch.sin();
+        let x = self.r * pitch.cos() * yaw.sin();
+        let z = self.r * pitch.cos() * yaw.cos();
+        self.focus + Vector3::new(x, y, z)
+    }
+
+    pub fn orbit_left_right(&mut self, delta: f32) {
+        self.left_right_angle += delta * self.sensitivity;
+    }
+
+    pub fn orbit_up_down(&mut self, delta: f32) {
+        self.up_down_angle += delta * self.sensitivity;
+        // stay above the ground
+        self.up_down_angle = self.up_down_angle.clamp(0.02, PI / 2.1);
+    }
+
+    pub fn zoom(&mut self, zoom: f32) {
+        self.r = (self.r + zoom).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Closes in as two fingers spread apart, keeping the scene under them.
+    pub fn pinch(&mut self, scale: f32) {
+        self.r = (self.r / scale).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Moves the focus along the ground, x to the right and y forward, for
+    /// `seconds`.
+    pub fn pan(&mut self, direction: Vector2<f32>, seconds: f32) {
+        let yaw = self.left_right_angle;
+        let forward = Vector3::new(-yaw.sin(), 0.0, -yaw.cos());
+        let right = Vector3::new(yaw.cos(), 0.0, -yaw.sin());
+        self.focus += (right * direction.x + forward * direction.y) * self.r * PAN_SPEED * seconds;
+    }
+}
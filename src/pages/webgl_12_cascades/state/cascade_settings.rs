@@ -0,0 +1,57 @@
+/// shadow map sizes to pick from, for every cascade
+pub const RESOLUTIONS: [i32; 3] = [512, 1024, 2048];
+/// view depths the shadows may end at, for the slider
+pub const MIN_SHADOW_DISTANCE: f32 = 20.0;
+pub const MAX_SHADOW_DISTANCE: f32 = 600.0;
+/// largest percentage closer filtering radius, in texels
+const MAX_PCF_RADIUS: i32 = 2;
+
+/// How the view frustum is cut in cascades, and how they are looked up.
+pub struct CascadeSettings {
+    /// 0 for uniform splits, 1 for logarithmic ones
+    pub lambda: f32,
+    /// view depth the last cascade ends at, nothing casts shadows further
+    pub shadow_distance: f32,
+    /// cross fades between a cascade and the next instead of a hard seam
+    pub blend: bool,
+    /// tints every fragment with the color of its cascade
+    pub debug_colors: bool,
+    /// the map is sampled over (2r + 1)² texels
+    pub pcf_radius: i32,
+    resolution: usize,
+}
+
+impl Default for CascadeSettings {
+    fn default() -> Self {
+        Self {
+            lambda: 0.75,
+            shadow_distance: 250.0,
+            blend: true,
+            debug_colors: false,
+            pcf_radius: 1,
+            resolution: 1,
+        }
+    }
+}
+
+impl CascadeSettings {
+    pub fn resolution(&self) -> i32 {
+        RESOLUTIONS[self.resolution]
+    }
+
+    pub fn next_resolution(&mut self) {
+        self.resolution = (self.resolution + 1) % RESOLUTIONS.len();
+    }
+
+    pub fn next_pcf_radius(&mut self) {
+        self.pcf_radius = (self.pcf_radius + 1) % (MAX_PCF_RADIUS + 1);
+    }
+
+    pub fn set_lambda(&mut self, lambda: f32) {
+        self.lambda = lambda.clamp(0.0, 1.0);
+    }
+
+    pub fn set_shadow_distance(&mut self, distance: f32) {
+        self.shadow_distance = distance.clamp(MIN_SHADOW_DISTANCE, MAX_SHADOW_DISTANCE);
+    }
+}
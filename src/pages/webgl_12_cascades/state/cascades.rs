@@ -0,0 +1,210 @@
+use cgmath::{EuclideanSpace, InnerSpace, Matrix4, Point3, Rad, SquareMatrix, Transform, Vector3};
+
+/// slices of the view frustum, and layers of the shadow map array
+pub const CASCADE_COUNT: usize = 4;
+
+/// The part of the camera frustum to cover with shadows.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    pub view: Matrix4<f32>,
+    pub fovy: Rad<f32>,
+    /// width / height
+    pub aspect: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+/// One slice of the view frustum, and the light projection covering it.
+#[derive(Clone, Copy, Debug)]
+pub struct Cascade {
+    /// view depth where the slice ends
+    pub far: f32,
+    /// world to the light clip space of this slice
+    pub view_projection: Matrix4<f32>,
+    /// world size of a shadow map texel
+    pub texel_size: f32,
+}
+
+/// Depths cutting `near..far` in `count` slices, `near` and `far` included.
+/// `lambda` mixes the logarithmic split at 1, as many texels per pixel in
+/// every slice, with the uniform one at 0, which wastes them far away.
+pub fn split_distances(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (0..=count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(t);
+            let uniform = near + (far - near) * t;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// World corners of the frustum between the view depths `near` and `far`,
+/// the near ones first.
+pub fn slice_corners(frustum: &Frustum, near: f32, far: f32) -> [Point3<f32>; 8] {
+    let to_world = frustum.view.invert().expect("view matrix not invertible");
+    let tan_y = (frustum.fovy.0 / 2.0).tan();
+    let tan_x = tan_y * frustum.aspect;
+    let mut corners = [Point3::origin(); 8];
+    for (i, depth) in [near, far].iter().enumerate() {
+        for (j, (x, y)) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter().enumerate() {
+            let corner = Point3::new(x * tan_x * depth, y * tan_y * depth, -depth);
+            corners[i * 4 + j] = to_world.transform_point(corner);
+        }
+    }
+    corners
+}
+
+/// World to light view rotation, looking along the light. Every cascade
+/// shares it, so they only differ by their box.
+fn light_rotation(to_light: Vector3<f32>) -> Matrix4<f32> {
+    let up = if to_light.y.abs() > 0.99 { Vector3::unit_z() } else { Vector3::unit_y() };
+    Matrix4::look_at_dir(Point3::origin(), -to_light, up)
+}
+
+/// Orthographic light projection around the sphere bounding `corners`, and
+/// the world size of its texels.
+///
+/// The box is as large whichever way the camera turns, and moves by whole
+/// texels only: the texels stay put on the ground as the camera moves, and
+/// the shadow edges don't shimmer. It reaches `caster_distance` further
+/// toward the light, for what casts shadows from outside of the slice.
+pub fn fit_cascade(corners: &[Point3<f32>; 8], to_light: Vector3<f32>, caster_distance: f32, resolution: i32) -> (Matrix4<f32>, f32) {
+    let center = Point3::centroid(corners);
+    let radius = corners.iter().map(|corner| (corner - center).magnitude()).fold(0.0, f32::max);
+    // rounded up, the rounding of the corners would make it wobble
+    let radius = (radius * 16.0).ceil() / 16.0;
+    let texel_size = 2.0 * radius / resolution as f32;
+
+    let rotation = light_rotation(to_light.normalize());
+    let center = rotation.transform_point(center);
+    let x = (center.x / texel_size).floor() * texel_size;
+    let y = (center.y / texel_size).floor() * texel_size;
+    // the light looks down -z
+    let near = -(center.z + radius + caster_distance);
+    let far = -(center.z - radius);
+    let projection = cgmath::ortho(x - radius, x + radius, y - radius, y + radius, near, far);
+    (projection * rotation, texel_size)
+}
+
+/// Splits the frustum and fits a cascade to every slice.
+pub fn fit_cascades(frustum: &Frustum, lambda: f32, to_light: Vector3<f32>, caster_distance: f32, resolution: i32) -> Vec<Cascade> {
+    let splits = split_distances(frustum.near, frustum.far, CASCADE_COUNT, lambda);
+    splits
+        .windows(2)
+        .map(|slice| {
+            let corners = slice_corners(frustum, slice[0], slice[1]);
+            let (view_projection, texel_size) = fit_cascade(&corners, to_light, caster_distance, resolution);
+            Cascade {
+                far: slice[1],
+                view_projection,
+                texel_size,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{Deg, Vector4};
+
+    fn frustum(eye: Point3<f32>) -> Frustum {
+        Frustum {
+            view: Matrix4::look_at(eye, Point3::new(20.0, 0.0, -30.0), Vector3::unit_y()),
+            fovy: Rad::from(Deg(45.0)),
+            aspect: 16.0 / 9.0,
+            near: 0.5,
+            far: 200.0,
+        }
+    }
+
+    fn sun() -> Vector3<f32> {
+        Vector3::new(0.4, 0.8, 0.3).normalize()
+    }
+
+    fn clip(view_projection: Matrix4<f32>, point: Point3<f32>) -> Vector3<f32> {
+        let clip = view_projection * Vector4::new(point.x, point.y, point.z, 1.0);
+        clip.truncate() / clip.w
+    }
+
+    #[test]
+    fn splits_go_from_near_to_far() {
+        for &lambda in &[0.0, 0.5, 0.75, 1.0] {
+            let splits = split_distances(0.5, 200.0, 4, lambda);
+            assert_eq!(splits.len(), 5);
+            assert!((splits[0] - 0.5).abs() < 1e-4);
+            assert!((splits[4] - 200.0).abs() < 1e-2);
+            assert!(splits.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", splits);
+        }
+    }
+
+    #[test]
+    fn uniform_and_logarithmic_splits() {
+        let uniform = split_distances(1.0, 9.0, 2, 0.0);
+        assert!((uniform[1] - 5.0).abs() < 1e-5);
+        // every slice is as many times deeper than the previous one
+        let logarithmic = split_distances(1.0, 9.0, 2, 1.0);
+        assert!((logarithmic[1] - 3.0).abs() < 1e-5);
+        // the practical split is in between
+        let practical = split_distances(1.0, 9.0, 2, 0.5);
+        assert!((practical[1] - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn slice_corners_are_at_their_depths() {
+        let frustum = frustum(Point3::new(0.0, 10.0, 0.0));
+        let corners = slice_corners(&frustum, 2.0, 50.0);
+        for (i, corner) in corners.iter().enumerate() {
+            let depth = -frustum.view.transform_point(*corner).z;
+            let expected = if i < 4 { 2.0 } else { 50.0 };
+            assert!((depth - expected).abs() < 1e-3, "corner {} at {}", i, depth);
+        }
+    }
+
+    #[test]
+    fn cascades_cover_their_slice() {
+        let frustum = frustum(Point3::new(0.0, 10.0, 0.0));
+        let splits = split_distances(frustum.near, frustum.far, CASCADE_COUNT, 0.75);
+        let cascades = fit_cascades(&frustum, 0.75, sun(), 50.0, 1024);
+        assert_eq!(cascades.len(), CASCADE_COUNT);
+        for (i, cascade) in cascades.iter().enumerate() {
+            assert!((cascade.far - splits[i + 1]).abs() < 1e-4);
+            for corner in slice_corners(&frustum, splits[i], splits[i + 1]).iter() {
+                let ndc = clip(cascade.view_projection, *corner);
+                assert!(ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && ndc.z.abs() <= 1.0, "{:?}", ndc);
+            }
+        }
+        // the nearer slices get the finer texels
+        assert!(cascades.windows(2).all(|pair| pair[0].texel_size < pair[1].texel_size));
+    }
+
+    #[test]
+    fn casters_toward_the_light_are_in_the_box() {
+        let frustum = frustum(Point3::new(0.0, 10.0, 0.0));
+        let corners = slice_corners(&frustum, 0.5, 20.0);
+        let (view_projection, _) = fit_cascade(&corners, sun(), 50.0, 1024);
+        let caster = Point3::centroid(&corners) + sun() * 40.0;
+        assert!(clip(view_projection, caster).z.abs() <= 1.0);
+    }
+
+    #[test]
+    fn texels_stay_put_as_the_camera_moves() {
+        let resolution = 1024;
+        // position of a fixed world point in the texels of the map
+        let texel_of = |eye: Point3<f32>| {
+            let corners = slice_corners(&frustum(eye), 0.5, 20.0);
+            let (view_projection, texel_size) = fit_cascade(&corners, sun(), 50.0, resolution);
+            let ndc = clip(view_projection, Point3::new(3.0, 0.0, -7.0));
+            ((ndc.x + 1.0) / 2.0 * resolution as f32, texel_size)
+        };
+        let (start, size) = texel_of(Point3::new(0.0, 10.0, 0.0));
+        for step in 1..10 {
+            let (texel, moved_size) = texel_of(Point3::new(step as f32 * 0.037, 10.0, step as f32 * 0.021));
+            assert_eq!(size, moved_size);
+            // only whole texels apart
+            let shift = texel - start;
+            assert!((shift - shift.round()).abs() < 0.05, "shifted {} texels", shift);
+        }
+    }
+}
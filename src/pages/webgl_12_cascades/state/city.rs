@@ -0,0 +1,71 @@
+use cgmath::{Matrix4, Vector3};
+
+/// blocks along each side of the city
+const BLOCKS: i32 = 16;
+/// side of a block, street included
+const BLOCK_SIZE: f32 = 12.0;
+const STREET_WIDTH: f32 = 3.0;
+const MIN_HEIGHT: f32 = 3.0;
+const MAX_HEIGHT: f32 = 40.0;
+/// the tallest building, the shadows reach this far toward the sun
+pub const CITY_HEIGHT: f32 = MAX_HEIGHT;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    Cube,
+    Plane,
+}
+
+pub struct SceneObject {
+    pub shape: Shape,
+    pub model: Matrix4<f32>,
+    pub color: [f32; 3],
+}
+
+/// Same pseudo random value in 0..1 for the same block and salt.
+fn noise(x: i32, z: i32, salt: u32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x27d4_eb2d) ^ (z as u32).wrapping_mul(0x1656_67b1) ^ salt.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    (h & 0xffff) as f32 / 65536.0
+}
+
+/// A ground and a grid of blocks of one to four buildings, taller toward
+/// the center: far more than one shadow map could cover sharply.
+pub fn city() -> Vec<SceneObject> {
+    let half_city = BLOCKS as f32 * BLOCK_SIZE / 2.0;
+    let mut objects = vec![SceneObject {
+        shape: Shape::Plane,
+        model: Matrix4::from_scale(half_city + BLOCK_SIZE * 2.0),
+        color: [0.45, 0.47, 0.45],
+    }];
+
+    let lot = (BLOCK_SIZE - STREET_WIDTH) / 2.0;
+    for bx in 0..BLOCKS {
+        for bz in 0..BLOCKS {
+            let block_x = -half_city + (bx as f32 + 0.5) * BLOCK_SIZE;
+            let block_z = -half_city + (bz as f32 + 0.5) * BLOCK_SIZE;
+            let from_center = (block_x.hypot(block_z) / half_city).min(1.0);
+            // one tower on the whole block, or a building on each quarter
+            let lots: &[(f32, f32, f32)] = if noise(bx, bz, 0) < 0.3 {
+                &[(0.0, 0.0, 2.0)]
+            } else {
+                &[(-0.5, -0.5, 1.0), (0.5, -0.5, 1.0), (-0.5, 0.5, 1.0), (0.5, 0.5, 1.0)]
+            };
+            for (i, &(dx, dz, size)) in lots.iter().enumerate() {
+                let salt = i as u32 + 1;
+                let height = MIN_HEIGHT + (MAX_HEIGHT - MIN_HEIGHT) * noise(bx, bz, salt).powi(2) * (1.0 - from_center * 0.8);
+                let half_width = lot * size / 2.0 * (0.7 + 0.25 * noise(bx, bz, salt + 10));
+                let center = Vector3::new(block_x + dx * lot, height / 2.0, block_z + dz * lot);
+                let gray = 0.55 + 0.35 * noise(bx, bz, salt + 20);
+                objects.push(SceneObject {
+                    shape: Shape::Cube,
+                    model: Matrix4::from_translation(center) * Matrix4::from_nonuniform_scale(half_width, height / 2.0, half_width),
+                    color: [gray, gray * 0.95, gray * 0.88],
+                });
+            }
+        }
+    }
+    objects
+}
@@ -0,0 +1,157 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+pub use cascade_settings::{CascadeSettings, MAX_SHADOW_DISTANCE, MIN_SHADOW_DISTANCE};
+use cascades::fit_cascades;
+pub use cascades::{Cascade, CASCADE_COUNT};
+use cgmath::{Point2, Vector2};
+use city::{city, CITY_HEIGHT};
+pub use city::{SceneObject, Shape};
+use clear_color::ClearColor;
+use sun::Sun;
+use viewport::Viewport;
+
+mod camera;
+mod cascade_settings;
+mod cascades;
+mod city;
+mod clear_color;
+mod sun;
+mod viewport;
+
+/// longest step of the animation, a frame after the tab was hidden would jump
+const MAX_TICK_SECONDS: f64 = 0.1;
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    objects: Vec<SceneObject>,
+    sun: Sun,
+    cascades: CascadeSettings,
+    /// where the held keys move the camera focus
+    movement: Vector2<f32>,
+    /// time of the previous tick, in milliseconds
+    last_tick: Option<f64>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::new(0.6, 0.72, 0.85, 1.0);
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            objects: city(),
+            sun: Sun::new(),
+            cascades: CascadeSettings::default(),
+            movement: Vector2::new(0.0, 0.0),
+            last_tick: None,
+        }
+    }
+
+    pub fn viewport(&self) -> &Viewport {
+        &self.viewport
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn objects(&self) -> &[SceneObject] {
+        &self.objects
+    }
+
+    pub fn sun(&self) -> &Sun {
+        &self.sun
+    }
+
+    pub fn settings(&self) -> &CascadeSettings {
+        &self.cascades
+    }
+
+    pub fn aspect(&self) -> f32 {
+        (self.viewport.width() / self.viewport.height().max(1.0)) as f32
+    }
+
+    /// Slices of the view up to the shadow distance, each with its light
+    /// projection. The shadow pass and the lit pass both need them.
+    pub fn cascades(&self) -> Vec<Cascade> {
+        let settings = &self.cascades;
+        let frustum = self.camera.frustum(self.aspect(), settings.shadow_distance);
+        // anything between the slice and the sun could cast a shadow in it
+        let caster_distance = CITY_HEIGHT / self.sun.direction().y;
+        fit_cascades(&frustum, settings.lambda, self.sun.direction(), caster_distance, settings.resolution())
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer) => self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time),
+            Msg::PointerMove(id, x, y) => match self.gestures.moved(id, Point2::new(x, y)) {
+                Some(Gesture::Drag { from, to, .. }) => {
+                    self.camera.orbit_left_right(from.x - to.x);
+                    self.camera.orbit_up_down(to.y - from.y);
+                }
+                Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                _ => {}
+            },
+            Msg::PointerUp(id, time) => {
+                self.gestures.up(id, time);
+            }
+            Msg::PointerCancel(id) => self.gestures.cancel(id),
+            Msg::ReleaseInput => self.gestures.clear(),
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::Movement(movement) => self.movement = movement,
+            Msg::Tick(now) => {
+                let seconds = self.last_tick.map_or(0.0, |last| ((now - last) / 1000.0).clamp(0.0, MAX_TICK_SECONDS));
+                self.last_tick = Some(now);
+                self.sun.update(seconds as f32);
+                self.camera.pan(self.movement, seconds as f32);
+            }
+            Msg::ToggleSunAnimation => self.sun.toggle_animation(),
+            Msg::ToggleCascadeColors => self.cascades.debug_colors = !self.cascades.debug_colors,
+            Msg::ToggleCascadeBlend => self.cascades.blend = !self.cascades.blend,
+            Msg::NextPcfRadius => self.cascades.next_pcf_radius(),
+            Msg::NextShadowResolution => self.cascades.next_resolution(),
+            Msg::SplitLambdaChange(lambda) => self.cascades.set_lambda(lambda),
+            Msg::ShadowDistanceChange(distance) => self.cascades.set_shadow_distance(distance),
+        }
+    }
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    /// held keys, x to the right and y forward, each in -1..1
+    Movement(Vector2<f32>),
+    /// a frame at `performance.now()`-like milliseconds
+    Tick(f64),
+    ToggleSunAnimation,
+    ToggleCascadeColors,
+    ToggleCascadeBlend,
+    NextPcfRadius,
+    NextShadowResolution,
+    SplitLambdaChange(f32),
+    ShadowDistanceChange(f32),
+}
@@ -0,0 +1,45 @@
+use cgmath::{Deg, Rad, Vector3};
+
+/// height of the sun above the horizon, low for long shadows
+const SUN_ELEVATION: Deg<f32> = Deg(35.0);
+/// radians per second of the sun around the city
+const ORBIT_SPEED: f32 = 0.05;
+
+/// The directional light, turning slowly around the vertical axis.
+pub struct Sun {
+    angle: Rad<f32>,
+    animated: bool,
+}
+
+impl Sun {
+    pub fn new() -> Self {
+        Self {
+            angle: Rad(0.6),
+            animated: true,
+        }
+    }
+
+    pub fn animated(&self) -> bool {
+        self.animated
+    }
+
+    pub fn toggle_animation(&mut self) {
+        self.animated = !self.animated;
+    }
+
+    pub fn update(&mut self, seconds: f32) {
+        if self.animated {
+            self.angle += Rad(seconds * ORBIT_SPEED);
+        }
+    }
+
+    /// World direction toward the sun.
+    pub fn direction(&self) -> Vector3<f32> {
+        let elevation = Rad::from(SUN_ELEVATION).0;
+        Vector3::new(
+            self.angle.0.cos() * elevation.cos(),
+            elevation.sin(),
+            self.angle.0.sin() * elevation.cos(),
+        )
+    }
+}
@@ -0,0 +1,283 @@
+use cgmath::{Deg, Matrix3, Matrix4, Point3, Vector3};
+use std::mem::size_of;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::state::{HdrImage, PREFILTERED_LEVELS};
+use super::Shader;
+use super::State;
+
+/// side of the faces of the environment cube map at level 0
+const ENVIRONMENT_SIZE: i32 = 512;
+/// the irradiance changes slowly with the normal, a few texels are enough
+const IRRADIANCE_SIZE: i32 = 32;
+/// side of level 0 of the prefiltered cube map, the mirror-like reflections
+const PREFILTERED_SIZE: i32 = 128;
+const BRDF_LUT_SIZE: i32 = 256;
+
+/// Texture units of the environment lighting in the metallic-roughness shader.
+const IRRADIANCE_UNIT: u32 = 0;
+const PREFILTERED_UNIT: u32 = 1;
+const BRDF_LUT_UNIT: u32 = 2;
+
+/// `aVertexPosition`
+const POSITION_LOCATION: u32 = 0;
+
+/// Where every face looks and its up, in the order of
+/// `TEXTURE_CUBE_MAP_POSITIVE_X + face`. Cube maps are laid out as seen
+/// from outside of the cube, hence the ups mostly pointing down.
+const FACES: [(Vector3<f32>, Vector3<f32>); 6] = [
+    (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+    (Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+    (Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, -1.0, 0.0)),
+    (Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, -1.0, 0.0)),
+];
+
+/// The light coming from every direction, and what the metallic-roughness
+/// shader needs of it precomputed: the irradiance for the diffuse part, the
+/// prefiltered radiance and the BRDF lookup table for the specular one.
+pub struct Environment {
+    equirectangular_shader: Shader,
+    irradiance_shader: Shader,
+    prefilter_shader: Shader,
+    skybox_shader: Shader,
+    framebuffer: WebGlFramebuffer,
+    cube_vao: Option<WebGlVertexArrayObject>,
+    environment: WebGlTexture,
+    irradiance: WebGlTexture,
+    prefiltered: WebGlTexture,
+    brdf_lut: WebGlTexture,
+}
+
+impl Environment {
+    /// Allocates the cube maps and computes the BRDF lookup table, which
+    /// depends on no environment. Call `load` to fill the rest.
+    pub fn new(gl: &GL) -> Result<Self, JsValue> {
+        // half floats are filterable in WebGL 2, but only renderable with one of these
+        if gl.get_extension("EXT_color_buffer_float")?.is_none() && gl.get_extension("EXT_color_buffer_half_float")?.is_none() {
+            return Err("rendering to floating point textures is not supported".into());
+        }
+        let framebuffer = gl.create_framebuffer().ok_or("create framebuffer error")?;
+        let levels = 32 - (ENVIRONMENT_SIZE as u32).leading_zeros() as i32;
+        let environment = create_cube_map(gl, ENVIRONMENT_SIZE, levels)?;
+        let irradiance = create_cube_map(gl, IRRADIANCE_SIZE, 1)?;
+        let prefiltered = create_cube_map(gl, PREFILTERED_SIZE, PREFILTERED_LEVELS as i32)?;
+
+        let brdf_lut = gl.create_texture().ok_or("create texture error")?;
+        gl.bind_texture(GL::TEXTURE_2D, Some(&brdf_lut));
+        gl.tex_storage_2d(GL::TEXTURE_2D, 1, GL::RG16F, BRDF_LUT_SIZE, BRDF_LUT_SIZE);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+        gl.bind_texture(GL::TEXTURE_2D, None);
+
+        let environment = Self {
+            equirectangular_shader: Shader::equirectangular_shader(gl)?,
+            irradiance_shader: Shader::irradiance_shader(gl)?,
+            prefilter_shader: Shader::prefilter_shader(gl)?,
+            skybox_shader: Shader::skybox_shader(gl)?,
+            framebuffer,
+            cube_vao: create_cube_vao(gl),
+            environment,
+            irradiance,
+            prefiltered,
+            brdf_lut,
+        };
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&environment.framebuffer));
+        gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, GL::TEXTURE_2D, Some(&environment.brdf_lut), 0);
+        let status = gl.check_framebuffer_status(GL::FRAMEBUFFER);
+        if status != GL::FRAMEBUFFER_COMPLETE {
+            gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+            return Err(format!("environment framebuffer incomplete: {:#x}", status).into());
+        }
+        let brdf_shader = Shader::brdf_shader(gl)?;
+        gl.use_program(Some(&brdf_shader.program));
+        gl.viewport(0, 0, BRDF_LUT_SIZE, BRDF_LUT_SIZE);
+        // no attributes, the vertex shader places the corners by their ids
+        gl.bind_vertex_array(None);
+        gl.draw_arrays(GL::TRIANGLES, 0, 3);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        gl.delete_program(Some(&brdf_shader.program));
+        Ok(environment)
+    }
+
+    /// Makes the cube map of the equirectangular `image`, then convolves it
+    /// into the irradiance and the prefiltered cube maps.
+    pub fn load(&self, gl: &GL, image: &HdrImage) -> Result<(), JsValue> {
+        let max_size = gl.get_parameter(GL::MAX_TEXTURE_SIZE)?.as_f64().unwrap_or(0.0) as usize;
+        if image.width > max_size || image.height > max_size {
+            return Err(format!("{}x{} is larger than the largest texture, {}", image.width, image.height, max_size).into());
+        }
+        let source = gl.create_texture().ok_or("create texture error")?;
+        gl.active_texture(GL::TEXTURE0);
+        // the cube maps are drawn into, none of them may be bound for sampling
+        gl.bind_texture(GL::TEXTURE_CUBE_MAP, None);
+        gl.bind_texture(GL::TEXTURE_2D, Some(&source));
+        // stored as half floats, which unlike floats are filterable
+        gl.tex_storage_2d(GL::TEXTURE_2D, 1, GL::RGB16F, image.width as i32, image.height as i32);
+        unsafe {
+            let data_array = js_sys::Float32Array::view(&image.pixels);
+            gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                GL::TEXTURE_2D,
+                0,
+                0,
+                0,
+                image.width as i32,
+                image.height as i32,
+                GL::RGB,
+                GL::FLOAT,
+                Some(&data_array),
+            )?;
+        }
+        // around the longitudes, but not over the poles
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::REPEAT as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::LINEAR as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+
+        gl.bind_framebuffer(GL::FRAMEBUFFER, Some(&self.framebuffer));
+        gl.bind_vertex_array(self.cube_vao.as_ref());
+
+        let shader = &self.equirectangular_shader;
+        gl.use_program(Some(&shader.program));
+        let location = shader.get_uniform_location(gl, "uEquirectangular");
+        gl.uniform1i(location.as_ref(), 0);
+        self.render_faces(gl, shader, &self.environment, ENVIRONMENT_SIZE, 0);
+        gl.delete_texture(Some(&source));
+        // the convolutions read the coarse levels where their samples are sparse
+        gl.bind_texture(GL::TEXTURE_CUBE_MAP, Some(&self.environment));
+        gl.generate_mipmap(GL::TEXTURE_CUBE_MAP);
+
+        let shader = &self.irradiance_shader;
+        gl.use_program(Some(&shader.program));
+        let location = shader.get_uniform_location(gl, "uEnvironment");
+        gl.uniform1i(location.as_ref(), 0);
+        self.render_faces(gl, shader, &self.irradiance, IRRADIANCE_SIZE, 0);
+
+        let shader = &self.prefilter_shader;
+        gl.use_program(Some(&shader.program));
+        let location = shader.get_uniform_location(gl, "uEnvironment");
+        gl.uniform1i(location.as_ref(), 0);
+        let location = shader.get_uniform_location(gl, "uEnvironmentSize");
+        gl.uniform1f(location.as_ref(), ENVIRONMENT_SIZE as f32);
+        for level in 0..PREFILTERED_LEVELS {
+            let location = shader.get_uniform_location(gl, "uRoughness");
+            gl.uniform1f(location.as_ref(), level as f32 / (PREFILTERED_LEVELS - 1) as f32);
+            self.render_faces(gl, shader, &self.prefiltered, PREFILTERED_SIZE >> level, level as i32);
+        }
+
+        gl.bind_texture(GL::TEXTURE_CUBE_MAP, None);
+        gl.bind_vertex_array(None);
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        Ok(())
+    }
+
+    /// Draws the six faces of level `level` of `target`, `size` texels wide,
+    /// with the cube VAO bound and `shader` in use.
+    fn render_faces(&self, gl: &GL, shader: &Shader, target: &WebGlTexture, size: i32, level: i32) {
+        gl.viewport(0, 0, size, size);
+        let projection = cgmath::perspective(Deg(90.0), 1.0, 0.1, 10.0);
+        for (face, (forward, up)) in FACES.iter().enumerate() {
+            let face_target = GL::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32;
+            gl.framebuffer_texture_2d(GL::FRAMEBUFFER, GL::COLOR_ATTACHMENT0, face_target, Some(target), level);
+            let view_projection = projection * Matrix4::look_at_dir(Point3::new(0.0, 0.0, 0.0), *forward, *up);
+            set_matrix4(gl, shader, "uViewProjection", view_projection);
+            gl.draw_arrays(GL::TRIANGLES, 0, 36);
+        }
+    }
+
+    /// Binds the precomputed maps for `shader`, a metallic-roughness one.
+    pub fn bind(&self, gl: &GL, shader: &Shader) {
+        let maps = [
+            ("uIrradiance", IRRADIANCE_UNIT, GL::TEXTURE_CUBE_MAP, &self.irradiance),
+            ("uPrefiltered", PREFILTERED_UNIT, GL::TEXTURE_CUBE_MAP, &self.prefiltered),
+            ("uBrdfLut", BRDF_LUT_UNIT, GL::TEXTURE_2D, &self.brdf_lut),
+        ];
+        for (uniform_name, unit, target, texture) in maps.iter() {
+            gl.active_texture(GL::TEXTURE0 + unit);
+            gl.bind_texture(*target, Some(texture));
+            let location = shader.get_uniform_location(gl, uniform_name);
+            gl.uniform1i(location.as_ref(), *unit as i32);
+        }
+        gl.active_texture(GL::TEXTURE0);
+    }
+
+    /// Draws the environment where nothing else was, into the current viewport.
+    pub fn render_skybox(&self, gl: &GL, state: &State, aspect: f32) {
+        let shader = &self.skybox_shader;
+        gl.use_program(Some(&shader.program));
+        let camera = state.camera();
+        let view_matrix = camera.view_matrix();
+        let rotation = Matrix3::from_cols(view_matrix.x.truncate(), view_matrix.y.truncate(), view_matrix.z.truncate());
+        set_matrix4(gl, shader, "uViewRotation", Matrix4::from(rotation));
+        set_matrix4(gl, shader, "uProjectiveMatrix", camera.projection_matrix(aspect));
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_CUBE_MAP, Some(&self.environment));
+        let location = shader.get_uniform_location(gl, "uEnvironment");
+        gl.uniform1i(location.as_ref(), 0);
+
+        // the cube sits at the far plane, where the depth buffer was cleared to
+        gl.depth_func(GL::LEQUAL);
+        gl.bind_vertex_array(self.cube_vao.as_ref());
+        gl.draw_arrays(GL::TRIANGLES, 0, 36);
+        gl.bind_vertex_array(None);
+        gl.depth_func(GL::LESS);
+    }
+}
+
+fn create_cube_map(gl: &GL, size: i32, levels: i32) -> Result<WebGlTexture, JsValue> {
+    let cube = gl.create_texture().ok_or("create texture error")?;
+    gl.bind_texture(GL::TEXTURE_CUBE_MAP, Some(&cube));
+    gl.tex_storage_2d(GL::TEXTURE_CUBE_MAP, levels, GL::RGBA16F, size, size);
+    gl.tex_parameteri(GL::TEXTURE_CUBE_MAP, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+    gl.tex_parameteri(GL::TEXTURE_CUBE_MAP, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+    let min_filter = if levels > 1 { GL::LINEAR_MIPMAP_LINEAR } else { GL::LINEAR };
+    gl.tex_parameteri(GL::TEXTURE_CUBE_MAP, GL::TEXTURE_MIN_FILTER, min_filter as i32);
+    gl.tex_parameteri(GL::TEXTURE_CUBE_MAP, GL::TEXTURE_MAG_FILTER, GL::LINEAR as i32);
+    gl.bind_texture(GL::TEXTURE_CUBE_MAP, None);
+    Ok(cube)
+}
+
+/// The 36 corners of the triangles of a cube around the origin, seen from
+/// inside as well as from outside: nothing is culled.
+fn create_cube_vao(gl: &GL) -> Option<WebGlVertexArrayObject> {
+    let mut vertices = Vec::with_capacity(36 * 3);
+    for axis in 0..3 {
+        for &side in [-1.0f32, 1.0].iter() {
+            let corner = |u: f32, v: f32| {
+                let mut position = [0.0; 3];
+                position[axis] = side;
+                position[(axis + 1) % 3] = u;
+                position[(axis + 2) % 3] = v;
+                position
+            };
+            for &(u, v) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter() {
+                vertices.extend_from_slice(&corner(u, v));
+            }
+        }
+    }
+
+    let vao = gl.create_vertex_array();
+    gl.bind_vertex_array(vao.as_ref());
+    let vbo = gl.create_buffer();
+    gl.bind_buffer(GL::ARRAY_BUFFER, vbo.as_ref());
+    unsafe {
+        let data_array = js_sys::Float32Array::view(&vertices);
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+    }
+    gl.enable_vertex_attrib_array(POSITION_LOCATION);
+    gl.vertex_attrib_pointer_with_i32(POSITION_LOCATION, 3, GL::FLOAT, false, 3 * size_of::<f32>() as i32, 0);
+    gl.bind_vertex_array(None);
+    gl.bind_buffer(GL::ARRAY_BUFFER, None);
+    vao
+}
+
+fn set_matrix4(gl: &GL, shader: &Shader, uniform_name: &str, matrix: Matrix4<f32>) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    let array: &[f32; 16] = matrix.as_ref();
+    gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+}
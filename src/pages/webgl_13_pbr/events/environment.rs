@@ -0,0 +1,60 @@
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::FileReader;
+
+use super::super::state::{parse_rgbe, Msg};
+use super::super::App;
+
+/// Reads the picked `.hdr` file, and lights the spheres with it.
+pub fn attach_file_handler(app: Rc<App>) -> Result<(), JsValue> {
+    let app2 = Rc::clone(&app);
+    let handler = move |_event: web_sys::Event| {
+        let file = match app2.hud.environment_input().files().and_then(|files| files.get(0)) {
+            Some(file) => file,
+            None => return,
+        };
+        let reader = match FileReader::new() {
+            Ok(reader) => reader,
+            Err(e) => return log::warn!("no file reader: {:?}", e),
+        };
+        let app3 = Rc::clone(&app2);
+        let reader2 = reader.clone();
+        let name = file.name();
+        let onload = Closure::once_into_js(move || {
+            let bytes = match reader2.result() {
+                Ok(buffer) => js_sys::Uint8Array::new(&buffer).to_vec(),
+                Err(e) => return log::warn!("{} could not be read: {:?}", name, e),
+            };
+            load(&app3, name, &bytes);
+        });
+        reader.set_onload(Some(onload.unchecked_ref()));
+        if let Err(e) = reader.read_as_array_buffer(&file) {
+            log::warn!("{} could not be read: {:?}", file.name(), e);
+        }
+    };
+
+    let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(_)>);
+    app.hud
+        .environment_input()
+        .add_event_listener_with_callback("change", handler.as_ref().unchecked_ref())?;
+    handler.forget();
+
+    Ok(())
+}
+
+/// Decodes the file and redoes the environment maps, the previous ones stay
+/// when the file is not a Radiance one.
+fn load(app: &App, name: String, bytes: &[u8]) {
+    let result = parse_rgbe(bytes)
+        .map_err(JsValue::from)
+        .and_then(|image| app.environment.load(&app.gl, &image));
+    match result {
+        Ok(()) => app.state.borrow_mut().msg(Msg::EnvironmentLoaded(name)),
+        Err(e) => {
+            let message = e.as_string().unwrap_or_else(|| format!("{:?}", e));
+            let window = web_sys::window().unwrap();
+            let _ = window.alert_with_message(&format!("Invalid environment {}: {}", name, message));
+        }
+    }
+}
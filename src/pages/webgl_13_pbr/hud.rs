@@ -0,0 +1,199 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Element, HtmlElement, HtmlInputElement};
+
+use super::state::{State, GRID_COLUMNS, GRID_ROWS, MAX_LIGHTS, MIN_ROUGHNESS};
+
+/// The material of the selected sphere, and what the grid sweeps.
+pub struct Hud {
+    info: HtmlElement,
+    object_label: HtmlElement,
+    base_color_input: HtmlInputElement,
+    emissive_input: HtmlInputElement,
+    metallic: Slider,
+    roughness: Slider,
+    ambient_occlusion: Slider,
+    environment_input: HtmlInputElement,
+}
+
+/// A range input with its value shown next to it.
+pub struct Slider {
+    input: HtmlInputElement,
+    label: HtmlElement,
+}
+
+impl Slider {
+    fn new(document: &Document, parent: &Element, name: &str, min: f32, max: f32) -> Result<Self, JsValue> {
+        let row = document.create_element("div")?;
+        row.set_inner_html(&format!("{} ", name));
+        let input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        input.set_type("range");
+        input.set_min(&min.to_string());
+        input.set_max(&max.to_string());
+        input.set_step("0.01");
+        row.append_child(&input)?;
+        let label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+        row.append_child(&label)?;
+        parent.append_child(&row)?;
+        Ok(Self { input, label })
+    }
+
+    pub fn input(&self) -> &HtmlInputElement {
+        &self.input
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.input.value().parse().ok()
+    }
+
+    fn sync(&self, value: f32) {
+        if self.value() != Some(value) {
+            self.input.set_value(&value.to_string());
+        }
+        self.label.set_inner_html(&format!(" {:.2}", value));
+    }
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let panel = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = panel.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&panel)?;
+
+        let picker = document.create_element("div")?;
+        let object_label = document.create_element("span")?.dyn_into::<HtmlElement>()?;
+        picker.append_child(&object_label)?;
+        picker.append_with_str_1("base ")?;
+        let base_color_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        base_color_input.set_type("color");
+        picker.append_child(&base_color_input)?;
+        picker.append_with_str_1(" emissive ")?;
+        let emissive_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        emissive_input.set_type("color");
+        picker.append_child(&emissive_input)?;
+        panel.append_child(&picker)?;
+
+        let metallic = Slider::new(&document, &panel, "metallic ", 0.0, 1.0)?;
+        let roughness = Slider::new(&document, &panel, "roughness", MIN_ROUGHNESS, 1.0)?;
+        let ambient_occlusion = Slider::new(&document, &panel, "occlusion", 0.0, 1.0)?;
+
+        let loader = document.create_element("div")?;
+        loader.set_inner_html("environment ");
+        let environment_input = document.create_element("input")?.dyn_into::<HtmlInputElement>()?;
+        environment_input.set_type("file");
+        environment_input.set_accept(".hdr");
+        loader.append_child(&environment_input)?;
+        panel.append_child(&loader)?;
+
+        let info = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        panel.append_child(&info)?;
+
+        Ok(Self {
+            info,
+            object_label,
+            base_color_input,
+            emissive_input,
+            metallic,
+            roughness,
+            ambient_occlusion,
+            environment_input,
+        })
+    }
+
+    pub fn base_color_input(&self) -> &HtmlInputElement {
+        &self.base_color_input
+    }
+
+    /// The color entered in the base color picker, in sRGB.
+    pub fn base_color(&self) -> Option<[f32; 3]> {
+        from_hex(&self.base_color_input.value())
+    }
+
+    pub fn emissive_input(&self) -> &HtmlInputElement {
+        &self.emissive_input
+    }
+
+    pub fn emissive(&self) -> Option<[f32; 3]> {
+        from_hex(&self.emissive_input.value())
+    }
+
+    pub fn metallic(&self) -> &Slider {
+        &self.metallic
+    }
+
+    pub fn roughness(&self) -> &Slider {
+        &self.roughness
+    }
+
+    pub fn ambient_occlusion(&self) -> &Slider {
+        &self.ambient_occlusion
+    }
+
+    /// The `.hdr` file picker.
+    pub fn environment_input(&self) -> &HtmlInputElement {
+        &self.environment_input
+    }
+
+    /// Follows the state, which clicks and keys change too.
+    pub fn sync(&self, state: &State) {
+        let object = state.selected_object();
+        let (row, column) = (object / GRID_COLUMNS, object % GRID_COLUMNS);
+        self.object_label
+            .set_inner_html(&format!("sphere {} ({}, {}) ", object + 1, row + 1, column + 1));
+        let material = state.selected_material();
+        // the pickers round to 8 bits, compare what they would show
+        for (input, rgb) in [(&self.base_color_input, material.base_color), (&self.emissive_input, material.emissive)].iter() {
+            let hex = to_hex(*rgb);
+            if input.value() != hex {
+                input.set_value(&hex);
+            }
+        }
+        self.metallic.sync(material.metallic);
+        self.roughness.sync(material.roughness);
+        self.ambient_occlusion.sync(material.ambient_occlusion);
+
+        let lights: Vec<_> = state.lights().iter().map(|light| light.name()).collect();
+        let selected = match state.selected_light() {
+            Some(index) => format!(", {} {} selected", state.lights()[index].name(), index + 1),
+            None => String::new(),
+        };
+        let ambient = if state.environment_lighting() {
+            format!("lit by the {}", state.environment_name())
+        } else {
+            "ambient from the lights".to_string()
+        };
+        self.info.set_inner_html(&format!(
+            "{} rows from dielectric to metal, {} columns from smooth to rough<br>{} of {} lights: {}{}<br>{}<br>?: keys, drag a light to move it, click a sphere to select it",
+            GRID_ROWS,
+            GRID_COLUMNS,
+            lights.len(),
+            MAX_LIGHTS,
+            lights.join(", "),
+            selected,
+            ambient
+        ));
+    }
+}
+
+/// `#rrggbb` of a color of the color inputs.
+fn to_hex(rgb: [f32; 3]) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", channel(rgb[0]), channel(rgb[1]), channel(rgb[2]))
+}
+
+fn from_hex(hex: &str) -> Option<[f32; 3]> {
+    let channel = |i: usize| {
+        let value = hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok())?;
+        Some(f32::from(value) / 255.0)
+    };
+    Some([channel(1)?, channel(3)?, channel(5)?])
+}
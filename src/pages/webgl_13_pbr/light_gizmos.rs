@@ -0,0 +1,166 @@
+use cgmath::{InnerSpace, Matrix4, Point3, Vector3};
+use std::f32::consts::PI;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::state::{Light, Pane, LIGHT_GIZMO_PIXELS};
+use super::Shader;
+use super::State;
+
+/// segments of the billboard circles and of the spot cones
+const SEGMENTS: usize = 16;
+/// world length of the drawn spot cones
+const CONE_LENGTH: f32 = 1.0;
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.85, 0.1, 1.0];
+const SELECTED_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Lines of one light, drawn in its own color.
+struct GizmoPart {
+    color: [f32; 4],
+    first: i32,
+    count: i32,
+}
+
+/// Draws every light as a circle facing the camera, with rays for a point
+/// light, its cone for a spot and an arrow for a directional light. They are
+/// drawn over the scene so a light inside or behind the sphere can be grabbed.
+pub struct LightGizmos {
+    shader: Shader,
+    vao: Option<WebGlVertexArrayObject>,
+    vbo: Option<WebGlBuffer>,
+}
+
+impl LightGizmos {
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            vao: None,
+            vbo: None,
+        }
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        // location 0 in the gizmo shader
+        gl.enable_vertex_attrib_array(0);
+        gl.vertex_attrib_pointer_with_i32(0, 3, GL::FLOAT, false, 0, 0);
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        self.vbo = Some(vbo);
+    }
+
+    /// Draws the gizmos into `pane`, the current gl viewport.
+    pub fn render(&self, gl: &GL, state: &State, pane: &Pane) {
+        let (positions, parts) = build_lines(state, pane);
+        gl.use_program(Some(&self.shader.program));
+        let camera = state.camera();
+        self.set_uniform_location_matrix4(gl, "uViewMatrix", camera.view_matrix());
+        self.set_uniform_location_matrix4(gl, "uProjectiveMatrix", camera.projection_matrix(pane.aspect()));
+        let color = self.shader.get_uniform_location(gl, "uColor");
+
+        gl.bind_buffer(GL::ARRAY_BUFFER, self.vbo.as_ref());
+        unsafe {
+            let data_array = js_sys::Float32Array::view(&positions);
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::DYNAMIC_DRAW);
+        }
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+
+        gl.disable(GL::DEPTH_TEST);
+        gl.bind_vertex_array(self.vao.as_ref());
+        for part in &parts {
+            gl.uniform4fv_with_f32_array(color.as_ref(), &part.color);
+            gl.draw_arrays(GL::LINES, part.first, part.count);
+        }
+        gl.bind_vertex_array(None);
+        gl.enable(GL::DEPTH_TEST);
+    }
+
+    fn set_uniform_location_matrix4(&self, gl: &GL, uniform_name: &str, matrix: Matrix4<f32>) {
+        let location = self.shader.get_uniform_location(gl, uniform_name);
+        let array: &[f32; 16] = matrix.as_ref();
+        gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+    }
+}
+
+/// Line segments of every light, in world space, sized for `pane`.
+fn build_lines(state: &State, pane: &Pane) -> (Vec<f32>, Vec<GizmoPart>) {
+    let camera = state.camera();
+    let (right, up) = camera.right_up();
+    let mut positions: Vec<f32> = Vec::new();
+    let mut parts = Vec::new();
+    for (index, light) in state.lights().iter().enumerate() {
+        let first = (positions.len() / 3) as i32;
+        let mut line = |a: Point3<f32>, b: Point3<f32>| positions.extend_from_slice(&[a.x, a.y, a.z, b.x, b.y, b.z]);
+
+        let anchor = state.light_anchor(index);
+        let radius = LIGHT_GIZMO_PIXELS * camera.pixel_size_at(anchor, pane.height);
+        let billboard = circle(anchor, right, up, radius);
+        for i in 0..SEGMENTS {
+            line(billboard[i], billboard[(i + 1) % SEGMENTS]);
+        }
+        match *light {
+            Light::Point { .. } => {
+                // short rays around the circle, like a bulb
+                for i in 0..4 {
+                    let angle = (i as f32 + 0.5) * PI / 2.0;
+                    let ray = right * angle.cos() + up * angle.sin();
+                    line(anchor + ray * radius * 1.3, anchor + ray * radius * 1.9);
+                }
+            }
+            Light::Spot {
+                position, direction, outer, ..
+            } => {
+                // the outer cone, where the light ends
+                let (u, v) = across(direction);
+                let base = circle(position + direction * CONE_LENGTH, u, v, CONE_LENGTH * outer.0.tan());
+                for i in 0..SEGMENTS {
+                    line(base[i], base[(i + 1) % SEGMENTS]);
+                }
+                for i in (0..SEGMENTS).step_by(SEGMENTS / 4) {
+                    line(position, base[i]);
+                }
+            }
+            Light::Directional { direction, .. } => {
+                // an arrow along the light, toward the scene
+                let tip = anchor - direction * radius * 5.0;
+                line(anchor, tip);
+                let (u, _) = across(direction);
+                line(tip, tip + (direction + u * 0.5) * radius);
+                line(tip, tip + (direction - u * 0.5) * radius);
+            }
+        }
+
+        let color = if state.highlighted_light() == Some(index) {
+            HIGHLIGHT_COLOR
+        } else if state.selected_light() == Some(index) {
+            SELECTED_COLOR
+        } else {
+            // the diffuse color, brought to full brightness to stay visible
+            let diffuse = light.color().diffuse;
+            let max = diffuse.iter().cloned().fold(1e-3, f32::max);
+            [diffuse[0] / max, diffuse[1] / max, diffuse[2] / max, 1.0]
+        };
+        let count = (positions.len() / 3) as i32 - first;
+        parts.push(GizmoPart { color, first, count });
+    }
+    (positions, parts)
+}
+
+/// `SEGMENTS` points around `center`, in the plane of `u` and `v`.
+fn circle(center: Point3<f32>, u: Vector3<f32>, v: Vector3<f32>, radius: f32) -> Vec<Point3<f32>> {
+    (0..SEGMENTS)
+        .map(|i| {
+            let angle = i as f32 / SEGMENTS as f32 * 2.0 * PI;
+            center + (u * angle.cos() + v * angle.sin()) * radius
+        })
+        .collect()
+}
+
+/// Two unit vectors across `direction` and across each other.
+fn across(direction: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let helper = if direction.y.abs() < 0.9 { Vector3::unit_y() } else { Vector3::unit_x() };
+    let u = direction.cross(helper).normalize();
+    (u, direction.cross(u).normalize())
+}
@@ -0,0 +1,89 @@
+use std::f32::consts::PI;
+
+/// floats per vertex: position(3) + normal(3)
+pub const VERTEX_SIZE: usize = 6;
+
+pub struct Mesh {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// Sphere around the origin, `stacks` rings from pole to pole and
+    /// `slices` around the y axis. The seam column is duplicated.
+    pub fn uv_sphere(radius: f32, stacks: usize, slices: usize) -> Self {
+        let mut vertices = Vec::with_capacity((stacks + 1) * (slices + 1) * VERTEX_SIZE);
+        for i in 0..=stacks {
+            let (sin_theta, cos_theta) = (i as f32 / stacks as f32 * PI).sin_cos();
+            for j in 0..=slices {
+                let (sin_phi, cos_phi) = ((j % slices) as f32 / slices as f32 * 2.0 * PI).sin_cos();
+                // on a sphere around the origin the normal is the position direction
+                let normal = [sin_theta * cos_phi, cos_theta, sin_theta * sin_phi];
+                vertices.extend_from_slice(&[normal[0] * radius, normal[1] * radius, normal[2] * radius]);
+                vertices.extend_from_slice(&normal);
+            }
+        }
+
+        let columns = slices + 1;
+        let mut indices = Vec::with_capacity(stacks * slices * 6);
+        for i in 0..stacks {
+            for j in 0..slices {
+                let a = (i * columns + j) as u16;
+                let b = ((i + 1) * columns + j) as u16;
+                let c = ((i + 1) * columns + j + 1) as u16;
+                let d = (i * columns + j + 1) as u16;
+                indices.extend_from_slice(&[a, d, b, b, d, c]);
+            }
+        }
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_normals_are_unit_and_outward() {
+        let sphere = Mesh::uv_sphere(2.0, 8, 12);
+        assert_eq!(sphere.vertices().len(), 9 * 13 * VERTEX_SIZE);
+        assert_eq!(sphere.indices().len(), 8 * 12 * 6);
+        for v in sphere.vertices().chunks(VERTEX_SIZE) {
+            let length = (v[3] * v[3] + v[4] * v[4] + v[5] * v[5]).sqrt();
+            assert!((length - 1.0).abs() < 1e-5);
+            for k in 0..3 {
+                assert!((v[k] - v[k + 3] * 2.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn triangles_wind_counter_clockwise_from_outside() {
+        let sphere = Mesh::uv_sphere(1.0, 8, 12);
+        let position = |i: u16| {
+            let v = &sphere.vertices()[i as usize * VERTEX_SIZE..];
+            [v[0], v[1], v[2]]
+        };
+        for t in sphere.indices().chunks(3) {
+            let (a, b, c) = (position(t[0]), position(t[1]), position(t[2]));
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            // triangles at the poles are degenerate
+            let area = (cross[0].powi(2) + cross[1].powi(2) + cross[2].powi(2)).sqrt();
+            if area < 1e-6 {
+                continue;
+            }
+            let centroid = [a[0] + b[0] + c[0], a[1] + b[1] + c[1], a[2] + b[2] + c[2]];
+            assert!(cross[0] * centroid[0] + cross[1] * centroid[1] + cross[2] * centroid[2] > 0.0);
+        }
+    }
+}
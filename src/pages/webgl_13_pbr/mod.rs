@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use crate::input::{HelpOverlay, Input};
+use canvas::{create_canvas, create_webgl_context};
+use environment::Environment;
+use events::{attach_event_handlers, handle_input};
+use hud::Hud;
+use light_gizmos::LightGizmos;
+use shader::Shader;
+use spheres::Spheres;
+use state::{procedural_sky, sun_direction, Msg, State};
+
+mod canvas;
+mod environment;
+mod events;
+mod hud;
+mod light_gizmos;
+mod mesh;
+mod shader;
+mod spheres;
+mod state;
+
+/// width of the procedural sky, twice its height as every equirectangular image
+const SKY_WIDTH: usize = 512;
+
+pub fn run() -> Result<(), JsValue> {
+    let app = Rc::new(App::new());
+    attach_event_handlers(Rc::clone(&app))?;
+    Rc::clone(&app).render()?;
+
+    let f = Rc::new(RefCell::new(None));
+    let g = f.clone();
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+        app.render().unwrap();
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut()>));
+    request_animation_frame(g.borrow().as_ref().unwrap());
+    Ok(())
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut()>) {
+    window()
+        .unwrap()
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame` OK");
+}
+
+pub struct App {
+    canvas: HtmlCanvasElement,
+    gl: GL,
+    state: RefCell<State>,
+    input: RefCell<Input>,
+    help: HelpOverlay,
+    hud: Hud,
+    spheres: Spheres,
+    environment: Environment,
+    light_gizmos: LightGizmos,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let canvas = create_canvas().unwrap();
+        let gl = create_webgl_context(&canvas).unwrap();
+        let state = RefCell::new(State::new());
+        state.borrow_mut().msg(Msg::ViewportResize(canvas.width() as f64, canvas.height() as f64));
+        let mut spheres = Spheres::new(Shader::pbr_shader(&gl).expect("pbr shader error"));
+        spheres.prepare_for_render(&gl);
+        let environment = Environment::new(&gl).expect("environment error");
+        environment
+            .load(&gl, &procedural_sky(SKY_WIDTH, SKY_WIDTH / 2, sun_direction()))
+            .expect("procedural sky error");
+        let mut light_gizmos = LightGizmos::new(Shader::gizmo_shader(&gl).expect("gizmo shader error"));
+        light_gizmos.prepare_for_render(&gl);
+        let input = Input::new(events::bindings());
+        let help = HelpOverlay::new(input.bindings()).expect("help overlay error");
+        let hud = Hud::new().expect("hud error");
+        Self {
+            canvas,
+            gl,
+            state,
+            input: RefCell::new(input),
+            help,
+            hud,
+            spheres,
+            environment,
+            light_gizmos,
+        }
+    }
+
+    pub fn render(&self) -> Result<(), JsValue> {
+        handle_input(self);
+        let state = self.state.borrow();
+        let clear_color = state.clear_color();
+
+        self.gl.clear_color(clear_color.r, clear_color.g, clear_color.b, clear_color.a);
+        self.gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+
+        let pane = state.pane();
+        self.gl.viewport(pane.left as i32, 0, pane.width as i32, pane.height as i32);
+        self.spheres.render(&self.gl, &state, &self.environment, pane.aspect());
+        if state.environment_lighting() {
+            self.environment.render_skybox(&self.gl, &state, pane.aspect());
+        }
+        self.light_gizmos.render(&self.gl, &state, &pane);
+        self.hud.sync(&state);
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+#version 300 es
+precision highp float;
+
+// The second sum of the split sum: the scale and the bias of f0 in the
+// reflected light, for n·v along u and the roughness along v.
+
+#include "brdf.glsl"
+
+const uint SAMPLE_COUNT = 512u;
+
+in vec2 vUv;
+
+out vec4 fragColor;
+
+void main(void) {
+    float nDotV = max(vUv.x, 1e-3);
+    float roughness = vUv.y;
+    vec3 toEye = vec3(sqrt(1.0 - nDotV * nDotV), 0.0, nDotV);
+    vec3 normal = vec3(0.0, 0.0, 1.0);
+    // remapped for image based lighting, the lights use (r + 1)² / 8
+    float k = roughness * roughness / 2.0;
+
+    float scale = 0.0;
+    float bias = 0.0;
+    for (uint i = 0u; i < SAMPLE_COUNT; i++) {
+        vec3 halfway = importanceSampleGgx(hammersley(i, SAMPLE_COUNT), normal, roughness);
+        vec3 toLight = normalize(2.0 * dot(toEye, halfway) * halfway - toEye);
+        float nDotL = max(toLight.z, 0.0);
+        if (nDotL <= 0.0) {
+            continue;
+        }
+        float nDotH = max(halfway.z, 0.0);
+        float vDotH = max(dot(toEye, halfway), 0.0);
+        float g = geometrySchlickGgx(nDotV, k) * geometrySchlickGgx(nDotL, k);
+        // the GGX distribution cancels out with the pdf of the samples
+        float visibility = g * vDotH / (nDotH * nDotV);
+        float fresnel = pow(1.0 - vDotH, 5.0);
+        scale += (1.0 - fresnel) * visibility;
+        bias += fresnel * visibility;
+    }
+    fragColor = vec4(scale / float(SAMPLE_COUNT), bias / float(SAMPLE_COUNT), 0.0, 1.0);
+}
@@ -0,0 +1,47 @@
+// The microfacet terms and the GGX sampling, shared by the metallic-roughness
+// shader and the precomputations of the environment lighting.
+
+const float PI = 3.14159265359;
+
+// GGX, or Trowbridge-Reitz: density of the microfacets facing along the halfway vector
+float distributionGgx(float nDotH, float roughness) {
+    // the perceptual roughness is squared, the sweep then looks even
+    float alpha = roughness * roughness;
+    float alpha2 = alpha * alpha;
+    float d = nDotH * nDotH * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * d * d);
+}
+
+// Schlick's approximation of the Smith term for one direction
+float geometrySchlickGgx(float nDotX, float k) {
+    return nDotX / (nDotX * (1.0 - k) + k);
+}
+
+// the bits of i mirrored around the binary point, well spread in [0, 1)
+float radicalInverse(uint bits) {
+    bits = (bits << 16u) | (bits >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return float(bits) * 2.3283064365386963e-10;
+}
+
+// sample i of n, evenly covering the unit square
+vec2 hammersley(uint i, uint n) {
+    return vec2(float(i) / float(n), radicalInverse(i));
+}
+
+// a halfway vector around the normal, as likely as the GGX distribution has it
+vec3 importanceSampleGgx(vec2 xi, vec3 normal, float roughness) {
+    float alpha = roughness * roughness;
+    float phi = 2.0 * PI * xi.x;
+    float cosTheta = sqrt((1.0 - xi.y) / (1.0 + (alpha * alpha - 1.0) * xi.y));
+    float sinTheta = sqrt(1.0 - cosTheta * cosTheta);
+    vec3 halfway = vec3(cos(phi) * sinTheta, sin(phi) * sinTheta, cosTheta);
+
+    vec3 up = abs(normal.z) < 0.999 ? vec3(0.0, 0.0, 1.0) : vec3(1.0, 0.0, 0.0);
+    vec3 tangent = normalize(cross(up, normal));
+    vec3 bitangent = cross(normal, tangent);
+    return normalize(tangent * halfway.x + bitangent * halfway.y + normal * halfway.z);
+}
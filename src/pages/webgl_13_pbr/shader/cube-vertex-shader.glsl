@@ -0,0 +1,15 @@
+#version 300 es
+precision mediump float;
+
+// one face of a cube map, seen from the center of the unit cube
+uniform mat4 uViewProjection;
+
+layout(location = 0) in vec3 aVertexPosition;
+
+// the direction looked up or convolved, from the center
+out vec3 vDirection;
+
+void main(void) {
+    vDirection = aVertexPosition;
+    gl_Position = uViewProjection * vec4(aVertexPosition, 1.0);
+}
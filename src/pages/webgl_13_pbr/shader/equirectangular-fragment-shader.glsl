@@ -0,0 +1,20 @@
+#version 300 es
+precision highp float;
+
+// Resamples the equirectangular image into a face of the environment cube
+// map: longitude around the y axis, the zenith at the top of the image.
+
+const float PI = 3.14159265359;
+
+uniform sampler2D uEquirectangular;
+
+in vec3 vDirection;
+
+out vec4 fragColor;
+
+void main(void) {
+    vec3 direction = normalize(vDirection);
+    vec2 uv = vec2(atan(direction.z, direction.x) / (2.0 * PI) + 0.5, 0.5 - asin(clamp(direction.y, -1.0, 1.0)) / PI);
+    // no mipmaps, and no derivatives across the seam where u wraps around
+    fragColor = vec4(textureLod(uEquirectangular, uv, 0.0).rgb, 1.0);
+}
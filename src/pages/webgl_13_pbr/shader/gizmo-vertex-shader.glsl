@@ -0,0 +1,12 @@
+#version 300 es
+precision mediump float;
+
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+
+// world space, the gizmo lines are rebuilt every frame
+layout(location = 0) in vec3 aVertexPosition;
+
+void main(void) {
+    gl_Position = uProjectiveMatrix * uViewMatrix * vec4(aVertexPosition, 1.0);
+}
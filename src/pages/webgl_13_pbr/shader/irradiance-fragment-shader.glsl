@@ -0,0 +1,37 @@
+#version 300 es
+precision highp float;
+
+// The light a Lambertian surface facing vDirection receives from the whole
+// hemisphere, divided by PI so that the base color is all left to apply.
+
+const float PI = 3.14159265359;
+// in radians, between the samples in both angles
+const float SAMPLE_DELTA = 0.05;
+// the samples are far apart, a coarse mip level stands for what falls between
+const float SOURCE_LOD = 3.0;
+
+uniform samplerCube uEnvironment;
+
+in vec3 vDirection;
+
+out vec4 fragColor;
+
+void main(void) {
+    vec3 normal = normalize(vDirection);
+    vec3 up = abs(normal.y) < 0.999 ? vec3(0.0, 1.0, 0.0) : vec3(0.0, 0.0, 1.0);
+    vec3 right = normalize(cross(up, normal));
+    up = cross(normal, right);
+
+    vec3 irradiance = vec3(0.0);
+    float count = 0.0;
+    for (float phi = 0.0; phi < 2.0 * PI; phi += SAMPLE_DELTA) {
+        for (float theta = 0.0; theta < 0.5 * PI; theta += SAMPLE_DELTA) {
+            vec3 tangent = vec3(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            vec3 direction = tangent.x * right + tangent.y * up + tangent.z * normal;
+            // cosine for the incidence, sine for the smaller rings near the pole
+            irradiance += textureLod(uEnvironment, direction, SOURCE_LOD).rgb * cos(theta) * sin(theta);
+            count += 1.0;
+        }
+    }
+    fragColor = vec4(PI * irradiance / count, 1.0);
+}
@@ -0,0 +1,135 @@
+use log::debug;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::state::{MAX_LIGHTS, PREFILTERED_LEVELS};
+
+static BRDF_SRC: &str = include_str!("./brdf.glsl");
+static PBR_VERT_SHADER_SRC: &str = include_str!("./pbr-vertex-shader.glsl");
+static PBR_FRAG_SHADER_SRC: &str = include_str!("./pbr-fragment-shader.glsl");
+static GIZMO_VERT_SHADER_SRC: &str = include_str!("./gizmo-vertex-shader.glsl");
+static GIZMO_FRAG_SHADER_SRC: &str = include_str!("./gizmo-fragment-shader.glsl");
+static CUBE_VERT_SHADER_SRC: &str = include_str!("./cube-vertex-shader.glsl");
+static EQUIRECTANGULAR_FRAG_SHADER_SRC: &str = include_str!("./equirectangular-fragment-shader.glsl");
+static IRRADIANCE_FRAG_SHADER_SRC: &str = include_str!("./irradiance-fragment-shader.glsl");
+static PREFILTER_FRAG_SHADER_SRC: &str = include_str!("./prefilter-fragment-shader.glsl");
+static BRDF_VERT_SHADER_SRC: &str = include_str!("./brdf-vertex-shader.glsl");
+static BRDF_FRAG_SHADER_SRC: &str = include_str!("./brdf-fragment-shader.glsl");
+static SKYBOX_VERT_SHADER_SRC: &str = include_str!("./skybox-vertex-shader.glsl");
+static SKYBOX_FRAG_SHADER_SRC: &str = include_str!("./skybox-fragment-shader.glsl");
+
+/// GLSL has no includes, the shared BRDF code replaces this line
+const BRDF_INCLUDE: &str = "#include \"brdf.glsl\"";
+
+pub struct Shader {
+    pub program: WebGlProgram,
+    uniforms: RefCell<HashMap<String, WebGlUniformLocation>>,
+}
+
+macro_rules! try_debug {
+    ($target: expr, $e: expr) => {
+        match $e {
+            Ok(result) => result,
+            Err(msg) => {
+                debug!("{} error: {}", $target, msg);
+                return Err(msg.into());
+            }
+        }
+    };
+}
+
+impl Shader {
+    pub fn new(gl: &GL, vert_shader: &str, frag_shader: &str) -> Result<Shader, JsValue> {
+        let vert_shader = try_debug!("compile vert shader", compile_shader(gl, GL::VERTEX_SHADER, vert_shader));
+        let frag_shader = try_debug!("compile frag shader", compile_shader(gl, GL::FRAGMENT_SHADER, frag_shader));
+        let program = try_debug!("link program", link_program(gl, &vert_shader, &frag_shader));
+        let uniforms = RefCell::new(HashMap::new());
+        Ok(Self { program, uniforms })
+    }
+
+    /// Metallic-roughness, lit per fragment.
+    pub fn pbr_shader(gl: &GL) -> Result<Shader, JsValue> {
+        // GLSL arrays need a constant size, the one of the lights
+        let defines = format!(
+            "#version 300 es\n#define MAX_LIGHTS {}\n#define PREFILTERED_LEVELS {}\n",
+            MAX_LIGHTS, PREFILTERED_LEVELS
+        );
+        let frag_shader = with_brdf(PBR_FRAG_SHADER_SRC).replacen("#version 300 es\n", &defines, 1);
+        Self::new(gl, PBR_VERT_SHADER_SRC, &frag_shader)
+    }
+
+    /// Plain colored lines, for the light gizmos.
+    pub fn gizmo_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, GIZMO_VERT_SHADER_SRC, GIZMO_FRAG_SHADER_SRC)
+    }
+
+    /// The equirectangular image into a face of the environment cube map.
+    pub fn equirectangular_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, CUBE_VERT_SHADER_SRC, EQUIRECTANGULAR_FRAG_SHADER_SRC)
+    }
+
+    /// The environment convolved for diffuse lighting.
+    pub fn irradiance_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, CUBE_VERT_SHADER_SRC, IRRADIANCE_FRAG_SHADER_SRC)
+    }
+
+    /// The environment convolved with GGX, one roughness per mip level.
+    pub fn prefilter_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, CUBE_VERT_SHADER_SRC, &with_brdf(PREFILTER_FRAG_SHADER_SRC))
+    }
+
+    /// The BRDF lookup table, drawn once over the whole viewport.
+    pub fn brdf_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, BRDF_VERT_SHADER_SRC, &with_brdf(BRDF_FRAG_SHADER_SRC))
+    }
+
+    /// The environment cube map behind the scene.
+    pub fn skybox_shader(gl: &GL) -> Result<Shader, JsValue> {
+        Self::new(gl, SKYBOX_VERT_SHADER_SRC, SKYBOX_FRAG_SHADER_SRC)
+    }
+
+    pub fn get_uniform_location(&self, gl: &GL, uniform_name: &str) -> Option<WebGlUniformLocation> {
+        let mut uniforms = self.uniforms.borrow_mut();
+        if uniforms.get(uniform_name).is_none() {
+            uniforms.insert(
+                uniform_name.to_string(),
+                gl.get_uniform_location(&self.program, uniform_name)
+                    .unwrap_or_else(|| panic!(r#"Uniform '{}' not found"#, uniform_name)),
+            );
+        }
+        Some(uniforms.get(uniform_name).expect("loc").clone())
+    }
+}
+
+fn with_brdf(source: &str) -> String {
+    source.replace(BRDF_INCLUDE, BRDF_SRC)
+}
+
+fn compile_shader(gl: &GL, shader_type: u32, source: &str) -> Result<WebGlShader, String> {
+    let shader = gl.create_shader(shader_type).ok_or_else(|| "Could not create shader".to_string())?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl.get_shader_parameter(&shader, GL::COMPILE_STATUS).as_bool().unwrap_or(false) {
+        Ok(shader)
+    } else {
+        Err(gl
+            .get_shader_info_log(&shader)
+            .unwrap_or_else(|| "Unknown error creating shader".to_string()))
+    }
+}
+
+fn link_program(gl: &GL, vert_shader: &WebGlShader, frag_shader: &WebGlShader) -> Result<WebGlProgram, String> {
+    let program = gl.create_program().ok_or_else(|| "Unable to create shader program".to_string())?;
+    gl.attach_shader(&program, vert_shader);
+    gl.attach_shader(&program, frag_shader);
+    gl.link_program(&program);
+    if gl.get_program_parameter(&program, GL::LINK_STATUS).as_bool().unwrap_or(false) {
+        Ok(program)
+    } else {
+        Err(gl
+            .get_program_info_log(&program)
+            .unwrap_or_else(|| "Unknown error creating program".to_string()))
+    }
+}
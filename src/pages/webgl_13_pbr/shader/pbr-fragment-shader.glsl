@@ -0,0 +1,144 @@
+#version 300 es
+precision highp float;
+
+// Cook-Torrance microfacet specular with a Lambert diffuse, metallic-roughness
+// parameters. Everything is in eye space and in linear light, the colors are
+// decoded from sRGB on the Rust side and the result encoded back at the end.
+// MAX_LIGHTS and PREFILTERED_LEVELS are defined from the Rust side.
+
+#include "brdf.glsl"
+
+const int DIRECTIONAL_LIGHT = 0;
+const int POINT_LIGHT = 1;
+const int SPOT_LIGHT = 2;
+
+struct Light {
+    int kind;
+    vec3 position;
+    // toward the light for a directional light, along the cone for a spot
+    vec3 direction;
+    vec3 ambient;
+    // the color of the light, the specular one is for Blinn-Phong
+    vec3 diffuse;
+    vec3 specular;
+    // distance the light fades out at
+    float radius;
+    // cosines of the inner and outer half angles of a spot
+    vec2 cone;
+};
+
+uniform Light uLights[MAX_LIGHTS];
+uniform int uLightCount;
+
+uniform vec3 uBaseColor;
+uniform float uMetallic;
+uniform float uRoughness;
+uniform float uAmbientOcclusion;
+uniform vec3 uEmissive;
+
+// the surroundings instead of the ambient of the lights
+uniform bool uEnvironmentLighting;
+// cosine weighted over the hemisphere around the normal
+uniform samplerCube uIrradiance;
+// reflected by increasingly rough surfaces along the mip levels
+uniform samplerCube uPrefiltered;
+// scale and bias of f0, by n·v and roughness
+uniform sampler2D uBrdfLut;
+// the environment is in world space
+uniform mat3 uEyeToWorld;
+
+in vec3 vPosition;
+in vec3 vNormal;
+
+out vec4 fragColor;
+
+// inverse square, windowed to reach exactly 0 at the radius
+float attenuate(float radius, float distance) {
+    float ratio = distance / radius;
+    float falloff = clamp(1.0 - ratio * ratio * ratio * ratio, 0.0, 1.0);
+    return falloff * falloff / (distance * distance + 1.0);
+}
+
+// Smith: share of the microfacets neither hidden from the light nor from the eye
+float geometrySmith(float nDotV, float nDotL, float roughness) {
+    // remapped for lights, image based lighting uses alpha / 2
+    float r = roughness + 1.0;
+    float k = r * r / 8.0;
+    return geometrySchlickGgx(nDotV, k) * geometrySchlickGgx(nDotL, k);
+}
+
+// reflected share of the light, from f0 head on to all of it at grazing angles
+vec3 fresnelSchlick(float cosTheta, vec3 f0) {
+    return f0 + (1.0 - f0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
+// the rougher, the less a grazing view reflects, as the highlights average out
+vec3 fresnelSchlickRoughness(float cosTheta, vec3 f0, float roughness) {
+    return f0 + (max(vec3(1.0 - roughness), f0) - f0) * pow(clamp(1.0 - cosTheta, 0.0, 1.0), 5.0);
+}
+
+vec3 linearToSrgb(vec3 color) {
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), color));
+}
+
+void main(void) {
+    vec3 normal = normalize(vNormal);
+    vec3 toEye = normalize(-vPosition);
+    float nDotV = max(dot(normal, toEye), 1e-4);
+    // dielectrics reflect about 4% head on, whatever their color; metals their color
+    vec3 f0 = mix(vec3(0.04), uBaseColor, uMetallic);
+
+    vec3 ambient = vec3(0.0);
+    vec3 color = uEmissive;
+    for (int i = 0; i < MAX_LIGHTS; i++) {
+        if (i >= uLightCount) {
+            break;
+        }
+        Light light = uLights[i];
+        ambient += light.ambient;
+
+        vec3 toLight = normalize(light.direction);
+        float intensity = 1.0;
+        if (light.kind != DIRECTIONAL_LIGHT) {
+            vec3 offset = light.position - vPosition;
+            float distance = length(offset);
+            toLight = offset / distance;
+            intensity = attenuate(light.radius, distance);
+        }
+        if (light.kind == SPOT_LIGHT) {
+            float cosine = dot(-toLight, normalize(light.direction));
+            intensity *= smoothstep(light.cone.y, light.cone.x, cosine);
+        }
+        float nDotL = max(dot(normal, toLight), 0.0);
+        if (nDotL <= 0.0) {
+            continue;
+        }
+
+        vec3 halfway = normalize(toLight + toEye);
+        float nDotH = max(dot(normal, halfway), 0.0);
+        vec3 fresnel = fresnelSchlick(max(dot(halfway, toEye), 0.0), f0);
+        float d = distributionGgx(nDotH, uRoughness);
+        float g = geometrySmith(nDotV, nDotL, uRoughness);
+        vec3 specular = d * g * fresnel / (4.0 * nDotV * nDotL);
+        // what isn't reflected enters the surface and is diffused, metals absorb it
+        vec3 diffuse = (1.0 - fresnel) * (1.0 - uMetallic) * uBaseColor / PI;
+        // times PI: a white light facing a white diffuse surface lights it fully, as with Blinn-Phong
+        vec3 radiance = light.diffuse * intensity * PI;
+        color += (diffuse + specular) * radiance * nDotL;
+    }
+    if (uEnvironmentLighting) {
+        vec3 fresnel = fresnelSchlickRoughness(nDotV, f0, uRoughness);
+        vec3 irradiance = texture(uIrradiance, uEyeToWorld * normal).rgb;
+        vec3 diffuse = (1.0 - fresnel) * (1.0 - uMetallic) * irradiance * uBaseColor;
+        // split sum: the prefiltered light times the integral of the BRDF
+        vec3 reflected = uEyeToWorld * reflect(-toEye, normal);
+        vec3 prefiltered = textureLod(uPrefiltered, reflected, uRoughness * float(PREFILTERED_LEVELS - 1)).rgb;
+        vec2 brdf = texture(uBrdfLut, vec2(nDotV, uRoughness)).rg;
+        vec3 specular = prefiltered * (fresnel * brdf.x + brdf.y);
+        color += (diffuse + specular) * uAmbientOcclusion;
+    } else {
+        color += ambient * uBaseColor * uAmbientOcclusion;
+    }
+
+    fragColor = vec4(linearToSrgb(clamp(color, 0.0, 1.0)), 1.0);
+}
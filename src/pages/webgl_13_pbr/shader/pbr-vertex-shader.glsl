@@ -0,0 +1,23 @@
+#version 300 es
+precision highp float;
+
+uniform mat4 uModelMatrix;
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+// inverse transpose of the model view matrix, a normal is no position
+uniform mat3 uNormalMatrix;
+
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+
+// eye space, lit per fragment
+out vec3 vPosition;
+out vec3 vNormal;
+
+void main(void) {
+    vec4 position = uViewMatrix * uModelMatrix * vec4(aVertexPosition, 1.0);
+    vPosition = position.xyz;
+    vNormal = uNormalMatrix * aVertexNormal;
+
+    gl_Position = uProjectiveMatrix * position;
+}
@@ -0,0 +1,46 @@
+#version 300 es
+precision highp float;
+
+// The environment as reflected by a surface of roughness uRoughness, seen
+// head on: one level of the prefiltered mip chain. The split sum takes the
+// rest of the view dependence from the BRDF lookup table.
+
+#include "brdf.glsl"
+
+const uint SAMPLE_COUNT = 256u;
+
+uniform samplerCube uEnvironment;
+// side of the faces of the environment cube map at level 0
+uniform float uEnvironmentSize;
+uniform float uRoughness;
+
+in vec3 vDirection;
+
+out vec4 fragColor;
+
+void main(void) {
+    vec3 normal = normalize(vDirection);
+    vec3 toEye = normal;
+    // solid angle of a texel at level 0
+    float texelAngle = 4.0 * PI / (6.0 * uEnvironmentSize * uEnvironmentSize);
+
+    vec3 color = vec3(0.0);
+    float weight = 0.0;
+    for (uint i = 0u; i < SAMPLE_COUNT; i++) {
+        vec3 halfway = importanceSampleGgx(hammersley(i, SAMPLE_COUNT), normal, uRoughness);
+        vec3 toLight = normalize(2.0 * dot(toEye, halfway) * halfway - toEye);
+        float nDotL = dot(normal, toLight);
+        if (nDotL <= 0.0) {
+            continue;
+        }
+        // the rarer the sample, the wider the area it stands for, read from
+        // the level whose texels are about as wide: no bright speckles
+        float nDotH = max(dot(normal, halfway), 0.0);
+        float pdf = distributionGgx(nDotH, uRoughness) / 4.0 + 1e-4;
+        float sampleAngle = 1.0 / (float(SAMPLE_COUNT) * pdf);
+        float lod = uRoughness == 0.0 ? 0.0 : 0.5 * log2(sampleAngle / texelAngle);
+        color += textureLod(uEnvironment, toLight, lod).rgb * nDotL;
+        weight += nDotL;
+    }
+    fragColor = vec4(color / weight, 1.0);
+}
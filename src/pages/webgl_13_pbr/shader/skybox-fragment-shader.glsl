@@ -0,0 +1,17 @@
+#version 300 es
+precision highp float;
+
+uniform samplerCube uEnvironment;
+
+in vec3 vDirection;
+
+out vec4 fragColor;
+
+vec3 linearToSrgb(vec3 color) {
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), color));
+}
+
+void main(void) {
+    vec3 color = textureLod(uEnvironment, vDirection, 0.0).rgb;
+    fragColor = vec4(linearToSrgb(clamp(color, 0.0, 1.0)), 1.0);
+}
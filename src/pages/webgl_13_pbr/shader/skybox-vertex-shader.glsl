@@ -0,0 +1,17 @@
+#version 300 es
+precision mediump float;
+
+// the view without its translation: the environment is infinitely far
+uniform mat4 uViewRotation;
+uniform mat4 uProjectiveMatrix;
+
+layout(location = 0) in vec3 aVertexPosition;
+
+out vec3 vDirection;
+
+void main(void) {
+    vDirection = aVertexPosition;
+    vec4 position = uProjectiveMatrix * uViewRotation * vec4(aVertexPosition, 1.0);
+    // at the far plane, behind everything drawn before
+    gl_Position = position.xyww;
+}
@@ -0,0 +1,147 @@
+use cgmath::{EuclideanSpace, Matrix, Matrix3, Matrix4, Point3, Transform, Vector3};
+use std::mem::size_of;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::environment::Environment;
+use super::mesh::{Mesh, VERTEX_SIZE};
+use super::state::{srgb_to_linear_rgb, Light, PbrMaterial, MAX_LIGHTS, TESSELLATION};
+use super::Shader;
+use super::State;
+
+/// `aVertexPosition` and `aVertexNormal`
+const POSITION_LOCATION: u32 = 0;
+const NORMAL_LOCATION: u32 = 1;
+
+/// The grid of spheres, each with its own metallic-roughness material.
+pub struct Spheres {
+    shader: Shader,
+    index_count: i32,
+    vao: Option<WebGlVertexArrayObject>,
+}
+
+impl Spheres {
+    pub fn new(shader: Shader) -> Self {
+        Self {
+            shader,
+            index_count: 0,
+            vao: None,
+        }
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        let (stacks, slices) = TESSELLATION;
+        let mesh = Mesh::uv_sphere(1.0, stacks, slices);
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(mesh.vertices());
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        let stride = (VERTEX_SIZE * size_of::<f32>()) as i32;
+        gl.enable_vertex_attrib_array(POSITION_LOCATION);
+        gl.vertex_attrib_pointer_with_i32(POSITION_LOCATION, 3, GL::FLOAT, false, stride, 0);
+        gl.enable_vertex_attrib_array(NORMAL_LOCATION);
+        gl.vertex_attrib_pointer_with_i32(NORMAL_LOCATION, 3, GL::FLOAT, false, stride, 3 * size_of::<f32>() as i32);
+
+        let ebo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(mesh.indices());
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        self.index_count = mesh.indices().len() as i32;
+    }
+
+    /// Draws into the current viewport, of width / height `aspect`.
+    pub fn render(&self, gl: &GL, state: &State, environment: &Environment, aspect: f32) {
+        let shader = &self.shader;
+        gl.use_program(Some(&shader.program));
+
+        let camera = state.camera();
+        let view_matrix = camera.view_matrix();
+        set_matrix4(gl, shader, "uViewMatrix", view_matrix);
+        set_matrix4(gl, shader, "uProjectiveMatrix", camera.projection_matrix(aspect));
+
+        // the lights are given in world space, the shader lights in eye space
+        let lights = state.lights();
+        let location = shader.get_uniform_location(gl, "uLightCount");
+        gl.uniform1i(location.as_ref(), lights.len().min(MAX_LIGHTS) as i32);
+        for (i, light) in lights.iter().take(MAX_LIGHTS).enumerate() {
+            let uniform = |member: &str| format!("uLights[{}].{}", i, member);
+            let location = shader.get_uniform_location(gl, &uniform("kind"));
+            gl.uniform1i(location.as_ref(), light.kind());
+            let (position, direction) = match *light {
+                Light::Directional { direction, .. } => (Point3::origin(), direction),
+                Light::Point { position, .. } => (position, Vector3::unit_z()),
+                Light::Spot { position, direction, .. } => (position, direction),
+            };
+            set_vector3(gl, shader, &uniform("position"), view_matrix.transform_point(position).into());
+            set_vector3(gl, shader, &uniform("direction"), camera.to_eye_direction(direction).into());
+            // the light colors are picked like the materials, in sRGB
+            let color = light.color();
+            set_vector3(gl, shader, &uniform("ambient"), srgb_to_linear_rgb(color.ambient));
+            set_vector3(gl, shader, &uniform("diffuse"), srgb_to_linear_rgb(color.diffuse));
+            let radius = match *light {
+                Light::Point { attenuation, .. } | Light::Spot { attenuation, .. } => attenuation.to_uniform(),
+                // never read for a directional light
+                Light::Directional { .. } => 1.0,
+            };
+            let location = shader.get_uniform_location(gl, &uniform("radius"));
+            gl.uniform1f(location.as_ref(), radius);
+            let location = shader.get_uniform_location(gl, &uniform("cone"));
+            gl.uniform2fv_with_f32_array(location.as_ref(), &light.cone_cosines());
+        }
+
+        let location = shader.get_uniform_location(gl, "uEnvironmentLighting");
+        gl.uniform1i(location.as_ref(), state.environment_lighting() as i32);
+        // the normals and the reflections go back to world space, where the environment is
+        let eye_to_world = Matrix3::from_cols(view_matrix.x.truncate(), view_matrix.y.truncate(), view_matrix.z.truncate()).transpose();
+        let location = shader.get_uniform_location(gl, "uEyeToWorld");
+        let array: &[f32; 9] = eye_to_world.as_ref();
+        gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+        environment.bind(gl, shader);
+
+        gl.bind_vertex_array(self.vao.as_ref());
+        for object in state.objects() {
+            let model_matrix = object.model_matrix();
+            set_matrix4(gl, shader, "uModelMatrix", model_matrix);
+            let normal_matrix: Matrix3<f32> = camera.normal_matrix(model_matrix);
+            let location = shader.get_uniform_location(gl, "uNormalMatrix");
+            let array: &[f32; 9] = normal_matrix.as_ref();
+            gl.uniform_matrix3fv_with_f32_array(location.as_ref(), false, &array[..]);
+            apply_material(gl, shader, &object.material);
+            gl.draw_elements_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0);
+        }
+        gl.bind_vertex_array(None);
+    }
+}
+
+/// The material in linear light, as the shader mixes it.
+fn apply_material(gl: &GL, shader: &Shader, material: &PbrMaterial) {
+    set_vector3(gl, shader, "uBaseColor", material.linear_base_color());
+    set_vector3(gl, shader, "uEmissive", material.linear_emissive());
+    let location = shader.get_uniform_location(gl, "uMetallic");
+    gl.uniform1f(location.as_ref(), material.metallic);
+    let location = shader.get_uniform_location(gl, "uRoughness");
+    gl.uniform1f(location.as_ref(), material.roughness);
+    let location = shader.get_uniform_location(gl, "uAmbientOcclusion");
+    gl.uniform1f(location.as_ref(), material.ambient_occlusion);
+}
+
+fn set_matrix4(gl: &GL, shader: &Shader, uniform_name: &str, matrix: Matrix4<f32>) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    let array: &[f32; 16] = matrix.as_ref();
+    gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+}
+
+fn set_vector3(gl: &GL, shader: &Shader, uniform_name: &str, vector: [f32; 3]) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    gl.uniform3fv_with_f32_array(location.as_ref(), &vector);
+}
@@ -0,0 +1,52 @@
+/// An sRGB encoded channel, as color pickers and 8 bit images store it, in
+/// linear light where the lighting adds and multiplies.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Back to sRGB for the screen, the reference for `linearToSrgb` in the
+/// shader.
+#[cfg(test)]
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_to_linear_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    [srgb_to_linear(rgb[0]), srgb_to_linear(rgb[1]), srgb_to_linear(rgb[2])]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for i in 0..=255 {
+            let c = i as f32 / 255.0;
+            assert!((linear_to_srgb(srgb_to_linear(c)) - c).abs() < 1e-5, "{}", c);
+        }
+    }
+
+    #[test]
+    fn mid_gray_is_darker_in_linear_light() {
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        // half of the light is about 73% in sRGB
+        assert!((srgb_to_linear(0.5) - 0.214).abs() < 1e-3);
+        assert!((linear_to_srgb(0.5) - 0.735).abs() < 1e-3);
+    }
+
+    #[test]
+    fn both_pieces_meet() {
+        let (below, above) = (srgb_to_linear(0.04045), srgb_to_linear(0.040_46));
+        assert!((above - below).abs() < 1e-5);
+    }
+}
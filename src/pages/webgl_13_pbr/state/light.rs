@@ -0,0 +1,183 @@
+use cgmath::{InnerSpace, Point3, Rad, Vector3};
+
+/// most lights the shaders loop over
+pub const MAX_LIGHTS: usize = 8;
+
+/// Color of each term a light contributes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightColor {
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+}
+
+impl LightColor {
+    /// Diffuse and specular `rgb`, with a faint ambient of the same hue.
+    pub fn new(rgb: [f32; 3]) -> Self {
+        Self {
+            ambient: [rgb[0] * 0.1, rgb[1] * 0.1, rgb[2] * 0.1],
+            diffuse: rgb,
+            specular: rgb,
+        }
+    }
+}
+
+/// How the light of a point or spot light fades with the distance: unlike
+/// the coefficients of the Phong lesson, by the inverse square, as
+/// physically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Attenuation {
+    /// inverse square, smoothly brought down to exactly 0 at `radius`
+    Radius(f32),
+}
+
+impl Attenuation {
+    /// Factor of the light at `distance`, the reference for `attenuate` in
+    /// the shaders.
+    #[cfg(test)]
+    pub fn factor(&self, distance: f32) -> f32 {
+        match *self {
+            Attenuation::Radius(radius) => {
+                let falloff = (1.0 - (distance / radius).powi(4)).clamp(0.0, 1.0);
+                falloff * falloff / (distance * distance + 1.0)
+            }
+        }
+    }
+
+    /// The radius the shaders take.
+    pub fn to_uniform(self) -> f32 {
+        match self {
+            Attenuation::Radius(radius) => radius,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+    /// Far away, like the sun: the same direction everywhere and no falloff.
+    Directional {
+        /// world space, from the surface toward the light
+        direction: Vector3<f32>,
+        color: LightColor,
+    },
+    /// Shines all around from `position`.
+    Point {
+        position: Point3<f32>,
+        color: LightColor,
+        attenuation: Attenuation,
+    },
+    /// Shines from `position` within a cone around `direction`, fully inside
+    /// `inner` and fading out up to `outer`, both half angles.
+    Spot {
+        position: Point3<f32>,
+        /// world space, from the light along the cone axis
+        direction: Vector3<f32>,
+        color: LightColor,
+        attenuation: Attenuation,
+        inner: Rad<f32>,
+        outer: Rad<f32>,
+    },
+}
+
+impl Light {
+    /// Number the shaders switch on.
+    pub fn kind(&self) -> i32 {
+        match self {
+            Light::Directional { .. } => 0,
+            Light::Point { .. } => 1,
+            Light::Spot { .. } => 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Light::Directional { .. } => "directional",
+            Light::Point { .. } => "point",
+            Light::Spot { .. } => "spot",
+        }
+    }
+
+    pub fn color(&self) -> &LightColor {
+        match self {
+            Light::Directional { color, .. } | Light::Point { color, .. } | Light::Spot { color, .. } => color,
+        }
+    }
+
+    /// Where the light is in the scene, directional lights have no position
+    /// of their own.
+    pub fn position(&self) -> Option<Point3<f32>> {
+        match *self {
+            Light::Directional { .. } => None,
+            Light::Point { position, .. } | Light::Spot { position, .. } => Some(position),
+        }
+    }
+
+    /// Moves a point or spot light, a spot keeps aiming at `target`.
+    pub fn move_to(&mut self, to: Point3<f32>, target: Point3<f32>) {
+        match self {
+            Light::Directional { .. } => {}
+            Light::Point { position, .. } => *position = to,
+            Light::Spot { position, direction, .. } => {
+                *position = to;
+                if to != target {
+                    *direction = (target - to).normalize();
+                }
+            }
+        }
+    }
+
+    /// Points a directional light from `toward`, ignored by the other kinds.
+    pub fn set_direction(&mut self, toward: Vector3<f32>) {
+        if let Light::Directional { direction, .. } = self {
+            if toward.magnitude2() > 0.0 {
+                *direction = toward.normalize();
+            }
+        }
+    }
+
+    /// Cosines of the inner and outer half angles, a cone wider than a half
+    /// sphere for the lights lit all around.
+    pub fn cone_cosines(&self) -> [f32; 2] {
+        match *self {
+            Light::Spot { inner, outer, .. } => [inner.0.cos(), outer.0.cos()],
+            _ => [-1.0, -1.0],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radius_attenuation_reaches_zero_at_the_radius() {
+        let attenuation = Attenuation::Radius(5.0);
+        assert!((attenuation.factor(0.0) - 1.0).abs() < 1e-6);
+        let mut last = 1.0;
+        for step in 1..=10 {
+            let factor = attenuation.factor(step as f32 * 0.5);
+            assert!(factor < last);
+            last = factor;
+        }
+        assert_eq!(attenuation.factor(5.0), 0.0);
+        assert_eq!(attenuation.factor(8.0), 0.0);
+    }
+
+    #[test]
+    fn spots_keep_aiming_at_the_target() {
+        let mut spot = Light::Spot {
+            position: Point3::new(0.0, 3.0, 0.0),
+            direction: -Vector3::unit_y(),
+            color: LightColor::new([1.0, 1.0, 1.0]),
+            attenuation: Attenuation::Radius(10.0),
+            inner: Rad(0.2),
+            outer: Rad(0.3),
+        };
+        spot.move_to(Point3::new(4.0, 0.0, 0.0), Point3::new(0.0, 0.0, 0.0));
+        match spot {
+            Light::Spot { direction, .. } => assert!((direction + Vector3::unit_x()).magnitude() < 1e-6),
+            _ => unreachable!(),
+        }
+        assert_eq!(spot.position(), Some(Point3::new(4.0, 0.0, 0.0)));
+    }
+}
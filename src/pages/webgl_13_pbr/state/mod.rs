@@ -0,0 +1,354 @@
+pub use crate::input::PointerInput;
+use crate::input::{Gesture, GestureRecognizer};
+use camera::Camera;
+use cgmath::{Deg, EuclideanSpace, InnerSpace, Point2, Point3, Vector3};
+use clear_color::ClearColor;
+pub use color::srgb_to_linear_rgb;
+pub use light::{Attenuation, Light, LightColor, MAX_LIGHTS};
+pub use pbr_material::{PbrMaterial, MIN_ROUGHNESS};
+pub use rgbe::{parse_rgbe, procedural_sky, HdrImage};
+use scene::{sphere_grid, SceneObject};
+pub use scene::{GRID_COLUMNS, GRID_ROWS};
+pub use viewport::Pane;
+use viewport::Viewport;
+
+mod camera;
+mod clear_color;
+mod color;
+mod light;
+mod pbr_material;
+mod rgbe;
+mod scene;
+mod viewport;
+
+/// stacks and slices of the spheres, fine enough for sharp highlights
+pub const TESSELLATION: (usize, usize) = (48, 64);
+/// radius on screen of the light gizmos
+pub const LIGHT_GIZMO_PIXELS: f32 = 10.0;
+/// distance from the focus at which the gizmo of a directional light is shown
+pub const DIRECTIONAL_GIZMO_DISTANCE: f32 = 6.0;
+/// levels of the prefiltered environment, from mirror-like to fully rough
+pub const PREFILTERED_LEVELS: usize = 5;
+/// shown until an `.hdr` file is loaded
+pub const PROCEDURAL_SKY: &str = "procedural sky";
+/// colors of the added lights, in turn
+const LIGHT_PALETTE: [[f32; 3]; 4] = [[1.0, 0.6, 0.2], [0.3, 0.7, 1.0], [0.6, 1.0, 0.4], [1.0, 0.4, 0.8]];
+
+pub struct State {
+    viewport: Viewport,
+    clear_color: ClearColor,
+    camera: Camera,
+    gestures: GestureRecognizer,
+    lights: Vec<Light>,
+    /// light under the pointer
+    hovered_light: Option<usize>,
+    /// pointer id and light it drags, the pointer then doesn't orbit
+    dragged_light: Option<(i32, usize)>,
+    /// last light clicked, the one removed first
+    selected_light: Option<usize>,
+    objects: Vec<SceneObject>,
+    /// sphere the material controls edit
+    selected_object: usize,
+    /// the environment maps light the spheres, instead of the ambient of the lights
+    environment_lighting: bool,
+    /// file the environment was loaded from
+    environment_name: String,
+}
+
+impl State {
+    pub fn new() -> Self {
+        let viewport = Viewport::default();
+        let clear_color = ClearColor::default();
+        let camera = Camera::new();
+        Self {
+            viewport,
+            clear_color,
+            camera,
+            gestures: GestureRecognizer::default(),
+            lights: default_lights(),
+            hovered_light: None,
+            dragged_light: None,
+            selected_light: None,
+            objects: sphere_grid(),
+            selected_object: 0,
+            environment_lighting: true,
+            environment_name: PROCEDURAL_SKY.to_string(),
+        }
+    }
+
+    pub fn clear_color(&self) -> &ClearColor {
+        &self.clear_color
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Hovered or dragged light, drawn highlighted.
+    pub fn highlighted_light(&self) -> Option<usize> {
+        self.dragged_light.map(|(_, index)| index).or(self.hovered_light)
+    }
+
+    pub fn selected_light(&self) -> Option<usize> {
+        self.selected_light
+    }
+
+    /// Where the gizmo of light `index` is drawn and grabbed.
+    pub fn light_anchor(&self, index: usize) -> Point3<f32> {
+        match self.lights[index] {
+            Light::Directional { direction, .. } => self.camera.focus() + direction * DIRECTIONAL_GIZMO_DISTANCE,
+            light => light.position().unwrap(),
+        }
+    }
+
+    /// The whole viewport, in one pane.
+    pub fn pane(&self) -> Pane {
+        self.viewport.pane()
+    }
+
+    pub fn objects(&self) -> &[SceneObject] {
+        &self.objects
+    }
+
+    pub fn selected_object(&self) -> usize {
+        self.selected_object
+    }
+
+    pub fn selected_material(&self) -> &PbrMaterial {
+        &self.objects[self.selected_object].material
+    }
+
+    pub fn environment_lighting(&self) -> bool {
+        self.environment_lighting
+    }
+
+    pub fn environment_name(&self) -> &str {
+        &self.environment_name
+    }
+
+    pub fn msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::ViewportResize(width, height) => self.viewport.set(width, height),
+            Msg::ClearColorChange(r, g, b, a) => self.clear_color.set(r, g, b, a),
+            Msg::PointerDown(pointer) => {
+                // a press on a light grabs it, the other presses orbit
+                if let Some(index) = self.light_at(pointer.x, pointer.y) {
+                    self.dragged_light = Some((pointer.id, index));
+                    self.selected_light = Some(index);
+                    return;
+                }
+                if let Some(object) = self.object_at(pointer.x, pointer.y) {
+                    self.selected_object = object;
+                }
+                self.gestures.down(pointer.id, pointer.button, pointer.position(), pointer.time);
+            }
+            Msg::PointerMove(id, x, y) => match self.dragged_light {
+                Some((dragging, index)) if dragging == id => self.drag_light(index, x, y),
+                _ => match self.gestures.moved(id, Point2::new(x, y)) {
+                    Some(Gesture::Drag { from, to, .. }) => {
+                        self.camera.orbit_left_right(from.x - to.x);
+                        self.camera.orbit_up_down(to.y - from.y);
+                    }
+                    Some(Gesture::Pinch { scale, .. }) => self.camera.pinch(scale),
+                    Some(_) => {}
+                    None => self.hovered_light = self.light_at(x, y),
+                },
+            },
+            Msg::PointerUp(id, time) => {
+                self.release_light(id);
+                self.gestures.up(id, time);
+            }
+            Msg::PointerCancel(id) => {
+                self.release_light(id);
+                self.gestures.cancel(id);
+            }
+            Msg::ReleaseInput => {
+                self.dragged_light = None;
+                self.gestures.clear();
+            }
+            Msg::Zoom(zoom) => self.camera.zoom(zoom),
+            Msg::NextObject => self.selected_object = (self.selected_object + 1) % self.objects.len(),
+            Msg::BaseColorChange(rgb) => self.selected_material_mut().base_color = rgb,
+            Msg::EmissiveChange(rgb) => self.selected_material_mut().emissive = rgb,
+            Msg::MetallicChange(metallic) => self.selected_material_mut().set_metallic(metallic),
+            Msg::RoughnessChange(roughness) => self.selected_material_mut().set_roughness(roughness),
+            Msg::AmbientOcclusionChange(ambient_occlusion) => self.selected_material_mut().set_ambient_occlusion(ambient_occlusion),
+            Msg::AddLight => self.add_light(),
+            Msg::RemoveLight => self.remove_light(),
+            Msg::ToggleEnvironmentLighting => self.environment_lighting = !self.environment_lighting,
+            Msg::EnvironmentLoaded(name) => {
+                self.environment_name = name;
+                self.environment_lighting = true;
+            }
+        }
+    }
+
+    fn selected_material_mut(&mut self) -> &mut PbrMaterial {
+        &mut self.objects[self.selected_object].material
+    }
+
+    /// The light whose gizmo is nearest to the client pixel `x`, `y`, if
+    /// close enough to grab.
+    fn light_at(&self, x: f32, y: f32) -> Option<usize> {
+        let (x, y) = (f64::from(x), f64::from(y));
+        let pane = self.pane();
+        let reach = f64::from(LIGHT_GIZMO_PIXELS) * 1.5;
+        (0..self.lights.len())
+            .filter_map(|index| {
+                let (ndc_x, ndc_y) = self.camera.project(self.light_anchor(index), pane.aspect())?;
+                let (light_x, light_y) = pane.to_client(ndc_x, ndc_y);
+                let distance = ((light_x - x).powi(2) + (light_y - y).powi(2)).sqrt();
+                Some((index, distance))
+            })
+            .filter(|&(_, distance)| distance <= reach)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// The nearest sphere under the client pixel `x`, `y`.
+    fn object_at(&self, x: f32, y: f32) -> Option<usize> {
+        let pane = self.pane();
+        let (ndc_x, ndc_y) = pane.to_ndc(f64::from(x), f64::from(y));
+        let (origin, direction) = self.camera.ray(ndc_x, ndc_y, pane.aspect());
+        self.objects
+            .iter()
+            .enumerate()
+            .filter_map(|(index, object)| Some((index, object.intersect(origin, direction)?)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .map(|(index, _)| index)
+    }
+
+    /// Moves light `index` under the pointer, at the depth it was grabbed at.
+    fn drag_light(&mut self, index: usize, x: f32, y: f32) {
+        let pane = self.pane();
+        let (ndc_x, ndc_y) = pane.to_ndc(f64::from(x), f64::from(y));
+        let to = self.camera.drag_point(self.light_anchor(index), ndc_x, ndc_y, pane.aspect());
+        let focus = self.camera.focus();
+        let light = &mut self.lights[index];
+        match light {
+            // the gizmo stays on its sphere around the focus, only the direction changes
+            Light::Directional { .. } => light.set_direction(to - focus),
+            _ => light.move_to(to, focus),
+        }
+    }
+
+    /// Drops the light dragged by pointer `id`, if any.
+    fn release_light(&mut self, id: i32) {
+        if matches!(self.dragged_light, Some((dragging, _)) if dragging == id) {
+            self.dragged_light = None;
+        }
+    }
+
+    /// Adds a point, a spot or a directional light in turn, up to `MAX_LIGHTS`.
+    fn add_light(&mut self) {
+        if self.lights.len() >= MAX_LIGHTS {
+            return;
+        }
+        let count = self.lights.len();
+        let color = LightColor::new(LIGHT_PALETTE[count % LIGHT_PALETTE.len()]);
+        // spread in front of the grid by the golden angle
+        let angle = Deg(137.5 * count as f32);
+        let around = Vector3::new(angle.0.to_radians().cos() * 4.0, angle.0.to_radians().sin() * 4.0, 3.0);
+        let position = self.camera.focus() + around;
+        let light = match count % 3 {
+            0 => Light::Point {
+                position,
+                color,
+                attenuation: Attenuation::Radius(12.0),
+            },
+            1 => Light::Spot {
+                position,
+                direction: (self.camera.focus() - position).normalize(),
+                color,
+                attenuation: Attenuation::Radius(16.0),
+                inner: Deg(15.0).into(),
+                outer: Deg(25.0).into(),
+            },
+            _ => Light::Directional {
+                direction: around.normalize(),
+                color,
+            },
+        };
+        self.lights.push(light);
+        self.selected_light = Some(count);
+    }
+
+    /// Removes the selected light, or the last one.
+    fn remove_light(&mut self) {
+        let index = match self.selected_light.or_else(|| self.lights.len().checked_sub(1)) {
+            Some(index) => index,
+            None => return,
+        };
+        self.lights.remove(index);
+        self.selected_light = None;
+        self.hovered_light = None;
+        self.dragged_light = None;
+    }
+}
+
+/// Toward the sun of the default light and of the procedural sky.
+pub fn sun_direction() -> Vector3<f32> {
+    Vector3::new(-1.0, 1.5, 2.0).normalize()
+}
+
+/// A white sun from the top left, a warm point light in front of the grid
+/// and a blue spot from the right.
+fn default_lights() -> Vec<Light> {
+    let spot_position = Point3::new(6.0, 1.0, 5.0);
+    vec![
+        Light::Directional {
+            direction: sun_direction(),
+            color: LightColor {
+                ambient: [0.03, 0.03, 0.03],
+                diffuse: [0.8, 0.8, 0.8],
+                specular: [0.8, 0.8, 0.8],
+            },
+        },
+        Light::Point {
+            position: Point3::new(-2.0, -2.0, 3.5),
+            color: LightColor::new([1.0, 0.6, 0.2]),
+            attenuation: Attenuation::Radius(12.0),
+        },
+        Light::Spot {
+            position: spot_position,
+            direction: (Point3::origin() - spot_position).normalize(),
+            color: LightColor::new([0.3, 0.7, 1.0]),
+            attenuation: Attenuation::Radius(16.0),
+            inner: Deg(15.0).into(),
+            outer: Deg(25.0).into(),
+        },
+    ]
+}
+
+pub enum Msg {
+    ViewportResize(f64, f64),
+    #[allow(dead_code)]
+    ClearColorChange(f32, f32, f32, f32),
+    PointerDown(PointerInput),
+    /// pointer id and client position
+    PointerMove(i32, f32, f32),
+    /// pointer id and event timestamp, in milliseconds
+    PointerUp(i32, f64),
+    /// the pointer was taken over by the browser, or released outside of the page
+    PointerCancel(i32),
+    /// the page lost the focus, nothing is held anymore
+    ReleaseInput,
+    Zoom(f32),
+    NextObject,
+    /// sRGB, for the selected sphere, like the other material changes
+    BaseColorChange([f32; 3]),
+    /// sRGB
+    EmissiveChange([f32; 3]),
+    MetallicChange(f32),
+    RoughnessChange(f32),
+    AmbientOcclusionChange(f32),
+    AddLight,
+    RemoveLight,
+    ToggleEnvironmentLighting,
+    /// the environment maps were made from the file of that name
+    EnvironmentLoaded(String),
+}
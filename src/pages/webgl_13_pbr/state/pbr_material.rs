@@ -0,0 +1,89 @@
+use super::color::srgb_to_linear_rgb;
+
+/// smoothest surface, the highlight of a perfect mirror would be a single point
+pub const MIN_ROUGHNESS: f32 = 0.05;
+
+/// Metallic-roughness parameters of a surface. The colors are sRGB, as
+/// picked, and decoded to linear light for the shader.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PbrMaterial {
+    pub base_color: [f32; 3],
+    /// 0 for a dielectric, 1 for a metal
+    pub metallic: f32,
+    /// 0 for a mirror, 1 for a fully diffuse surface
+    pub roughness: f32,
+    /// share of the ambient light reaching the surface
+    pub ambient_occlusion: f32,
+    /// light of its own, added whatever the lights
+    pub emissive: [f32; 3],
+}
+
+impl PbrMaterial {
+    pub fn new(base_color: [f32; 3], metallic: f32, roughness: f32) -> Self {
+        Self {
+            base_color,
+            metallic: metallic.clamp(0.0, 1.0),
+            roughness: roughness.clamp(MIN_ROUGHNESS, 1.0),
+            ambient_occlusion: 1.0,
+            emissive: [0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn set_metallic(&mut self, metallic: f32) {
+        self.metallic = metallic.clamp(0.0, 1.0);
+    }
+
+    pub fn set_roughness(&mut self, roughness: f32) {
+        self.roughness = roughness.clamp(MIN_ROUGHNESS, 1.0);
+    }
+
+    pub fn set_ambient_occlusion(&mut self, ambient_occlusion: f32) {
+        self.ambient_occlusion = ambient_occlusion.clamp(0.0, 1.0);
+    }
+
+    pub fn linear_base_color(&self) -> [f32; 3] {
+        srgb_to_linear_rgb(self.base_color)
+    }
+
+    pub fn linear_emissive(&self) -> [f32; 3] {
+        srgb_to_linear_rgb(self.emissive)
+    }
+}
+
+/// `rows` by `columns` materials of one base color, row after row: metallic
+/// from 0 on the first row to 1 on the last, roughness from the smoothest
+/// on the first column to 1 on the last.
+pub fn sweep(rows: usize, columns: usize, base_color: [f32; 3]) -> Vec<PbrMaterial> {
+    let step = |i: usize, count: usize| if count > 1 { i as f32 / (count - 1) as f32 } else { 0.0 };
+    (0..rows)
+        .flat_map(|row| (0..columns).map(move |column| (row, column)))
+        .map(|(row, column)| PbrMaterial::new(base_color, step(row, rows), step(column, columns)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweep_covers_both_ranges() {
+        let materials = sweep(3, 4, [1.0, 0.0, 0.0]);
+        assert_eq!(materials.len(), 12);
+        assert_eq!(materials[0].metallic, 0.0);
+        assert_eq!(materials[0].roughness, MIN_ROUGHNESS);
+        assert_eq!(materials[3].roughness, 1.0);
+        assert_eq!(materials[4].metallic, 0.5);
+        assert_eq!(materials[11].metallic, 1.0);
+        assert_eq!(materials[11].roughness, 1.0);
+    }
+
+    #[test]
+    fn parameters_stay_in_range() {
+        let mut material = PbrMaterial::new([0.5, 0.5, 0.5], 2.0, 0.0);
+        assert_eq!((material.metallic, material.roughness), (1.0, MIN_ROUGHNESS));
+        material.set_metallic(-1.0);
+        material.set_roughness(3.0);
+        material.set_ambient_occlusion(1.5);
+        assert_eq!((material.metallic, material.roughness, material.ambient_occlusion), (0.0, 1.0, 1.0));
+    }
+}
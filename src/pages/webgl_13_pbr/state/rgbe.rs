@@ -0,0 +1,315 @@
+use cgmath::{InnerSpace, Vector3};
+use std::convert::TryFrom;
+use std::f32::consts::PI;
+
+/// largest image decoded, a 16k by 8k panorama, beyond it a file is more
+/// likely broken than meant for the browser
+const MAX_PIXELS: usize = 16384 * 8192;
+/// a scanline takes at least one 4 byte pixel, however well it compresses
+const MIN_SCANLINE_BYTES: usize = 4;
+
+/// A linear light image in the equirectangular projection: longitude from
+/// left to right, latitude from the zenith at the top to the nadir.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HdrImage {
+    pub width: usize,
+    pub height: usize,
+    /// `r, g, b` of every pixel, row after row from the top
+    pub pixels: Vec<f32>,
+}
+
+/// Decodes a Radiance `.hdr` file: the header, then the scanlines either
+/// run length encoded per channel or flat, older run lengths included.
+pub fn parse_rgbe(bytes: &[u8]) -> Result<HdrImage, String> {
+    let mut reader = Reader { bytes, at: 0 };
+    let magic = reader.line()?;
+    if !magic.starts_with("#?") {
+        return Err("not a Radiance file, no #? signature".to_string());
+    }
+    loop {
+        let line = reader.line()?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(format!("unsupported format {}", format));
+            }
+        }
+    }
+    let (width, height, flip) = parse_resolution(&reader.line()?)?;
+    // the resolution is read from the file, check it before allocating for it
+    let count = width.checked_mul(height).filter(|&count| count <= MAX_PIXELS);
+    let count = count.ok_or_else(|| format!("image of {} by {} pixels is too large", width, height))?;
+    if height.saturating_mul(MIN_SCANLINE_BYTES) > reader.remaining() {
+        return Err("unexpected end of file".to_string());
+    }
+
+    let mut pixels = vec![0.0; count * 3];
+    let mut scanline = vec![[0u8; 4]; width];
+    for row in 0..height {
+        reader.scanline(&mut scanline)?;
+        let row = if flip { height - 1 - row } else { row };
+        for (column, rgbe) in scanline.iter().enumerate() {
+            let offset = (row * width + column) * 3;
+            pixels[offset..offset + 3].copy_from_slice(&decode(*rgbe));
+        }
+    }
+    Ok(HdrImage { width, height, pixels })
+}
+
+/// `-Y height +X width` is the usual top down order, `+Y` is bottom up.
+fn parse_resolution(line: &str) -> Result<(usize, usize, bool), String> {
+    let invalid = || format!("unsupported resolution {:?}", line);
+    let fields: Vec<_> = line.split_whitespace().collect();
+    if fields.len() != 4 || fields[2] != "+X" {
+        return Err(invalid());
+    }
+    let flip = match fields[0] {
+        "-Y" => false,
+        "+Y" => true,
+        _ => return Err(invalid()),
+    };
+    let height = fields[1].parse().map_err(|_| invalid())?;
+    let width = fields[3].parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width, height, flip))
+}
+
+/// A shared exponent for the three mantissas: `m * 2^(e - 128 - 8)`.
+fn decode([r, g, b, e]: [u8; 4]) -> [f32; 3] {
+    if e == 0 {
+        return [0.0; 3];
+    }
+    let scale = 2f32.powi(i32::from(e) - 136);
+    [f32::from(r) * scale, f32::from(g) * scale, f32::from(b) * scale]
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.bytes.get(self.at).ok_or("unexpected end of file")?;
+        self.at += 1;
+        Ok(byte)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.at
+    }
+
+    fn pixel(&mut self) -> Result<[u8; 4], String> {
+        Ok([self.byte()?, self.byte()?, self.byte()?, self.byte()?])
+    }
+
+    /// A header line, without its newline.
+    fn line(&mut self) -> Result<String, String> {
+        let rest = &self.bytes[self.at..];
+        let end = rest.iter().position(|&b| b == b'\n').ok_or("unexpected end of header")?;
+        self.at += end + 1;
+        Ok(String::from_utf8_lossy(&rest[..end]).trim_end_matches('\r').to_string())
+    }
+
+    fn scanline(&mut self, scanline: &mut [[u8; 4]]) -> Result<(), String> {
+        let width = scanline.len();
+        let first = self.pixel()?;
+        // the per channel encoding starts with 2, 2 and the width, and is
+        // only used for widths it can tell apart from a flat pixel
+        if (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2 && first[2] & 0x80 == 0 {
+            if usize::from(first[2]) << 8 | usize::from(first[3]) != width {
+                return Err("scanline width mismatch".to_string());
+            }
+            for channel in 0..4 {
+                let mut column = 0;
+                while column < width {
+                    let count = self.byte()?;
+                    if count > 128 {
+                        let run = usize::from(count - 128);
+                        let value = self.byte()?;
+                        for pixel in scanline.get_mut(column..column + run).ok_or("run past the scanline")? {
+                            pixel[channel] = value;
+                        }
+                        column += run;
+                    } else {
+                        let count = usize::from(count);
+                        if count == 0 || column + count > width {
+                            return Err("bad scanline run".to_string());
+                        }
+                        for pixel in &mut scanline[column..column + count] {
+                            pixel[channel] = self.byte()?;
+                        }
+                        column += count;
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        // flat pixels, where 1, 1, 1, n repeats the previous one, n shifted
+        // by 8 more bits for every repeat in a row
+        let mut column: usize = 0;
+        let mut shift = 0u32;
+        let mut pixel = first;
+        loop {
+            if pixel[0] == 1 && pixel[1] == 1 && pixel[2] == 1 {
+                let previous = *scanline.get(column.wrapping_sub(1)).ok_or("repeat without a previous pixel")?;
+                // many repeats in a row shift the count past any scanline,
+                // or past the integer
+                let run = u64::from(pixel[3])
+                    .checked_shl(shift)
+                    .and_then(|run| usize::try_from(run).ok())
+                    .filter(|&run| run <= width - column)
+                    .ok_or("run past the scanline")?;
+                for repeated in &mut scanline[column..column + run] {
+                    *repeated = previous;
+                }
+                column += run;
+                shift += 8;
+            } else {
+                scanline[column] = pixel;
+                column += 1;
+                shift = 0;
+            }
+            if column == width {
+                return Ok(());
+            }
+            pixel = self.pixel()?;
+        }
+    }
+}
+
+/// Direction of the center of pixel `column`, `row` of a `width` by `height`
+/// equirectangular image, the inverse of the lookup in the shaders.
+fn texel_direction(width: usize, height: usize, column: usize, row: usize) -> Vector3<f32> {
+    let longitude = (column as f32 + 0.5) / width as f32 * 2.0 * PI - PI;
+    let latitude = PI / 2.0 - (row as f32 + 0.5) / height as f32 * PI;
+    Vector3::new(latitude.cos() * longitude.cos(), latitude.sin(), latitude.cos() * longitude.sin())
+}
+
+/// The environment without a file: a sky from the horizon to the zenith, a
+/// dim ground, and a sun toward `sun`, far brighter than the rest.
+pub fn procedural_sky(width: usize, height: usize, sun: Vector3<f32>) -> HdrImage {
+    const ZENITH: [f32; 3] = [0.15, 0.3, 0.8];
+    const HORIZON: [f32; 3] = [0.8, 0.85, 0.9];
+    const GROUND: [f32; 3] = [0.12, 0.1, 0.08];
+    const SUN: [f32; 3] = [60.0, 55.0, 45.0];
+    // cosine of the angular radius of the sun, larger than the real one to
+    // survive a small equirectangular image
+    const SUN_COS: f32 = 0.9985;
+
+    let sun = sun.normalize();
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in 0..height {
+        for column in 0..width {
+            let direction = texel_direction(width, height, column, row);
+            let color = if direction.dot(sun) > SUN_COS {
+                SUN
+            } else if direction.y >= 0.0 {
+                let t = direction.y.sqrt();
+                [0, 1, 2].map(|i| HORIZON[i] + (ZENITH[i] - HORIZON[i]) * t)
+            } else {
+                // a quick fade, for no seam at the horizon
+                let t = (-direction.y * 8.0).min(1.0);
+                [0, 1, 2].map(|i| HORIZON[i] + (GROUND[i] - HORIZON[i]) * t)
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+    HdrImage { width, height, pixels }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(resolution: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = format!("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\nEXPOSURE=1.0\n\n{}\n", resolution).into_bytes();
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn decodes_the_shared_exponent() {
+        assert_eq!(decode([128, 64, 32, 129]), [1.0, 0.5, 0.25]);
+        assert_eq!(decode([128, 128, 128, 132]), [8.0, 8.0, 8.0]);
+        assert_eq!(decode([200, 100, 50, 0]), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn reads_flat_scanlines_top_down() {
+        let image = parse_rgbe(&file("-Y 2 +X 2", &[128, 0, 0, 129, 0, 128, 0, 129, 0, 0, 128, 129, 128, 128, 128, 130])).unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.pixels, vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn flips_bottom_up_files() {
+        let image = parse_rgbe(&file("+Y 2 +X 1", &[128, 0, 0, 129, 0, 128, 0, 129])).unwrap();
+        assert_eq!(image.pixels, vec![0.0, 1.0, 0.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn reads_run_length_encoded_scanlines() {
+        // width 8: red as one run, green as literals, blue as a run of 3 and
+        // 5 literals, the exponent as one run
+        let mut data = vec![2, 2, 0, 8];
+        data.extend_from_slice(&[128 + 8, 128]);
+        data.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+        data.extend_from_slice(&[128 + 3, 64, 5, 1, 2, 3, 4, 5]);
+        data.extend_from_slice(&[128 + 8, 129]);
+        let image = parse_rgbe(&file("-Y 1 +X 8", &data)).unwrap();
+        assert_eq!(&image.pixels[..3], &[1.0, 0.0, 0.5]);
+        assert_eq!(&image.pixels[21..], &[1.0, 112.0 / 128.0, 5.0 / 128.0]);
+    }
+
+    #[test]
+    fn repeats_old_style_runs() {
+        let image = parse_rgbe(&file("-Y 1 +X 4", &[128, 0, 0, 129, 1, 1, 1, 3])).unwrap();
+        assert_eq!(image.pixels, [1.0, 0.0, 0.0].repeat(4));
+    }
+
+    #[test]
+    fn rejects_broken_files() {
+        assert!(parse_rgbe(b"P6\n1 1\n255\n").is_err());
+        assert!(parse_rgbe(&file("-Y 1 +X 2", &[128, 0, 0, 129])).is_err());
+        assert!(parse_rgbe(&file("+X 1 -Y 1", &[128, 0, 0, 129])).is_err());
+        let mut xyze = b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n".to_vec();
+        xyze.extend_from_slice(&[128, 0, 0, 129]);
+        assert!(parse_rgbe(&xyze).is_err());
+    }
+
+    #[test]
+    fn rejects_resolutions_too_large_for_the_file() {
+        let overflow = format!("-Y {} +X {}", usize::MAX, usize::MAX);
+        assert!(parse_rgbe(&file(&overflow, &[128, 0, 0, 129])).is_err());
+        assert!(parse_rgbe(&file("-Y 100000 +X 100000", &[128, 0, 0, 129])).is_err());
+        assert!(parse_rgbe(&file("-Y 1000 +X 1000", &[128, 0, 0, 129])).is_err());
+    }
+
+    #[test]
+    fn rejects_endless_repeats() {
+        // empty repeats in a row shift their count by 8 more bits each
+        let mut data = vec![128, 0, 0, 129];
+        data.extend([1, 1, 1, 0].repeat(10));
+        assert!(parse_rgbe(&file("-Y 1 +X 4", &data)).is_err());
+        let mut data = vec![128, 0, 0, 129, 1, 1, 1, 1];
+        data.extend([1, 1, 1, 255].repeat(8));
+        assert!(parse_rgbe(&file("-Y 1 +X 4", &data)).is_err());
+    }
+
+    #[test]
+    fn the_sun_is_the_brightest_texel() {
+        let sun = Vector3::new(-1.0, 1.5, 2.0);
+        let image = procedural_sky(256, 128, sun);
+        let brightest = (0..image.width * image.height)
+            .max_by(|&a, &b| image.pixels[a * 3].partial_cmp(&image.pixels[b * 3]).unwrap())
+            .unwrap();
+        let direction = texel_direction(image.width, image.height, brightest % image.width, brightest / image.width);
+        assert!(direction.dot(sun.normalize()) > 0.99);
+    }
+}
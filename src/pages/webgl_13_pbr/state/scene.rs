@@ -0,0 +1,84 @@
+use cgmath::{InnerSpace, Matrix4, Point3, Vector3};
+
+use super::pbr_material::{sweep, PbrMaterial};
+
+/// rows of the grid, from dielectric to metal
+pub const GRID_ROWS: usize = 5;
+/// columns of the grid, from smooth to rough
+pub const GRID_COLUMNS: usize = 5;
+const SPACING: f32 = 2.4;
+const RADIUS: f32 = 1.0;
+/// a gold-ish color, telling metals from dielectrics apart at a glance
+const SWEEP_COLOR: [f32; 3] = [1.0, 0.76, 0.33];
+
+/// A sphere of the grid with its own material.
+#[derive(Clone, Debug)]
+pub struct SceneObject {
+    pub center: Point3<f32>,
+    pub radius: f32,
+    pub material: PbrMaterial,
+}
+
+impl SceneObject {
+    /// Places the unit sphere mesh.
+    pub fn model_matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.center - Point3::new(0.0, 0.0, 0.0)) * Matrix4::from_scale(self.radius)
+    }
+
+    /// Distance along the unit `direction` from `origin` to the sphere, if hit.
+    pub fn intersect(&self, origin: Point3<f32>, direction: Vector3<f32>) -> Option<f32> {
+        let offset = origin - self.center;
+        let b = offset.dot(direction);
+        let c = offset.magnitude2() - self.radius * self.radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let t = -b - discriminant.sqrt();
+        // from inside, the far side is the hit
+        let t = if t >= 0.0 { t } else { -b + discriminant.sqrt() };
+        if t >= 0.0 {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+/// The spheres facing the camera, metallic growing upward and roughness to
+/// the right.
+pub fn sphere_grid() -> Vec<SceneObject> {
+    let materials = sweep(GRID_ROWS, GRID_COLUMNS, SWEEP_COLOR);
+    let offset = |i: usize, count: usize| (i as f32 - (count - 1) as f32 / 2.0) * SPACING;
+    materials
+        .into_iter()
+        .enumerate()
+        .map(|(index, material)| {
+            let (row, column) = (index / GRID_COLUMNS, index % GRID_COLUMNS);
+            SceneObject {
+                center: Point3::new(offset(column, GRID_COLUMNS), offset(row, GRID_ROWS), 0.0),
+                radius: RADIUS,
+                material,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_is_centered_and_sorted() {
+        let grid = sphere_grid();
+        assert_eq!(grid.len(), GRID_ROWS * GRID_COLUMNS);
+        let center = grid.iter().fold(Vector3::new(0.0, 0.0, 0.0), |sum, object| {
+            sum + (object.center - Point3::new(0.0, 0.0, 0.0))
+        });
+        assert!(center.magnitude() < 1e-4);
+        // the last sphere is the roughest metal, top right
+        let last = grid.last().unwrap();
+        assert!(last.center.x > 0.0 && last.center.y > 0.0);
+        assert_eq!((last.material.metallic, last.material.roughness), (1.0, 1.0));
+    }
+}
@@ -0,0 +1,60 @@
+pub struct Viewport {
+    width: f64,
+    height: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new(200.0, 300.0)
+    }
+}
+
+impl Viewport {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+
+    pub fn set(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+}
+
+/// Part of the viewport drawn with its own projection, all of it here.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pane {
+    pub left: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Pane {
+    pub fn aspect(&self) -> f32 {
+        (self.width / self.height.max(1.0)) as f32
+    }
+
+    /// Normalized device coordinates of the client pixel `x`, `y`.
+    pub fn to_ndc(self, x: f64, y: f64) -> (f32, f32) {
+        let ndc_x = (x - self.left) / self.width.max(1.0) * 2.0 - 1.0;
+        let ndc_y = 1.0 - y / self.height.max(1.0) * 2.0;
+        (ndc_x as f32, ndc_y as f32)
+    }
+
+    /// Client pixel of the normalized device coordinates.
+    pub fn to_client(self, ndc_x: f32, ndc_y: f32) -> (f64, f64) {
+        let x = self.left + (f64::from(ndc_x) + 1.0) * 0.5 * self.width;
+        let y = (1.0 - f64::from(ndc_y)) * 0.5 * self.height;
+        (x, y)
+    }
+}
+
+impl Viewport {
+    /// The whole viewport.
+    pub fn pane(&self) -> Pane {
+        Pane {
+            left: 0.0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use super::state::{Filtering, State};
+use crate::texture::Wrap;
+
+/// How the textures are sampled.
+pub struct Hud {
+    info: HtmlElement,
+}
+
+impl Hud {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let body = document.body().unwrap();
+
+        let info = document.create_element("div")?.dyn_into::<HtmlElement>()?;
+        let style = info.style();
+        style.set_property("position", "absolute")?;
+        style.set_property("left", "8px")?;
+        style.set_property("top", "8px")?;
+        style.set_property("font", "13px monospace")?;
+        style.set_property("color", "white")?;
+        body.append_child(&info)?;
+
+        Ok(Self { info })
+    }
+
+    /// Follows the state, `anisotropy` being what the device applies.
+    pub fn sync(&self, state: &State, anisotropy: f32) {
+        let sampling = state.sampling();
+        let filtering = match sampling.filtering {
+            Filtering::Anisotropic if anisotropy <= 1.0 => "anisotropic (not supported)".to_string(),
+            Filtering::Anisotropic => format!("anisotropic {}x", anisotropy),
+            filtering => filtering.label().to_string(),
+        };
+        let wrap = match sampling.wrap() {
+            Wrap::Repeat => "repeat",
+            Wrap::MirroredRepeat => "mirrored repeat",
+            Wrap::ClampToEdge => "clamp to edge",
+        };
+        let decode = if sampling.srgb {
+            "sRGB texture, decoded"
+        } else {
+            "RGBA8 texture, sRGB read as linear"
+        };
+        self.info.set_inner_html(&format!(
+            "filter {}<br>wrap {}, tiled {}x<br>{}<br>?: keys, drag to orbit, scroll to zoom out and see the mip levels",
+            filtering,
+            wrap,
+            sampling.tiling(),
+            decode
+        ));
+    }
+}
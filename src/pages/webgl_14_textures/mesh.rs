@@ -0,0 +1,69 @@
+/// floats per vertex: position(3) + normal(3) + uv(2) + layer(1)
+pub const VERTEX_SIZE: usize = 9;
+
+/// Normal, right and up of every face, in the order of the layers of the
+/// face texture. Right × up is the normal: seen from outside, the texture
+/// is upright.
+const FACES: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+];
+
+pub struct Mesh {
+    vertices: Vec<f32>,
+    indices: Vec<u16>,
+}
+
+impl Mesh {
+    /// The cube from -1 to 1, four vertices per face for its own normal and
+    /// texture coordinates, `v` going down from the top of the face as the
+    /// rows of the images do.
+    pub fn textured_cube() -> Self {
+        let mut vertices = Vec::with_capacity(6 * 4 * VERTEX_SIZE);
+        let mut indices = Vec::with_capacity(6 * 6);
+        for (layer, (normal, right, up)) in FACES.iter().enumerate() {
+            for &(a, b) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter() {
+                for k in 0..3 {
+                    vertices.push(normal[k] + right[k] * a + up[k] * b);
+                }
+                vertices.extend_from_slice(normal);
+                vertices.extend_from_slice(&[(a + 1.0) / 2.0, (1.0 - b) / 2.0, layer as f32]);
+            }
+            let first = (layer * 4) as u16;
+            indices.extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        }
+        Self { vertices, indices }
+    }
+
+    pub fn vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[u16] {
+        &self.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triangles_wind_counter_clockwise_from_outside() {
+        let cube = Mesh::textured_cube();
+        assert_eq!(cube.vertices().len(), 24 * VERTEX_SIZE);
+        let vertex = |i: u16| &cube.vertices()[i as usize * VERTEX_SIZE..(i as usize + 1) * VERTEX_SIZE];
+        for t in cube.indices().chunks(3) {
+            let (a, b, c) = (vertex(t[0]), vertex(t[1]), vertex(t[2]));
+            let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let cross = [u[1] * v[2] - u[2] * v[1], u[2] * v[0] - u[0] * v[2], u[0] * v[1] - u[1] * v[0]];
+            let facing = cross[0] * a[3] + cross[1] * a[4] + cross[2] * a[5];
+            assert!(facing > 0.0);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+#version 300 es
+precision mediump float;
+precision mediump sampler2DArray;
+
+// A tiled albedo times a tiled detail texture, under the pips of the face
+// from a texture array, with a little diffuse light to tell the faces apart.
+
+// sRGB8_ALPHA8, decoded to linear light by the sampler, or the same bytes as
+// RGBA8, read as linear when they are not
+uniform sampler2D uAlbedo;
+// one channel, multiplied in
+uniform sampler2D uDetail;
+// a layer per face, sampled once across the face whatever the tiling
+uniform sampler2DArray uFaces;
+// repeats of the tiled textures across a face, around its center
+uniform float uTiling;
+// world space, toward the light
+uniform vec3 uLightDirection;
+
+in vec3 vNormal;
+in vec2 vTextureCoord;
+flat in float vLayer;
+
+out vec4 fragColor;
+
+vec3 linearToSrgb(vec3 color) {
+    return mix(color * 12.92, 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, step(vec3(0.0031308), color));
+}
+
+void main(void) {
+    vec2 tiled = (vTextureCoord - 0.5) * uTiling + 0.5;
+    vec3 color = texture(uAlbedo, tiled).rgb * texture(uDetail, tiled).r;
+    vec4 pips = texture(uFaces, vec3(vTextureCoord, vLayer));
+    color = mix(color, pips.rgb, pips.a);
+
+    float diffuse = max(dot(normalize(vNormal), uLightDirection), 0.0);
+    color *= 0.35 + 0.65 * diffuse;
+    fragColor = vec4(linearToSrgb(color), 1.0);
+}
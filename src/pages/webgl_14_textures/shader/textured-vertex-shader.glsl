@@ -0,0 +1,23 @@
+#version 300 es
+precision mediump float;
+
+uniform mat4 uViewMatrix;
+uniform mat4 uProjectiveMatrix;
+
+// the cube stays at the origin, world space is its model space
+layout(location = 0) in vec3 aVertexPosition;
+layout(location = 1) in vec3 aVertexNormal;
+layout(location = 2) in vec2 aTextureCoord;
+layout(location = 3) in float aLayer;
+
+out vec3 vNormal;
+out vec2 vTextureCoord;
+// the same on the whole face, never interpolated
+flat out float vLayer;
+
+void main(void) {
+    vNormal = aVertexNormal;
+    vTextureCoord = aTextureCoord;
+    vLayer = aLayer;
+    gl_Position = uProjectiveMatrix * uViewMatrix * vec4(aVertexPosition, 1.0);
+}
@@ -0,0 +1,72 @@
+use cgmath::{Matrix4, PerspectiveFov, Point3, Rad, Vector3};
+use std::f32::consts::PI;
+
+/// closest and farthest the eye gets from the focus
+const MIN_DISTANCE: f32 = 2.0;
+const MAX_DISTANCE: f32 = 120.0;
+
+/// Orbits the cube, near enough to magnify the texels and far enough to
+/// squeeze many of them into a pixel.
+pub struct Camera {
+    projection: PerspectiveFov<f32>,
+    up_down_angle: f32,    // pitch
+    left_right_angle: f32, // yaw
+    r: f32,
+    sensitivity: f32,
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        Camera {
+            projection: PerspectiveFov {
+                fovy: Rad(PI / 4.0),
+                aspect: 1.0,
+                near: 0.1,
+                far: 200.0,
+            },
+            // a corner ahead, three faces in view
+            up_down_angle: 30.0f32.to_radians(),
+            left_right_angle: 35.0f32.to_radians(),
+            r: 5.0,
+            sensitivity: 0.01,
+        }
+    }
+
+    pub fn view_matrix(&self) -> Matrix4<f32> {
+        Matrix4::look_at(self.get_eye(), Point3::new(0.0, 0.0, 0.0), Vector3::unit_y())
+    }
+
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4<f32> {
+        PerspectiveFov { aspect, ..self.projection }.into()
+    }
+
+    pub fn get_eye(&self) -> Point3<f32> {
+        let pitch = self.up_down_angle;
+        let yaw = self.left_right_angle;
+        let y = self.r * pitch.sin();
+        let x = self.r * pitch.cos() * yaw.sin();
+        let z = self.r * pitch.cos() * yaw.cos();
+        Point3::new(x, y, z)
+    }
+
+    pub fn orbit_left_right(&mut self, delta: f32) {
+        self.left_right_angle += delta * self.sensitivity;
+    }
+
+    pub fn orbit_up_down(&mut self, delta: f32) {
+        self.up_down_angle += delta * self.sensitivity;
+        // protect some weird camera movements
+        // -pi/2 < up_down_angle < pi/2
+        self.up_down_angle = self.up_down_angle.clamp(-(PI / 2.1), PI / 2.1);
+    }
+
+    pub fn zoom(&mut self, zoom: f32) {
+        // far out, a face is only a few pixels wide and the mip levels show
+        self.r = (self.r + zoom * 0.2 * self.r.sqrt()).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    /// Closes in as two fingers spread apart, keeping the scene under them.
+    pub fn pinch(&mut self, scale: f32) {
+        self.r = (self.r / scale).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+}
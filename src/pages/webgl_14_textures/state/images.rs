@@ -0,0 +1,117 @@
+//! The textures of the cube, drawn texel by texel: no image files to load.
+
+/// pips on each layer, in the order of the faces, opposite faces adding up to 7
+pub const PIPS_BY_FACE: [usize; 6] = [1, 6, 2, 5, 3, 4];
+
+/// A checkerboard of sRGB colors crossed by thin diagonals, which alias into
+/// moiré patterns when minified without mipmaps. RGBA, `size` a power of 2.
+pub fn albedo_texels(size: usize) -> Vec<u8> {
+    const LIGHT: [u8; 3] = [0xf0, 0xe6, 0xd2];
+    const DARK: [u8; 3] = [0xc8, 0x5a, 0x32];
+    const LINE: [u8; 3] = [0x30, 0x3c, 0x5a];
+    let tile = (size / 8).max(1);
+    let mut texels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let color = if (x + y) % 16 < 2 {
+                LINE
+            } else if (x / tile + y / tile).is_multiple_of(2) {
+                LIGHT
+            } else {
+                DARK
+            };
+            texels.extend_from_slice(&color);
+            texels.push(255);
+        }
+    }
+    texels
+}
+
+/// One channel of darker grout lines around 4x4 tiles, multiplied with the
+/// albedo: a second texture of the same material.
+pub fn detail_texels(size: usize) -> Vec<u8> {
+    let tile = (size / 4).max(1);
+    let width = (size / 64).max(1);
+    let mut texels = Vec::with_capacity(size * size);
+    for y in 0..size {
+        for x in 0..size {
+            let grout = x % tile < width || y % tile < width;
+            texels.push(if grout { 110 } else { 255 });
+        }
+    }
+    texels
+}
+
+/// The faces of a die, one RGBA layer each: transparent but for the pips.
+pub fn die_face_texels(size: usize) -> Vec<u8> {
+    let radius = size as f32 * 0.09;
+    let mut texels = Vec::with_capacity(size * size * 4 * PIPS_BY_FACE.len());
+    for &pips in PIPS_BY_FACE.iter() {
+        let centers: Vec<_> = pip_cells(pips)
+            .iter()
+            .map(|&(column, row)| ((0.25 + 0.25 * column as f32) * size as f32, (0.25 + 0.25 * row as f32) * size as f32))
+            .collect();
+        for y in 0..size {
+            for x in 0..size {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let distance = centers
+                    .iter()
+                    .map(|&(cx, cy)| ((px - cx).powi(2) + (py - cy).powi(2)).sqrt())
+                    .fold(f32::INFINITY, f32::min);
+                // a pixel wide smooth edge
+                let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+                texels.extend_from_slice(&[0x1e, 0x23, 0x32, (coverage * 255.0).round() as u8]);
+            }
+        }
+    }
+    texels
+}
+
+/// Cells of a 3x3 grid holding the pips of a die face showing `pips`.
+fn pip_cells(pips: usize) -> Vec<(usize, usize)> {
+    const CENTER: (usize, usize) = (1, 1);
+    const DIAGONAL: [(usize, usize); 2] = [(0, 0), (2, 2)];
+    const OTHER_DIAGONAL: [(usize, usize); 2] = [(2, 0), (0, 2)];
+    const MIDDLES: [(usize, usize); 2] = [(0, 1), (2, 1)];
+    let mut cells = Vec::new();
+    if pips % 2 == 1 {
+        cells.push(CENTER);
+    }
+    if pips >= 2 {
+        cells.extend_from_slice(&DIAGONAL);
+    }
+    if pips >= 4 {
+        cells.extend_from_slice(&OTHER_DIAGONAL);
+    }
+    if pips == 6 {
+        cells.extend_from_slice(&MIDDLES);
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_face_has_its_pips() {
+        for pips in 1..=6 {
+            assert_eq!(pip_cells(pips).len(), pips);
+        }
+        for (face, opposite) in [(0, 1), (2, 3), (4, 5)].iter() {
+            assert_eq!(PIPS_BY_FACE[*face] + PIPS_BY_FACE[*opposite], 7);
+        }
+    }
+
+    #[test]
+    fn the_center_pip_shows_on_odd_faces() {
+        let size = 32;
+        let faces = die_face_texels(size);
+        assert_eq!(faces.len(), size * size * 4 * 6);
+        for (layer, &pips) in PIPS_BY_FACE.iter().enumerate() {
+            let center = layer * size * size + (size / 2) * size + size / 2;
+            let alpha = faces[center * 4 + 3];
+            assert_eq!(alpha == 255, pips % 2 == 1, "{} pips", pips);
+        }
+    }
+}
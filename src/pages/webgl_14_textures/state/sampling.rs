@@ -0,0 +1,116 @@
+use crate::texture::{Filter, SamplerOptions, Wrap};
+
+/// anisotropy asked for, the sampler brings it down to what the device has
+const ANISOTROPY: f32 = 16.0;
+/// times the textures repeat across a face
+const TILINGS: [f32; 4] = [1.0, 2.0, 4.0, 8.0];
+const WRAPS: [Wrap; 3] = [Wrap::Repeat, Wrap::MirroredRepeat, Wrap::ClampToEdge];
+
+/// The filters to compare, from the cheapest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filtering {
+    Nearest,
+    Bilinear,
+    Trilinear,
+    Anisotropic,
+}
+
+impl Filtering {
+    pub fn next(self) -> Self {
+        match self {
+            Filtering::Nearest => Filtering::Bilinear,
+            Filtering::Bilinear => Filtering::Trilinear,
+            Filtering::Trilinear => Filtering::Anisotropic,
+            Filtering::Anisotropic => Filtering::Nearest,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Filtering::Nearest => "nearest",
+            Filtering::Bilinear => "bilinear",
+            Filtering::Trilinear => "trilinear",
+            Filtering::Anisotropic => "anisotropic",
+        }
+    }
+}
+
+/// How the tiled textures of the cube are sampled, changed with the keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampling {
+    pub filtering: Filtering,
+    wrap: usize,
+    tiling: usize,
+    /// the albedo texture is sRGB, decoded to linear light, or wrongly read as linear
+    pub srgb: bool,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            filtering: Filtering::Trilinear,
+            wrap: 0,
+            tiling: 1,
+            srgb: true,
+        }
+    }
+}
+
+impl Sampling {
+    pub fn wrap(&self) -> Wrap {
+        WRAPS[self.wrap]
+    }
+
+    pub fn next_wrap(&mut self) {
+        self.wrap = (self.wrap + 1) % WRAPS.len();
+    }
+
+    pub fn tiling(&self) -> f32 {
+        TILINGS[self.tiling]
+    }
+
+    pub fn next_tiling(&mut self) {
+        self.tiling = (self.tiling + 1) % TILINGS.len();
+    }
+
+    pub fn sampler_options(&self) -> SamplerOptions {
+        let (filter, anisotropy) = match self.filtering {
+            Filtering::Nearest => (Filter::Nearest, 1.0),
+            Filtering::Bilinear => (Filter::Bilinear, 1.0),
+            Filtering::Trilinear => (Filter::Trilinear, 1.0),
+            Filtering::Anisotropic => (Filter::Trilinear, ANISOTROPY),
+        };
+        SamplerOptions {
+            wrap: self.wrap(),
+            filter,
+            anisotropy,
+            ..SamplerOptions::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_filters_cycle_back() {
+        let mut filtering = Filtering::Nearest;
+        for _ in 0..4 {
+            filtering = filtering.next();
+        }
+        assert_eq!(filtering, Filtering::Nearest);
+    }
+
+    #[test]
+    fn only_anisotropic_asks_for_anisotropy() {
+        let mut sampling = Sampling::default();
+        assert_eq!(sampling.sampler_options().anisotropy, 1.0);
+        sampling.filtering = Filtering::Anisotropic;
+        let options = sampling.sampler_options();
+        assert_eq!(options.filter, Filter::Trilinear);
+        assert!(options.anisotropy > 1.0);
+        sampling.next_wrap();
+        assert_eq!(sampling.sampler_options().wrap, Wrap::MirroredRepeat);
+    }
+}
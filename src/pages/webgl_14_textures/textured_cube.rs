@@ -0,0 +1,189 @@
+use cgmath::{InnerSpace, Matrix4, Vector3};
+use std::cell::RefCell;
+use std::mem::size_of;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, *};
+
+use super::mesh::{Mesh, VERTEX_SIZE};
+use super::state::{albedo_texels, detail_texels, die_face_texels};
+use super::Shader;
+use super::State;
+use crate::texture::{Filter, Mipmaps, Sampler, SamplerOptions, Texture, TextureFormat, TextureUnits, Wrap};
+
+/// `aVertexPosition`, `aVertexNormal`, `aTextureCoord` and `aLayer`
+const POSITION_LOCATION: u32 = 0;
+const NORMAL_LOCATION: u32 = 1;
+const TEXTURE_COORD_LOCATION: u32 = 2;
+const LAYER_LOCATION: u32 = 3;
+
+/// side of the tiled textures
+const TEXTURE_SIZE: usize = 256;
+/// side of the layers of the face texture
+const FACE_SIZE: usize = 128;
+
+/// The textures of the cube. Each takes the next texture unit when bound,
+/// whatever their number.
+pub struct CubeMaterial {
+    /// the same texels twice: stored as sRGB, and as plain RGBA8
+    albedo_srgb: Texture,
+    albedo_linear: Texture,
+    detail: Texture,
+    faces: Texture,
+    /// the pips are stretched once over their face, never tiled
+    faces_sampler: Sampler,
+}
+
+impl CubeMaterial {
+    pub fn new(gl: &GL) -> Result<Self, JsValue> {
+        let size = TEXTURE_SIZE as u32;
+        let albedo_texels = albedo_texels(TEXTURE_SIZE);
+        let albedo_srgb = Texture::new_2d(gl, TextureFormat::Srgb8Alpha8, size, size, Mipmaps::Full)?;
+        albedo_srgb.upload(gl, 0, 0, &albedo_texels)?;
+        albedo_srgb.generate_mipmaps(gl)?;
+        let albedo_linear = Texture::new_2d(gl, TextureFormat::Rgba8, size, size, Mipmaps::Full)?;
+        albedo_linear.upload(gl, 0, 0, &albedo_texels)?;
+        albedo_linear.generate_mipmaps(gl)?;
+
+        let detail = Texture::new_2d(gl, TextureFormat::R8, size, size, Mipmaps::Full)?;
+        detail.upload(gl, 0, 0, &detail_texels(TEXTURE_SIZE))?;
+        detail.generate_mipmaps(gl)?;
+
+        let face_size = FACE_SIZE as u32;
+        let faces = Texture::new_2d_array(gl, TextureFormat::Rgba8, face_size, face_size, 6, Mipmaps::Full)?;
+        faces.upload(gl, 0, 0, &die_face_texels(FACE_SIZE))?;
+        faces.generate_mipmaps(gl)?;
+        let faces_sampler = Sampler::new(
+            gl,
+            SamplerOptions {
+                wrap: Wrap::ClampToEdge,
+                filter: Filter::Trilinear,
+                ..SamplerOptions::default()
+            },
+        )?;
+
+        Ok(Self {
+            albedo_srgb,
+            albedo_linear,
+            detail,
+            faces,
+            faces_sampler,
+        })
+    }
+
+    /// Binds the textures to units of `units`, and the sampler uniforms of
+    /// `shader` to those units. The tiled ones are sampled with `sampler`.
+    pub fn bind(&self, gl: &GL, shader: &Shader, units: &mut TextureUnits, sampler: &Sampler, srgb: bool) -> Result<(), JsValue> {
+        let albedo = if srgb { &self.albedo_srgb } else { &self.albedo_linear };
+        let textures = [
+            ("uAlbedo", albedo, sampler),
+            ("uDetail", &self.detail, sampler),
+            ("uFaces", &self.faces, &self.faces_sampler),
+        ];
+        for (uniform_name, texture, sampler) in textures.iter() {
+            let unit = units.bind(gl, texture, sampler)?;
+            let location = shader.get_uniform_location(gl, uniform_name);
+            gl.uniform1i(location.as_ref(), unit);
+        }
+        Ok(())
+    }
+}
+
+pub struct TexturedCube {
+    shader: Shader,
+    index_count: i32,
+    vao: Option<WebGlVertexArrayObject>,
+    material: CubeMaterial,
+    units: RefCell<TextureUnits>,
+    /// of the tiled textures, made again when the options asked for change
+    sampler: RefCell<(SamplerOptions, Sampler)>,
+}
+
+impl TexturedCube {
+    pub fn new(gl: &GL, shader: Shader) -> Result<Self, JsValue> {
+        let options = SamplerOptions::default();
+        Ok(Self {
+            shader,
+            index_count: 0,
+            vao: None,
+            material: CubeMaterial::new(gl)?,
+            units: RefCell::new(TextureUnits::new(gl)),
+            sampler: RefCell::new((options, Sampler::new(gl, options)?)),
+        })
+    }
+
+    pub fn prepare_for_render(&mut self, gl: &GL) {
+        let mesh = Mesh::textured_cube();
+        self.vao = gl.create_vertex_array();
+        gl.bind_vertex_array(self.vao.as_ref());
+
+        let vbo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&vbo));
+        unsafe {
+            let data_array = js_sys::Float32Array::view(mesh.vertices());
+            gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+        let stride = (VERTEX_SIZE * size_of::<f32>()) as i32;
+        let attributes = [
+            (POSITION_LOCATION, 3, 0),
+            (NORMAL_LOCATION, 3, 3),
+            (TEXTURE_COORD_LOCATION, 2, 6),
+            (LAYER_LOCATION, 1, 8),
+        ];
+        for &(location, size, offset) in attributes.iter() {
+            gl.enable_vertex_attrib_array(location);
+            gl.vertex_attrib_pointer_with_i32(location, size, GL::FLOAT, false, stride, offset * size_of::<f32>() as i32);
+        }
+
+        let ebo = gl.create_buffer().unwrap();
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, Some(&ebo));
+        unsafe {
+            let data_array = js_sys::Uint16Array::view(mesh.indices());
+            gl.buffer_data_with_array_buffer_view(GL::ELEMENT_ARRAY_BUFFER, &data_array, GL::STATIC_DRAW);
+        }
+
+        gl.bind_vertex_array(None);
+        gl.bind_buffer(GL::ELEMENT_ARRAY_BUFFER, None);
+        gl.bind_buffer(GL::ARRAY_BUFFER, None);
+        self.index_count = mesh.indices().len() as i32;
+    }
+
+    /// The anisotropy of the tiled textures, as the device allows it.
+    pub fn anisotropy(&self) -> f32 {
+        self.sampler.borrow().1.options().anisotropy
+    }
+
+    pub fn render(&self, gl: &GL, state: &State) -> Result<(), JsValue> {
+        let options = state.sampling().sampler_options();
+        if self.sampler.borrow().0 != options {
+            let (_, old) = self.sampler.replace((options, Sampler::new(gl, options)?));
+            old.delete(gl);
+        }
+
+        let shader = &self.shader;
+        gl.use_program(Some(&shader.program));
+        let camera = state.camera();
+        set_matrix4(gl, shader, "uViewMatrix", camera.view_matrix());
+        set_matrix4(gl, shader, "uProjectiveMatrix", camera.projection_matrix(state.viewport().aspect()));
+        let location = shader.get_uniform_location(gl, "uTiling");
+        gl.uniform1f(location.as_ref(), state.sampling().tiling());
+        let light_direction = Vector3::new(0.4, 1.0, 0.7).normalize();
+        let location = shader.get_uniform_location(gl, "uLightDirection");
+        gl.uniform3fv_with_f32_array(location.as_ref(), &[light_direction.x, light_direction.y, light_direction.z]);
+
+        let mut units = self.units.borrow_mut();
+        units.reset();
+        self.material
+            .bind(gl, shader, &mut units, &self.sampler.borrow().1, state.sampling().srgb)?;
+
+        gl.bind_vertex_array(self.vao.as_ref());
+        gl.draw_elements_with_i32(GL::TRIANGLES, self.index_count, GL::UNSIGNED_SHORT, 0);
+        gl.bind_vertex_array(None);
+        Ok(())
+    }
+}
+
+fn set_matrix4(gl: &GL, shader: &Shader, uniform_name: &str, matrix: Matrix4<f32>) {
+    let location = shader.get_uniform_location(gl, uniform_name);
+    let array: &[f32; 16] = matrix.as_ref();
+    gl.uniform_matrix4fv_with_f32_array(location.as_ref(), false, &array[..]);
+}
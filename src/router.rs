@@ -1,9 +1,36 @@
 use log::debug;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 type Endpoint = Box<dyn Fn() -> Result<(), JsValue>>;
 
+thread_local! {
+    /// registered paths in index order, for moving between lessons
+    static LESSONS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Goes `offset` lessons forward or back from the current one, wrapping around.
+pub(crate) fn goto_lesson(offset: i32) {
+    let window = web_sys::window().expect("no global `window` exists");
+    let location = window.location();
+    let hash_str = location.hash().expect("get hash string error");
+    let current = hash_str.trim_start_matches('#').to_string();
+    let next = LESSONS.with(|lessons| {
+        let lessons = lessons.borrow();
+        let count = lessons.len() as i32;
+        lessons
+            .iter()
+            .position(|p| *p == current)
+            .map(|index| lessons[(index as i32 + offset).rem_euclid(count) as usize].clone())
+    });
+    if let Some(path) = next {
+        location.set_hash(&path).expect("set hash error");
+        // every page sets itself up once on load
+        location.reload().expect("reload error");
+    }
+}
+
 pub(crate) struct Router<'a> {
     endpoint_map: HashMap<&'a str, Endpoint>,
 }
@@ -19,6 +46,7 @@ impl<'a> Router<'a> {
     }
 
     pub(crate) fn route(&self) -> Result<(), JsValue> {
+        LESSONS.with(|lessons| *lessons.borrow_mut() = self.sorted_paths().into_iter().map(String::from).collect());
         let window = web_sys::window().expect("no global `window` exists");
         let location = window.location();
         let mut hash_str = location.hash().expect("get hash string error");
@@ -47,9 +75,7 @@ impl<'a> Router<'a> {
 
         let ul = document.create_element("ul")?;
 
-        let mut paths: Vec<_> = self.endpoint_map.keys().collect();
-        paths.sort();
-        for path in paths {
+        for path in self.sorted_paths() {
             let text = path.replace('/', " ");
             let link_text = document.create_text_node(text.trim());
             let link = document.create_element("a")?;
@@ -66,4 +92,10 @@ impl<'a> Router<'a> {
 
         Ok(())
     }
+
+    fn sorted_paths(&self) -> Vec<&'a str> {
+        let mut paths: Vec<_> = self.endpoint_map.keys().copied().collect();
+        paths.sort_unstable();
+        paths
+    }
 }
@@ -0,0 +1,114 @@
+use web_sys::WebGl2RenderingContext as GL;
+
+/// The internal formats the lessons use, with what uploading and sampling
+/// them takes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureFormat {
+    Rgba8,
+    /// decoded to linear light when sampled, for colors picked or painted in sRGB
+    Srgb8Alpha8,
+    R8,
+    /// drawn into and compared, never uploaded
+    Depth24,
+}
+
+impl TextureFormat {
+    pub fn internal_format(self) -> u32 {
+        match self {
+            TextureFormat::Rgba8 => GL::RGBA8,
+            TextureFormat::Srgb8Alpha8 => GL::SRGB8_ALPHA8,
+            TextureFormat::R8 => GL::R8,
+            TextureFormat::Depth24 => GL::DEPTH_COMPONENT24,
+        }
+    }
+
+    /// The channels of the uploaded texels.
+    pub fn format(self) -> u32 {
+        match self {
+            TextureFormat::Rgba8 | TextureFormat::Srgb8Alpha8 => GL::RGBA,
+            TextureFormat::R8 => GL::RED,
+            TextureFormat::Depth24 => GL::DEPTH_COMPONENT,
+        }
+    }
+
+    /// The type of the uploaded channels.
+    pub fn data_type(self) -> u32 {
+        match self {
+            TextureFormat::Rgba8 | TextureFormat::Srgb8Alpha8 | TextureFormat::R8 => GL::UNSIGNED_BYTE,
+            TextureFormat::Depth24 => GL::UNSIGNED_INT,
+        }
+    }
+
+    pub fn channels(self) -> usize {
+        match self {
+            TextureFormat::Rgba8 | TextureFormat::Srgb8Alpha8 => 4,
+            TextureFormat::R8 | TextureFormat::Depth24 => 1,
+        }
+    }
+
+    /// Uploaded as `u8`, the only texels `Texture::upload` takes.
+    pub fn is_bytes(self) -> bool {
+        self.data_type() == GL::UNSIGNED_BYTE
+    }
+
+    pub fn is_depth(self) -> bool {
+        matches!(self, TextureFormat::Depth24)
+    }
+
+    /// Linear filtering, mipmaps included. Depth textures are compared instead.
+    pub fn is_filterable(self) -> bool {
+        !self.is_depth()
+    }
+
+    /// Drawable into as a color, which `generateMipmap` needs too.
+    pub fn is_color_renderable(self) -> bool {
+        matches!(self, TextureFormat::Rgba8 | TextureFormat::Srgb8Alpha8 | TextureFormat::R8)
+    }
+}
+
+/// Levels of a full mip chain, down to 1x1.
+pub fn mip_level_count(width: u32, height: u32) -> i32 {
+    let largest = width.max(height).max(1);
+    (32 - largest.leading_zeros()) as i32
+}
+
+/// Channels in a `width` by `height` by `depth` block of `format`, what an
+/// upload of it must hold.
+pub fn texel_count(format: TextureFormat, width: u32, height: u32, depth: u32) -> usize {
+    width as usize * height as usize * depth as usize * format.channels()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_mip_chains() {
+        assert_eq!(mip_level_count(1, 1), 1);
+        assert_eq!(mip_level_count(256, 256), 9);
+        assert_eq!(mip_level_count(300, 20), 9);
+        assert_eq!(mip_level_count(0, 0), 1);
+    }
+
+    #[test]
+    fn only_colors_upload_as_bytes() {
+        assert_eq!(TextureFormat::Depth24.data_type(), GL::UNSIGNED_INT);
+        assert!(!TextureFormat::Depth24.is_bytes());
+        assert!(TextureFormat::Srgb8Alpha8.is_bytes());
+        assert_eq!(texel_count(TextureFormat::Rgba8, 4, 2, 1), 32);
+        assert_eq!(texel_count(TextureFormat::R8, 4, 4, 6), 96);
+    }
+
+    #[test]
+    fn only_color_formats_generate_mipmaps() {
+        let formats = [
+            TextureFormat::Rgba8,
+            TextureFormat::Srgb8Alpha8,
+            TextureFormat::R8,
+            TextureFormat::Depth24,
+        ];
+        let mipmapped: Vec<_> = formats.iter().filter(|f| f.is_filterable() && f.is_color_renderable()).collect();
+        assert_eq!(mipmapped, [&TextureFormat::Rgba8, &TextureFormat::Srgb8Alpha8, &TextureFormat::R8]);
+        assert!(formats.iter().filter(|f| f.is_depth()).all(|f| !f.is_filterable()));
+    }
+}
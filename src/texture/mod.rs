@@ -0,0 +1,201 @@
+//! Textures shared by the lessons: immutable storage for the 2D, cube map
+//! and array targets, the formats they use, sampler objects, and the
+//! texture units a draw binds.
+
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, WebGlTexture};
+
+pub use format::TextureFormat;
+use format::{mip_level_count, texel_count};
+pub use sampler::{Filter, Sampler, SamplerOptions, Wrap};
+pub use units::TextureUnits;
+
+mod format;
+mod sampler;
+mod units;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextureTarget {
+    Texture2d,
+    /// six square faces, in the order of `TEXTURE_CUBE_MAP_POSITIVE_X + face`
+    CubeMap,
+    /// layers of the same size, picked by an integer, never blended together
+    Texture2dArray,
+}
+
+impl TextureTarget {
+    pub fn gl(self) -> u32 {
+        match self {
+            TextureTarget::Texture2d => GL::TEXTURE_2D,
+            TextureTarget::CubeMap => GL::TEXTURE_CUBE_MAP,
+            TextureTarget::Texture2dArray => GL::TEXTURE_2D_ARRAY,
+        }
+    }
+}
+
+/// How many levels to allocate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mipmaps {
+    None,
+    /// down to 1x1, filled by `generate_mipmaps` or uploaded one by one
+    Full,
+}
+
+/// An immutable storage texture: its size, format and levels are set once,
+/// the texels can be uploaded again. Sampling is left to the `Sampler`s.
+pub struct Texture {
+    texture: WebGlTexture,
+    target: TextureTarget,
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    /// layers of an array, 1 otherwise
+    depth: u32,
+    levels: i32,
+}
+
+impl Texture {
+    pub fn new_2d(gl: &GL, format: TextureFormat, width: u32, height: u32, mipmaps: Mipmaps) -> Result<Self, JsValue> {
+        Self::new(gl, TextureTarget::Texture2d, format, (width, height, 1), mipmaps)
+    }
+
+    pub fn new_cube(gl: &GL, format: TextureFormat, size: u32, mipmaps: Mipmaps) -> Result<Self, JsValue> {
+        Self::new(gl, TextureTarget::CubeMap, format, (size, size, 1), mipmaps)
+    }
+
+    pub fn new_2d_array(gl: &GL, format: TextureFormat, width: u32, height: u32, layers: u32, mipmaps: Mipmaps) -> Result<Self, JsValue> {
+        Self::new(gl, TextureTarget::Texture2dArray, format, (width, height, layers), mipmaps)
+    }
+
+    fn new(
+        gl: &GL,
+        target: TextureTarget,
+        format: TextureFormat,
+        (width, height, depth): (u32, u32, u32),
+        mipmaps: Mipmaps,
+    ) -> Result<Self, JsValue> {
+        if width == 0 || height == 0 || depth == 0 {
+            return Err(format!("empty {:?} texture", target).into());
+        }
+        let levels = match mipmaps {
+            Mipmaps::None => 1,
+            // the layers of an array are no dimension, they are never halved
+            Mipmaps::Full => mip_level_count(width, height),
+        };
+        let texture = gl.create_texture().ok_or("create texture error")?;
+        gl.bind_texture(target.gl(), Some(&texture));
+        match target {
+            TextureTarget::Texture2d | TextureTarget::CubeMap => {
+                gl.tex_storage_2d(target.gl(), levels, format.internal_format(), width as i32, height as i32)
+            }
+            TextureTarget::Texture2dArray => {
+                gl.tex_storage_3d(target.gl(), levels, format.internal_format(), width as i32, height as i32, depth as i32)
+            }
+        }
+        gl.bind_texture(target.gl(), None);
+        Ok(Self {
+            texture,
+            target,
+            format,
+            width,
+            height,
+            depth,
+            levels,
+        })
+    }
+
+    pub fn target(&self) -> TextureTarget {
+        self.target
+    }
+
+    /// For framebuffer attachments.
+    pub fn raw(&self) -> &WebGlTexture {
+        &self.texture
+    }
+
+    /// Width, height and layers of `level`.
+    pub fn level_size(&self, level: i32) -> (u32, u32, u32) {
+        let halve = |size: u32| (size >> level).max(1);
+        (halve(self.width), halve(self.height), self.depth)
+    }
+
+    /// Replaces level `level`: of face `layer` of a cube map, of every layer
+    /// at once otherwise, `layer` then being 0.
+    pub fn upload(&self, gl: &GL, level: i32, layer: u32, texels: &[u8]) -> Result<(), JsValue> {
+        if level >= self.levels {
+            return Err(format!("level {} of a texture of {}", level, self.levels).into());
+        }
+        if !self.format.is_bytes() {
+            return Err(format!("{:?} texels are not uploaded", self.format).into());
+        }
+        let (width, height, depth) = self.level_size(level);
+        let (expected, length) = (texel_count(self.format, width, height, depth), texels.len());
+        if length != expected {
+            return Err(format!(
+                "{} values for a {}x{}x{} {:?} level, not {}",
+                length, width, height, depth, self.format, expected
+            )
+            .into());
+        }
+
+        let target = self.target.gl();
+        gl.bind_texture(target, Some(&self.texture));
+        // rows of R8 texels are not always 4 byte aligned
+        gl.pixel_storei(GL::UNPACK_ALIGNMENT, 1);
+        // safety: the view is used right away, before anything allocates
+        let result = unsafe {
+            let view = js_sys::Uint8Array::view(texels);
+            let (format, data_type) = (self.format.format(), self.format.data_type());
+            match self.target {
+                TextureTarget::Texture2d | TextureTarget::CubeMap => {
+                    let face_target = if self.target == TextureTarget::CubeMap {
+                        GL::TEXTURE_CUBE_MAP_POSITIVE_X + layer
+                    } else {
+                        target
+                    };
+                    gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                        face_target,
+                        level,
+                        0,
+                        0,
+                        width as i32,
+                        height as i32,
+                        format,
+                        data_type,
+                        Some(&view),
+                    )
+                }
+                TextureTarget::Texture2dArray => gl.tex_sub_image_3d_with_opt_array_buffer_view(
+                    target,
+                    level,
+                    0,
+                    0,
+                    0,
+                    width as i32,
+                    height as i32,
+                    depth as i32,
+                    format,
+                    data_type,
+                    Some(&view),
+                ),
+            }
+        };
+        gl.pixel_storei(GL::UNPACK_ALIGNMENT, 4);
+        gl.bind_texture(target, None);
+        result
+    }
+
+    /// Fills the levels past 0 by halving it over and over.
+    pub fn generate_mipmaps(&self, gl: &GL) -> Result<(), JsValue> {
+        if self.levels == 1 {
+            return Err("a texture without mipmaps".into());
+        }
+        if !self.format.is_filterable() || !self.format.is_color_renderable() {
+            return Err(format!("no mipmap generation for {:?}", self.format).into());
+        }
+        gl.bind_texture(self.target.gl(), Some(&self.texture));
+        gl.generate_mipmap(self.target.gl());
+        gl.bind_texture(self.target.gl(), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext as GL, WebGlSampler};
+
+/// `EXT_texture_filter_anisotropic`, absent from the WebGL 2 constants
+const TEXTURE_MAX_ANISOTROPY: u32 = 0x84FE;
+const MAX_TEXTURE_MAX_ANISOTROPY: u32 = 0x84FF;
+
+/// What happens past the edges of the texture coordinates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+impl Wrap {
+    pub fn gl(self) -> u32 {
+        match self {
+            Wrap::Repeat => GL::REPEAT,
+            Wrap::MirroredRepeat => GL::MIRRORED_REPEAT,
+            Wrap::ClampToEdge => GL::CLAMP_TO_EDGE,
+        }
+    }
+}
+
+/// How texels are blended, between them and between the mip levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// the nearest texel of level 0, blocky up close and shimmering far away
+    Nearest,
+    /// the 4 nearest texels of level 0
+    Linear,
+    /// the 4 nearest texels of the nearest level
+    Bilinear,
+    /// blended between the two nearest levels as well
+    Trilinear,
+}
+
+impl Filter {
+    /// `TEXTURE_MIN_FILTER` and `TEXTURE_MAG_FILTER`.
+    pub fn gl(self) -> (u32, u32) {
+        match self {
+            Filter::Nearest => (GL::NEAREST, GL::NEAREST),
+            Filter::Linear => (GL::LINEAR, GL::LINEAR),
+            Filter::Bilinear => (GL::LINEAR_MIPMAP_NEAREST, GL::LINEAR),
+            Filter::Trilinear => (GL::LINEAR_MIPMAP_LINEAR, GL::LINEAR),
+        }
+    }
+}
+
+/// The sampling state, apart from the texture it applies to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerOptions {
+    pub wrap: Wrap,
+    pub filter: Filter,
+    /// samples along the direction a texture is squashed in, 1 for none
+    pub anisotropy: f32,
+    /// for a `sampler2DShadow` and the like, comparing with `LEQUAL`
+    pub compare: bool,
+}
+
+impl Default for SamplerOptions {
+    fn default() -> Self {
+        Self {
+            wrap: Wrap::Repeat,
+            filter: Filter::Trilinear,
+            anisotropy: 1.0,
+            compare: false,
+        }
+    }
+}
+
+/// A WebGL 2 sampler object: the sampling state kept apart from the
+/// textures, so that one texture can be sampled in several ways.
+pub struct Sampler {
+    sampler: WebGlSampler,
+    options: SamplerOptions,
+}
+
+impl Sampler {
+    /// The anisotropy is brought down to what the device supports, to 1
+    /// without the extension.
+    pub fn new(gl: &GL, options: SamplerOptions) -> Result<Self, JsValue> {
+        let sampler = gl.create_sampler().ok_or("create sampler error")?;
+        for &pname in [GL::TEXTURE_WRAP_S, GL::TEXTURE_WRAP_T, GL::TEXTURE_WRAP_R].iter() {
+            gl.sampler_parameteri(&sampler, pname, options.wrap.gl() as i32);
+        }
+        let (min_filter, mag_filter) = options.filter.gl();
+        gl.sampler_parameteri(&sampler, GL::TEXTURE_MIN_FILTER, min_filter as i32);
+        gl.sampler_parameteri(&sampler, GL::TEXTURE_MAG_FILTER, mag_filter as i32);
+        if options.compare {
+            gl.sampler_parameteri(&sampler, GL::TEXTURE_COMPARE_MODE, GL::COMPARE_REF_TO_TEXTURE as i32);
+            gl.sampler_parameteri(&sampler, GL::TEXTURE_COMPARE_FUNC, GL::LEQUAL as i32);
+        }
+        let anisotropy = options.anisotropy.min(max_anisotropy(gl)).max(1.0);
+        if anisotropy > 1.0 {
+            gl.sampler_parameterf(&sampler, TEXTURE_MAX_ANISOTROPY, anisotropy);
+        }
+        Ok(Self {
+            sampler,
+            options: SamplerOptions { anisotropy, ..options },
+        })
+    }
+
+    /// The options asked for, with the anisotropy actually applied.
+    pub fn options(&self) -> SamplerOptions {
+        self.options
+    }
+
+    pub fn bind(&self, gl: &GL, unit: u32) {
+        gl.bind_sampler(unit, Some(&self.sampler));
+    }
+
+    pub fn delete(self, gl: &GL) {
+        gl.delete_sampler(Some(&self.sampler));
+    }
+}
+
+/// The largest anisotropy of the device, 1 without the extension.
+fn max_anisotropy(gl: &GL) -> f32 {
+    match gl.get_extension("EXT_texture_filter_anisotropic") {
+        Ok(Some(_)) => gl
+            .get_parameter(MAX_TEXTURE_MAX_ANISOTROPY)
+            .ok()
+            .and_then(|max| max.as_f64())
+            .unwrap_or(1.0) as f32,
+        _ => 1.0,
+    }
+}
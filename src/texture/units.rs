@@ -0,0 +1,98 @@
+use wasm_bindgen::prelude::*;
+use web_sys::WebGl2RenderingContext as GL;
+
+use super::{Sampler, Texture};
+
+/// Hands out the texture units of a draw in turn, so that the textures of
+/// a material, and whatever else the shader samples, never share one.
+pub struct TextureUnits {
+    /// units taken since the last `reset`, the reserved ones included
+    used: u32,
+    /// the first units, kept by `reset`
+    reserved: u32,
+    count: u32,
+}
+
+impl TextureUnits {
+    /// All the units a program can sample from.
+    pub fn new(gl: &GL) -> Self {
+        let count = gl
+            .get_parameter(GL::MAX_COMBINED_TEXTURE_IMAGE_UNITS)
+            .ok()
+            .and_then(|count| count.as_f64())
+            // the least WebGL 2 guarantees
+            .unwrap_or(32.0) as u32;
+        Self::with_count(count)
+    }
+
+    pub fn with_count(count: u32) -> Self {
+        Self { used: 0, reserved: 0, count }
+    }
+
+    /// Frees every unit but the reserved ones, before the textures of the
+    /// next draw are bound.
+    pub fn reset(&mut self) {
+        self.used = self.reserved;
+    }
+
+    /// Keeps the units taken so far past the `reset`s, for the textures
+    /// every draw samples, like shadow maps.
+    pub fn reserve(&mut self) {
+        self.reserved = self.used;
+    }
+
+    /// Frees every unit, the reserved ones too.
+    pub fn release(&mut self) {
+        self.used = 0;
+        self.reserved = 0;
+    }
+
+    /// The next free unit.
+    pub fn allocate(&mut self) -> Result<u32, String> {
+        if self.used == self.count {
+            return Err(format!("all {} texture units are taken", self.count));
+        }
+        self.used += 1;
+        Ok(self.used - 1)
+    }
+
+    /// Binds `texture` and `sampler` to the next free unit, the value of the
+    /// sampler uniform to set.
+    pub fn bind(&mut self, gl: &GL, texture: &Texture, sampler: &Sampler) -> Result<i32, JsValue> {
+        let unit = self.allocate()?;
+        gl.active_texture(GL::TEXTURE0 + unit);
+        gl.bind_texture(texture.target().gl(), Some(texture.raw()));
+        sampler.bind(gl, unit);
+        gl.active_texture(GL::TEXTURE0);
+        Ok(unit as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_are_taken_in_turn_until_reset() {
+        let mut units = TextureUnits::with_count(2);
+        assert_eq!(units.allocate(), Ok(0));
+        assert_eq!(units.allocate(), Ok(1));
+        assert!(units.allocate().is_err());
+        units.reset();
+        assert_eq!(units.allocate(), Ok(0));
+    }
+
+    #[test]
+    fn reserved_units_are_kept_until_released() {
+        let mut units = TextureUnits::with_count(3);
+        assert_eq!(units.allocate(), Ok(0));
+        units.reserve();
+        assert_eq!(units.allocate(), Ok(1));
+        units.reset();
+        assert_eq!(units.allocate(), Ok(1));
+        assert_eq!(units.allocate(), Ok(2));
+        assert!(units.allocate().is_err());
+        units.release();
+        assert_eq!(units.allocate(), Ok(0));
+    }
+}